@@ -0,0 +1,103 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use std::collections::VecDeque;
+use std::hint::black_box;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use cds::ms_queue::MsQueue;
+use cds::treiber_stack::TreiberStack;
+
+const OPS_PER_THREAD: u64 = 10_000;
+
+trait Container: Default + Sync {
+    fn push(&self, value: u64);
+    fn pop(&self) -> Option<u64>;
+}
+
+impl Container for MsQueue<u64> {
+    fn push(&self, value: u64) {
+        Self::push(self, value);
+    }
+
+    fn pop(&self) -> Option<u64> {
+        Self::pop(self)
+    }
+}
+
+impl Container for TreiberStack<u64> {
+    fn push(&self, value: u64) {
+        Self::push(self, value);
+    }
+
+    fn pop(&self) -> Option<u64> {
+        Self::pop(self)
+    }
+}
+
+#[derive(Default)]
+struct MutexQueue(Mutex<VecDeque<u64>>);
+
+impl Container for MutexQueue {
+    fn push(&self, value: u64) {
+        self.0.lock().unwrap().push_back(value);
+    }
+
+    fn pop(&self) -> Option<u64> {
+        self.0.lock().unwrap().pop_front()
+    }
+}
+
+// 每个线程交替地插入和弹出元素, 返回所有线程完成的总时间.
+fn run<C: Container>(threads: usize, iters: u64) -> Duration {
+    let mut total = Duration::ZERO;
+    for _ in 0..iters {
+        let container = C::default();
+        let start = Instant::now();
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| {
+                    for i in 0..OPS_PER_THREAD {
+                        container.push(i);
+                        black_box(container.pop());
+                    }
+                });
+            }
+        });
+        total += start.elapsed();
+    }
+    total
+}
+
+fn push_pop(c: &mut Criterion) {
+    let mut group = c.benchmark_group("push_pop");
+    for threads in [1, 2, 4, 8] {
+        group.throughput(Throughput::Elements(threads as u64 * OPS_PER_THREAD * 2));
+        group.bench_with_input(BenchmarkId::new("MsQueue", threads), &threads, |b, &n| {
+            b.iter_custom(|iters| run::<MsQueue<u64>>(n, iters));
+        });
+        group.bench_with_input(
+            BenchmarkId::new("TreiberStack", threads),
+            &threads,
+            |b, &n| {
+                b.iter_custom(|iters| run::<TreiberStack<u64>>(n, iters));
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("Mutex<VecDeque>", threads),
+            &threads,
+            |b, &n| {
+                b.iter_custom(|iters| run::<MutexQueue>(n, iters));
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, push_pop);
+criterion_main!(benches);
@@ -0,0 +1,313 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! A minimal hazard pointer scheme for memory reclamation.
+//!
+//! Before dereferencing a shared node, a thread publishes its address in a
+//! hazard pointer, then checks that the node is still reachable. A node
+//! removed from the data structure is retired instead of freed; it is only
+//! freed by a later scan when no hazard pointer refers to it.
+//!
+//! Each data structure owns one [`Domain`], so nodes of different structures
+//! never wait for each other.
+
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+/// Scan retired nodes once there are this many of them.
+const SCAN_THRESHOLD: usize = 64;
+
+pub struct Domain {
+    /// Push-only list of hazard records, records are reused but never freed
+    /// before the domain is dropped.
+    records: AtomicPtr<Record>,
+    /// Lock-free stack of retired nodes.
+    retired: AtomicPtr<Retired>,
+    retired_count: AtomicUsize,
+}
+
+struct Record {
+    hazard: AtomicPtr<()>,
+    active: AtomicBool,
+    next: *mut Self,
+}
+
+struct Retired {
+    ptr: *mut (),
+    deleter: unsafe fn(*mut ()),
+    next: *mut Self,
+}
+
+/// A hazard pointer owned by one thread, released on drop.
+pub struct HazardPointer<'a> {
+    record: &'a Record,
+}
+
+// Records and retired nodes are only shared through atomic operations.
+unsafe impl Send for Domain {}
+unsafe impl Sync for Domain {}
+
+impl Default for Domain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Domain {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            records: AtomicPtr::new(ptr::null_mut()),
+            retired: AtomicPtr::new(ptr::null_mut()),
+            retired_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Acquire a hazard pointer, reusing an inactive record if possible.
+    pub fn hazard_pointer(&self) -> HazardPointer<'_> {
+        let mut node = self.records.load(Ordering::Acquire);
+        while !node.is_null() {
+            let record = unsafe { &*node };
+            if !record.active.load(Ordering::Relaxed)
+                && record
+                    .active
+                    .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            {
+                return HazardPointer { record };
+            }
+            node = record.next;
+        }
+
+        let record = Box::into_raw(Box::new(Record {
+            hazard: AtomicPtr::new(ptr::null_mut()),
+            active: AtomicBool::new(true),
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.records.load(Ordering::Acquire);
+        loop {
+            unsafe {
+                (*record).next = head;
+            }
+            match self.records.compare_exchange_weak(
+                head,
+                record,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => break,
+                Err(current) => head = current,
+            }
+        }
+        HazardPointer {
+            record: unsafe { &*record },
+        }
+    }
+
+    /// Hand over a node which has been unlinked from the data structure,
+    /// it is freed once no hazard pointer protects it.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be created by `Box::into_raw()`, must not be reachable by
+    /// threads which do not hold a hazard pointer to it, and must not be
+    /// retired twice.
+    ///
+    /// The node is dropped by whichever thread runs the next scan or drops
+    /// the domain. `Domain` is `Send` and `Sync` whatever it holds, so unless
+    /// the domain is only ever used by the current thread, dropping a `T`
+    /// on another thread must be sound, i.e. `T` must behave as if it were
+    /// `Send`.
+    pub unsafe fn retire<T>(&self, ptr: *mut T) {
+        let retired = Box::into_raw(Box::new(Retired {
+            ptr: ptr.cast(),
+            deleter: drop_box::<T>,
+            next: ptr::null_mut(),
+        }));
+        // Count it before it becomes visible to scanners.
+        let count = self.retired_count.fetch_add(1, Ordering::AcqRel) + 1;
+        self.push_retired(retired);
+        if count >= SCAN_THRESHOLD {
+            self.scan();
+        }
+    }
+
+    /// Free retired nodes which are not protected by any hazard pointer.
+    pub fn scan(&self) {
+        // Take the whole list, so that no other thread can touch these nodes.
+        let mut node = self.retired.swap(ptr::null_mut(), Ordering::AcqRel);
+        if node.is_null() {
+            return;
+        }
+
+        let mut hazards = Vec::new();
+        let mut record = self.records.load(Ordering::Acquire);
+        while !record.is_null() {
+            let hazard = unsafe { (*record).hazard.load(Ordering::SeqCst) };
+            if !hazard.is_null() {
+                hazards.push(hazard);
+            }
+            record = unsafe { (*record).next };
+        }
+
+        while !node.is_null() {
+            let retired = node;
+            unsafe {
+                node = (*retired).next;
+                if hazards.contains(&(*retired).ptr) {
+                    self.push_retired(retired);
+                } else {
+                    let retired = Box::from_raw(retired);
+                    (retired.deleter)(retired.ptr);
+                    self.retired_count.fetch_sub(1, Ordering::AcqRel);
+                }
+            }
+        }
+    }
+
+    /// Returns the number of retired nodes which are not freed yet.
+    #[must_use]
+    pub fn retired_count(&self) -> usize {
+        self.retired_count.load(Ordering::Acquire)
+    }
+
+    fn push_retired(&self, retired: *mut Retired) {
+        let mut head = self.retired.load(Ordering::Acquire);
+        loop {
+            unsafe {
+                (*retired).next = head;
+            }
+            match self.retired.compare_exchange_weak(
+                head,
+                retired,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => break,
+                Err(current) => head = current,
+            }
+        }
+    }
+}
+
+impl Drop for Domain {
+    fn drop(&mut self) {
+        // No hazard pointer can outlive the domain, free everything.
+        let mut node = *self.retired.get_mut();
+        while !node.is_null() {
+            let retired = unsafe { Box::from_raw(node) };
+            unsafe {
+                (retired.deleter)(retired.ptr);
+            }
+            node = retired.next;
+        }
+        let mut node = *self.records.get_mut();
+        while !node.is_null() {
+            let record = unsafe { Box::from_raw(node) };
+            node = record.next;
+        }
+    }
+}
+
+impl HazardPointer<'_> {
+    /// Load a pointer from `src` and protect it.
+    ///
+    /// The returned pointer, if not null, can be dereferenced until this
+    /// hazard pointer protects another one or is dropped.
+    pub fn protect<T>(&self, src: &AtomicPtr<T>) -> *mut T {
+        let mut ptr = src.load(Ordering::Relaxed);
+        loop {
+            self.record.hazard.store(ptr.cast(), Ordering::SeqCst);
+            // Check that the node is still reachable after it was published.
+            let current = src.load(Ordering::SeqCst);
+            if current == ptr {
+                return ptr;
+            }
+            ptr = current;
+        }
+    }
+
+    /// Stop protecting current pointer.
+    pub fn reset(&self) {
+        self.record.hazard.store(ptr::null_mut(), Ordering::Release);
+    }
+}
+
+impl Drop for HazardPointer<'_> {
+    fn drop(&mut self) {
+        self.reset();
+        self.record.active.store(false, Ordering::Release);
+    }
+}
+
+unsafe fn drop_box<T>(ptr: *mut ()) {
+    drop(Box::from_raw(ptr.cast::<T>()));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::{Domain, SCAN_THRESHOLD};
+
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_protected_node_is_not_freed() {
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let domain = Domain::new();
+        let node = Box::into_raw(Box::new(Counted(Arc::clone(&dropped))));
+        let src = AtomicPtr::new(node);
+
+        let hazard = domain.hazard_pointer();
+        assert_eq!(hazard.protect(&src), node);
+        src.store(std::ptr::null_mut(), Ordering::SeqCst);
+        unsafe {
+            domain.retire(node);
+        }
+        domain.scan();
+        assert_eq!(dropped.load(Ordering::SeqCst), 0);
+        assert_eq!(domain.retired_count(), 1);
+
+        hazard.reset();
+        domain.scan();
+        assert_eq!(dropped.load(Ordering::SeqCst), 1);
+        assert_eq!(domain.retired_count(), 0);
+    }
+
+    #[test]
+    fn test_records_are_reused() {
+        let domain = Domain::new();
+        let first = domain.hazard_pointer();
+        let first_record: *const _ = first.record;
+        let second = domain.hazard_pointer();
+        assert!(!std::ptr::eq(first_record, second.record));
+        drop(first);
+        // The record released by `first` is reused.
+        let third = domain.hazard_pointer();
+        assert!(std::ptr::eq(first_record, third.record));
+    }
+
+    #[test]
+    fn test_scan_threshold_and_drop() {
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let domain = Domain::new();
+        for _ in 0..SCAN_THRESHOLD * 2 + 3 {
+            let node = Box::into_raw(Box::new(Counted(Arc::clone(&dropped))));
+            unsafe {
+                domain.retire(node);
+            }
+        }
+        assert!(domain.retired_count() < SCAN_THRESHOLD);
+        drop(domain);
+        assert_eq!(dropped.load(Ordering::SeqCst), SCAN_THRESHOLD * 2 + 3);
+    }
+}
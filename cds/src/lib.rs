@@ -10,4 +10,7 @@
     clippy::pedantic
 )]
 
-//pub mod lock_coupling_linked_list;
+pub mod hazard;
+pub mod lock_coupling_linked_list;
+pub mod ms_queue;
+pub mod treiber_stack;
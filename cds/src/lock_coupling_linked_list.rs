@@ -2,29 +2,449 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
+//! Sorted linked list with hand-over-hand locking.
+//!
+//! Each link is protected by its own mutex. A thread walking the list locks
+//! the next link before unlocking the current one, so that no other thread
+//! can remove the node in between. Threads working on different parts of the
+//! list do not block each other.
+
+use std::cmp::Ordering;
+use std::fmt;
 use std::ptr::NonNull;
-use std::sync::Mutex;
+use std::sync::atomic::{self, AtomicUsize};
+use std::sync::{Mutex, MutexGuard, PoisonError};
 
 pub struct LockCouplingLinkedList<T> {
-    len: usize,
+    len: AtomicUsize,
     head: NodePtr<T>,
 }
 
 type NodePtr<T> = Mutex<Option<NonNull<Node<T>>>>;
 
-pub struct Node<T> {
+struct Node<T> {
     next: NodePtr<T>,
     value: T,
 }
 
-impl<T> LockCouplingLinkedList<T> {}
+// Nodes are owned by the list, and links are only accessed with lock held.
+unsafe impl<T: Send> Send for LockCouplingLinkedList<T> {}
+unsafe impl<T: Send + Sync> Sync for LockCouplingLinkedList<T> {}
 
-impl<T> Node<T> {
+impl<T> Default for LockCouplingLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> LockCouplingLinkedList<T> {
+    /// Create an empty list.
     #[must_use]
-    fn new(value: T) -> Self {
+    pub const fn new() -> Self {
         Self {
-            next: Mutex::new(None),
+            len: AtomicUsize::new(0),
+            head: Mutex::new(None),
+        }
+    }
+
+    /// Returns the number of elements in list.
+    ///
+    /// The value may be outdated if other threads are modifying the list.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len.load(atomic::Ordering::Acquire)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a snapshot of elements in ascending order.
+    ///
+    /// The whole list is traversed with hand-over-hand locking, so the result
+    /// may mix states before and after concurrent modifications.
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let mut vec = Vec::new();
+        let mut guard = lock(&self.head);
+        while let Some(node_ptr) = *guard {
+            // The node can not be freed while the link pointing to it is locked.
+            let node = unsafe { node_ptr.as_ref() };
+            vec.push(node.value.clone());
+            guard = lock(&node.next);
+        }
+        drop(guard);
+        vec
+    }
+}
+
+impl<T: Ord> LockCouplingLinkedList<T> {
+    /// Insert `value` into list, keeping elements in ascending order.
+    ///
+    /// Returns false if the value is already present.
+    pub fn insert(&self, value: T) -> bool {
+        let mut guard = self.find(&value);
+        if let Some(node_ptr) = *guard {
+            if unsafe { node_ptr.as_ref() }.value == value {
+                return false;
+            }
+        }
+
+        let node = Box::new(Node {
+            next: Mutex::new(*guard),
             value,
+        });
+        *guard = Some(NonNull::from(Box::leak(node)));
+        self.len.fetch_add(1, atomic::Ordering::Release);
+        drop(guard);
+        true
+    }
+
+    /// Remove `value` from list.
+    ///
+    /// Returns false if the value is not found.
+    pub fn remove(&self, value: &T) -> bool {
+        let mut guard = self.find(value);
+        let Some(node_ptr) = *guard else {
+            return false;
+        };
+        let node = unsafe { node_ptr.as_ref() };
+        if node.value != *value {
+            return false;
+        }
+
+        // Wait for threads that are still holding the link of this node,
+        // new threads can not reach it as the previous link is locked.
+        let next = *lock(&node.next);
+        *guard = next;
+        self.len.fetch_sub(1, atomic::Ordering::Release);
+        drop(guard);
+        drop(unsafe { Box::from_raw(node_ptr.as_ptr()) });
+        true
+    }
+
+    /// Check whether `value` is in list.
+    #[must_use]
+    pub fn contains(&self, value: &T) -> bool {
+        let guard = self.find(value);
+        guard.is_some_and(|node_ptr| unsafe { node_ptr.as_ref() }.value == *value)
+    }
+
+    /// Returns the locked link which points to the first node not less than `value`.
+    fn find(&self, value: &T) -> MutexGuard<'_, Option<NonNull<Node<T>>>> {
+        let mut guard = lock(&self.head);
+        while let Some(node_ptr) = *guard {
+            let node = unsafe { node_ptr.as_ref() };
+            if node.value.cmp(value) != Ordering::Less {
+                break;
+            }
+            // Lock the next link before releasing current one.
+            guard = lock(&node.next);
+        }
+        guard
+    }
+}
+
+impl<T> Drop for LockCouplingLinkedList<T> {
+    fn drop(&mut self) {
+        let mut link = self
+            .head
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        while let Some(node_ptr) = link {
+            let mut node = unsafe { Box::from_raw(node_ptr.as_ptr()) };
+            link = node
+                .next
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner)
+                .take();
+        }
+    }
+}
+
+impl<T: Clone + fmt::Debug> fmt::Debug for LockCouplingLinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.to_vec()).finish()
+    }
+}
+
+/// Links are always left in a consistent state, so a poisoned lock is still usable.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    use super::LockCouplingLinkedList;
+
+    /// Linear congruential generator, so that each thread is reproducible.
+    struct Random(u64);
+
+    impl Random {
+        fn new(seed: usize) -> Self {
+            Self(seed as u64)
+        }
+
+        fn next(&mut self) -> usize {
+            self.0 = self
+                .0
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            usize::try_from(self.0 >> 33).unwrap()
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Op {
+        Insert,
+        Remove,
+        Contains,
+    }
+
+    /// A completed operation, with logical time of invocation and response.
+    #[derive(Debug, Clone, Copy)]
+    struct Event {
+        op: Op,
+        key: usize,
+        result: bool,
+        start: u64,
+        end: u64,
+    }
+
+    /// Apply `op` to the sequential model of one key, returns the expected result.
+    fn apply(present: &mut bool, op: Op) -> bool {
+        match op {
+            Op::Insert => !std::mem::replace(present, true),
+            Op::Remove => std::mem::replace(present, false),
+            Op::Contains => *present,
+        }
+    }
+
+    /// Search for a linearization of `events` on a single key, which respects
+    /// real-time order and the sequential model.
+    fn linearizable(
+        events: &[Event],
+        done: u128,
+        present: bool,
+        seen: &mut HashSet<(u128, bool)>,
+    ) -> bool {
+        if done.count_ones() as usize == events.len() {
+            return true;
+        }
+        if !seen.insert((done, present)) {
+            return false;
+        }
+        // An operation can go next only if no pending one finished before it started.
+        let pending = || (0..events.len()).filter(|&i| done & (1 << i) == 0);
+        let min_end = pending().map(|i| events[i].end).min().unwrap_or(u64::MAX);
+        for i in pending() {
+            let event = &events[i];
+            if event.start > min_end {
+                continue;
+            }
+            let mut state = present;
+            if apply(&mut state, event.op) == event.result
+                && linearizable(events, done | (1 << i), state, seen)
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn test_sequential() {
+        let list = LockCouplingLinkedList::new();
+        assert!(list.is_empty());
+        for value in [5, 1, 4, 2, 3] {
+            assert!(list.insert(value));
+        }
+        assert!(!list.insert(3));
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.to_vec(), [1, 2, 3, 4, 5]);
+        assert!(list.contains(&4));
+        assert!(!list.contains(&6));
+        assert!(list.remove(&1));
+        assert!(list.remove(&5));
+        assert!(list.remove(&3));
+        assert!(!list.remove(&3));
+        assert!(!list.remove(&0));
+        assert_eq!(list.len(), 2);
+        assert_eq!(format!("{list:?}"), "[2, 4]");
+    }
+
+    #[test]
+    fn test_drop_values() {
+        let list = LockCouplingLinkedList::new();
+        for i in 0..1000 {
+            list.insert(format!("{i:04}"));
+        }
+        assert!(list.remove(&"0500".to_owned()));
+        assert_eq!(list.len(), 999);
+        drop(list);
+    }
+
+    #[test]
+    fn test_stress() {
+        const THREADS: usize = 8;
+        const OPS: usize = 20_000;
+        const KEYS: usize = 64;
+
+        let list = Arc::new(LockCouplingLinkedList::new());
+        let handles: Vec<_> = (0..THREADS)
+            .map(|id| {
+                let list = Arc::clone(&list);
+                thread::spawn(move || {
+                    let mut random = Random::new(id + 1);
+                    // Count of successful insertions and removals for each key.
+                    let mut balance = vec![0_i64; KEYS];
+                    for _ in 0..OPS {
+                        let key = random.next() % KEYS;
+                        match random.next() % 3 {
+                            0 => {
+                                if list.insert(key) {
+                                    balance[key] += 1;
+                                }
+                            }
+                            1 => {
+                                if list.remove(&key) {
+                                    balance[key] -= 1;
+                                }
+                            }
+                            _ => {
+                                let _ = list.contains(&key);
+                            }
+                        }
+                    }
+                    balance
+                })
+            })
+            .collect();
+
+        let mut balance = vec![0_i64; KEYS];
+        for handle in handles {
+            for (total, count) in balance.iter_mut().zip(handle.join().unwrap()) {
+                *total += count;
+            }
+        }
+
+        let values = list.to_vec();
+        assert!(values.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(values.len(), list.len());
+        for (key, &count) in balance.iter().enumerate() {
+            let expected = i64::from(values.contains(&key));
+            assert_eq!(count, expected, "key: {key}");
+            assert_eq!(list.contains(&key), expected == 1);
+        }
+    }
+
+    #[test]
+    fn test_linearizable() {
+        const THREADS: usize = 4;
+        const OPS: usize = 24;
+        const KEYS: usize = 4;
+
+        for round in 0..100 {
+            let list = Arc::new(LockCouplingLinkedList::new());
+            let clock = Arc::new(AtomicU64::new(0));
+            let barrier = Arc::new(Barrier::new(THREADS));
+            // Threads must all be spawned before joining any of them.
+            #[allow(clippy::needless_collect)]
+            let handles: Vec<_> = (0..THREADS)
+                .map(|id| {
+                    let list = Arc::clone(&list);
+                    let clock = Arc::clone(&clock);
+                    let barrier = Arc::clone(&barrier);
+                    thread::spawn(move || {
+                        let mut random = Random::new(round * THREADS + id + 1);
+                        let mut events = Vec::new();
+                        barrier.wait();
+                        for _ in 0..OPS {
+                            let key = random.next() % KEYS;
+                            let op = match random.next() % 3 {
+                                0 => Op::Insert,
+                                1 => Op::Remove,
+                                _ => Op::Contains,
+                            };
+                            let start = clock.fetch_add(1, Ordering::SeqCst);
+                            let result = match op {
+                                Op::Insert => list.insert(key),
+                                Op::Remove => list.remove(&key),
+                                Op::Contains => list.contains(&key),
+                            };
+                            let end = clock.fetch_add(1, Ordering::SeqCst);
+                            events.push(Event {
+                                op,
+                                key,
+                                result,
+                                start,
+                                end,
+                            });
+                        }
+                        events
+                    })
+                })
+                .collect();
+
+            let events: Vec<Event> = handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect();
+            // Keys are independent objects, and linearizability is compositional.
+            for key in 0..KEYS {
+                let history: Vec<Event> = events.iter().copied().filter(|e| e.key == key).collect();
+                assert!(history.len() <= 128);
+                assert!(
+                    linearizable(&history, 0, false, &mut HashSet::new()),
+                    "round {round}, key {key}: {history:?}"
+                );
+            }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_checker_rejects_invalid_history() {
+        // Two sequential successful insertions of the same key are impossible.
+        let history = [
+            Event {
+                op: Op::Insert,
+                key: 0,
+                result: true,
+                start: 0,
+                end: 1,
+            },
+            Event {
+                op: Op::Insert,
+                key: 0,
+                result: true,
+                start: 2,
+                end: 3,
+            },
+        ];
+        assert!(!linearizable(&history, 0, false, &mut HashSet::new()));
+        // But they are fine if there is a concurrent removal.
+        let remove = Event {
+            op: Op::Remove,
+            key: 0,
+            result: true,
+            start: 1,
+            end: 4,
+        };
+        assert!(linearizable(
+            &[history[0], history[1], remove],
+            0,
+            false,
+            &mut HashSet::new()
+        ));
+    }
+}
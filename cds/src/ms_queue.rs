@@ -0,0 +1,283 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Michael-Scott queue, a lock-free MPMC queue.
+//!
+//! The queue always contains a dummy node at head. Dequeuing moves head to
+//! the next node, which becomes the new dummy after its value is taken out.
+//! Tail may lag behind by one node, and any thread which sees this helps to
+//! move it forward.
+//!
+//! Dequeued nodes are reclaimed with hazard pointers.
+
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::hazard::Domain;
+
+pub struct MsQueue<T> {
+    head: AtomicPtr<Node<T>>,
+    tail: AtomicPtr<Node<T>>,
+    domain: Domain,
+}
+
+struct Node<T> {
+    /// Uninitialized in the dummy node.
+    value: MaybeUninit<T>,
+    next: AtomicPtr<Self>,
+}
+
+unsafe impl<T: Send> Send for MsQueue<T> {}
+unsafe impl<T: Send> Sync for MsQueue<T> {}
+
+impl<T> Default for MsQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> MsQueue<T> {
+    /// Create an empty queue.
+    #[must_use]
+    pub fn new() -> Self {
+        let dummy = Node::new_ptr(MaybeUninit::uninit());
+        Self {
+            head: AtomicPtr::new(dummy),
+            tail: AtomicPtr::new(dummy),
+            domain: Domain::new(),
+        }
+    }
+
+    /// Check whether the queue is empty.
+    ///
+    /// The result may be outdated if other threads are modifying the queue.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        let hazard = self.domain.hazard_pointer();
+        let head = hazard.protect(&self.head);
+        unsafe { (*head).next.load(Ordering::Acquire).is_null() }
+    }
+
+    /// Add an element to the end of queue.
+    pub fn push(&self, value: T) {
+        let node = Node::new_ptr(MaybeUninit::new(value));
+        let hazard = self.domain.hazard_pointer();
+        loop {
+            let tail = hazard.protect(&self.tail);
+            let next = unsafe { (*tail).next.load(Ordering::Acquire) };
+            if tail != self.tail.load(Ordering::Acquire) {
+                continue;
+            }
+            if next.is_null() {
+                let linked = unsafe {
+                    (*tail).next.compare_exchange(
+                        ptr::null_mut(),
+                        node,
+                        Ordering::SeqCst,
+                        Ordering::Relaxed,
+                    )
+                };
+                if linked.is_ok() {
+                    // It is fine to fail, another thread has moved tail forward.
+                    let _ =
+                        self.tail
+                            .compare_exchange(tail, node, Ordering::SeqCst, Ordering::Relaxed);
+                    return;
+                }
+            } else {
+                // Tail is lagging behind, help to move it forward.
+                let _ = self
+                    .tail
+                    .compare_exchange(tail, next, Ordering::SeqCst, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Remove the first element of queue and returns it.
+    pub fn pop(&self) -> Option<T> {
+        let head_hazard = self.domain.hazard_pointer();
+        let next_hazard = self.domain.hazard_pointer();
+        loop {
+            let head = head_hazard.protect(&self.head);
+            let next = next_hazard.protect(unsafe { &(*head).next });
+            // `next` is only safe to use if head is unchanged after it was protected.
+            if head != self.head.load(Ordering::SeqCst) {
+                continue;
+            }
+            if next.is_null() {
+                return None;
+            }
+            let tail = self.tail.load(Ordering::Acquire);
+            if head == tail {
+                // Tail is lagging behind, move it forward before head passes it.
+                let _ = self
+                    .tail
+                    .compare_exchange(tail, next, Ordering::SeqCst, Ordering::Relaxed);
+                continue;
+            }
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::SeqCst, Ordering::Relaxed)
+                .is_ok()
+            {
+                // `next` becomes the new dummy node, and this thread owns its value.
+                let value = unsafe { (*next).value.assume_init_read() };
+                drop(head_hazard);
+                drop(next_hazard);
+                // SAFETY: the old dummy node is unlinked, as `Domain::retire` requires.
+                unsafe {
+                    self.domain.retire(head);
+                }
+                return Some(value);
+            }
+        }
+    }
+}
+
+impl<T> Drop for MsQueue<T> {
+    fn drop(&mut self) {
+        let dummy = unsafe { Box::from_raw(*self.head.get_mut()) };
+        let mut node = dummy.next.into_inner();
+        while !node.is_null() {
+            let mut boxed = unsafe { Box::from_raw(node) };
+            unsafe {
+                boxed.value.assume_init_drop();
+            }
+            node = boxed.next.into_inner();
+        }
+    }
+}
+
+impl<T> fmt::Debug for MsQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MsQueue")
+            .field("is_empty", &self.is_empty())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Node<T> {
+    fn new_ptr(value: MaybeUninit<T>) -> *mut Self {
+        Box::into_raw(Box::new(Self {
+            value,
+            next: AtomicPtr::new(ptr::null_mut()),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    use super::MsQueue;
+
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let queue = MsQueue::new();
+        assert!(queue.is_empty());
+        assert_eq!(queue.pop(), None);
+        for i in 0..5 {
+            queue.push(i);
+        }
+        assert!(!queue.is_empty());
+        for i in 0..5 {
+            assert_eq!(queue.pop(), Some(i));
+        }
+        assert_eq!(queue.pop(), None);
+        queue.push(5);
+        assert_eq!(queue.pop(), Some(5));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_values_dropped_once() {
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let queue = MsQueue::new();
+        for _ in 0..500 {
+            queue.push(Counted(Arc::clone(&dropped)));
+        }
+        for _ in 0..300 {
+            drop(queue.pop());
+        }
+        assert_eq!(dropped.load(Ordering::SeqCst), 300);
+        drop(queue);
+        assert_eq!(dropped.load(Ordering::SeqCst), 500);
+    }
+
+    #[test]
+    fn test_mpmc() {
+        const PRODUCERS: usize = 4;
+        const CONSUMERS: usize = 4;
+        const PER_PRODUCER: usize = 20_000;
+
+        let queue = Arc::new(MsQueue::new());
+        let barrier = Arc::new(Barrier::new(PRODUCERS + CONSUMERS));
+        let consumed = Arc::new(AtomicUsize::new(0));
+
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|id| {
+                let queue = Arc::clone(&queue);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..PER_PRODUCER {
+                        queue.push((id, i));
+                    }
+                })
+            })
+            .collect();
+        // Threads must all be spawned before joining any of them.
+        #[allow(clippy::needless_collect)]
+        let consumers: Vec<_> = (0..CONSUMERS)
+            .map(|_| {
+                let queue = Arc::clone(&queue);
+                let barrier = Arc::clone(&barrier);
+                let consumed = Arc::clone(&consumed);
+                thread::spawn(move || {
+                    barrier.wait();
+                    let mut received = Vec::new();
+                    while consumed.load(Ordering::SeqCst) < PRODUCERS * PER_PRODUCER {
+                        if let Some(item) = queue.pop() {
+                            consumed.fetch_add(1, Ordering::SeqCst);
+                            received.push(item);
+                        }
+                    }
+                    received
+                })
+            })
+            .collect();
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let mut all = vec![Vec::new(); PRODUCERS];
+        for consumer in consumers {
+            let received = consumer.join().unwrap();
+            // Items from the same producer are received in FIFO order.
+            let mut last = [None; PRODUCERS];
+            for (id, i) in received {
+                assert!(last[id] < Some(i));
+                last[id] = Some(i);
+                all[id].push(i);
+            }
+        }
+        for mut items in all {
+            items.sort_unstable();
+            assert_eq!(items, (0..PER_PRODUCER).collect::<Vec<_>>());
+        }
+        assert!(queue.is_empty());
+    }
+}
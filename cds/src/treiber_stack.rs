@@ -0,0 +1,212 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Treiber stack, a lock-free stack.
+//!
+//! The top of stack is an atomic pointer, which is updated with CAS.
+//! Popped nodes are reclaimed with hazard pointers, which also prevents
+//! the ABA problem as a protected node can not be freed and reused.
+
+use std::fmt;
+use std::mem::ManuallyDrop;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::hazard::Domain;
+
+pub struct TreiberStack<T> {
+    head: AtomicPtr<Node<T>>,
+    domain: Domain,
+}
+
+struct Node<T> {
+    /// Moved out by the thread which pops this node.
+    value: ManuallyDrop<T>,
+    /// Never changes after the node is pushed.
+    next: *mut Self,
+}
+
+unsafe impl<T: Send> Send for TreiberStack<T> {}
+unsafe impl<T: Send> Sync for TreiberStack<T> {}
+
+impl<T> Default for TreiberStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> TreiberStack<T> {
+    /// Create an empty stack.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            head: AtomicPtr::new(ptr::null_mut()),
+            domain: Domain::new(),
+        }
+    }
+
+    /// Check whether the stack is empty.
+    ///
+    /// The result may be outdated if other threads are modifying the stack.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire).is_null()
+    }
+
+    /// Push an element to top of stack.
+    pub fn push(&self, value: T) {
+        let node = Box::into_raw(Box::new(Node {
+            value: ManuallyDrop::new(value),
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            unsafe {
+                (*node).next = head;
+            }
+            match self
+                .head
+                .compare_exchange_weak(head, node, Ordering::SeqCst, Ordering::Relaxed)
+            {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// Remove the top element of stack and returns it.
+    pub fn pop(&self) -> Option<T> {
+        let hazard = self.domain.hazard_pointer();
+        loop {
+            let head = hazard.protect(&self.head);
+            if head.is_null() {
+                return None;
+            }
+            // `head` is protected, so reading its fields is safe.
+            let next = unsafe { (*head).next };
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::SeqCst, Ordering::Relaxed)
+                .is_ok()
+            {
+                // Only this thread unlinked the node, so it owns the value.
+                let value = unsafe { ManuallyDrop::take(&mut (*head).value) };
+                drop(hazard);
+                // SAFETY: the popped node is unlinked, as `Domain::retire` requires.
+                unsafe {
+                    self.domain.retire(head);
+                }
+                return Some(value);
+            }
+        }
+    }
+}
+
+impl<T> Drop for TreiberStack<T> {
+    fn drop(&mut self) {
+        let mut node = *self.head.get_mut();
+        while !node.is_null() {
+            let mut boxed = unsafe { Box::from_raw(node) };
+            unsafe {
+                ManuallyDrop::drop(&mut boxed.value);
+            }
+            node = boxed.next;
+        }
+    }
+}
+
+impl<T> fmt::Debug for TreiberStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TreiberStack")
+            .field("is_empty", &self.is_empty())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    use super::TreiberStack;
+
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let stack = TreiberStack::new();
+        assert!(stack.is_empty());
+        assert_eq!(stack.pop(), None);
+        for i in 0..5 {
+            stack.push(i);
+        }
+        assert!(!stack.is_empty());
+        for i in (0..5).rev() {
+            assert_eq!(stack.pop(), Some(i));
+        }
+        assert_eq!(stack.pop(), None);
+    }
+
+    #[test]
+    fn test_values_dropped_once() {
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let stack = TreiberStack::new();
+        for _ in 0..500 {
+            stack.push(Counted(Arc::clone(&dropped)));
+        }
+        for _ in 0..300 {
+            drop(stack.pop());
+        }
+        assert_eq!(dropped.load(Ordering::SeqCst), 300);
+        drop(stack);
+        assert_eq!(dropped.load(Ordering::SeqCst), 500);
+    }
+
+    #[test]
+    fn test_concurrent() {
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 10_000;
+
+        let stack = Arc::new(TreiberStack::new());
+        let barrier = Arc::new(Barrier::new(THREADS));
+        // Every thread pushes its own values and pops whatever it can.
+        #[allow(clippy::needless_collect)]
+        let handles: Vec<_> = (0..THREADS)
+            .map(|id| {
+                let stack = Arc::clone(&stack);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    let mut popped = Vec::new();
+                    for i in 0..PER_THREAD {
+                        stack.push(id * PER_THREAD + i);
+                        if i % 2 == 0 {
+                            popped.extend(stack.pop());
+                        }
+                    }
+                    popped
+                })
+            })
+            .collect();
+
+        let mut seen = HashSet::new();
+        for handle in handles {
+            for value in handle.join().unwrap() {
+                assert!(seen.insert(value), "{value} popped twice");
+            }
+        }
+        while let Some(value) = stack.pop() {
+            assert!(seen.insert(value), "{value} popped twice");
+        }
+        assert_eq!(seen.len(), THREADS * PER_THREAD);
+    }
+}
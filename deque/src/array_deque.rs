@@ -0,0 +1,891 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 基于可增长的环形缓冲区实现的双端队列, 接口与 `VecDeque` 兼容.
+//!
+//! 元素存放在 `buf[head..]` 以及回绕后的 `buf[..]` 中, 逻辑下标 `index`
+//! 对应的物理下标是 `(head + index) % capacity`. 缓冲区已满时容量翻倍,
+//! 同时把元素重新排列到新缓冲区的开头.
+
+use std::fmt;
+use std::iter::FusedIterator;
+use std::mem::MaybeUninit;
+use std::ops::{Bound, Index, IndexMut, Range, RangeBounds};
+use std::slice;
+
+/// 第一次分配内存时的最小容量.
+const MIN_CAPACITY: usize = 4;
+
+pub struct ArrayDeque<T> {
+    buf: Box<[MaybeUninit<T>]>,
+    head: usize,
+    len: usize,
+}
+
+impl<T> Default for ArrayDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ArrayDeque<T> {
+    /// 创建一个空的队列, 不分配内存.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// 创建一个空的队列, 并预留 `capacity` 个元素的空间.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buf: Box::new_uninit_slice(capacity),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 把逻辑下标转换为物理下标.
+    #[inline]
+    fn to_physical(&self, index: usize) -> usize {
+        let index = self.head + index;
+        if index >= self.capacity() {
+            index - self.capacity()
+        } else {
+            index
+        }
+    }
+
+    /// 返回 `head` 前面的一个物理下标.
+    #[inline]
+    fn wrap_sub_one(&self, index: usize) -> usize {
+        if index == 0 {
+            self.capacity() - 1
+        } else {
+            index - 1
+        }
+    }
+
+    /// 返回前后两个连续区间的物理下标范围.
+    fn slice_ranges(&self) -> (Range<usize>, Range<usize>) {
+        let tail_room = self.capacity() - self.head;
+        if self.len <= tail_room {
+            (self.head..self.head + self.len, 0..0)
+        } else {
+            (self.head..self.capacity(), 0..self.len - tail_room)
+        }
+    }
+
+    /// 把元素移到容量为 `new_cap` 的新缓冲区的开头.
+    fn realloc(&mut self, new_cap: usize) {
+        debug_assert!(new_cap >= self.len);
+        let mut buf = Box::new_uninit_slice(new_cap);
+        let (front, back) = self.slice_ranges();
+        let front_len = front.len();
+        let back_len = back.len();
+        buf[..front_len].swap_with_slice(&mut self.buf[front]);
+        buf[front_len..front_len + back_len].swap_with_slice(&mut self.buf[back]);
+        self.buf = buf;
+        self.head = 0;
+    }
+
+    fn grow(&mut self) {
+        let new_cap = (self.capacity() * 2).max(MIN_CAPACITY);
+        self.realloc(new_cap);
+    }
+
+    /// 确保至少还能再存放 `additional` 个元素.
+    ///
+    /// # Panics
+    ///
+    /// 新的容量超出 `usize` 时 panic.
+    pub fn reserve(&mut self, additional: usize) {
+        let needed = self.len.checked_add(additional).expect("capacity overflow");
+        if needed > self.capacity() {
+            self.realloc(needed.max(self.capacity() * 2));
+        }
+    }
+
+    /// 释放多余的内存, 使容量等于元素个数.
+    pub fn shrink_to_fit(&mut self) {
+        if self.capacity() > self.len {
+            self.realloc(self.len);
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            let index = self.to_physical(index);
+            Some(unsafe { self.buf[index].assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            let index = self.to_physical(index);
+            Some(unsafe { self.buf[index].assume_init_mut() })
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn back(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.len
+            .checked_sub(1)
+            .and_then(|index| self.get_mut(index))
+    }
+
+    /// 在队尾添加元素.
+    pub fn push_back(&mut self, value: T) {
+        if self.len == self.capacity() {
+            self.grow();
+        }
+        let index = self.to_physical(self.len);
+        self.buf[index].write(value);
+        self.len += 1;
+    }
+
+    /// 在队首添加元素.
+    pub fn push_front(&mut self, value: T) {
+        if self.len == self.capacity() {
+            self.grow();
+        }
+        self.head = self.wrap_sub_one(self.head);
+        self.buf[self.head].write(value);
+        self.len += 1;
+    }
+
+    /// 移除队首的元素, 如果队列为空, 就返回 `None`.
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let value = unsafe { self.buf[self.head].assume_init_read() };
+        self.head = self.to_physical(1);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// 移除队尾的元素, 如果队列为空, 就返回 `None`.
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.len -= 1;
+        let index = self.to_physical(self.len);
+        Some(unsafe { self.buf[index].assume_init_read() })
+    }
+
+    /// 在 `index` 处插入元素, 只移动距离较近的一端的元素.
+    ///
+    /// # Panics
+    ///
+    /// `index` 大于队列长度时 panic.
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.len, "index out of bounds");
+        if self.len == self.capacity() {
+            self.grow();
+        }
+        if index < self.len - index {
+            // 前面的元素向前移动一位, 空位从下标 0 移到 `index`.
+            self.head = self.wrap_sub_one(self.head);
+            for i in 0..index {
+                let (a, b) = (self.to_physical(i), self.to_physical(i + 1));
+                self.buf.swap(a, b);
+            }
+        } else {
+            // 后面的元素向后移动一位, 空位从下标 `len` 移到 `index`.
+            for i in (index..self.len).rev() {
+                let (a, b) = (self.to_physical(i), self.to_physical(i + 1));
+                self.buf.swap(a, b);
+            }
+        }
+        let index = self.to_physical(index);
+        self.buf[index].write(value);
+        self.len += 1;
+    }
+
+    /// 移除 `index` 处的元素, 只移动距离较近的一端的元素.
+    ///
+    /// 如果 `index` 越界, 就返回 `None`.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+        let physical = self.to_physical(index);
+        let value = unsafe { self.buf[physical].assume_init_read() };
+        if index < self.len - 1 - index {
+            // 前面的元素向后移动一位, 空位移到队首.
+            for i in (0..index).rev() {
+                let (a, b) = (self.to_physical(i), self.to_physical(i + 1));
+                self.buf.swap(a, b);
+            }
+            self.head = self.to_physical(1);
+        } else {
+            // 后面的元素向前移动一位, 空位移到队尾.
+            for i in index + 1..self.len {
+                let (a, b) = (self.to_physical(i - 1), self.to_physical(i));
+                self.buf.swap(a, b);
+            }
+        }
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// 交换下标 `i` 和 `j` 处的元素.
+    ///
+    /// # Panics
+    ///
+    /// 任意一个下标越界时 panic.
+    pub fn swap(&mut self, i: usize, j: usize) {
+        assert!(i < self.len && j < self.len, "index out of bounds");
+        let (i, j) = (self.to_physical(i), self.to_physical(j));
+        self.buf.swap(i, j);
+    }
+
+    /// 只保留前 `len` 个元素.
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            drop(self.pop_back());
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+        self.head = 0;
+    }
+
+    #[must_use]
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|item| item == value)
+    }
+
+    /// 向左旋转 `n` 个位置, 使下标 `n` 处的元素成为队首.
+    ///
+    /// 只移动 `min(n, len - n)` 个元素.
+    ///
+    /// # Panics
+    ///
+    /// `n` 大于队列长度时 panic.
+    pub fn rotate_left(&mut self, n: usize) {
+        assert!(n <= self.len, "n is greater than len");
+        let k = self.len - n;
+        if n <= k {
+            self.rotate_left_inner(n);
+        } else {
+            self.rotate_right_inner(k);
+        }
+    }
+
+    /// 向右旋转 `n` 个位置, 使下标 `len - n` 处的元素成为队首.
+    ///
+    /// 只移动 `min(n, len - n)` 个元素.
+    ///
+    /// # Panics
+    ///
+    /// `n` 大于队列长度时 panic.
+    pub fn rotate_right(&mut self, n: usize) {
+        assert!(n <= self.len, "n is greater than len");
+        let k = self.len - n;
+        if n <= k {
+            self.rotate_right_inner(n);
+        } else {
+            self.rotate_left_inner(k);
+        }
+    }
+
+    /// 把队首的 `n` 个元素逐个移到队尾.
+    fn rotate_left_inner(&mut self, n: usize) {
+        for _ in 0..n {
+            // 队列已满时两个下标相同, 只需要移动 `head`.
+            let tail = self.to_physical(self.len);
+            self.buf.swap(self.head, tail);
+            self.head = self.to_physical(1);
+        }
+    }
+
+    /// 把队尾的 `n` 个元素逐个移到队首.
+    fn rotate_right_inner(&mut self, n: usize) {
+        for _ in 0..n {
+            let last = self.to_physical(self.len - 1);
+            self.head = self.wrap_sub_one(self.head);
+            self.buf.swap(last, self.head);
+        }
+    }
+
+    /// 返回两个连续的切片, 依次连接起来就是队列中的全部元素.
+    #[must_use]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (front, back) = self.slice_ranges();
+        unsafe {
+            (
+                assume_init_slice(&self.buf[front]),
+                assume_init_slice(&self.buf[back]),
+            )
+        }
+    }
+
+    #[must_use]
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (front, back) = self.slice_ranges();
+        let front_len = front.len();
+        let (left, right) = self.buf.split_at_mut(self.head);
+        unsafe {
+            (
+                assume_init_slice_mut(&mut right[..front_len]),
+                assume_init_slice_mut(&mut left[back]),
+            )
+        }
+    }
+
+    /// 重新排列元素, 使它们在内存中是连续的, 并返回这个切片.
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        if self.head + self.len > self.capacity() {
+            self.buf.rotate_left(self.head);
+            self.head = 0;
+        }
+        let range = self.head..self.head + self.len;
+        unsafe { assume_init_slice_mut(&mut self.buf[range]) }
+    }
+
+    /// 移除 `range` 范围内的元素, 并返回包含这些元素的迭代器.
+    ///
+    /// 迭代器被释放时, 剩下的元素会被移除, 并且只移动较短一侧的元素来填补空缺.
+    ///
+    /// # Panics
+    ///
+    /// 范围的起点大于终点, 或者终点大于队列长度时 panic.
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len,
+        };
+        assert!(start <= end, "drain start is greater than end");
+        assert!(end <= self.len, "drain end is out of bounds");
+
+        let orig_len = self.len;
+        // 如果 `Drain` 被 `mem::forget()`, 后面的元素会被泄露, 但不会被重复释放.
+        self.len = start;
+        Drain {
+            deque: self,
+            start,
+            end,
+            front: start,
+            back: end,
+            orig_len,
+        }
+    }
+
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (front, back) = self.as_slices();
+        Iter {
+            front: front.iter(),
+            back: back.iter(),
+        }
+    }
+
+    #[must_use]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (front, back) = self.as_mut_slices();
+        IterMut {
+            front: front.iter_mut(),
+            back: back.iter_mut(),
+        }
+    }
+}
+
+const unsafe fn assume_init_slice<T>(slice: &[MaybeUninit<T>]) -> &[T] {
+    slice::from_raw_parts(slice.as_ptr().cast(), slice.len())
+}
+
+const unsafe fn assume_init_slice_mut<T>(slice: &mut [MaybeUninit<T>]) -> &mut [T] {
+    slice::from_raw_parts_mut(slice.as_mut_ptr().cast(), slice.len())
+}
+
+impl<T> Drop for ArrayDeque<T> {
+    fn drop(&mut self) {
+        let (front, back) = self.as_mut_slices();
+        unsafe {
+            std::ptr::drop_in_place(front);
+            std::ptr::drop_in_place(back);
+        }
+    }
+}
+
+impl<T> Index<usize> for ArrayDeque<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T> IndexMut<usize> for ArrayDeque<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+impl<T: Clone> Clone for ArrayDeque<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: PartialEq> PartialEq for ArrayDeque<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for ArrayDeque<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for ArrayDeque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> FromIterator<T> for ArrayDeque<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut deque = Self::new();
+        deque.extend(iter);
+        deque
+    }
+}
+
+impl<T> Extend<T> for ArrayDeque<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for ArrayDeque<T> {
+    fn from(vec: Vec<T>) -> Self {
+        vec.into_iter().collect()
+    }
+}
+
+pub struct Iter<'a, T> {
+    front: slice::Iter<'a, T>,
+    back: slice::Iter<'a, T>,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            front: self.front.clone(),
+            back: self.back.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    front: slice::IterMut<'a, T>,
+    back: slice::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+pub struct IntoIter<T> {
+    deque: ArrayDeque<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.deque.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.deque.len, Some(self.deque.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.deque.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for ArrayDeque<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { deque: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArrayDeque<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut ArrayDeque<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// `ArrayDeque::drain()` 返回的迭代器.
+pub struct Drain<'a, T> {
+    deque: &'a mut ArrayDeque<T>,
+    /// 被移除的范围是 `start..end`.
+    start: usize,
+    end: usize,
+    /// 还没有被迭代的元素是 `front..back`.
+    front: usize,
+    back: usize,
+    orig_len: usize,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        let index = self.deque.to_physical(self.front);
+        self.front += 1;
+        Some(unsafe { self.deque.buf[index].assume_init_read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        let index = self.deque.to_physical(self.back);
+        Some(unsafe { self.deque.buf[index].assume_init_read() })
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+
+        let drained = self.end - self.start;
+        let tail_len = self.orig_len - self.end;
+        let deque = &mut *self.deque;
+        if self.start < tail_len {
+            // 前面的元素向后移动, 填补空缺.
+            for i in (0..self.start).rev() {
+                let (a, b) = (deque.to_physical(i), deque.to_physical(i + drained));
+                deque.buf.swap(a, b);
+            }
+            deque.head = deque.to_physical(drained);
+        } else {
+            // 后面的元素向前移动, 填补空缺.
+            for i in 0..tail_len {
+                let (a, b) = (
+                    deque.to_physical(self.start + i),
+                    deque.to_physical(self.end + i),
+                );
+                deque.buf.swap(a, b);
+            }
+        }
+        deque.len = self.orig_len - drained;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::ArrayDeque;
+
+    fn assert_same(deque: &ArrayDeque<i32>, expected: &VecDeque<i32>) {
+        assert_eq!(deque.len(), expected.len());
+        assert!(deque.iter().eq(expected.iter()));
+        assert!(deque.iter().rev().eq(expected.iter().rev()));
+        let (front, back) = deque.as_slices();
+        assert_eq!(front.len() + back.len(), deque.len());
+        assert!(front.iter().chain(back).eq(expected.iter()));
+        assert_eq!(deque.front(), expected.front());
+        assert_eq!(deque.back(), expected.back());
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut deque = ArrayDeque::new();
+        assert_eq!(deque.capacity(), 0);
+        assert_eq!(deque.pop_front(), None);
+        assert_eq!(deque.pop_back(), None);
+        for i in 0..10 {
+            deque.push_back(i);
+            deque.push_front(-i);
+        }
+        assert_eq!(deque.len(), 20);
+        assert_eq!(deque[0], -9);
+        assert_eq!(deque[19], 9);
+        assert_eq!(deque.pop_front(), Some(-9));
+        assert_eq!(deque.pop_back(), Some(9));
+        deque[0] = 100;
+        assert_eq!(deque.front(), Some(&100));
+        assert_eq!(deque.get(18), None);
+    }
+
+    #[test]
+    fn test_rotate_and_contiguous() {
+        let mut deque: ArrayDeque<i32> = (0..8).collect();
+        // 让元素回绕到缓冲区的开头.
+        deque.rotate_right(3);
+        assert!(deque.iter().copied().eq([5, 6, 7, 0, 1, 2, 3, 4]));
+        deque.rotate_left(6);
+        assert!(deque.iter().copied().eq([3, 4, 5, 6, 7, 0, 1, 2]));
+
+        deque.pop_back();
+        deque.push_front(2);
+        assert!(!deque.as_slices().1.is_empty());
+        assert_eq!(deque.make_contiguous(), &[2, 3, 4, 5, 6, 7, 0, 1]);
+        assert!(deque.as_slices().1.is_empty());
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut deque: ArrayDeque<i32> = (0..10).collect();
+        deque.rotate_left(4);
+        let drained: Vec<_> = deque.drain(2..5).collect();
+        assert_eq!(drained, [6, 7, 8]);
+        assert!(deque.iter().copied().eq([4, 5, 9, 0, 1, 2, 3]));
+
+        let mut drain = deque.drain(4..);
+        assert_eq!(drain.next_back(), Some(3));
+        assert_eq!(drain.next(), Some(1));
+        drop(drain);
+        assert!(deque.iter().copied().eq([4, 5, 9, 0]));
+
+        assert_eq!(deque.drain(..).len(), 4);
+        assert!(deque.is_empty());
+    }
+
+    #[test]
+    fn test_values_dropped_once() {
+        let value = Rc::new(());
+        {
+            let mut deque = ArrayDeque::new();
+            for _ in 0..20 {
+                deque.push_front(Rc::clone(&value));
+            }
+            deque.truncate(15);
+            drop(deque.drain(3..7));
+            deque.rotate_left(4);
+            assert_eq!(Rc::strong_count(&value), 12);
+            let mut iter = deque.into_iter();
+            drop(iter.next());
+            assert_eq!(Rc::strong_count(&value), 11);
+        }
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn test_random_against_vec_deque() {
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut deque = ArrayDeque::new();
+            let mut expected = VecDeque::new();
+            for _ in 0..2000 {
+                let len = expected.len();
+                match rng.gen_range(0..14) {
+                    0 | 1 => {
+                        let value = rng.gen();
+                        deque.push_back(value);
+                        expected.push_back(value);
+                    }
+                    2 | 3 => {
+                        let value = rng.gen();
+                        deque.push_front(value);
+                        expected.push_front(value);
+                    }
+                    4 => assert_eq!(deque.pop_front(), expected.pop_front()),
+                    5 => assert_eq!(deque.pop_back(), expected.pop_back()),
+                    6 => {
+                        let index = rng.gen_range(0..=len);
+                        let value = rng.gen();
+                        deque.insert(index, value);
+                        expected.insert(index, value);
+                    }
+                    7 => {
+                        let index = rng.gen_range(0..=len);
+                        assert_eq!(deque.remove(index), expected.remove(index));
+                    }
+                    8 => {
+                        let n = rng.gen_range(0..=len);
+                        deque.rotate_left(n);
+                        expected.rotate_left(n);
+                    }
+                    9 => {
+                        let n = rng.gen_range(0..=len);
+                        deque.rotate_right(n);
+                        expected.rotate_right(n);
+                    }
+                    10 if len > 0 => {
+                        let i = rng.gen_range(0..len);
+                        let j = rng.gen_range(0..len);
+                        deque.swap(i, j);
+                        expected.swap(i, j);
+                    }
+                    11 => {
+                        let start = rng.gen_range(0..=len);
+                        let end = rng.gen_range(start..=len);
+                        assert!(deque.drain(start..end).eq(expected.drain(start..end)));
+                    }
+                    12 => {
+                        assert_eq!(deque.make_contiguous(), expected.make_contiguous());
+                    }
+                    _ => {
+                        if rng.gen_bool(0.5) {
+                            deque.shrink_to_fit();
+                        } else {
+                            deque.reserve(rng.gen_range(0..8));
+                        }
+                    }
+                }
+                assert_same(&deque, &expected);
+                if len > 0 {
+                    let index = rng.gen_range(0..len.min(expected.len()).max(1));
+                    assert_eq!(deque.get(index), expected.get(index));
+                }
+            }
+            assert!(deque
+                .clone()
+                .into_iter()
+                .rev()
+                .eq(expected.into_iter().rev()));
+        }
+    }
+}
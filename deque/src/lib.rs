@@ -9,3 +9,7 @@
     clippy::nursery,
     clippy::pedantic
 )]
+
+pub mod array_deque;
+
+pub use array_deque::ArrayDeque;
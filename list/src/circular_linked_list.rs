@@ -0,0 +1,677 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! Doubly circular linked list.
+//!
+//! The last node links back to the first one, so only the head pointer
+//! is stored, and the tail is always `head.prev`.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use crate::traits::List;
+
+pub struct CircularDoublyLinkedList<T> {
+    head: NodePtr<T>,
+    len: usize,
+    _marker: PhantomData<Box<Node<T>>>,
+}
+
+type NodePtr<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
+    prev: NonNull<Self>,
+    next: NonNull<Self>,
+    value: T,
+}
+
+pub struct IntoIter<T>(CircularDoublyLinkedList<T>);
+
+pub struct Iter<'a, T: 'a> {
+    head: NodePtr<T>,
+    tail: NodePtr<T>,
+    len: usize,
+    _marker: PhantomData<&'a Node<T>>,
+}
+
+pub struct IterMut<'a, T: 'a> {
+    head: NodePtr<T>,
+    tail: NodePtr<T>,
+    len: usize,
+    _marker: PhantomData<&'a mut Node<T>>,
+}
+
+// Public functions for list.
+impl<T> CircularDoublyLinkedList<T> {
+    /// Create an empty list.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            head: None,
+            len: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements in list.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether the list is empty.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Access the first node.
+    #[must_use]
+    pub fn front(&self) -> Option<&T> {
+        self.head.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// Access the first node exclusively.
+    #[must_use]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.head.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Access the last node.
+    #[must_use]
+    pub fn back(&self) -> Option<&T> {
+        self.tail().map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// Access the last node exclusively.
+    #[must_use]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.tail()
+            .map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Access the element at `pos`.
+    ///
+    /// Time is O(min(pos, len - pos)).
+    #[must_use]
+    pub fn get(&self, pos: usize) -> Option<&T> {
+        self.node_at(pos)
+            .map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// Access the element at `pos` exclusively.
+    #[must_use]
+    pub fn get_mut(&mut self, pos: usize) -> Option<&mut T> {
+        self.node_at(pos)
+            .map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq<T>,
+    {
+        self.iter().any(|item| item == value)
+    }
+
+    #[must_use]
+    pub const fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head: self.head,
+            tail: self.tail(),
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+
+    #[must_use]
+    pub const fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            head: self.head,
+            tail: self.tail(),
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Remove all elements.
+    pub fn clear(&mut self) {
+        while self.pop_front_node().is_some() {
+            // dropped
+        }
+    }
+
+    /// Add an element to the beginning of list.
+    pub fn push_front(&mut self, value: T) {
+        self.push_back(value);
+        // The new tail is the element just before head.
+        self.head = self.tail();
+    }
+
+    /// Add an element to the end of list.
+    pub fn push_back(&mut self, value: T) {
+        let node = Node::new_ptr(value);
+        match self.head {
+            Some(head) => unsafe { Self::insert_before(head, node) },
+            None => self.head = Some(node),
+        }
+        self.len += 1;
+    }
+
+    /// Remove the first node in the list.
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.pop_front_node().map(Node::into_inner)
+    }
+
+    /// Remove the last node in the list.
+    pub fn pop_back(&mut self) -> Option<T> {
+        let tail = self.tail()?;
+        Some(self.unlink(tail).into_inner())
+    }
+
+    /// Insert element at `pos`.
+    ///
+    /// # Panics
+    ///
+    /// Panic if `pos > len`.
+    pub fn insert_at(&mut self, pos: usize, value: T) {
+        assert!(pos <= self.len);
+        if pos == self.len {
+            self.push_back(value);
+            return;
+        }
+        if let Some(next) = self.node_at(pos) {
+            let node = Node::new_ptr(value);
+            unsafe {
+                Self::insert_before(next, node);
+            }
+            if pos == 0 {
+                self.head = Some(node);
+            }
+            self.len += 1;
+        }
+    }
+
+    /// Remove element at `pos` and returns that element.
+    ///
+    /// # Panics
+    ///
+    /// Raise panic if `pos >= len`.
+    pub fn pop_at(&mut self, pos: usize) -> Option<T> {
+        assert!(pos < self.len);
+        let node = self.node_at(pos)?;
+        Some(self.unlink(node).into_inner())
+    }
+
+    /// Rotate the list `n` places to the left, the element at `n % len`
+    /// becomes the first one.
+    ///
+    /// Only the head pointer moves, time is O(min(n % len, len - n % len)).
+    pub fn rotate_left(&mut self, n: usize) {
+        if self.len > 0 {
+            self.head = self.node_at(n % self.len);
+        }
+    }
+
+    /// Rotate the list `n` places to the right, the last `n % len` elements
+    /// are moved to the front.
+    pub fn rotate_right(&mut self, n: usize) {
+        if self.len > 0 {
+            let n = n % self.len;
+            self.rotate_left(self.len - n);
+        }
+    }
+
+    /// Move all elements of `other` to the end of list.
+    ///
+    /// Time is O(1).
+    pub fn append(&mut self, other: &mut Self) {
+        let Some(other_head) = other.head.take() else {
+            return;
+        };
+        match self.head {
+            Some(head) => unsafe {
+                let tail = (*head.as_ptr()).prev;
+                let other_tail = (*other_head.as_ptr()).prev;
+                (*tail.as_ptr()).next = other_head;
+                (*other_head.as_ptr()).prev = tail;
+                (*other_tail.as_ptr()).next = head;
+                (*head.as_ptr()).prev = other_tail;
+            },
+            None => self.head = Some(other_head),
+        }
+        self.len += other.len;
+        other.len = 0;
+    }
+
+    /// Reverse orders of node in list.
+    pub fn reverse(&mut self) {
+        let Some(head) = self.head else {
+            return;
+        };
+        let mut node = head;
+        loop {
+            unsafe {
+                let node_ref = &mut *node.as_ptr();
+                std::mem::swap(&mut node_ref.prev, &mut node_ref.next);
+                node = node_ref.next;
+            }
+            if node == head {
+                break;
+            }
+        }
+        // The old tail becomes the new head.
+        self.head = Some(unsafe { (*head.as_ptr()).next });
+    }
+}
+
+// Private functions for list.
+impl<T> CircularDoublyLinkedList<T> {
+    const fn tail(&self) -> NodePtr<T> {
+        match self.head {
+            Some(head) => Some(unsafe { (*head.as_ptr()).prev }),
+            None => None,
+        }
+    }
+
+    /// Walk from head in the shorter direction.
+    fn node_at(&self, pos: usize) -> NodePtr<T> {
+        if pos >= self.len {
+            return None;
+        }
+        let mut node = self.head?;
+        unsafe {
+            if pos <= self.len / 2 {
+                for _ in 0..pos {
+                    node = (*node.as_ptr()).next;
+                }
+            } else {
+                for _ in pos..self.len {
+                    node = (*node.as_ptr()).prev;
+                }
+            }
+        }
+        Some(node)
+    }
+
+    /// Link `node` just before `next`.
+    const unsafe fn insert_before(next: NonNull<Node<T>>, node: NonNull<Node<T>>) {
+        let prev = (*next.as_ptr()).prev;
+        (*node.as_ptr()).prev = prev;
+        (*node.as_ptr()).next = next;
+        (*prev.as_ptr()).next = node;
+        (*next.as_ptr()).prev = node;
+    }
+
+    fn pop_front_node(&mut self) -> Option<Box<Node<T>>> {
+        let head = self.head?;
+        Some(self.unlink(head))
+    }
+
+    /// Unlink `node` which must belong to this list.
+    #[allow(clippy::unnecessary_box_returns)]
+    fn unlink(&mut self, node: NonNull<Node<T>>) -> Box<Node<T>> {
+        unsafe {
+            if self.len == 1 {
+                self.head = None;
+            } else {
+                let prev = (*node.as_ptr()).prev;
+                let next = (*node.as_ptr()).next;
+                (*prev.as_ptr()).next = next;
+                (*next.as_ptr()).prev = prev;
+                if self.head == Some(node) {
+                    self.head = Some(next);
+                }
+            }
+            self.len -= 1;
+            Node::from_ptr(node)
+        }
+    }
+}
+
+impl<T> List<T> for CircularDoublyLinkedList<T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn front(&self) -> Option<&T> {
+        Self::front(self)
+    }
+
+    fn front_mut(&mut self) -> Option<&mut T> {
+        Self::front_mut(self)
+    }
+
+    fn back(&self) -> Option<&T> {
+        Self::back(self)
+    }
+
+    fn back_mut(&mut self) -> Option<&mut T> {
+        Self::back_mut(self)
+    }
+
+    fn get(&self, pos: usize) -> Option<&T> {
+        Self::get(self, pos)
+    }
+
+    fn push_front(&mut self, value: T) {
+        Self::push_front(self, value);
+    }
+
+    fn push_back(&mut self, value: T) {
+        Self::push_back(self, value);
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        Self::pop_front(self)
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        Self::pop_back(self)
+    }
+
+    fn insert_at(&mut self, pos: usize, value: T) {
+        Self::insert_at(self, pos, value);
+    }
+
+    fn pop_at(&mut self, pos: usize) -> Option<T> {
+        Self::pop_at(self, pos)
+    }
+
+    fn clear(&mut self) {
+        Self::clear(self);
+    }
+}
+
+impl<T> Drop for CircularDoublyLinkedList<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Default for CircularDoublyLinkedList<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CircularDoublyLinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: Clone> Clone for CircularDoublyLinkedList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: PartialEq> PartialEq for CircularDoublyLinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for CircularDoublyLinkedList<T> {}
+
+impl<T> Extend<T> for CircularDoublyLinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| self.push_back(value));
+    }
+}
+
+impl<T> FromIterator<T> for CircularDoublyLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> IntoIterator for CircularDoublyLinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a CircularDoublyLinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut CircularDoublyLinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.head.map(|node| unsafe {
+            let node: &Node<T> = node.as_ref();
+            self.len -= 1;
+            self.head = Some(node.next);
+            &node.value
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.tail.map(|node| unsafe {
+            let node: &Node<T> = node.as_ref();
+            self.len -= 1;
+            self.tail = Some(node.prev);
+            &node.value
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.head.map(|node| unsafe {
+            let node: &mut Node<T> = &mut *node.as_ptr();
+            self.len -= 1;
+            self.head = Some(node.next);
+            &mut node.value
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.tail.map(|node| unsafe {
+            let node: &mut Node<T> = &mut *node.as_ptr();
+            self.len -= 1;
+            self.tail = Some(node.prev);
+            &mut node.value
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> Node<T> {
+    /// Create a node which links to itself.
+    #[must_use]
+    fn new_ptr(value: T) -> NonNull<Self> {
+        let node = Box::new(Self {
+            prev: NonNull::dangling(),
+            next: NonNull::dangling(),
+            value,
+        });
+        let ptr = NonNull::from(Box::leak(node));
+        unsafe {
+            (*ptr.as_ptr()).prev = ptr;
+            (*ptr.as_ptr()).next = ptr;
+        }
+        ptr
+    }
+
+    #[must_use]
+    #[inline]
+    #[allow(clippy::unnecessary_box_returns)]
+    unsafe fn from_ptr(ptr: NonNull<Self>) -> Box<Self> {
+        Box::from_raw(ptr.as_ptr())
+    }
+
+    #[must_use]
+    #[inline]
+    #[allow(clippy::boxed_local)]
+    fn into_inner(self: Box<Self>) -> T {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CircularDoublyLinkedList;
+
+    fn to_vec(list: &CircularDoublyLinkedList<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut list = CircularDoublyLinkedList::new();
+        assert!(list.is_empty());
+        list.push_back(2);
+        list.push_front(1);
+        list.push_back(3);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.front(), Some(&1));
+        assert_eq!(list.back(), Some(&3));
+        assert_eq!(to_vec(&list), [1, 2, 3]);
+        assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), [3, 2, 1]);
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_front(), Some(2));
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+    }
+
+    #[test]
+    fn test_insert_pop_at() {
+        let mut list: CircularDoublyLinkedList<i32> = [1, 3, 5].into_iter().collect();
+        list.insert_at(0, 0);
+        list.insert_at(2, 2);
+        list.insert_at(4, 4);
+        list.insert_at(6, 6);
+        assert_eq!(to_vec(&list), [0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(list.get(5), Some(&5));
+        assert_eq!(list.get(7), None);
+        assert_eq!(list.pop_at(6), Some(6));
+        assert_eq!(list.pop_at(0), Some(0));
+        assert_eq!(list.pop_at(2), Some(3));
+        assert_eq!(to_vec(&list), [1, 2, 4, 5]);
+        assert!(list.contains(&4));
+        assert!(!list.contains(&3));
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut list: CircularDoublyLinkedList<i32> = (0..5).collect();
+        list.rotate_left(2);
+        assert_eq!(to_vec(&list), [2, 3, 4, 0, 1]);
+        list.rotate_right(3);
+        assert_eq!(to_vec(&list), [4, 0, 1, 2, 3]);
+        list.rotate_left(11);
+        assert_eq!(to_vec(&list), [0, 1, 2, 3, 4]);
+        list.rotate_right(5);
+        assert_eq!(to_vec(&list), [0, 1, 2, 3, 4]);
+        assert_eq!(list.back(), Some(&4));
+
+        let mut empty = CircularDoublyLinkedList::<i32>::new();
+        empty.rotate_left(3);
+        empty.rotate_right(3);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_append_reverse() {
+        let mut list: CircularDoublyLinkedList<i32> = (0..3).collect();
+        let mut other: CircularDoublyLinkedList<i32> = (3..6).collect();
+        list.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(to_vec(&list), [0, 1, 2, 3, 4, 5]);
+        other.append(&mut list);
+        assert_eq!(to_vec(&other), [0, 1, 2, 3, 4, 5]);
+        other.reverse();
+        assert_eq!(to_vec(&other), [5, 4, 3, 2, 1, 0]);
+        assert_eq!(other.back(), Some(&0));
+        for value in &mut other {
+            *value *= 2;
+        }
+        assert_eq!(
+            other.clone().into_iter().rev().collect::<Vec<_>>(),
+            [0, 2, 4, 6, 8, 10]
+        );
+        assert_eq!(format!("{other:?}"), "[10, 8, 6, 4, 2, 0]");
+    }
+}
@@ -9,6 +9,8 @@ use std::marker::PhantomData;
 use std::ptr::NonNull;
 use std::{fmt, mem};
 
+use crate::traits::List;
+
 pub struct DoublyLinkedList<T> {
     head: NodePtr<T>,
     tail: NodePtr<T>,
@@ -40,6 +42,25 @@ pub struct IterMut<'a, T: 'a> {
     _marker: PhantomData<&'a mut Node<T>>,
 }
 
+/// A cursor over a list, pointing to an element or the "ghost" non-element.
+///
+/// The ghost non-element sits between the tail and the head of list,
+/// moving next from the tail or moving previous from the head reaches it.
+pub struct Cursor<'a, T: 'a> {
+    index: usize,
+    current: NodePtr<T>,
+    list: &'a DoublyLinkedList<T>,
+}
+
+/// A cursor over a list with editing operations.
+///
+/// Inserting or removing elements at the cursor takes O(1) time.
+pub struct CursorMut<'a, T: 'a> {
+    index: usize,
+    current: NodePtr<T>,
+    list: &'a mut DoublyLinkedList<T>,
+}
+
 // Public functions for list.
 impl<T> DoublyLinkedList<T> {
     /// Create an empty list.
@@ -108,21 +129,62 @@ impl<T> DoublyLinkedList<T> {
     }
 
     // Iterators
-    pub fn iter(&self) -> Iter<'_, T> {
+    #[must_use]
+    pub const fn iter(&self) -> Iter<'_, T> {
         Iter {
             head: self.head,
             tail: self.tail,
             len: self.len,
-            _marker: Default::default(),
+            _marker: PhantomData,
         }
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+    pub const fn iter_mut(&mut self) -> IterMut<'_, T> {
         IterMut {
             head: self.head,
             tail: self.tail,
             len: self.len,
-            _marker: Default::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a cursor at the first element.
+    ///
+    /// The cursor points to the ghost non-element if the list is empty.
+    #[must_use]
+    pub const fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor {
+            index: 0,
+            current: self.head,
+            list: self,
+        }
+    }
+
+    /// Returns a cursor at the last element.
+    #[must_use]
+    pub const fn cursor_back(&self) -> Cursor<'_, T> {
+        Cursor {
+            index: self.len.saturating_sub(1),
+            current: self.tail,
+            list: self,
+        }
+    }
+
+    /// Returns a cursor with editing operations at the first element.
+    pub const fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: 0,
+            current: self.head,
+            list: self,
+        }
+    }
+
+    /// Returns a cursor with editing operations at the last element.
+    pub const fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: self.len.saturating_sub(1),
+            current: self.tail,
+            list: self,
         }
     }
 
@@ -171,9 +233,14 @@ impl<T> DoublyLinkedList<T> {
         }
     }
 
+    /// Insert elements from `iter` at `pos`.
+    ///
+    /// # Panics
+    ///
+    /// Panic if `pos > len`.
     pub fn insert_iter<I: IntoIterator<Item = T>>(&mut self, mut pos: usize, iter: I) {
         assert!(pos <= self.len);
-        let mut new_list = DoublyLinkedList::from_iter(iter);
+        let mut new_list = Self::from_iter(iter);
 
         if pos == 0 {
             self.prepend(&mut new_list);
@@ -280,7 +347,7 @@ impl<T> DoublyLinkedList<T> {
     }
 
     /// Append all elements in another list to self.
-    pub fn append(&mut self, other: &mut Self) {
+    pub const fn append(&mut self, other: &mut Self) {
         match self.tail {
             Some(mut tail) => {
                 // connect tail of self to head of other.
@@ -304,7 +371,7 @@ impl<T> DoublyLinkedList<T> {
 
     /// Prepend all elements in another list to self.
     #[inline]
-    pub fn prepend(&mut self, other: &mut Self) {
+    pub const fn prepend(&mut self, other: &mut Self) {
         other.append(self);
         self.swap(other);
     }
@@ -356,34 +423,109 @@ impl<T> DoublyLinkedList<T> {
 
     /// Swap the contents.
     #[inline]
-    pub fn swap(&mut self, other: &mut Self) {
+    pub const fn swap(&mut self, other: &mut Self) {
         mem::swap(self, other);
     }
 
     // Operations
 
-    /// Merges two sorted lists.
-    pub fn merge(&mut self, _other: &mut Self)
+    /// Merges two sorted lists into one.
+    ///
+    /// `other` becomes empty after this call. No elements are copied or moved,
+    /// only the links between nodes are changed.
+    /// The merge is stable: for equivalent elements, elements from `self` always
+    /// precede elements from `other`.
+    pub fn merge(&mut self, other: &mut Self)
     where
         T: PartialOrd<T>,
     {
-        todo!()
+        self.merge_by(other, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
     }
 
-    // pub fn merge_by(&mut self, other: &mut Self, predict: P)
-    // where
-    //     P: PartialOrd<T>,
-    // {
-    //     todo!()
-    // }
+    /// Merges two sorted lists into one, using `compare` to compare elements.
+    pub fn merge_by<F>(&mut self, other: &mut Self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        if other.is_empty() {
+            return;
+        }
+        let len = self.len + other.len;
+        let head = unsafe { Self::merge_nodes(self.head, other.head, &mut compare) };
+        other.head = None;
+        other.tail = None;
+        other.len = 0;
+        unsafe {
+            self.relink(head);
+        }
+        self.len = len;
+    }
 
-    /// Move elements from another list.
-    pub fn splice(&mut self, _other: &mut Self) {
-        todo!()
+    /// Merges two sorted lists into one, comparing elements by key.
+    pub fn merge_by_key<K, F>(&mut self, other: &mut Self, mut f: F)
+    where
+        F: FnMut(&T) -> K,
+        K: PartialOrd<K>,
+    {
+        self.merge_by(other, |a, b| {
+            f(a).partial_cmp(&f(b)).unwrap_or(Ordering::Equal)
+        });
+    }
+
+    /// Move all elements from `other` into the list, before the element at `pos`.
+    ///
+    /// No elements are copied or moved, re-linking the nodes takes O(1) time,
+    /// while finding the node at `pos` takes O(min(pos, len - pos)) time.
+    /// `other` becomes empty after this call.
+    ///
+    /// # Panics
+    ///
+    /// Panic if `pos > len`.
+    pub fn splice(&mut self, pos: usize, other: &mut Self) {
+        assert!(pos <= self.len);
+        if other.is_empty() {
+            return;
+        }
+        if pos == 0 {
+            self.prepend(other);
+            return;
+        }
+        if pos == self.len {
+            self.append(other);
+            return;
+        }
+
+        if let Some(node) = self.node_at(pos - 1) {
+            self.len += other.len;
+            unsafe {
+                Self::append_nodes(node, other);
+            }
+        }
+    }
+
+    /// Splits the list into two at the given index.
+    ///
+    /// Returns everything after the given index, including the index.
+    ///
+    /// # Panics
+    ///
+    /// Panic if `at > len`.
+    #[must_use]
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len);
+        if at == 0 {
+            return mem::take(self);
+        }
+        if at == self.len {
+            return Self::new();
+        }
+
+        let node = self.node_at(at - 1);
+        unsafe { self.split_off_after_node(node, at) }
     }
 
     /// Reverses the order of the elements.
-    pub fn reverse(&mut self) {
+    pub const fn reverse(&mut self) {
         unsafe { Self::base_reverse(self.head) };
         mem::swap(&mut self.head, &mut self.tail);
     }
@@ -407,17 +549,72 @@ impl<T> DoublyLinkedList<T> {
                     }
                 }
             }
+            self.tail = Some(node);
         }
+        self.len -= count;
 
         count
     }
 
-    pub fn sort(&mut self) {
-        todo!()
+    /// Sorts the list in place.
+    ///
+    /// This sort is stable and takes O(n log(n)) time, nodes are re-linked
+    /// instead of moving elements, so no extra memory is allocated.
+    pub fn sort(&mut self)
+    where
+        T: PartialOrd<T>,
+    {
+        self.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
     }
 
-    //pub fn sort_by(&mut self) { }
-    //pub fn sort_by_key(&mut self) { }
+    /// Sorts the list with a comparator function.
+    ///
+    /// It is a bottom-up merge sort: in each pass, adjacent sorted runs of
+    /// `width` nodes are merged, and `width` is doubled after the pass.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        if self.len < 2 {
+            return;
+        }
+
+        let mut head = self.head;
+        let mut width = 1;
+        while width < self.len {
+            let mut rest = head;
+            let mut new_head: NodePtr<T> = None;
+            let mut new_tail: NodePtr<T> = None;
+            while rest.is_some() {
+                // Cut two runs from the remaining nodes.
+                let left = rest;
+                let right = unsafe { Self::cut_after(left, width) };
+                rest = unsafe { Self::cut_after(right, width) };
+
+                let merged = unsafe { Self::merge_nodes(left, right, &mut compare) };
+                match new_tail {
+                    Some(mut tail) => unsafe { tail.as_mut().next = merged },
+                    None => new_head = merged,
+                }
+                new_tail = unsafe { Self::last_node(merged) };
+            }
+            head = new_head;
+            width *= 2;
+        }
+
+        unsafe {
+            self.relink(head);
+        }
+    }
+
+    /// Sorts the list with a key extraction function.
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> K,
+        K: PartialOrd<K>,
+    {
+        self.sort_by(|a, b| f(a).partial_cmp(&f(b)).unwrap_or(Ordering::Equal));
+    }
 }
 
 // Private or unsafe functions for list.
@@ -484,7 +681,10 @@ impl<T> DoublyLinkedList<T> {
         })
     }
 
-    unsafe fn insert_after(mut prev_node: NonNull<Node<T>>, mut new_node_ptr: NonNull<Node<T>>) {
+    const unsafe fn insert_after(
+        mut prev_node: NonNull<Node<T>>,
+        mut new_node_ptr: NonNull<Node<T>>,
+    ) {
         if let Some(mut next_node) = prev_node.as_mut().next {
             new_node_ptr.as_mut().next = Some(next_node);
             next_node.as_mut().prev = Some(new_node_ptr);
@@ -493,7 +693,7 @@ impl<T> DoublyLinkedList<T> {
         prev_node.as_mut().next = Some(new_node_ptr);
     }
 
-    unsafe fn append_nodes(mut prev_node: NonNull<Node<T>>, other: &mut Self) {
+    const unsafe fn append_nodes(mut prev_node: NonNull<Node<T>>, other: &mut Self) {
         if other.is_empty() {
             return;
         }
@@ -513,20 +713,192 @@ impl<T> DoublyLinkedList<T> {
     }
 
     unsafe fn remove_after(mut node: NonNull<Node<T>>) -> Option<Box<Node<T>>> {
-        if let Some(mut next_node) = node.as_mut().next {
-            let mut next_next_node = next_node.as_mut().next.take();
-            if let Some(next_next_node) = next_next_node.as_mut() {
-                next_next_node.as_mut().prev = Some(node);
+        let mut next_node = node.as_mut().next?;
+        let mut next_next_node = next_node.as_mut().next.take();
+        if let Some(next_next_node) = next_next_node.as_mut() {
+            next_next_node.as_mut().prev = Some(node);
+        }
+        node.as_mut().next = next_next_node;
+
+        Some(Node::from_ptr(next_node))
+    }
+
+    /// Link nodes from `first` to `last` between `prev` and `next`.
+    ///
+    /// `prev` and `next` must be adjacent in list, `None` means the ghost
+    /// non-element.
+    const unsafe fn splice_nodes(
+        &mut self,
+        prev: NodePtr<T>,
+        next: NodePtr<T>,
+        mut first: NonNull<Node<T>>,
+        mut last: NonNull<Node<T>>,
+        count: usize,
+    ) {
+        match prev {
+            Some(mut prev) => prev.as_mut().next = Some(first),
+            None => self.head = Some(first),
+        }
+        match next {
+            Some(mut next) => next.as_mut().prev = Some(last),
+            None => self.tail = Some(last),
+        }
+        first.as_mut().prev = prev;
+        last.as_mut().next = next;
+        self.len += count;
+    }
+
+    /// Unlink `node` from list, without freeing it.
+    const unsafe fn unlink_node(&mut self, mut node: NonNull<Node<T>>) {
+        let node = node.as_mut();
+        match node.prev {
+            Some(mut prev) => prev.as_mut().next = node.next,
+            None => self.head = node.next,
+        }
+        match node.next {
+            Some(mut next) => next.as_mut().prev = node.prev,
+            None => self.tail = node.prev,
+        }
+        node.prev = None;
+        node.next = None;
+        self.len -= 1;
+    }
+
+    /// Split list after `node`, `at` is number of nodes up to and including `node`.
+    ///
+    /// If `node` is `None`, the whole list is moved.
+    unsafe fn split_off_after_node(&mut self, node: NodePtr<T>, at: usize) -> Self {
+        let Some(mut node) = node else {
+            return mem::take(self);
+        };
+        let mut other = Self::new();
+        if let Some(mut next_node) = node.as_mut().next.take() {
+            next_node.as_mut().prev = None;
+            other.head = Some(next_node);
+            other.tail = self.tail;
+            other.len = self.len - at;
+            self.tail = Some(node);
+            self.len = at;
+        }
+        other
+    }
+
+    /// Split list before `node`, `at` is number of nodes before `node`.
+    ///
+    /// If `node` is `None`, the whole list is moved.
+    unsafe fn split_off_before_node(&mut self, node: NodePtr<T>, at: usize) -> Self {
+        let Some(mut node) = node else {
+            return mem::take(self);
+        };
+        let mut other = Self::new();
+        if let Some(mut prev_node) = node.as_mut().prev.take() {
+            prev_node.as_mut().next = None;
+            other.head = self.head;
+            other.tail = Some(prev_node);
+            other.len = at;
+            self.head = Some(node);
+            self.len -= at;
+        }
+        other
+    }
+
+    /// Returns the node at `pos`, walking from the nearer end.
+    fn node_at(&self, pos: usize) -> NodePtr<T> {
+        if pos >= self.len {
+            return None;
+        }
+        unsafe {
+            if pos < self.len / 2 {
+                let mut node = self.head;
+                for _ in 0..pos {
+                    node = node.and_then(|node| node.as_ref().next);
+                }
+                node
+            } else {
+                let mut node = self.tail;
+                for _ in 0..(self.len - 1 - pos) {
+                    node = node.and_then(|node| node.as_ref().prev);
+                }
+                node
             }
-            node.as_mut().next = next_next_node;
+        }
+    }
 
-            Some(Node::from_ptr(next_node))
-        } else {
-            None
+    /// Cut the chain after `count` nodes, returns head of the remaining chain.
+    ///
+    /// Only `next` links are used.
+    unsafe fn cut_after(head: NodePtr<T>, count: usize) -> NodePtr<T> {
+        let mut node = head?;
+        for _ in 1..count {
+            match node.as_ref().next {
+                Some(next_node) => node = next_node,
+                None => return None,
+            }
+        }
+        node.as_mut().next.take()
+    }
+
+    unsafe fn last_node(head: NodePtr<T>) -> NodePtr<T> {
+        let mut node = head?;
+        while let Some(next_node) = node.as_ref().next {
+            node = next_node;
         }
+        Some(node)
     }
 
-    unsafe fn base_reverse(node: NodePtr<T>) {
+    /// Merges two sorted chains linked by `next`, returns the new head.
+    ///
+    /// `prev` links are not maintained, call `relink()` after merging.
+    unsafe fn merge_nodes<F>(
+        mut left: NodePtr<T>,
+        mut right: NodePtr<T>,
+        compare: &mut F,
+    ) -> NodePtr<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut head: NodePtr<T> = None;
+        let mut tail: NodePtr<T> = None;
+        while let (Some(left_node), Some(right_node)) = (left, right) {
+            // Take from right only if it is strictly less, to keep the merge stable.
+            let node = if compare(&right_node.as_ref().value, &left_node.as_ref().value)
+                == Ordering::Less
+            {
+                right = right_node.as_ref().next;
+                right_node
+            } else {
+                left = left_node.as_ref().next;
+                left_node
+            };
+            match tail {
+                Some(mut tail) => tail.as_mut().next = Some(node),
+                None => head = Some(node),
+            }
+            tail = Some(node);
+        }
+
+        let remaining = left.or(right);
+        match tail {
+            Some(mut tail) => tail.as_mut().next = remaining,
+            None => head = remaining,
+        }
+        head
+    }
+
+    /// Reset `prev` links, head and tail of list from a chain linked by `next`.
+    const unsafe fn relink(&mut self, head: NodePtr<T>) {
+        let mut prev: NodePtr<T> = None;
+        let mut node = head;
+        while let Some(mut current) = node {
+            current.as_mut().prev = prev;
+            prev = Some(current);
+            node = current.as_ref().next;
+        }
+        self.head = head;
+        self.tail = prev;
+    }
+
+    const unsafe fn base_reverse(node: NodePtr<T>) {
         let mut temp = node;
         while let Some(mut temp_node) = temp {
             mem::swap(&mut temp_node.as_mut().prev, &mut temp_node.as_mut().next);
@@ -583,6 +955,60 @@ impl<T: Hash> Hash for DoublyLinkedList<T> {
     }
 }
 
+impl<T> List<T> for DoublyLinkedList<T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn front(&self) -> Option<&T> {
+        Self::front(self)
+    }
+
+    fn front_mut(&mut self) -> Option<&mut T> {
+        Self::front_mut(self)
+    }
+
+    fn back(&self) -> Option<&T> {
+        Self::back(self)
+    }
+
+    fn back_mut(&mut self) -> Option<&mut T> {
+        Self::back_mut(self)
+    }
+
+    fn get(&self, pos: usize) -> Option<&T> {
+        self.iter().nth(pos)
+    }
+
+    fn push_front(&mut self, value: T) {
+        Self::push_front(self, value);
+    }
+
+    fn push_back(&mut self, value: T) {
+        Self::push_back(self, value);
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        Self::pop_front(self)
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        Self::pop_back(self)
+    }
+
+    fn insert_at(&mut self, pos: usize, value: T) {
+        Self::insert_at(self, pos, value);
+    }
+
+    fn pop_at(&mut self, pos: usize) -> Option<T> {
+        Self::pop_at(self, pos)
+    }
+
+    fn clear(&mut self) {
+        Self::clear(self);
+    }
+}
+
 impl<T> Extend<T> for DoublyLinkedList<T> {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
         iter.into_iter().for_each(|value| self.push_back(value));
@@ -740,6 +1166,297 @@ impl<T> DoubleEndedIterator for IterMut<'_, T> {
 
 impl<T> ExactSizeIterator for IterMut<'_, T> {}
 
+impl<'a, T> Cursor<'a, T> {
+    /// Returns index of current element, `None` for the ghost non-element.
+    #[must_use]
+    pub const fn index(&self) -> Option<usize> {
+        if self.current.is_some() {
+            Some(self.index)
+        } else {
+            None
+        }
+    }
+
+    /// Move cursor to the next element.
+    ///
+    /// Moves to the head if pointing to the ghost non-element,
+    /// and moves to the ghost non-element if pointing to the tail.
+    pub const fn move_next(&mut self) {
+        if let Some(current) = self.current.take() {
+            unsafe {
+                self.current = current.as_ref().next;
+                self.index += 1;
+            }
+        } else {
+            self.current = self.list.head;
+            self.index = 0;
+        }
+    }
+
+    /// Move cursor to the previous element.
+    ///
+    /// Moves to the tail if pointing to the ghost non-element,
+    /// and moves to the ghost non-element if pointing to the head.
+    pub fn move_prev(&mut self) {
+        if let Some(current) = self.current.take() {
+            unsafe {
+                self.current = current.as_ref().prev;
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.len);
+            }
+        } else {
+            self.current = self.list.tail;
+            self.index = self.list.len.saturating_sub(1);
+        }
+    }
+
+    /// Returns reference to current element.
+    #[must_use]
+    pub fn current(&self) -> Option<&'a T> {
+        unsafe { self.current.map(|node| &(*node.as_ptr()).value) }
+    }
+
+    /// Returns reference to the next element.
+    #[must_use]
+    pub fn peek_next(&self) -> Option<&'a T> {
+        unsafe {
+            let next = self
+                .current
+                .map_or(self.list.head, |current| current.as_ref().next);
+            next.map(|node| &(*node.as_ptr()).value)
+        }
+    }
+
+    /// Returns reference to the previous element.
+    #[must_use]
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        unsafe {
+            let prev = self
+                .current
+                .map_or(self.list.tail, |current| current.as_ref().prev);
+            prev.map(|node| &(*node.as_ptr()).value)
+        }
+    }
+}
+
+impl<T> Clone for Cursor<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            index: self.index,
+            current: self.current,
+            list: self.list,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Cursor<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Cursor")
+            .field(self.list)
+            .field(&self.index())
+            .finish()
+    }
+}
+
+impl<T> CursorMut<'_, T> {
+    /// Returns index of current element, `None` for the ghost non-element.
+    #[must_use]
+    pub const fn index(&self) -> Option<usize> {
+        if self.current.is_some() {
+            Some(self.index)
+        } else {
+            None
+        }
+    }
+
+    /// Move cursor to the next element.
+    pub const fn move_next(&mut self) {
+        if let Some(current) = self.current.take() {
+            unsafe {
+                self.current = current.as_ref().next;
+                self.index += 1;
+            }
+        } else {
+            self.current = self.list.head;
+            self.index = 0;
+        }
+    }
+
+    /// Move cursor to the previous element.
+    pub fn move_prev(&mut self) {
+        if let Some(current) = self.current.take() {
+            unsafe {
+                self.current = current.as_ref().prev;
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.len);
+            }
+        } else {
+            self.current = self.list.tail;
+            self.index = self.list.len.saturating_sub(1);
+        }
+    }
+
+    /// Returns mutable reference to current element.
+    #[must_use]
+    pub fn current(&mut self) -> Option<&mut T> {
+        unsafe { self.current.map(|node| &mut (*node.as_ptr()).value) }
+    }
+
+    /// Returns mutable reference to the next element.
+    #[must_use]
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        unsafe {
+            let next = self
+                .current
+                .map_or(self.list.head, |current| current.as_ref().next);
+            next.map(|node| &mut (*node.as_ptr()).value)
+        }
+    }
+
+    /// Returns mutable reference to the previous element.
+    #[must_use]
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        unsafe {
+            let prev = self
+                .current
+                .map_or(self.list.tail, |current| current.as_ref().prev);
+            prev.map(|node| &mut (*node.as_ptr()).value)
+        }
+    }
+
+    /// Returns a read-only cursor at current position.
+    ///
+    /// The mutable cursor is frozen while the returned cursor is alive.
+    #[must_use]
+    pub const fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor {
+            index: self.index,
+            current: self.current,
+            list: self.list,
+        }
+    }
+
+    /// Insert a new element after current one.
+    ///
+    /// If cursor points to the ghost non-element, the new element is inserted
+    /// at the front of list.
+    pub fn insert_after(&mut self, value: T) {
+        let node = Node::new_ptr(value);
+        unsafe {
+            let next = match self.current {
+                Some(current) => current.as_ref().next,
+                None => self.list.head,
+            };
+            self.list.splice_nodes(self.current, next, node, node, 1);
+        }
+        if self.current.is_none() {
+            // The ghost non-element is always at index `len`.
+            self.index = self.list.len;
+        }
+    }
+
+    /// Insert a new element before current one.
+    ///
+    /// If cursor points to the ghost non-element, the new element is inserted
+    /// at the end of list.
+    pub fn insert_before(&mut self, value: T) {
+        let node = Node::new_ptr(value);
+        unsafe {
+            let prev = match self.current {
+                Some(current) => current.as_ref().prev,
+                None => self.list.tail,
+            };
+            self.list.splice_nodes(prev, self.current, node, node, 1);
+        }
+        self.index += 1;
+    }
+
+    /// Remove current element and returns it.
+    ///
+    /// Cursor is moved to the next element.
+    /// Returns `None` if cursor points to the ghost non-element.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        unsafe {
+            self.current = node.as_ref().next;
+            self.list.unlink_node(node);
+            Some(Node::from_ptr(node).into_inner())
+        }
+    }
+
+    /// Insert all elements of `other` after current one.
+    ///
+    /// If cursor points to the ghost non-element, the elements are inserted
+    /// at the front of list.
+    pub fn splice_after(&mut self, mut other: DoublyLinkedList<T>) {
+        let (Some(first), Some(last)) = (other.head.take(), other.tail.take()) else {
+            return;
+        };
+        let count = mem::take(&mut other.len);
+        unsafe {
+            let next = match self.current {
+                Some(current) => current.as_ref().next,
+                None => self.list.head,
+            };
+            self.list
+                .splice_nodes(self.current, next, first, last, count);
+        }
+        if self.current.is_none() {
+            self.index = self.list.len;
+        }
+    }
+
+    /// Insert all elements of `other` before current one.
+    ///
+    /// If cursor points to the ghost non-element, the elements are inserted
+    /// at the end of list.
+    pub fn splice_before(&mut self, mut other: DoublyLinkedList<T>) {
+        let (Some(first), Some(last)) = (other.head.take(), other.tail.take()) else {
+            return;
+        };
+        let count = mem::take(&mut other.len);
+        unsafe {
+            let prev = match self.current {
+                Some(current) => current.as_ref().prev,
+                None => self.list.tail,
+            };
+            self.list
+                .splice_nodes(prev, self.current, first, last, count);
+        }
+        self.index += count;
+    }
+
+    /// Split list into two after current element.
+    ///
+    /// Returns a new list with everything after the cursor, the original list
+    /// keeps everything up to and including current element.
+    /// If cursor points to the ghost non-element, the whole list is moved.
+    pub fn split_after(&mut self) -> DoublyLinkedList<T> {
+        if self.current.is_none() {
+            self.index = 0;
+        }
+        unsafe { self.list.split_off_after_node(self.current, self.index + 1) }
+    }
+
+    /// Split list into two before current element.
+    ///
+    /// Returns a new list with everything before the cursor, the original list
+    /// keeps everything from current element to the end.
+    /// If cursor points to the ghost non-element, the whole list is moved.
+    pub fn split_before(&mut self) -> DoublyLinkedList<T> {
+        let at = self.index;
+        self.index = 0;
+        unsafe { self.list.split_off_before_node(self.current, at) }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CursorMut<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CursorMut")
+            .field(&*self.list)
+            .field(&self.index())
+            .finish()
+    }
+}
+
 impl<T> Node<T> {
     #[must_use]
     #[inline]
@@ -760,6 +1477,7 @@ impl<T> Node<T> {
 
     #[must_use]
     #[inline]
+    #[allow(clippy::unnecessary_box_returns)]
     unsafe fn from_ptr(ptr: NonNull<Self>) -> Box<Self> {
         Box::from_raw(ptr.as_ptr())
     }
@@ -965,4 +1683,229 @@ mod tests {
         list.reverse();
         assert_eq!(list.into_iter().collect::<Vec<_>>(), [4, 3, 2, 1]);
     }
+
+    #[test]
+    fn test_unique_tail() {
+        let mut list = DoublyLinkedList::from_iter([1, 2, 2, 2]);
+        assert_eq!(list.unique(), 2);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.back(), Some(&2));
+        list.push_back(3);
+        assert_eq!(list, DoublyLinkedList::from_iter([1, 2, 3]));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut list1 = DoublyLinkedList::from_iter([1, 3, 5, 7]);
+        let mut list2 = DoublyLinkedList::from_iter([0, 2, 3, 8, 9]);
+        list1.merge(&mut list2);
+        assert!(list2.is_empty());
+        assert_eq!(list1.len(), 9);
+        assert_eq!(
+            list1.iter().copied().collect::<Vec<_>>(),
+            [0, 1, 2, 3, 3, 5, 7, 8, 9]
+        );
+        assert_eq!(
+            list1.iter().rev().copied().collect::<Vec<_>>(),
+            [9, 8, 7, 5, 3, 3, 2, 1, 0]
+        );
+
+        let mut empty = DoublyLinkedList::new();
+        empty.merge(&mut list1);
+        assert_eq!(empty.len(), 9);
+        assert_eq!(empty.back(), Some(&9));
+    }
+
+    #[test]
+    fn test_merge_by_key_stable() {
+        let mut list1 = DoublyLinkedList::from_iter([(1, 'a'), (2, 'a')]);
+        let mut list2 = DoublyLinkedList::from_iter([(1, 'b'), (2, 'b')]);
+        list1.merge_by_key(&mut list2, |pair| pair.0);
+        assert_eq!(
+            list1.into_iter().collect::<Vec<_>>(),
+            [(1, 'a'), (1, 'b'), (2, 'a'), (2, 'b')]
+        );
+    }
+
+    #[test]
+    fn test_splice() {
+        let mut list = DoublyLinkedList::from_iter([1, 5]);
+        list.splice(1, &mut DoublyLinkedList::from_iter([2, 3, 4]));
+        list.splice(0, &mut DoublyLinkedList::from_iter([0]));
+        list.splice(6, &mut DoublyLinkedList::from_iter([6, 7]));
+        assert_eq!(list.len(), 8);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            (0..8).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            (0..8).rev().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut list = (0..10).collect::<DoublyLinkedList<_>>();
+        let tail = list.split_off(7);
+        assert_eq!(list.len(), 7);
+        assert_eq!(list.back(), Some(&6));
+        assert_eq!(tail.iter().rev().copied().collect::<Vec<_>>(), [9, 8, 7]);
+        let all = list.split_off(0);
+        assert!(list.is_empty());
+        assert_eq!(all.len(), 7);
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut list: DoublyLinkedList<i32> = (0..1000).map(|i| (i * 7919) % 1000).collect();
+        list.sort();
+        assert_eq!(list.len(), 1000);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            (0..1000).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            (0..1000).rev().collect::<Vec<_>>()
+        );
+        assert_eq!(list.back(), Some(&999));
+
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(list.front(), Some(&999));
+        assert_eq!(list.back(), Some(&0));
+    }
+
+    #[test]
+    fn test_sort_by_key_stable() {
+        let mut list: DoublyLinkedList<(usize, usize)> = (0..100).map(|i| (i % 3, i)).collect();
+        list.sort_by_key(|pair| pair.0);
+        let values: Vec<_> = list.into_iter().collect();
+        let mut expected: Vec<_> = (0..100).map(|i| (i % 3, i)).collect();
+        expected.sort_by_key(|pair| pair.0);
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn test_cursor() {
+        let list = DoublyLinkedList::from_iter([1, 2, 3]);
+        let mut cursor = list.cursor_front();
+        assert_eq!(cursor.current(), Some(&1));
+        assert_eq!(cursor.peek_prev(), None);
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(1));
+        assert_eq!(cursor.peek_next(), Some(&3));
+        cursor.move_next();
+        cursor.move_next();
+        // Ghost non-element.
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&1));
+        assert_eq!(cursor.peek_prev(), Some(&3));
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&3));
+
+        let mut cursor = list.cursor_back();
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(0));
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+    }
+
+    #[test]
+    fn test_cursor_mut_insert_remove() {
+        let mut list = DoublyLinkedList::from_iter([1, 3, 5]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.insert_after(2);
+        cursor.insert_before(0);
+        assert_eq!(cursor.index(), Some(1));
+        assert_eq!(cursor.current(), Some(&mut 1));
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(3));
+        assert_eq!(cursor.current(), Some(&mut 5));
+        assert_eq!(cursor.index(), Some(3));
+        assert_eq!(cursor.remove_current(), Some(5));
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.remove_current(), None);
+        cursor.insert_after(-1);
+        cursor.insert_before(6);
+        if let Some(value) = cursor.peek_prev() {
+            *value = 7;
+        }
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [-1, 0, 1, 2, 7]);
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            [7, 2, 1, 0, -1]
+        );
+    }
+
+    #[test]
+    fn test_cursor_mut_split() {
+        let mut list = (0..6).collect::<DoublyLinkedList<_>>();
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.move_next();
+        let after = cursor.split_after();
+        assert_eq!(cursor.index(), Some(2));
+        let before = cursor.split_before();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(after.iter().copied().collect::<Vec<_>>(), [3, 4, 5]);
+        assert_eq!(before.iter().rev().copied().collect::<Vec<_>>(), [1, 0]);
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.front(), list.back());
+
+        let mut list = (0..3).collect::<DoublyLinkedList<_>>();
+        let mut cursor = list.cursor_back_mut();
+        cursor.move_next();
+        let all = cursor.split_after();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(all.len(), 3);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_cursor_mut_splice() {
+        let mut list = DoublyLinkedList::from_iter([0, 5]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.splice_after(DoublyLinkedList::from_iter([1, 2]));
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 5));
+        cursor.splice_before(DoublyLinkedList::from_iter([3, 4]));
+        assert_eq!(cursor.index(), Some(5));
+        cursor.move_next();
+        cursor.splice_before(DoublyLinkedList::from_iter([6]));
+        cursor.splice_after(DoublyLinkedList::from_iter([-1]));
+        assert_eq!(cursor.index(), None);
+        assert_eq!(list.len(), 8);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            (-1..7).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            (-1..7).rev().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_cursor_lru() {
+        // Move the accessed element to the front.
+        let mut list = DoublyLinkedList::from_iter(["a", "b", "c", "d"]);
+        let mut cursor = list.cursor_front_mut();
+        while cursor.current().is_some_and(|key| *key != "c") {
+            cursor.move_next();
+        }
+        let key = cursor.remove_current().unwrap();
+        list.push_front(key);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            ["c", "a", "b", "d"]
+        );
+    }
 }
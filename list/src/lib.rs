@@ -2,5 +2,23 @@
 // Use of this source is governed by General Public License that can be
 // found in the LICENSE file.
 
-mod doubly_linked_list;
-mod singly_linked_list;
+#![deny(
+    warnings,
+    clippy::all,
+    clippy::cargo,
+    clippy::nursery,
+    clippy::pedantic
+)]
+#![allow(clippy::module_name_repetitions)]
+
+pub mod circular_linked_list;
+pub mod doubly_linked_list;
+pub mod list_sort;
+pub mod single_v2;
+pub mod singly_linked_list;
+pub mod skip_list;
+pub mod traits;
+pub mod unrolled_linked_list;
+pub mod xor_linked_list;
+
+pub use traits::List;
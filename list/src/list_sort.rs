@@ -0,0 +1,159 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 链表排序.
+//!
+//! 链表不支持随机访问, 所以这里的算法都只是顺序地遍历链表:
+//! - 冒泡排序和选择排序直接交换节点中的值;
+//! - 插入排序每次从原链表中取出一个元素, 插入到有序链表中;
+//! - 归并排序和快速排序把链表拆分成多个子链表, 排序后再拼接起来,
+//!   拆分和拼接只需要修改节点之间的链接.
+
+use std::mem;
+
+use crate::doubly_linked_list::DoublyLinkedList;
+
+/// 冒泡排序, 交换相邻节点的值.
+///
+/// 如果某一轮遍历中没有交换任何元素, 说明链表已经有序.
+pub fn bubble_sort<T: PartialOrd>(list: &mut DoublyLinkedList<T>) {
+    let len = list.len();
+    for round in 0..len {
+        let mut swapped = false;
+        let mut iter = list.iter_mut();
+        let Some(mut prev) = iter.next() else {
+            return;
+        };
+        // 每一轮之后, 最大的元素都被移到了链表尾部.
+        for current in iter.take(len - 1 - round) {
+            if *current < *prev {
+                mem::swap(prev, current);
+                swapped = true;
+            }
+            prev = current;
+        }
+        if !swapped {
+            break;
+        }
+    }
+}
+
+/// 选择排序, 每次从未排序部分选出最小的值, 与未排序部分的第一个节点交换.
+pub fn selection_sort<T: PartialOrd>(list: &mut DoublyLinkedList<T>) {
+    for start in 0..list.len() {
+        let mut iter = list.iter_mut().skip(start);
+        let Some(first) = iter.next() else {
+            return;
+        };
+        let mut min: Option<&mut T> = None;
+        for current in iter {
+            let min_value: &T = min.as_deref().unwrap_or(first);
+            if *current < *min_value {
+                min = Some(current);
+            }
+        }
+        if let Some(min) = min {
+            mem::swap(first, min);
+        }
+    }
+}
+
+/// 插入排序, 把元素依次插入到有序链表中.
+///
+/// 新元素插入到所有与之相等的元素之后, 所以是稳定排序.
+pub fn insertion_sort<T: PartialOrd>(list: &mut DoublyLinkedList<T>) {
+    let mut sorted = DoublyLinkedList::new();
+    while let Some(value) = list.pop_front() {
+        // 从尾部向前查找, 对于接近有序的链表, 只需要比较很少的次数.
+        let pos = sorted.len()
+            - sorted
+                .iter()
+                .rev()
+                .take_while(|item| value < **item)
+                .count();
+        sorted.insert_at(pos, value);
+    }
+    *list = sorted;
+}
+
+/// 自顶向下的归并排序.
+///
+/// 把链表从中间拆分成两个, 分别排序后再合并.
+pub fn merge_sort<T: PartialOrd>(list: &mut DoublyLinkedList<T>) {
+    if list.len() < 2 {
+        return;
+    }
+    let mut right = list.split_off(list.len() / 2);
+    merge_sort(list);
+    merge_sort(&mut right);
+    list.merge(&mut right);
+}
+
+/// 快速排序.
+///
+/// 以第一个元素作为基准值, 把链表分成小于, 等于和大于基准值的三部分,
+/// 递归地排序前后两部分, 再把三部分拼接起来.
+pub fn quicksort<T: PartialOrd>(list: &mut DoublyLinkedList<T>) {
+    let Some(pivot) = list.pop_front() else {
+        return;
+    };
+    let mut less = DoublyLinkedList::new();
+    let mut equal = DoublyLinkedList::new();
+    let mut greater = DoublyLinkedList::new();
+    while let Some(value) = list.pop_front() {
+        if value < pivot {
+            less.push_back(value);
+        } else if value > pivot {
+            greater.push_back(value);
+        } else {
+            equal.push_back(value);
+        }
+    }
+    equal.push_front(pivot);
+
+    quicksort(&mut less);
+    quicksort(&mut greater);
+    list.append(&mut less);
+    list.append(&mut equal);
+    list.append(&mut greater);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bubble_sort, insertion_sort, merge_sort, quicksort, selection_sort};
+    use crate::doubly_linked_list::DoublyLinkedList;
+
+    fn check_sort(sort: fn(&mut DoublyLinkedList<i32>)) {
+        let mut list: DoublyLinkedList<i32> = (0..200).map(|i| (i * 37) % 200).collect();
+        sort(&mut list);
+        assert_eq!(list.len(), 200);
+        assert_eq!(
+            list.into_iter().collect::<Vec<_>>(),
+            (0..200).collect::<Vec<_>>()
+        );
+
+        let mut list: DoublyLinkedList<i32> = [3, 1, 3, 2, 1, 3].into_iter().collect();
+        sort(&mut list);
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            [3, 3, 3, 2, 1, 1]
+        );
+
+        let mut list = DoublyLinkedList::new();
+        sort(&mut list);
+        assert!(list.is_empty());
+        list.push_back(1);
+        sort(&mut list);
+        assert_eq!(list.front(), Some(&1));
+    }
+
+    #[test]
+    fn test_list_sorts() {
+        check_sort(bubble_sort);
+        check_sort(selection_sort);
+        check_sort(insertion_sort);
+        check_sort(merge_sort);
+        check_sort(quicksort);
+    }
+}
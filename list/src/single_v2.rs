@@ -2,7 +2,7 @@
 // Use of this source is governed by General Public License that can be
 // found in the LICENSE file.
 
-#![allow(dead_code)]
+//! Singly linked list with tail pointer, nodes are shared with `Rc<RefCell<T>>`.
 
 use std::cell::{RefCell, RefMut};
 use std::rc::Rc;
@@ -20,17 +20,12 @@ struct ListNode<T> {
     next: ListNodePtr<T>,
 }
 
-//pub struct Iter<'a, T> {
-//    next: ListNodePtr<T>,
-//    phantom_data: PhantomData<&'a T>,
-//}
-
-impl<T: Clone> Clone for ListNode<T> {
-    fn clone(&self) -> Self {
-        unimplemented!()
-    }
+pub struct Iter<'a, T> {
+    next: Option<&'a Rc<RefCell<ListNode<T>>>>,
 }
 
+pub struct IntoIter<T>(LinkedList<T>);
+
 impl<T> ListNode<T> {
     #[must_use]
     pub fn new(value: T) -> Rc<RefCell<Self>> {
@@ -41,16 +36,9 @@ impl<T> ListNode<T> {
     pub fn with_next(value: T, next: ListNodePtr<T>) -> Rc<RefCell<Self>> {
         Rc::new(RefCell::new(Self { value, next }))
     }
-
-    /// Check wether current node is the the last one in list.
-    #[must_use]
-    pub const fn is_last(&self) -> bool {
-        self.next.is_none()
-    }
 }
 
 impl<T> Default for LinkedList<T> {
-    #[must_use]
     #[inline]
     fn default() -> Self {
         Self::new()
@@ -68,20 +56,12 @@ impl<T> LinkedList<T> {
         }
     }
 
+    /// Build a list by pushing each item of `vec` to the front.
+    ///
+    /// The last item of `vec` becomes the head, so the list is in reverse order.
     #[must_use]
     pub fn from_vec(vec: Vec<T>) -> Self {
-        let length = vec.len();
-        let mut tail = None;
-        let mut node = None;
-        for item in vec {
-            let node_rc = ListNode::with_next(item, node);
-            if tail.is_none() {
-                tail.replace(node_rc.clone());
-            }
-            node = Some(node_rc);
-        }
-        let head = node;
-        Self { length, head, tail }
+        vec.into_iter().collect()
     }
 
     #[inline]
@@ -109,10 +89,9 @@ impl<T> LinkedList<T> {
 
     /// Add a new node to head of list.
     pub fn push_front(&mut self, value: T) {
-        let node = ListNode::new(value);
-        match self.head.take() {
-            Some(head) => node.borrow_mut().next = Some(head),
-            None => self.tail = Some(node.clone()),
+        let node = ListNode::with_next(value, self.head.take());
+        if self.tail.is_none() {
+            self.tail = Some(node.clone());
         }
         self.head = Some(node);
         self.length += 1;
@@ -139,12 +118,44 @@ impl<T> LinkedList<T> {
     /// Remove a node from tail of list.
     ///
     /// Time is O(n).
-    fn pop_back() -> Option<T> {
-        unimplemented!()
+    pub fn pop_back(&mut self) -> Option<T> {
+        let old_tail = self.tail.take()?;
+        if self.length == 1 {
+            self.head.take();
+        } else {
+            // Find the node before tail.
+            let mut node = self.head.clone()?;
+            loop {
+                let next = node.borrow().next.clone()?;
+                if Rc::ptr_eq(&next, &old_tail) {
+                    break;
+                }
+                node = next;
+            }
+            node.borrow_mut().next.take();
+            self.tail = Some(node);
+        }
+        self.length -= 1;
+
+        Rc::try_unwrap(old_tail)
+            .ok()
+            .map(|tail| tail.into_inner().value)
     }
 
     /// Reverse orders of node in list.
-    pub fn reverse(&mut self) {}
+    ///
+    /// Time is O(n).
+    pub fn reverse(&mut self) {
+        let mut prev: ListNodePtr<T> = None;
+        let mut node = self.head.take();
+        self.tail.clone_from(&node);
+        while let Some(current) = node {
+            node = current.borrow_mut().next.take();
+            current.borrow_mut().next = prev;
+            prev = Some(current);
+        }
+        self.head = prev;
+    }
 
     /// Get reference of value in head node of list.
     #[must_use]
@@ -159,8 +170,10 @@ impl<T> LinkedList<T> {
 
     /// Get mutable reference of value in head node of list.
     #[must_use]
-    pub fn front_mut(&mut self) -> Option<RefMut<T>> {
-        unimplemented!()
+    pub fn front_mut(&mut self) -> Option<RefMut<'_, T>> {
+        self.head
+            .as_ref()
+            .map(|node| RefMut::map(node.borrow_mut(), |node| &mut node.value))
     }
 
     /// Get reference of value in tail node of list.
@@ -176,23 +189,67 @@ impl<T> LinkedList<T> {
 
     /// Get mutable reference of value in tail node of list.
     #[must_use]
-    pub fn back_mut(&mut self) -> Option<&mut T> {
-        unimplemented!()
-    }
-
-    // TODO(Shaohua): Impl Iter
-    //    #[must_use]
-    //    pub fn iter(&self) -> Iter<T> {
-    //        Iter {
-    //            next: self.head.clone(),
-    //            phantom_data: PhantomData,
-    //        }
-    //    }
+    pub fn back_mut(&mut self) -> Option<RefMut<'_, T>> {
+        self.tail
+            .as_ref()
+            .map(|node| RefMut::map(node.borrow_mut(), |node| &mut node.value))
+    }
+
+    #[must_use]
+    pub const fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_ref(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next?;
+        // Nodes are only mutably borrowed by `front_mut()` and `back_mut()`,
+        // which require `&mut LinkedList` and so can not overlap with `iter()`.
+        let node: &'a ListNode<T> = unsafe { node.try_borrow_unguarded().ok()? };
+        self.next = node.next.as_ref();
+        Some(&node.value)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
 }
 
+/// Items are pushed to the front one by one, so the list is in reverse order
+/// of the iterator, same as `from_vec()`.
 impl<T> FromIterator<T> for LinkedList<T> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let iter = iter.into_iter();
         let mut list = Self::new();
         for item in iter {
             list.push_front(item);
@@ -201,6 +258,15 @@ impl<T> FromIterator<T> for LinkedList<T> {
     }
 }
 
+/// Items are appended to the back of list, in order of the iterator.
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
 impl<T> Drop for LinkedList<T> {
     fn drop(&mut self) {
         // Reset tail first.
@@ -258,4 +324,64 @@ mod tests {
         }
         drop(list);
     }
+
+    #[test]
+    fn test_from_iter() {
+        let list = LinkedList::from_vec(vec![1, 2, 3]);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.front(), Some(&3));
+        assert_eq!(list.back(), Some(&1));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [3, 2, 1]);
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), [3, 2, 1]);
+
+        // `collect()` keeps the same reversed order, while `extend()` appends.
+        let mut list: LinkedList<i32> = (1..4).collect();
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [3, 2, 1]);
+        list.extend([4, 5]);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [3, 2, 1, 4, 5]);
+    }
+
+    #[test]
+    fn test_pop_back() {
+        let mut list: LinkedList<i32> = (0..4).rev().collect();
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.back(), Some(&2));
+        list.push_back(4);
+        assert_eq!(list.pop_back(), Some(4));
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_back(), Some(1));
+        assert_eq!(list.pop_back(), Some(0));
+        assert_eq!(list.pop_back(), None);
+        assert!(list.is_empty());
+        assert_eq!(list.front(), None);
+        list.push_back(5);
+        assert_eq!(list.front(), Some(&5));
+        assert_eq!(list.back(), Some(&5));
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut list: LinkedList<i32> = (0..5).rev().collect();
+        list.reverse();
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [4, 3, 2, 1, 0]);
+        assert_eq!(list.back(), Some(&0));
+        list.push_back(-1);
+        assert_eq!(list.pop_front(), Some(4));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [3, 2, 1, 0, -1]);
+    }
+
+    #[test]
+    fn test_front_back_mut() {
+        let mut list: LinkedList<i32> = (1..4).rev().collect();
+        if let Some(mut value) = list.front_mut() {
+            *value *= 10;
+        }
+        if let Some(mut value) = list.back_mut() {
+            *value *= 100;
+        }
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [10, 2, 300]);
+        let mut empty = LinkedList::<i32>::new();
+        assert!(empty.front_mut().is_none());
+        assert!(empty.back_mut().is_none());
+    }
 }
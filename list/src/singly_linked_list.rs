@@ -1,4 +1,654 @@
-// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
-// Use of this source is governed by General Public License that can be found
-// in the LICENSE file.
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be
+// found in the LICENSE file.
 
+//! Singly linked list.
+//!
+//! Each node owns the next node with a `Box`, so the list is always acyclic.
+//! To detect cycles in other linked structures, see [`find_cycle()`].
+
+use std::fmt;
+
+type ListNodePtr<T> = Option<Box<ListNode<T>>>;
+
+pub struct LinkedList<T> {
+    length: usize,
+    head: ListNodePtr<T>,
+}
+
+struct ListNode<T> {
+    value: T,
+    next: ListNodePtr<T>,
+}
+
+pub struct IntoIter<T>(LinkedList<T>);
+
+pub struct Iter<'a, T> {
+    next: Option<&'a ListNode<T>>,
+}
+
+pub struct IterMut<'a, T> {
+    next: Option<&'a mut ListNode<T>>,
+}
+
+impl<T> ListNode<T> {
+    #[must_use]
+    #[allow(clippy::unnecessary_box_returns)]
+    pub fn new(value: T) -> Box<Self> {
+        Box::new(Self { value, next: None })
+    }
+
+    #[must_use]
+    #[allow(clippy::unnecessary_box_returns)]
+    pub fn with_next(value: T, next: ListNodePtr<T>) -> Box<Self> {
+        Box::new(Self { value, next })
+    }
+}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> LinkedList<T> {
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            length: 0,
+            head: None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.length
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Add a new node to head of list.
+    pub fn push(&mut self, value: T) {
+        let head = ListNode::with_next(value, self.head.take());
+        self.head = Some(head);
+        self.length += 1;
+    }
+
+    /// Remove the head node and return the value.
+    #[must_use]
+    pub fn pop(&mut self) -> Option<T> {
+        self.head.take().map(|head| {
+            self.head = head.next;
+            self.length -= 1;
+            head.value
+        })
+    }
+
+    /// Add a new node to tail of list.
+    ///
+    /// Time is O(n).
+    pub fn push_back(&mut self, value: T) {
+        *self.tail_link() = Some(ListNode::new(value));
+        self.length += 1;
+    }
+
+    /// Insert the value at specific position in list.
+    ///
+    /// Time is O(n).
+    ///
+    /// # Panics
+    ///
+    /// Panic if `pos > len`.
+    pub fn insert_at(&mut self, pos: usize, value: T) {
+        assert!(pos <= self.length);
+        let link = self.link_at(pos);
+        let node = ListNode::with_next(value, link.take());
+        *link = Some(node);
+        self.length += 1;
+    }
+
+    /// Remove a node at position and returns its value.
+    ///
+    /// Returns None if `pos >= len`.
+    pub fn remove_at(&mut self, pos: usize) -> Option<T> {
+        if pos >= self.length {
+            return None;
+        }
+        let link = self.link_at(pos);
+        let node = link.take()?;
+        *link = node.next;
+        self.length -= 1;
+        Some(node.value)
+    }
+
+    /// Reverse orders of node in list.
+    ///
+    /// Time is O(n).
+    pub fn reverse(&mut self) {
+        let mut prev: ListNodePtr<T> = None;
+        let mut node = self.head.take();
+        while let Some(mut current) = node {
+            node = current.next.take();
+            current.next = prev;
+            prev = Some(current);
+        }
+        self.head = prev;
+    }
+
+    /// Splits the list into two at the given index.
+    ///
+    /// Returns everything after the given index, including the index.
+    ///
+    /// # Panics
+    ///
+    /// Panic if `at > len`.
+    #[must_use]
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.length);
+        let head = self.link_at(at).take();
+        let other = Self {
+            length: self.length - at,
+            head,
+        };
+        self.length = at;
+        other
+    }
+
+    /// Move all elements of `other` to the end of list.
+    ///
+    /// Time is O(n), as it has to walk to the tail of list.
+    pub fn append(&mut self, other: &mut Self) {
+        *self.tail_link() = other.head.take();
+        self.length += other.length;
+        other.length = 0;
+    }
+
+    /// Get reference of value in head node in list.
+    #[must_use]
+    pub fn head(&self) -> Option<&T> {
+        self.head.as_ref().map(|head| &head.value)
+    }
+
+    /// Get mutable reference of value in head node in list.
+    #[must_use]
+    pub fn head_mut(&mut self) -> Option<&mut T> {
+        self.head.as_mut().map(|head| &mut head.value)
+    }
+
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+        }
+    }
+
+    #[must_use]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            next: self.head.as_deref_mut(),
+        }
+    }
+}
+
+// Private functions for list.
+impl<T> LinkedList<T> {
+    /// Returns the link which points to node at `pos`.
+    fn link_at(&mut self, pos: usize) -> &mut ListNodePtr<T> {
+        let mut link = &mut self.head;
+        for _ in 0..pos {
+            match link {
+                Some(node) => link = &mut node.next,
+                None => break,
+            }
+        }
+        link
+    }
+
+    /// Returns the `next` link of the last node, or head if list is empty.
+    fn tail_link(&mut self) -> &mut ListNodePtr<T> {
+        let mut link = &mut self.head;
+        while let Some(node) = link {
+            link = &mut node.next;
+        }
+        link
+    }
+}
+
+impl<T> Drop for LinkedList<T> {
+    fn drop(&mut self) {
+        let mut node = self.head.take();
+        while let Some(mut boxed_node) = node {
+            node = boxed_node.next.take();
+            // No need to drop boxed_node explicitly.
+            //drop(boxed_node);
+        }
+    }
+}
+
+// Or use move
+//impl<T> Drop for LinkedList<T> {
+//    fn drop(&mut self) {
+//        let mut node = self.head.take();
+//        while let Some(boxed_node) = node {
+//            // Partial move.
+//            node = boxed_node.next;
+//        }
+//    }
+//}
+
+impl<T> LinkedList<T>
+where
+    T: PartialEq,
+{
+    /// Returns position of value in list.
+    ///
+    /// Returns None if not found.
+    pub fn find(&self, value: &T) -> Option<usize> {
+        self.iter().position(|item| item == value)
+    }
+
+    /// Delete the first node from list with same value.
+    ///
+    /// Returns position of the deleted node.
+    pub fn remove(&mut self, value: &T) -> Option<usize> {
+        let pos = self.find(value)?;
+        self.remove_at(pos);
+        Some(pos)
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    /// Keeps the order of elements in `iter`.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut link = self.tail_link();
+        let mut count = 0;
+        for value in iter {
+            let node = link.insert(ListNode::new(value));
+            link = &mut node.next;
+            count += 1;
+        }
+        self.length += count;
+    }
+}
+
+impl<T: Clone> Clone for LinkedList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: PartialEq> PartialEq for LinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.length == other.length && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for LinkedList<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for LinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+/// A cycle found by [`find_cycle()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// Number of steps from head to the first node in cycle.
+    pub start: usize,
+    /// Number of nodes in cycle.
+    pub length: usize,
+}
+
+/// Detect cycle in a linked structure with Floyd's tortoise and hare algorithm.
+///
+/// Nodes are identified by `N`, which can be an index or a pointer, and
+/// `next` returns the node after the given one, or `None` at the end.
+///
+/// Time is O(n) and extra space is O(1).
+pub fn find_cycle<N, F>(head: Option<N>, next: F) -> Option<Cycle>
+where
+    N: Copy + PartialEq,
+    F: Fn(N) -> Option<N>,
+{
+    // Phase 1: hare moves two steps while tortoise moves one step,
+    // they meet inside the cycle if there is one.
+    let mut tortoise = head?;
+    let mut hare = head?;
+    loop {
+        tortoise = next(tortoise)?;
+        hare = next(next(hare)?)?;
+        if tortoise == hare {
+            break;
+        }
+    }
+
+    // Phase 2: distance from head to start of cycle equals distance from
+    // meeting point to start of cycle, modulo length of cycle.
+    let mut start = 0;
+    tortoise = head?;
+    while tortoise != hare {
+        tortoise = next(tortoise)?;
+        hare = next(hare)?;
+        start += 1;
+    }
+
+    // Phase 3: walk around the cycle once.
+    let mut length = 1;
+    hare = next(tortoise)?;
+    while tortoise != hare {
+        hare = next(hare)?;
+        length += 1;
+    }
+
+    Some(Cycle { start, length })
+}
+
+/// Check whether there is a cycle in a linked structure.
+pub fn has_cycle<N, F>(head: Option<N>, next: F) -> bool
+where
+    N: Copy + PartialEq,
+    F: Fn(N) -> Option<N>,
+{
+    find_cycle(head, next).is_some()
+}
+
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            self.next = node.next.as_deref();
+            &node.value
+        })
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.take().map(|node| {
+            self.next = node.next.as_deref_mut();
+            &mut node.value
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_cycle, has_cycle, Cycle, LinkedList, ListNode};
+
+    #[test]
+    fn test_new() {
+        let list = LinkedList::<i32>::new();
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_push() {
+        let mut list = LinkedList::new();
+        list.push(2);
+        list.push(3);
+        list.push(5);
+        list.push(7);
+        list.push(11);
+        assert_eq!(list.len(), 5);
+    }
+
+    #[test]
+    fn test_pop() {
+        let mut list = LinkedList::new();
+        list.push(5);
+        list.push(7);
+        assert_eq!(list.pop(), Some(7));
+        assert_eq!(list.len(), 1);
+        let _ = list.pop();
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_drop() {
+        // The default recursive limit rustc-v1.74 is 128.
+        // See https://doc.rust-lang.org/reference/attributes/limits.html
+        let mut list = LinkedList::new();
+        for i in 0..(128 * 200) {
+            list.push(i);
+        }
+        drop(list);
+    }
+
+    #[test]
+    fn test_head() {
+        let mut list = LinkedList::new();
+        list.push(5);
+        list.push(7);
+        assert_eq!(list.head(), Some(&7));
+    }
+
+    #[test]
+    fn test_head_mut() {
+        let mut list = LinkedList::new();
+        list.push(5);
+        list.push(7);
+        if let Some(value) = list.head_mut() {
+            *value = 11;
+        }
+        // Option::replace() will not work, as it requires `&mut Option<T>`.
+        // list.head_mut().replace(11);
+        assert_eq!(list.head(), Some(&11));
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut list = LinkedList::new();
+        list.push(2);
+        list.push(3);
+        list.push(5);
+        list.push(7);
+        let mut iter = list.into_iter();
+        assert_eq!(iter.next(), Some(7));
+        assert_eq!(iter.next(), Some(5));
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut list = LinkedList::new();
+        list.push(2);
+        list.push(3);
+        list.push(5);
+        list.push(7);
+        let nums = &[7, 5, 3, 2];
+        for (val, num) in list.iter().zip(nums) {
+            assert_eq!(val, num);
+        }
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut list = LinkedList::new();
+        list.push(2);
+        list.push(3);
+        list.push(5);
+        list.push(7);
+        for val in &mut list {
+            *val *= 2;
+        }
+        let nums = &[14, 10, 6, 4];
+        for (val, num) in list.iter().zip(nums) {
+            assert_eq!(val, num);
+        }
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let list = LinkedList::from_iter([1, 2, 3]);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(list.clone(), list);
+    }
+
+    #[test]
+    fn test_insert_remove_at() {
+        let mut list = LinkedList::from_iter([1, 3]);
+        list.insert_at(1, 2);
+        list.insert_at(0, 0);
+        list.insert_at(4, 4);
+        list.push_back(5);
+        assert_eq!(list.len(), 6);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(list.remove_at(6), None);
+        assert_eq!(list.remove_at(5), Some(5));
+        assert_eq!(list.remove_at(0), Some(0));
+        assert_eq!(list.remove_at(1), Some(2));
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [1, 3, 4]);
+    }
+
+    #[test]
+    fn test_find_remove() {
+        let mut list = LinkedList::from_iter([5, 7, 9, 7]);
+        assert_eq!(list.find(&7), Some(1));
+        assert_eq!(list.find(&8), None);
+        assert_eq!(list.remove(&7), Some(1));
+        assert_eq!(list.find(&7), Some(2));
+        assert_eq!(list.remove(&8), None);
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut list: LinkedList<i32> = (0..5).collect();
+        list.reverse();
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [4, 3, 2, 1, 0]);
+        let mut empty = LinkedList::<i32>::new();
+        empty.reverse();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_split_off_append() {
+        let mut list: LinkedList<i32> = (0..6).collect();
+        let mut tail = list.split_off(4);
+        assert_eq!(list.len(), 4);
+        assert_eq!(tail, LinkedList::from_iter([4, 5]));
+        let mut all = list.split_off(0);
+        assert!(list.is_empty());
+        all.append(&mut tail);
+        assert!(tail.is_empty());
+        list.append(&mut all);
+        assert_eq!(list.len(), 6);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [0, 1, 2, 3, 4, 5]);
+        list.extend([6, 7]);
+        assert_eq!(list.len(), 8);
+        assert_eq!(format!("{list:?}"), "[0, 1, 2, 3, 4, 5, 6, 7]");
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        // Nodes stored in an array, `next[i]` is the index of next node.
+        //   0 -> 1 -> 2 -> 3 -> 4 -> 5
+        //                  ^         |
+        //                  +---------+
+        let next = [Some(1), Some(2), Some(3), Some(4), Some(5), Some(3)];
+        let cycle = find_cycle(Some(0), |i: usize| next[i]);
+        assert_eq!(
+            cycle,
+            Some(Cycle {
+                start: 3,
+                length: 3
+            })
+        );
+
+        // A self loop.
+        let next = [Some(1), Some(1)];
+        assert_eq!(
+            find_cycle(Some(0), |i: usize| next[i]),
+            Some(Cycle {
+                start: 1,
+                length: 1
+            })
+        );
+
+        let next = [Some(1), Some(2), None];
+        assert!(!has_cycle(Some(0), |i: usize| next[i]));
+        assert!(!has_cycle(None, |i: usize| next[i]));
+
+        // Function iteration, x -> (x * x + 1) mod 255.
+        let cycle = find_cycle(Some(3_u32), |x| Some((x * x + 1) % 255)).unwrap();
+        let mut seen = vec![3_u32];
+        while !seen.contains(&((seen[seen.len() - 1].pow(2) + 1) % 255)) {
+            seen.push((seen[seen.len() - 1].pow(2) + 1) % 255);
+        }
+        let repeated = (seen[seen.len() - 1].pow(2) + 1) % 255;
+        let start = seen.iter().position(|&x| x == repeated).unwrap();
+        assert_eq!(
+            cycle,
+            Cycle {
+                start,
+                length: seen.len() - start
+            }
+        );
+    }
+
+    #[test]
+    fn test_list_is_acyclic() {
+        let list = LinkedList::from_iter([1, 2, 3]);
+        let head = list.head.as_deref().map(std::ptr::from_ref);
+        let next =
+            |node: *const ListNode<i32>| unsafe { (*node).next.as_deref().map(std::ptr::from_ref) };
+        assert!(!has_cycle(head, next));
+    }
+}
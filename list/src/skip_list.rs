@@ -0,0 +1,997 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! Skip list map and set.
+//!
+//! Nodes are stored in an arena (`Vec`) and linked by index. Each forward link
+//! also records its span, the number of nodes it skips over at the bottom
+//! level, so that `rank()` and `select()` are O(log n) like searching.
+
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::ops::{Bound, RangeBounds};
+
+/// Default max level of skip list, good enough for 2^16 elements with p = 0.5.
+pub const DEFAULT_MAX_LEVEL: usize = 16;
+
+/// Default probability to promote a node to the next level.
+pub const DEFAULT_P: f64 = 0.5;
+
+/// Index of the head node in arena.
+const HEAD: usize = 0;
+
+/// Xorshift pseudo random number generator.
+#[derive(Debug, Clone)]
+struct XorShiftRng(u64);
+
+impl XorShiftRng {
+    const fn new(seed: u64) -> Self {
+        // Zero is a fixed point of xorshift.
+        if seed == 0 {
+            Self(0x9e37_79b9_7f4a_7c15)
+        } else {
+            Self(seed)
+        }
+    }
+
+    fn from_entropy() -> Self {
+        Self::new(RandomState::new().hash_one(0_u64))
+    }
+
+    const fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    /// Returns a float in `[0, 1)`.
+    #[allow(clippy::cast_precision_loss)]
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node<K, V> {
+    /// Head node and free nodes have no entry.
+    entry: Option<(K, V)>,
+    /// Forward link at each level.
+    next: Vec<Option<usize>>,
+    /// Number of nodes skipped by `next` at each level.
+    span: Vec<usize>,
+}
+
+impl<K, V> Node<K, V> {
+    fn new(entry: Option<(K, V)>, level: usize) -> Self {
+        Self {
+            entry,
+            next: vec![None; level],
+            span: vec![0; level],
+        }
+    }
+}
+
+/// An ordered map based on skip list.
+#[derive(Clone)]
+pub struct SkipListMap<K, V> {
+    nodes: Vec<Node<K, V>>,
+    free: Vec<usize>,
+    /// Number of levels in use, at least 1.
+    level: usize,
+    len: usize,
+    max_level: usize,
+    p: f64,
+    rng: XorShiftRng,
+}
+
+impl<K, V> Default for SkipListMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> SkipListMap<K, V> {
+    /// Create an empty map with default max level and probability.
+    ///
+    /// The random number generator is seeded randomly.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_config(DEFAULT_MAX_LEVEL, DEFAULT_P)
+    }
+
+    /// Create an empty map with `max_level` and probability `p`.
+    ///
+    /// # Panics
+    ///
+    /// Panic if `max_level` is 0 or `p` is not in range `(0, 1)`.
+    #[must_use]
+    pub fn with_config(max_level: usize, p: f64) -> Self {
+        Self::with_rng(max_level, p, XorShiftRng::from_entropy())
+    }
+
+    /// Create an empty map with a seeded random number generator,
+    /// so that levels of nodes are reproducible.
+    ///
+    /// # Panics
+    ///
+    /// Panic if `max_level` is 0 or `p` is not in range `(0, 1)`.
+    #[must_use]
+    pub fn with_seed(max_level: usize, p: f64, seed: u64) -> Self {
+        Self::with_rng(max_level, p, XorShiftRng::new(seed))
+    }
+
+    fn with_rng(max_level: usize, p: f64, rng: XorShiftRng) -> Self {
+        assert!(max_level > 0, "max_level must be positive");
+        assert!(p > 0.0 && p < 1.0, "p must be in range (0, 1)");
+        Self {
+            nodes: vec![Node::new(None, max_level)],
+            free: Vec::new(),
+            level: 1,
+            len: 0,
+            max_level,
+            p,
+            rng,
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn max_level(&self) -> usize {
+        self.max_level
+    }
+
+    /// Remove all elements, keeps config and state of random number generator.
+    pub fn clear(&mut self) {
+        self.nodes.truncate(1);
+        self.nodes[HEAD] = Node::new(None, self.max_level);
+        self.free.clear();
+        self.level = 1;
+        self.len = 0;
+    }
+
+    /// Returns the first key-value pair, the minimum one.
+    #[must_use]
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.nodes[HEAD].next[0].map(|index| self.pair(index))
+    }
+
+    /// Returns the last key-value pair, the maximum one.
+    ///
+    /// Time is O(log n).
+    #[must_use]
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.nodes[x].next[i] {
+                x = next;
+            }
+        }
+        (x != HEAD).then(|| self.pair(x))
+    }
+
+    /// Returns the key-value pair at `index` in sorted order.
+    ///
+    /// Time is O(log n).
+    #[must_use]
+    pub fn select(&self, index: usize) -> Option<(&K, &V)> {
+        if index >= self.len {
+            return None;
+        }
+        let target = index + 1;
+        let mut traversed = 0;
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.nodes[x].next[i] {
+                if traversed + self.nodes[x].span[i] > target {
+                    break;
+                }
+                traversed += self.nodes[x].span[i];
+                x = next;
+            }
+            if traversed == target {
+                return Some(self.pair(x));
+            }
+        }
+        None
+    }
+
+    /// Gets an iterator over entries of the map, sorted by key.
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            map: self,
+            next: self.nodes[HEAD].next[0],
+            end: None,
+            remaining: self.len,
+        }
+    }
+
+    /// Gets an iterator over keys of the map, in sorted order.
+    #[must_use]
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys(self.iter())
+    }
+
+    /// Gets an iterator over values of the map, sorted by key.
+    #[must_use]
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values(self.iter())
+    }
+
+    /// Remove the first element and return it.
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        let first = self.nodes[HEAD].next[0]?;
+        // All links pointing to the first node start from head.
+        let update = vec![HEAD; self.level];
+        Some(self.unlink(first, &update))
+    }
+
+    fn pair(&self, index: usize) -> (&K, &V) {
+        let (key, value) = self.nodes[index]
+            .entry
+            .as_ref()
+            .expect("linked node has an entry");
+        (key, value)
+    }
+
+    fn key(&self, index: usize) -> &K {
+        self.pair(index).0
+    }
+
+    fn random_level(&mut self) -> usize {
+        let mut level = 1;
+        while level < self.max_level && self.rng.next_f64() < self.p {
+            level += 1;
+        }
+        level
+    }
+
+    /// Returns the first node whose key is not `before`, with all nodes
+    /// whose key is `before` being placed ahead of it.
+    fn find_first<F>(&self, before: F) -> Option<usize>
+    where
+        F: Fn(&K) -> bool,
+    {
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.nodes[x].next[i] {
+                if !before(self.key(next)) {
+                    break;
+                }
+                x = next;
+            }
+        }
+        self.nodes[x].next[0]
+    }
+
+    /// Store a new node in arena and returns its index.
+    fn alloc(&mut self, key: K, value: V, level: usize) -> usize {
+        let node = Node::new(Some((key, value)), level);
+        if let Some(index) = self.free.pop() {
+            self.nodes[index] = node;
+            index
+        } else {
+            self.nodes.push(node);
+            self.nodes.len() - 1
+        }
+    }
+
+    /// Remove node `x` from list, `update[i]` is the node before `x` at level `i`.
+    fn unlink(&mut self, x: usize, update: &[usize]) -> (K, V) {
+        for (i, &prev) in update.iter().enumerate().take(self.level) {
+            if self.nodes[prev].next[i] == Some(x) {
+                self.nodes[prev].span[i] += self.nodes[x].span[i];
+                self.nodes[prev].span[i] -= 1;
+                self.nodes[prev].next[i] = self.nodes[x].next[i];
+            } else {
+                self.nodes[prev].span[i] -= 1;
+            }
+        }
+        while self.level > 1 && self.nodes[HEAD].next[self.level - 1].is_none() {
+            self.level -= 1;
+        }
+        self.len -= 1;
+
+        let node = std::mem::replace(&mut self.nodes[x], Node::new(None, 0));
+        self.free.push(x);
+        node.entry.expect("linked node has an entry")
+    }
+}
+
+impl<K: Ord, V> SkipListMap<K, V> {
+    /// Insert a key-value pair into the map.
+    ///
+    /// If the map already had this key, the value is updated and the old
+    /// value is returned.
+    ///
+    /// Time is O(log n) on average.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let mut update = vec![HEAD; self.max_level];
+        let mut rank = vec![0; self.max_level];
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            rank[i] = if i + 1 == self.level { 0 } else { rank[i + 1] };
+            while let Some(next) = self.nodes[x].next[i] {
+                if self.key(next) >= &key {
+                    break;
+                }
+                rank[i] += self.nodes[x].span[i];
+                x = next;
+            }
+            update[i] = x;
+        }
+
+        if let Some(next) = self.nodes[x].next[0] {
+            if self.key(next) == &key {
+                let entry = self.nodes[next].entry.as_mut()?;
+                return Some(std::mem::replace(&mut entry.1, value));
+            }
+        }
+
+        let level = self.random_level();
+        if level > self.level {
+            for i in self.level..level {
+                rank[i] = 0;
+                update[i] = HEAD;
+                self.nodes[HEAD].span[i] = self.len;
+            }
+            self.level = level;
+        }
+
+        let x = self.alloc(key, value, level);
+        for i in 0..level {
+            let prev = update[i];
+            let skipped = rank[0] - rank[i];
+            self.nodes[x].next[i] = self.nodes[prev].next[i];
+            self.nodes[x].span[i] = self.nodes[prev].span[i] - skipped;
+            self.nodes[prev].next[i] = Some(x);
+            self.nodes[prev].span[i] = skipped + 1;
+        }
+        for (i, &prev) in update.iter().enumerate().take(self.level).skip(level) {
+            self.nodes[prev].span[i] += 1;
+        }
+        self.len += 1;
+        None
+    }
+
+    /// Remove a key from the map, returning the value if it was in the map.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_key, value)| value)
+    }
+
+    /// Remove a key from the map, returning the stored key and value.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut update = vec![HEAD; self.level];
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.nodes[x].next[i] {
+                if self.key(next).borrow() >= key {
+                    break;
+                }
+                x = next;
+            }
+            update[i] = x;
+        }
+        let next = self.nodes[x].next[0]?;
+        if self.key(next).borrow() == key {
+            Some(self.unlink(next, &update))
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the value of the key.
+    #[must_use]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).map(|index| self.pair(index).1)
+    }
+
+    /// Returns a mutable reference to the value of the key.
+    #[must_use]
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.find(key)?;
+        self.nodes[index].entry.as_mut().map(|(_key, value)| value)
+    }
+
+    #[must_use]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Returns index of the key in sorted order.
+    ///
+    /// Time is O(log n).
+    #[must_use]
+    pub fn rank<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut rank = 0;
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.nodes[x].next[i] {
+                if self.key(next).borrow() > key {
+                    break;
+                }
+                rank += self.nodes[x].span[i];
+                x = next;
+            }
+            if x != HEAD && self.key(x).borrow() == key {
+                return Some(rank - 1);
+            }
+        }
+        None
+    }
+
+    /// Gets an iterator over a sub-range of entries in the map.
+    ///
+    /// Time is O(log n) to locate both ends of range.
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let next = match range.start_bound() {
+            Bound::Included(start) => self.find_first(|key| key.borrow() < start),
+            Bound::Excluded(start) => self.find_first(|key| key.borrow() <= start),
+            Bound::Unbounded => self.nodes[HEAD].next[0],
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => self.find_first(|key| key.borrow() <= end),
+            Bound::Excluded(end) => self.find_first(|key| key.borrow() < end),
+            Bound::Unbounded => None,
+        };
+        let remaining = match (next, end) {
+            (None, _) => 0,
+            (Some(first), None) => self.len - self.index_of(first),
+            (Some(first), Some(end)) => self.index_of(end).saturating_sub(self.index_of(first)),
+        };
+        Iter {
+            map: self,
+            next: if remaining == 0 { None } else { next },
+            end,
+            remaining,
+        }
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.find_first(|k| k.borrow() < key)?;
+        (self.key(index).borrow() == key).then_some(index)
+    }
+
+    /// Returns index of a linked node in sorted order.
+    fn index_of(&self, node: usize) -> usize {
+        let key = self.key(node);
+        let mut rank = 0;
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.nodes[x].next[i] {
+                if self.key(next) > key {
+                    break;
+                }
+                rank += self.nodes[x].span[i];
+                x = next;
+            }
+        }
+        rank - 1
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for SkipListMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for SkipListMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for SkipListMap<K, V> {}
+
+impl<K: Ord, V> FromIterator<(K, V)> for SkipListMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for SkipListMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a SkipListMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K, V> IntoIterator for SkipListMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+/// An iterator over entries of [`SkipListMap`], sorted by key.
+pub struct Iter<'a, K, V> {
+    map: &'a SkipListMap<K, V>,
+    next: Option<usize>,
+    /// Stop before this node.
+    end: Option<usize>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.filter(|&current| Some(current) != self.end)?;
+        self.next = self.map.nodes[current].next[0];
+        self.remaining -= 1;
+        Some(self.map.pair(current))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+/// An iterator over keys of [`SkipListMap`].
+pub struct Keys<'a, K, V>(Iter<'a, K, V>);
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, _value)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// An iterator over values of [`SkipListMap`].
+pub struct Values<'a, K, V>(Iter<'a, K, V>);
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(_key, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// An owning iterator over entries of [`SkipListMap`], sorted by key.
+pub struct IntoIter<K, V>(SkipListMap<K, V>);
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_first()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+/// An ordered set based on skip list.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SkipListSet<K> {
+    map: SkipListMap<K, ()>,
+}
+
+impl<K> Default for SkipListSet<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> SkipListSet<K> {
+    /// Create an empty set with default max level and probability.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            map: SkipListMap::new(),
+        }
+    }
+
+    /// Create an empty set with `max_level` and probability `p`.
+    ///
+    /// # Panics
+    ///
+    /// Panic if `max_level` is 0 or `p` is not in range `(0, 1)`.
+    #[must_use]
+    pub fn with_config(max_level: usize, p: f64) -> Self {
+        Self {
+            map: SkipListMap::with_config(max_level, p),
+        }
+    }
+
+    /// Create an empty set with a seeded random number generator.
+    ///
+    /// # Panics
+    ///
+    /// Panic if `max_level` is 0 or `p` is not in range `(0, 1)`.
+    #[must_use]
+    pub fn with_seed(max_level: usize, p: f64, seed: u64) -> Self {
+        Self {
+            map: SkipListMap::with_seed(max_level, p, seed),
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    #[must_use]
+    pub fn first(&self) -> Option<&K> {
+        self.map.first_key_value().map(|(key, ())| key)
+    }
+
+    #[must_use]
+    pub fn last(&self) -> Option<&K> {
+        self.map.last_key_value().map(|(key, ())| key)
+    }
+
+    pub fn pop_first(&mut self) -> Option<K> {
+        self.map.pop_first().map(|(key, ())| key)
+    }
+
+    /// Returns the key at `index` in sorted order.
+    #[must_use]
+    pub fn select(&self, index: usize) -> Option<&K> {
+        self.map.select(index).map(|(key, ())| key)
+    }
+
+    #[must_use]
+    pub fn iter(&self) -> SetIter<'_, K> {
+        SetIter(self.map.keys())
+    }
+}
+
+impl<K: Ord> SkipListSet<K> {
+    /// Adds a value to the set.
+    ///
+    /// Returns whether the value was newly inserted.
+    pub fn insert(&mut self, key: K) -> bool {
+        self.map.insert(key, ()).is_none()
+    }
+
+    /// Returns whether the value was present in the set.
+    pub fn remove<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove(key).is_some()
+    }
+
+    #[must_use]
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Returns index of the key in sorted order.
+    #[must_use]
+    pub fn rank<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.rank(key)
+    }
+
+    /// Gets an iterator over a sub-range of keys in the set.
+    pub fn range<Q, R>(&self, range: R) -> SetIter<'_, K>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        SetIter(Keys(self.map.range(range)))
+    }
+}
+
+impl<K: fmt::Debug> fmt::Debug for SkipListSet<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Ord> FromIterator<K> for SkipListSet<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<K: Ord> Extend<K> for SkipListSet<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for key in iter {
+            self.insert(key);
+        }
+    }
+}
+
+impl<'a, K> IntoIterator for &'a SkipListSet<K> {
+    type Item = &'a K;
+    type IntoIter = SetIter<'a, K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over keys of [`SkipListSet`], in sorted order.
+pub struct SetIter<'a, K>(Keys<'a, K, ()>);
+
+impl<'a, K> Iterator for SetIter<'a, K> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{SkipListMap, SkipListSet, XorShiftRng};
+
+    /// Check links and spans of every level against the bottom level.
+    fn check_map<K: Ord, V>(map: &SkipListMap<K, V>) {
+        let order: Vec<usize> = {
+            let mut order = vec![super::HEAD];
+            let mut x = map.nodes[super::HEAD].next[0];
+            while let Some(index) = x {
+                order.push(index);
+                x = map.nodes[index].next[0];
+            }
+            order
+        };
+        assert_eq!(order.len(), map.len() + 1);
+        assert!(order[1..].windows(2).all(|w| map.key(w[0]) < map.key(w[1])));
+        let position = |index: usize| order.iter().position(|&x| x == index).unwrap();
+
+        for i in 0..map.level {
+            let mut x = super::HEAD;
+            while let Some(next) = map.nodes[x].next[i] {
+                assert_eq!(map.nodes[x].span[i], position(next) - position(x));
+                x = next;
+            }
+        }
+    }
+
+    #[test]
+    fn test_insert_get() {
+        let mut map = SkipListMap::with_seed(8, 0.5, 42);
+        assert!(map.is_empty());
+        assert_eq!(map.insert(3, "c"), None);
+        assert_eq!(map.insert(1, "a"), None);
+        assert_eq!(map.insert(2, "b"), None);
+        assert_eq!(map.insert(2, "B"), Some("b"));
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get(&2), Some(&"B"));
+        assert_eq!(map.get(&4), None);
+        assert!(map.contains_key(&1));
+        if let Some(value) = map.get_mut(&1) {
+            *value = "A";
+        }
+        assert_eq!(map.first_key_value(), Some((&1, &"A")));
+        assert_eq!(map.last_key_value(), Some((&3, &"c")));
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(map.values().copied().collect::<Vec<_>>(), ["A", "B", "c"]);
+        assert_eq!(format!("{map:?}"), r#"{1: "A", 2: "B", 3: "c"}"#);
+        check_map(&map);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut map: SkipListMap<i32, i32> = (0..100).map(|i| (i, i * i)).collect();
+        for i in (0..100).step_by(3) {
+            assert_eq!(map.remove(&i), Some(i * i));
+            assert_eq!(map.remove(&i), None);
+        }
+        check_map(&map);
+        assert_eq!(map.len(), 66);
+        assert_eq!(map.pop_first(), Some((1, 1)));
+        assert_eq!(map.first_key_value(), Some((&2, &4)));
+        // Removed slots are reused.
+        let capacity = map.nodes.len();
+        map.insert(0, 0);
+        map.insert(3, 9);
+        assert_eq!(map.nodes.len(), capacity);
+        check_map(&map);
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.first_key_value(), None);
+        assert_eq!(map.last_key_value(), None);
+    }
+
+    #[test]
+    fn test_rank_select() {
+        let map: SkipListMap<i32, ()> = (0..200).map(|i| (i * 2, ())).collect();
+        for (index, i) in (0..200).enumerate() {
+            assert_eq!(map.rank(&(i * 2)), Some(index));
+            assert_eq!(map.rank(&(i * 2 + 1)), None);
+            assert_eq!(map.select(index), Some((&(i * 2), &())));
+        }
+        assert_eq!(map.select(200), None);
+    }
+
+    #[test]
+    fn test_range() {
+        let map: SkipListMap<i32, ()> = (0..20).map(|i| (i * 5, ())).collect();
+        let keys = |iter: super::Iter<'_, i32, ()>| iter.map(|(k, ())| *k).collect::<Vec<_>>();
+        assert_eq!(keys(map.range(10..25)), [10, 15, 20]);
+        assert_eq!(keys(map.range(11..=25)), [15, 20, 25]);
+        assert_eq!(keys(map.range(..10)), [0, 5]);
+        assert_eq!(keys(map.range(90..)), [90, 95]);
+        assert_eq!(keys(map.range(96..)), []);
+        assert_eq!(keys(map.range(30..30)), []);
+        assert_eq!(map.range(12..43).len(), 6);
+        assert_eq!(map.range(..).len(), 20);
+        assert_eq!(
+            keys(map.range((std::ops::Bound::Excluded(10), std::ops::Bound::Included(20)))),
+            [15, 20]
+        );
+    }
+
+    #[test]
+    fn test_seed_is_deterministic() {
+        let levels = |seed| {
+            let mut map = SkipListMap::with_seed(12, 0.25, seed);
+            for i in 0..500 {
+                map.insert(i, ());
+            }
+            map.nodes
+                .iter()
+                .map(|node| node.next.len())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(levels(7), levels(7));
+        assert_ne!(levels(7), levels(8));
+        assert!(levels(7).iter().all(|&level| level <= 12));
+
+        let mut rng = XorShiftRng::new(0);
+        assert!((0..1000)
+            .map(|_| rng.next_f64())
+            .all(|x| (0.0..1.0).contains(&x)));
+    }
+
+    #[test]
+    fn test_random_against_btree_map() {
+        let mut map = SkipListMap::with_seed(6, 0.5, 2024);
+        let mut model = BTreeMap::new();
+        let mut rng = XorShiftRng::new(99);
+        for _ in 0..5000 {
+            let key = rng.next_u64() % 300;
+            if rng.next_u64().is_multiple_of(3) {
+                assert_eq!(map.remove(&key), model.remove(&key));
+            } else {
+                assert_eq!(map.insert(key, key + 1), model.insert(key, key + 1));
+            }
+            assert_eq!(map.len(), model.len());
+        }
+        check_map(&map);
+        assert!(map.iter().eq(model.iter()));
+        for (index, key) in model.keys().enumerate() {
+            assert_eq!(map.rank(key), Some(index));
+        }
+        assert!(map.range(50..150).eq(model.range(50..150)));
+        assert!(map.into_iter().eq(model.into_iter()));
+    }
+
+    #[test]
+    fn test_set() {
+        let mut set = SkipListSet::with_seed(8, 0.5, 1);
+        assert!(set.insert("pear"));
+        assert!(set.insert("apple"));
+        assert!(set.insert("fig"));
+        assert!(!set.insert("fig"));
+        assert_eq!(set.len(), 3);
+        assert!(set.contains("apple"));
+        assert_eq!(set.first(), Some(&"apple"));
+        assert_eq!(set.last(), Some(&"pear"));
+        assert_eq!(set.rank("fig"), Some(1));
+        assert_eq!(set.select(2), Some(&"pear"));
+        assert_eq!(set.range("b".."g").copied().collect::<Vec<_>>(), ["fig"]);
+        assert!(set.remove("fig"));
+        assert!(!set.remove("fig"));
+        assert_eq!(format!("{set:?}"), r#"{"apple", "pear"}"#);
+        assert_eq!(set.pop_first(), Some("apple"));
+
+        let set: SkipListSet<i32> = [5, 1, 4, 1, 3].into_iter().collect();
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), [1, 3, 4, 5]);
+        assert_eq!(set, [1, 3, 4, 5].into_iter().collect());
+    }
+}
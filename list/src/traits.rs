@@ -0,0 +1,137 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! Common interface of list variants.
+
+/// A sequence of elements which supports insertion and removal at both ends.
+pub trait List<T> {
+    /// Returns the number of elements in list.
+    fn len(&self) -> usize;
+
+    /// Check whether the list is empty.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Access the first element.
+    fn front(&self) -> Option<&T>;
+
+    /// Access the first element exclusively.
+    fn front_mut(&mut self) -> Option<&mut T>;
+
+    /// Access the last element.
+    fn back(&self) -> Option<&T>;
+
+    /// Access the last element exclusively.
+    fn back_mut(&mut self) -> Option<&mut T>;
+
+    /// Access the element at `pos`.
+    fn get(&self, pos: usize) -> Option<&T>;
+
+    /// Add an element to the beginning of list.
+    fn push_front(&mut self, value: T);
+
+    /// Add an element to the end of list.
+    fn push_back(&mut self, value: T);
+
+    /// Remove the first element in list.
+    fn pop_front(&mut self) -> Option<T>;
+
+    /// Remove the last element in list.
+    fn pop_back(&mut self) -> Option<T>;
+
+    /// Insert element at `pos`.
+    ///
+    /// # Panics
+    ///
+    /// Panic if `pos > len`.
+    fn insert_at(&mut self, pos: usize, value: T);
+
+    /// Remove element at `pos` and returns that element.
+    ///
+    /// # Panics
+    ///
+    /// Panic if `pos >= len`.
+    fn pop_at(&mut self, pos: usize) -> Option<T>;
+
+    /// Remove all elements.
+    fn clear(&mut self);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::List;
+    use crate::circular_linked_list::CircularDoublyLinkedList;
+    use crate::doubly_linked_list::DoublyLinkedList;
+    use crate::unrolled_linked_list::UnrolledLinkedList;
+    use crate::xor_linked_list::XorLinkedList;
+
+    /// Apply random operations to `list` and check it against `VecDeque`.
+    fn check_list<L: List<u32>>(mut list: L) {
+        let mut model = VecDeque::new();
+        let mut seed: u32 = 12345;
+        let mut random = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            seed >> 8
+        };
+
+        for _ in 0..3000 {
+            let value = random();
+            match random() % 8 {
+                0 => {
+                    list.push_front(value);
+                    model.push_front(value);
+                }
+                1 | 2 => {
+                    list.push_back(value);
+                    model.push_back(value);
+                }
+                3 => assert_eq!(list.pop_front(), model.pop_front()),
+                4 => assert_eq!(list.pop_back(), model.pop_back()),
+                5 => {
+                    let pos = value as usize % (model.len() + 1);
+                    list.insert_at(pos, value);
+                    model.insert(pos, value);
+                }
+                6 if !model.is_empty() => {
+                    let pos = value as usize % model.len();
+                    assert_eq!(list.pop_at(pos), model.remove(pos));
+                }
+                _ => {
+                    if let (Some(x), Some(y)) = (list.front_mut(), model.front_mut()) {
+                        *x += 1;
+                        *y += 1;
+                    }
+                    if let (Some(x), Some(y)) = (list.back_mut(), model.back_mut()) {
+                        *x += 1;
+                        *y += 1;
+                    }
+                }
+            }
+            assert_eq!(list.len(), model.len());
+            assert_eq!(list.front(), model.front());
+            assert_eq!(list.back(), model.back());
+        }
+
+        for (pos, value) in model.iter().enumerate() {
+            assert_eq!(list.get(pos), Some(value));
+        }
+        assert_eq!(list.get(model.len()), None);
+        list.clear();
+        assert!(list.is_empty());
+        assert_eq!(list.front(), None);
+    }
+
+    #[test]
+    fn test_all_lists() {
+        check_list(DoublyLinkedList::new());
+        check_list(CircularDoublyLinkedList::new());
+        check_list(XorLinkedList::new());
+        check_list(UnrolledLinkedList::<_, 2>::new());
+        check_list(UnrolledLinkedList::<_, 7>::new());
+        check_list(UnrolledLinkedList::<_, 64>::new());
+    }
+}
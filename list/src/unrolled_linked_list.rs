@@ -0,0 +1,582 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! Unrolled linked list.
+//!
+//! Elements are stored in chunks of at most `N` elements, and chunks are
+//! linked in a [`DoublyLinkedList`]. Elements in the same chunk are contiguous
+//! in memory, which is friendly to cache, and inserting in the middle only
+//! moves elements inside one chunk.
+
+use std::fmt;
+use std::iter::Flatten;
+
+use crate::doubly_linked_list::{self, CursorMut, DoublyLinkedList};
+use crate::traits::List;
+
+/// Invariant: every chunk has `1..=N` elements.
+pub struct UnrolledLinkedList<T, const N: usize> {
+    chunks: DoublyLinkedList<Vec<T>>,
+    len: usize,
+}
+
+pub struct Iter<'a, T> {
+    inner: Flatten<doubly_linked_list::Iter<'a, Vec<T>>>,
+    len: usize,
+}
+
+pub struct IterMut<'a, T> {
+    inner: Flatten<doubly_linked_list::IterMut<'a, Vec<T>>>,
+    len: usize,
+}
+
+pub struct IntoIter<T> {
+    inner: Flatten<doubly_linked_list::IntoIter<Vec<T>>>,
+    len: usize,
+}
+
+// Public functions for list.
+impl<T, const N: usize> UnrolledLinkedList<T, N> {
+    /// Create an empty list.
+    ///
+    /// Capacity of chunk `N` must be at least 2, which is checked at compile time.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        const { assert!(N >= 2, "capacity of chunk must be at least 2") };
+        Self {
+            chunks: DoublyLinkedList::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements in list.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether the list is empty.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of chunks in list.
+    #[must_use]
+    #[inline]
+    pub const fn chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Access the first element.
+    #[must_use]
+    pub fn front(&self) -> Option<&T> {
+        self.chunks.front().and_then(|chunk| chunk.first())
+    }
+
+    /// Access the first element exclusively.
+    #[must_use]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.chunks.front_mut().and_then(|chunk| chunk.first_mut())
+    }
+
+    /// Access the last element.
+    #[must_use]
+    pub fn back(&self) -> Option<&T> {
+        self.chunks.back().and_then(|chunk| chunk.last())
+    }
+
+    /// Access the last element exclusively.
+    #[must_use]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.chunks.back_mut().and_then(|chunk| chunk.last_mut())
+    }
+
+    /// Access the element at `pos`.
+    ///
+    /// Time is O(pos / N).
+    #[must_use]
+    pub fn get(&self, mut pos: usize) -> Option<&T> {
+        for chunk in &self.chunks {
+            if pos < chunk.len() {
+                return chunk.get(pos);
+            }
+            pos -= chunk.len();
+        }
+        None
+    }
+
+    /// Access the element at `pos` exclusively.
+    #[must_use]
+    pub fn get_mut(&mut self, mut pos: usize) -> Option<&mut T> {
+        for chunk in &mut self.chunks {
+            if pos < chunk.len() {
+                return chunk.get_mut(pos);
+            }
+            pos -= chunk.len();
+        }
+        None
+    }
+
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq<T>,
+    {
+        self.chunks.iter().any(|chunk| chunk.contains(value))
+    }
+
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.chunks.iter().flatten(),
+            len: self.len,
+        }
+    }
+
+    #[must_use]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            inner: self.chunks.iter_mut().flatten(),
+            len: self.len,
+        }
+    }
+
+    /// Remove all elements.
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.len = 0;
+    }
+
+    /// Add an element to the beginning of list.
+    pub fn push_front(&mut self, value: T) {
+        match self.chunks.front_mut() {
+            Some(chunk) if chunk.len() < N => chunk.insert(0, value),
+            _ => self.chunks.push_front(Self::new_chunk(value)),
+        }
+        self.len += 1;
+    }
+
+    /// Add an element to the end of list.
+    pub fn push_back(&mut self, value: T) {
+        match self.chunks.back_mut() {
+            Some(chunk) if chunk.len() < N => chunk.push(value),
+            _ => self.chunks.push_back(Self::new_chunk(value)),
+        }
+        self.len += 1;
+    }
+
+    /// Remove the first element in the list.
+    pub fn pop_front(&mut self) -> Option<T> {
+        let chunk = self.chunks.front_mut()?;
+        let value = chunk.remove(0);
+        if chunk.is_empty() {
+            self.chunks.pop_front();
+        }
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Remove the last element in the list.
+    pub fn pop_back(&mut self) -> Option<T> {
+        let chunk = self.chunks.back_mut()?;
+        let value = chunk.pop();
+        if chunk.is_empty() {
+            self.chunks.pop_back();
+        }
+        self.len -= 1;
+        value
+    }
+
+    /// Insert element at `pos`.
+    ///
+    /// If the chunk is full, it is split into two halves first.
+    ///
+    /// # Panics
+    ///
+    /// Panic if `pos > len`.
+    pub fn insert_at(&mut self, pos: usize, value: T) {
+        assert!(pos <= self.len);
+        if pos == self.len {
+            self.push_back(value);
+            return;
+        }
+
+        let (mut cursor, offset) = self.cursor_at(pos);
+        if let Some(chunk) = cursor.current() {
+            if chunk.len() < N {
+                chunk.insert(offset, value);
+            } else {
+                let mid = N / 2;
+                let mut tail = Vec::with_capacity(N);
+                tail.extend(chunk.drain(mid..));
+                if offset <= mid {
+                    chunk.insert(offset, value);
+                } else {
+                    tail.insert(offset - mid, value);
+                }
+                cursor.insert_after(tail);
+            }
+        }
+        self.len += 1;
+    }
+
+    /// Remove element at `pos` and returns that element.
+    ///
+    /// If the chunk becomes less than half full, it is merged with the next
+    /// chunk when they fit into one chunk.
+    ///
+    /// # Panics
+    ///
+    /// Raise panic if `pos >= len`.
+    pub fn pop_at(&mut self, pos: usize) -> Option<T> {
+        assert!(pos < self.len);
+        let (mut cursor, offset) = self.cursor_at(pos);
+        let chunk = cursor.current()?;
+        let value = chunk.remove(offset);
+        let remaining = chunk.len();
+
+        if remaining == 0 {
+            cursor.remove_current();
+        } else if remaining < N / 2 {
+            let next_len = cursor.peek_next().map_or(N, |next| next.len());
+            if remaining + next_len <= N {
+                cursor.move_next();
+                let next = cursor.remove_current();
+                cursor.move_prev();
+                if let (Some(chunk), Some(next)) = (cursor.current(), next) {
+                    chunk.extend(next);
+                }
+            }
+        }
+        self.len -= 1;
+        Some(value)
+    }
+}
+
+// Private functions for list.
+impl<T, const N: usize> UnrolledLinkedList<T, N> {
+    fn new_chunk(value: T) -> Vec<T> {
+        let mut chunk = Vec::with_capacity(N);
+        chunk.push(value);
+        chunk
+    }
+
+    /// Returns a cursor to the chunk containing element at `pos`,
+    /// and offset of that element in chunk.
+    fn cursor_at(&mut self, mut pos: usize) -> (CursorMut<'_, Vec<T>>, usize) {
+        let mut cursor = self.chunks.cursor_front_mut();
+        while let Some(chunk) = cursor.current() {
+            let chunk_len = chunk.len();
+            if pos < chunk_len {
+                break;
+            }
+            pos -= chunk_len;
+            cursor.move_next();
+        }
+        (cursor, pos)
+    }
+}
+
+impl<T, const N: usize> List<T> for UnrolledLinkedList<T, N> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn front(&self) -> Option<&T> {
+        Self::front(self)
+    }
+
+    fn front_mut(&mut self) -> Option<&mut T> {
+        Self::front_mut(self)
+    }
+
+    fn back(&self) -> Option<&T> {
+        Self::back(self)
+    }
+
+    fn back_mut(&mut self) -> Option<&mut T> {
+        Self::back_mut(self)
+    }
+
+    fn get(&self, pos: usize) -> Option<&T> {
+        Self::get(self, pos)
+    }
+
+    fn push_front(&mut self, value: T) {
+        Self::push_front(self, value);
+    }
+
+    fn push_back(&mut self, value: T) {
+        Self::push_back(self, value);
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        Self::pop_front(self)
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        Self::pop_back(self)
+    }
+
+    fn insert_at(&mut self, pos: usize, value: T) {
+        Self::insert_at(self, pos, value);
+    }
+
+    fn pop_at(&mut self, pos: usize) -> Option<T> {
+        Self::pop_at(self, pos)
+    }
+
+    fn clear(&mut self) {
+        Self::clear(self);
+    }
+}
+
+impl<T, const N: usize> Default for UnrolledLinkedList<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for UnrolledLinkedList<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for UnrolledLinkedList<T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            chunks: self.chunks.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for UnrolledLinkedList<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for UnrolledLinkedList<T, N> {}
+
+impl<T, const N: usize> Extend<T> for UnrolledLinkedList<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| self.push_back(value));
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for UnrolledLinkedList<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T, const N: usize> IntoIterator for UnrolledLinkedList<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.chunks.into_iter().flatten(),
+            len: self.len,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a UnrolledLinkedList<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut UnrolledLinkedList<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.inner.next()?;
+        self.len -= 1;
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let value = self.inner.next_back()?;
+        self.len -= 1;
+        Some(value)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.inner.next()?;
+        self.len -= 1;
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let value = self.inner.next_back()?;
+        self.len -= 1;
+        Some(value)
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.inner.next()?;
+        self.len -= 1;
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let value = self.inner.next_back()?;
+        self.len -= 1;
+        Some(value)
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::UnrolledLinkedList;
+
+    fn check_chunks<T, const N: usize>(list: &UnrolledLinkedList<T, N>) {
+        assert!(list
+            .chunks
+            .iter()
+            .all(|chunk| (1..=N).contains(&chunk.len())));
+        assert_eq!(list.chunks.iter().map(Vec::len).sum::<usize>(), list.len());
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut list = UnrolledLinkedList::<i32, 4>::new();
+        for i in 0..10 {
+            list.push_back(i);
+        }
+        list.push_front(-1);
+        assert_eq!(list.len(), 11);
+        assert_eq!(list.chunks(), 4);
+        assert_eq!(list.front(), Some(&-1));
+        assert_eq!(list.back(), Some(&9));
+        check_chunks(&list);
+        assert_eq!(list.pop_front(), Some(-1));
+        assert_eq!(list.pop_back(), Some(9));
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            (0..9).collect::<Vec<_>>()
+        );
+        assert_eq!(list.iter().next_back(), Some(&8));
+        check_chunks(&list);
+    }
+
+    #[test]
+    fn test_insert_split() {
+        let mut list: UnrolledLinkedList<i32, 4> = (0..4).collect();
+        assert_eq!(list.chunks(), 1);
+        list.insert_at(1, 10);
+        // The full chunk is split into [0, 10, 1] and [2, 3].
+        assert_eq!(list.chunks(), 2);
+        list.insert_at(5, 20);
+        list.insert_at(4, 30);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            [0, 10, 1, 2, 30, 3, 20]
+        );
+        assert_eq!(list.get(4), Some(&30));
+        assert_eq!(list.get(7), None);
+        if let Some(value) = list.get_mut(6) {
+            *value = 21;
+        }
+        assert_eq!(list.back(), Some(&21));
+        check_chunks(&list);
+    }
+
+    #[test]
+    fn test_pop_at_merge() {
+        let mut list: UnrolledLinkedList<i32, 8> = (0..16).collect();
+        assert_eq!(list.chunks(), 2);
+        for i in 8..13 {
+            assert_eq!(list.pop_at(8), Some(i));
+        }
+        // The last chunk [13, 14, 15] has no next chunk to merge with.
+        assert_eq!(list.chunks(), 2);
+        for i in 0..4 {
+            assert_eq!(list.pop_at(0), Some(i));
+        }
+        assert_eq!(list.chunks(), 2);
+        // The first chunk [5, 6, 7] is merged with the next one.
+        assert_eq!(list.pop_at(0), Some(4));
+        assert_eq!(list.chunks(), 1);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            [5, 6, 7, 13, 14, 15]
+        );
+        check_chunks(&list);
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut list: UnrolledLinkedList<i32, 3> = (0..10).collect();
+        for value in &mut list {
+            *value *= 2;
+        }
+        assert!(list.contains(&18));
+        assert!(!list.contains(&17));
+        let cloned = list.clone();
+        assert_eq!(cloned, list);
+        assert_eq!(
+            list.into_iter().rev().collect::<Vec<_>>(),
+            [18, 16, 14, 12, 10, 8, 6, 4, 2, 0]
+        );
+        assert_eq!(format!("{cloned:?}"), "[0, 2, 4, 6, 8, 10, 12, 14, 16, 18]");
+    }
+}
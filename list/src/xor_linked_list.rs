@@ -0,0 +1,553 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! XOR linked list.
+//!
+//! Each node stores `address(prev) ^ address(next)` in a single link field,
+//! so the list can be traversed in both directions with the memory of a
+//! singly linked list. Address 0 means no node.
+//!
+//! Addresses are exposed with `expose_provenance()`, so that pointers can be
+//! recovered from the xor-ed link later.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ptr;
+
+use crate::traits::List;
+
+pub struct XorLinkedList<T> {
+    head: usize,
+    tail: usize,
+    len: usize,
+    _marker: PhantomData<Box<Node<T>>>,
+}
+
+struct Node<T> {
+    link: usize,
+    value: T,
+}
+
+pub struct IntoIter<T>(XorLinkedList<T>);
+
+pub struct Iter<'a, T: 'a> {
+    /// The node before `head`.
+    head_prev: usize,
+    head: usize,
+    tail: usize,
+    /// The node after `tail`.
+    tail_next: usize,
+    len: usize,
+    _marker: PhantomData<&'a Node<T>>,
+}
+
+// Public functions for list.
+impl<T> XorLinkedList<T> {
+    /// Create an empty list.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            head: 0,
+            tail: 0,
+            len: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements in list.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether the list is empty.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Access the first node.
+    #[must_use]
+    pub fn front(&self) -> Option<&T> {
+        (self.head != 0).then(|| unsafe { &(*Node::<T>::from_addr(self.head)).value })
+    }
+
+    /// Access the first node exclusively.
+    #[must_use]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        (self.head != 0).then(|| unsafe { &mut (*Node::<T>::from_addr(self.head)).value })
+    }
+
+    /// Access the last node.
+    #[must_use]
+    pub fn back(&self) -> Option<&T> {
+        (self.tail != 0).then(|| unsafe { &(*Node::<T>::from_addr(self.tail)).value })
+    }
+
+    /// Access the last node exclusively.
+    #[must_use]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        (self.tail != 0).then(|| unsafe { &mut (*Node::<T>::from_addr(self.tail)).value })
+    }
+
+    /// Access the element at `pos`.
+    ///
+    /// Time is O(min(pos, len - pos)).
+    #[must_use]
+    pub fn get(&self, pos: usize) -> Option<&T> {
+        if pos >= self.len {
+            return None;
+        }
+        let (_prev, current, _next) = self.locate(pos);
+        Some(unsafe { &(*Node::<T>::from_addr(current)).value })
+    }
+
+    #[must_use]
+    pub const fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head_prev: 0,
+            head: self.head,
+            tail: self.tail,
+            tail_next: 0,
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Remove all elements.
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {
+            // dropped
+        }
+    }
+
+    /// Add an element to the beginning of list.
+    pub fn push_front(&mut self, value: T) {
+        let node = Node::new_addr(value, self.head);
+        if self.head == 0 {
+            self.tail = node;
+        } else {
+            unsafe {
+                (*Node::<T>::from_addr(self.head)).link ^= node;
+            }
+        }
+        self.head = node;
+        self.len += 1;
+    }
+
+    /// Add an element to the end of list.
+    pub fn push_back(&mut self, value: T) {
+        let node = Node::new_addr(value, self.tail);
+        if self.tail == 0 {
+            self.head = node;
+        } else {
+            unsafe {
+                (*Node::<T>::from_addr(self.tail)).link ^= node;
+            }
+        }
+        self.tail = node;
+        self.len += 1;
+    }
+
+    /// Remove the first node in the list.
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.head == 0 {
+            return None;
+        }
+        let node = unsafe { Node::<T>::into_box(self.head) };
+        // The node before head is null, so link is address of next node.
+        let next = node.link;
+        if next == 0 {
+            self.tail = 0;
+        } else {
+            unsafe {
+                (*Node::<T>::from_addr(next)).link ^= self.head;
+            }
+        }
+        self.head = next;
+        self.len -= 1;
+        Some(node.value)
+    }
+
+    /// Remove the last node in the list.
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.tail == 0 {
+            return None;
+        }
+        let node = unsafe { Node::<T>::into_box(self.tail) };
+        let prev = node.link;
+        if prev == 0 {
+            self.head = 0;
+        } else {
+            unsafe {
+                (*Node::<T>::from_addr(prev)).link ^= self.tail;
+            }
+        }
+        self.tail = prev;
+        self.len -= 1;
+        Some(node.value)
+    }
+
+    /// Insert element at `pos`.
+    ///
+    /// # Panics
+    ///
+    /// Panic if `pos > len`.
+    pub fn insert_at(&mut self, pos: usize, value: T) {
+        assert!(pos <= self.len);
+        if pos == 0 {
+            self.push_front(value);
+            return;
+        }
+        if pos == self.len {
+            self.push_back(value);
+            return;
+        }
+
+        let (prev, current, _next) = self.locate(pos);
+        let node = Node::new_addr(value, prev ^ current);
+        unsafe {
+            (*Node::<T>::from_addr(prev)).link ^= current ^ node;
+            (*Node::<T>::from_addr(current)).link ^= prev ^ node;
+        }
+        self.len += 1;
+    }
+
+    /// Remove element at `pos` and returns that element.
+    ///
+    /// # Panics
+    ///
+    /// Raise panic if `pos >= len`.
+    pub fn pop_at(&mut self, pos: usize) -> Option<T> {
+        assert!(pos < self.len);
+        if pos == 0 {
+            return self.pop_front();
+        }
+        if pos == self.len - 1 {
+            return self.pop_back();
+        }
+
+        let (prev, current, next) = self.locate(pos);
+        let node = unsafe { Node::<T>::into_box(current) };
+        unsafe {
+            (*Node::<T>::from_addr(prev)).link ^= current ^ next;
+            (*Node::<T>::from_addr(next)).link ^= current ^ prev;
+        }
+        self.len -= 1;
+        Some(node.value)
+    }
+
+    /// Reverse orders of node in list.
+    ///
+    /// Time is O(1), as links of nodes are symmetric.
+    pub const fn reverse(&mut self) {
+        std::mem::swap(&mut self.head, &mut self.tail);
+    }
+}
+
+// Private functions for list.
+impl<T> XorLinkedList<T> {
+    /// Returns addresses of `(prev, current, next)` nodes, where `current` is at `pos`.
+    ///
+    /// Walk from the nearer end, `pos` must be less than `len`.
+    fn locate(&self, pos: usize) -> (usize, usize, usize) {
+        if pos <= self.len / 2 {
+            let (prev, current) = unsafe { Self::walk(self.head, pos) };
+            let next = unsafe { (*Node::<T>::from_addr(current)).link } ^ prev;
+            (prev, current, next)
+        } else {
+            let (next, current) = unsafe { Self::walk(self.tail, self.len - 1 - pos) };
+            let prev = unsafe { (*Node::<T>::from_addr(current)).link } ^ next;
+            (prev, current, next)
+        }
+    }
+
+    /// Move `steps` nodes away from the end node `start`.
+    ///
+    /// Returns addresses of the node reached and the node visited before it.
+    unsafe fn walk(start: usize, steps: usize) -> (usize, usize) {
+        let mut prev = 0;
+        let mut current = start;
+        for _ in 0..steps {
+            let next = (*Node::<T>::from_addr(current)).link ^ prev;
+            prev = current;
+            current = next;
+        }
+        (prev, current)
+    }
+}
+
+impl<T> List<T> for XorLinkedList<T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn front(&self) -> Option<&T> {
+        Self::front(self)
+    }
+
+    fn front_mut(&mut self) -> Option<&mut T> {
+        Self::front_mut(self)
+    }
+
+    fn back(&self) -> Option<&T> {
+        Self::back(self)
+    }
+
+    fn back_mut(&mut self) -> Option<&mut T> {
+        Self::back_mut(self)
+    }
+
+    fn get(&self, pos: usize) -> Option<&T> {
+        Self::get(self, pos)
+    }
+
+    fn push_front(&mut self, value: T) {
+        Self::push_front(self, value);
+    }
+
+    fn push_back(&mut self, value: T) {
+        Self::push_back(self, value);
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        Self::pop_front(self)
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        Self::pop_back(self)
+    }
+
+    fn insert_at(&mut self, pos: usize, value: T) {
+        Self::insert_at(self, pos, value);
+    }
+
+    fn pop_at(&mut self, pos: usize) -> Option<T> {
+        Self::pop_at(self, pos)
+    }
+
+    fn clear(&mut self) {
+        Self::clear(self);
+    }
+}
+
+impl<T> Drop for XorLinkedList<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Default for XorLinkedList<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for XorLinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: Clone> Clone for XorLinkedList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: PartialEq> PartialEq for XorLinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for XorLinkedList<T> {}
+
+impl<T> Extend<T> for XorLinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| self.push_back(value));
+    }
+}
+
+impl<T> FromIterator<T> for XorLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> IntoIterator for XorLinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a XorLinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let node: &'a Node<T> = unsafe { &*Node::<T>::from_addr(self.head) };
+        let next = node.link ^ self.head_prev;
+        self.head_prev = self.head;
+        self.head = next;
+        self.len -= 1;
+        Some(&node.value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let node: &Node<T> = unsafe { &*Node::<T>::from_addr(self.tail) };
+        let prev = node.link ^ self.tail_next;
+        self.tail_next = self.tail;
+        self.tail = prev;
+        self.len -= 1;
+        Some(&node.value)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> Node<T> {
+    /// Allocate a new node and returns its address.
+    fn new_addr(value: T, link: usize) -> usize {
+        let node = Box::new(Self { link, value });
+        Box::into_raw(node).expose_provenance()
+    }
+
+    const fn from_addr(addr: usize) -> *mut Self {
+        ptr::with_exposed_provenance_mut(addr)
+    }
+
+    #[allow(clippy::unnecessary_box_returns)]
+    unsafe fn into_box(addr: usize) -> Box<Self> {
+        Box::from_raw(Self::from_addr(addr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::XorLinkedList;
+
+    fn to_vec(list: &XorLinkedList<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut list = XorLinkedList::new();
+        list.push_back(2);
+        list.push_front(1);
+        list.push_back(3);
+        assert_eq!(list.len(), 3);
+        assert_eq!(to_vec(&list), [1, 2, 3]);
+        assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), [3, 2, 1]);
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_back(), None);
+        assert_eq!(list.pop_front(), None);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_insert_pop_at() {
+        let mut list: XorLinkedList<i32> = (0..10).filter(|x| x % 2 == 0).collect();
+        for pos in [1, 3, 5, 7, 9] {
+            list.insert_at(pos, i32::try_from(pos).unwrap());
+        }
+        assert_eq!(to_vec(&list), (0..10).collect::<Vec<_>>());
+        assert_eq!(list.get(7), Some(&7));
+        assert_eq!(list.get(10), None);
+        assert_eq!(list.pop_at(8), Some(8));
+        assert_eq!(list.pop_at(2), Some(2));
+        assert_eq!(to_vec(&list), [0, 1, 3, 4, 5, 6, 7, 9]);
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            [9, 7, 6, 5, 4, 3, 1, 0]
+        );
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut list: XorLinkedList<i32> = (0..5).collect();
+        list.reverse();
+        assert_eq!(to_vec(&list), [4, 3, 2, 1, 0]);
+        list.push_back(-1);
+        list.insert_at(1, 10);
+        assert_eq!(to_vec(&list), [4, 10, 3, 2, 1, 0, -1]);
+        if let Some(value) = list.front_mut() {
+            *value = 40;
+        }
+        assert_eq!(
+            list.clone().into_iter().rev().collect::<Vec<_>>(),
+            [-1, 0, 1, 2, 3, 10, 40]
+        );
+        assert_eq!(format!("{list:?}"), "[40, 10, 3, 2, 1, 0, -1]");
+    }
+
+    #[test]
+    fn test_iter_both_ends() {
+        let list: XorLinkedList<i32> = (0..6).collect();
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&0));
+        assert_eq!(iter.next_back(), Some(&5));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next_back(), Some(&4));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next_back(), Some(&3));
+        assert_eq!(iter.next_back(), Some(&2));
+        assert_eq!(iter.next(), None);
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use priority_queue::binomial_heap::MinBinomialHeap;
+use priority_queue::fibonacci_heap::MinFibonacciHeap;
+use priority_queue::indexed_heap::IndexedMinHeap;
+use priority_queue::pairing_heap::MinPairingHeap;
+use priority_queue::skew_heap::MinSkewHeap;
+use priority_queue::traits::PriorityQueue;
+
+type Graph = Vec<Vec<(usize, u64)>>;
+
+// 用线性同余生成器构造随机的有向图, 保证每次运行的结果一样.
+fn random_graph(vertices: usize, edges_per_vertex: usize) -> Graph {
+    let mut seed: u64 = 0x5eed;
+    let mut next_random = move || {
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        seed >> 33
+    };
+    (0..vertices)
+        .map(|_| {
+            (0..edges_per_vertex)
+                .map(|_| {
+                    #[allow(clippy::cast_possible_truncation)]
+                    let to = next_random() as usize % vertices;
+                    (to, next_random() % 1000 + 1)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// 所有顶点一开始都放入堆中, 之后不断调用 decrease-key.
+fn dijkstra<Q: PriorityQueue<(u64, usize)>>(graph: &Graph, mut queue: Q) -> Vec<u64> {
+    let mut dist = vec![u64::MAX; graph.len()];
+    let handles: Vec<_> = (0..graph.len())
+        .map(|vertex| queue.push((if vertex == 0 { 0 } else { u64::MAX }, vertex)))
+        .collect();
+    while let Some((d, vertex)) = queue.pop() {
+        dist[vertex] = d;
+        if d == u64::MAX {
+            continue;
+        }
+        for &(next, weight) in &graph[vertex] {
+            if let Some(&(old, _)) = queue.get(&handles[next]) {
+                if d + weight < old {
+                    let _old = queue.decrease_key(&handles[next], (d + weight, next));
+                }
+            }
+        }
+    }
+    dist
+}
+
+fn push_pop<Q: PriorityQueue<(u64, usize)>>(graph: &Graph, mut queue: Q) -> u64 {
+    for (vertex, edges) in graph.iter().enumerate() {
+        queue.push((edges[0].1, vertex));
+    }
+    let mut sum = 0;
+    while let Some((weight, _vertex)) = queue.pop() {
+        sum += weight;
+    }
+    sum
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let graph = random_graph(10_000, 8);
+    let expected = dijkstra(&graph, IndexedMinHeap::new_min());
+
+    c.bench_function("dijkstra indexed_binary_heap", |b| {
+        b.iter(|| assert_eq!(dijkstra(&graph, IndexedMinHeap::new_min()), expected));
+    });
+    c.bench_function("dijkstra binomial_heap", |b| {
+        b.iter(|| assert_eq!(dijkstra(&graph, MinBinomialHeap::new_min()), expected));
+    });
+    c.bench_function("dijkstra fibonacci_heap", |b| {
+        b.iter(|| assert_eq!(dijkstra(&graph, MinFibonacciHeap::new_min()), expected));
+    });
+    c.bench_function("dijkstra pairing_heap", |b| {
+        b.iter(|| assert_eq!(dijkstra(&graph, MinPairingHeap::new_min()), expected));
+    });
+    c.bench_function("dijkstra skew_heap", |b| {
+        b.iter(|| assert_eq!(dijkstra(&graph, MinSkewHeap::new_min()), expected));
+    });
+
+    c.bench_function("push_pop indexed_binary_heap", |b| {
+        b.iter(|| black_box(push_pop(&graph, IndexedMinHeap::new_min())));
+    });
+    c.bench_function("push_pop binomial_heap", |b| {
+        b.iter(|| black_box(push_pop(&graph, MinBinomialHeap::new_min())));
+    });
+    c.bench_function("push_pop fibonacci_heap", |b| {
+        b.iter(|| black_box(push_pop(&graph, MinFibonacciHeap::new_min())));
+    });
+    c.bench_function("push_pop pairing_heap", |b| {
+        b.iter(|| black_box(push_pop(&graph, MinPairingHeap::new_min())));
+    });
+    c.bench_function("push_pop skew_heap", |b| {
+        b.iter(|| black_box(push_pop(&graph, MinSkewHeap::new_min())));
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
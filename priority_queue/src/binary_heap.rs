@@ -0,0 +1,457 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 基于数组的二叉堆.
+//!
+//! 节点 `i` 的左右子节点分别是 `2 * i + 1` 和 `2 * i + 2`, 父节点是 `(i - 1) / 2`.
+//! 堆顶元素是优先级最高的那个, 优先级由比较器 [`Compare`] 决定,
+//! 默认的 [`MaxComparator`] 对应大顶堆, [`MinComparator`] 对应小顶堆.
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::slice;
+
+use crate::compare::{Compare, FnComparator, MaxComparator, MinComparator};
+
+#[derive(Clone)]
+pub struct BinaryHeap<T, C = MaxComparator> {
+    heap: Vec<T>,
+    cmp: C,
+}
+
+/// 小顶堆.
+pub type MinBinaryHeap<T> = BinaryHeap<T, MinComparator>;
+
+impl<T: Ord> Default for BinaryHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> BinaryHeap<T> {
+    /// 创建一个空的大顶堆.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self::with_comparator(MaxComparator)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_comparator(capacity, MaxComparator)
+    }
+}
+
+impl<T: Ord> BinaryHeap<T, MinComparator> {
+    /// 创建一个空的小顶堆.
+    #[must_use]
+    #[inline]
+    pub const fn new_min() -> Self {
+        Self::with_comparator(MinComparator)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn with_capacity_min(capacity: usize) -> Self {
+        Self::with_capacity_and_comparator(capacity, MinComparator)
+    }
+}
+
+impl<T, F> BinaryHeap<T, FnComparator<F>>
+where
+    F: Fn(&T, &T) -> std::cmp::Ordering,
+{
+    /// 使用闭包作为比较函数, 返回 `Greater` 的元素更靠近堆顶.
+    #[must_use]
+    #[inline]
+    pub const fn new_by(compare: F) -> Self {
+        Self::with_comparator(FnComparator(compare))
+    }
+}
+
+impl<T, C: Compare<T>> BinaryHeap<T, C> {
+    #[must_use]
+    #[inline]
+    pub const fn with_comparator(cmp: C) -> Self {
+        Self {
+            heap: Vec::new(),
+            cmp,
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn with_capacity_and_comparator(capacity: usize, cmp: C) -> Self {
+        Self {
+            heap: Vec::with_capacity(capacity),
+            cmp,
+        }
+    }
+
+    /// 把数组原地调整成堆.
+    ///
+    /// 从最后一个非叶子节点开始, 依次向前对每个节点做下沉操作 (heapify),
+    /// 时间复杂度是 O(n), 比逐个插入的 O(n log(n)) 要快.
+    #[must_use]
+    pub fn from_vec_with_comparator(vec: Vec<T>, cmp: C) -> Self {
+        let mut heap = Self { heap: vec, cmp };
+        heap.rebuild();
+        heap
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        self.heap.capacity()
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn comparator(&self) -> &C {
+        &self.cmp
+    }
+
+    /// 返回堆顶元素.
+    #[must_use]
+    #[inline]
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.first()
+    }
+
+    /// 返回堆顶元素的可变引用.
+    ///
+    /// 修改之后, 在 `PeekMut` 被丢弃时会重新调整堆顶元素的位置.
+    #[must_use]
+    pub const fn peek_mut(&mut self) -> Option<PeekMut<'_, T, C>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(PeekMut { heap: self })
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        // 先将新的元素插入到数组尾部.
+        self.heap.push(value);
+
+        // 然后将该元素进行上移 (shift up).
+        self.shift_up(self.heap.len() - 1);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        // 将堆顶的元素交换到数组的尾部, 并弹出它.
+        let len = self.heap.len();
+        if len == 0 {
+            return None;
+        }
+        self.heap.swap(0, len - 1);
+        let top = self.heap.pop();
+
+        // 然后调整堆顶的位置, 让它保持堆的特性.
+        self.shift_down(0, len - 1);
+
+        top
+    }
+
+    /// 先插入再弹出, 比分别调用 `push()` 和 `pop()` 少一次调整.
+    pub fn push_pop(&mut self, mut value: T) -> T {
+        if let Some(top) = self.heap.first_mut() {
+            if self.cmp.compare(top, &value).is_gt() {
+                std::mem::swap(top, &mut value);
+                self.shift_down(0, self.heap.len());
+            }
+        }
+        value
+    }
+
+    /// 以任意顺序遍历堆中的元素.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.heap.iter()
+    }
+
+    /// 返回底层的数组, 元素是堆的顺序.
+    #[must_use]
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        &self.heap
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn into_vec(self) -> Vec<T> {
+        self.heap
+    }
+
+    /// 堆排序, 按照优先级从低到高排列.
+    ///
+    /// 对大顶堆来说是升序, 对小顶堆来说是降序.
+    #[must_use]
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut end = self.heap.len();
+        while end > 1 {
+            end -= 1;
+            // 把堆顶元素放到已排序部分的头部.
+            self.heap.swap(0, end);
+            self.shift_down(0, end);
+        }
+        self.heap
+    }
+
+    /// 按照优先级从高到低依次弹出所有元素.
+    pub fn drain_sorted(&mut self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(move || self.pop())
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    /// 把 `other` 中的元素都移到当前堆中.
+    pub fn append(&mut self, other: &mut Self) {
+        if other.len() > self.len() {
+            std::mem::swap(&mut self.heap, &mut other.heap);
+        }
+        let start = self.heap.len();
+        self.heap.append(&mut other.heap);
+        if self.heap.len() - start > start {
+            // 新加入的元素较多时, 直接重建整个堆更快.
+            self.rebuild();
+        } else {
+            for pos in start..self.heap.len() {
+                self.shift_up(pos);
+            }
+        }
+    }
+
+    fn rebuild(&mut self) {
+        let len = self.heap.len();
+        for pos in (0..len / 2).rev() {
+            self.shift_down(pos, len);
+        }
+    }
+
+    // 将该节点与父节点进行比较, 如果优先级比父节点高, 就让它跟父节点交换,
+    // 一直重复这个过程, 直到找到合适的位置.
+    //
+    // 时间复杂度是 O(log(n)), 是树的高度.
+    fn shift_up(&mut self, mut pos: usize) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if self
+                .cmp
+                .compare(&self.heap[pos], &self.heap[parent])
+                .is_le()
+            {
+                break;
+            }
+            self.heap.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    // 将该节点与子节点进行比较, 如果优先级比子节点低, 就让它跟优先级较高的那个子节点交换,
+    // 一直重复这个过程, 直到找到合适的位置.
+    //
+    // 时间复杂度是 O(log(n)), 是树的高度.
+    fn shift_down(&mut self, mut pos: usize, len: usize) {
+        while pos * 2 + 1 < len {
+            let left = pos * 2 + 1;
+            let right = left + 1;
+
+            let larger = if right < len
+                && self
+                    .cmp
+                    .compare(&self.heap[right], &self.heap[left])
+                    .is_gt()
+            {
+                right
+            } else {
+                left
+            };
+
+            // 当前节点与较大的子节点进行比较, 如果比它小就进行交换.
+            if self
+                .cmp
+                .compare(&self.heap[pos], &self.heap[larger])
+                .is_lt()
+            {
+                self.heap.swap(pos, larger);
+                pos = larger;
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+impl<T: Ord> From<Vec<T>> for BinaryHeap<T> {
+    fn from(vec: Vec<T>) -> Self {
+        Self::from_vec_with_comparator(vec, MaxComparator)
+    }
+}
+
+impl<T: Ord, const N: usize> From<[T; N]> for BinaryHeap<T> {
+    fn from(array: [T; N]) -> Self {
+        Self::from(Vec::from(array))
+    }
+}
+
+impl<T, C: Compare<T> + Default> FromIterator<T> for BinaryHeap<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_vec_with_comparator(iter.into_iter().collect(), C::default())
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for BinaryHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T, C> IntoIterator for BinaryHeap<T, C> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    /// 以任意顺序遍历.
+    fn into_iter(self) -> Self::IntoIter {
+        self.heap.into_iter()
+    }
+}
+
+impl<'a, T, C> IntoIterator for &'a BinaryHeap<T, C> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.heap.iter()
+    }
+}
+
+impl<T: fmt::Debug, C> fmt::Debug for BinaryHeap<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.heap.iter()).finish()
+    }
+}
+
+/// 堆顶元素的可变引用, 由 [`BinaryHeap::peek_mut()`] 返回.
+pub struct PeekMut<'a, T, C: Compare<T>> {
+    heap: &'a mut BinaryHeap<T, C>,
+}
+
+impl<T, C: Compare<T>> PeekMut<'_, T, C> {
+    /// 弹出堆顶元素.
+    #[must_use]
+    // 需要消耗掉 `PeekMut`, 避免弹出之后继续访问堆顶.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn pop(this: Self) -> T {
+        // 把最后一个元素移到堆顶, 由 drop() 负责让它下沉.
+        this.heap.heap.swap_remove(0)
+    }
+}
+
+impl<T, C: Compare<T>> Deref for PeekMut<'_, T, C> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.heap.heap[0]
+    }
+}
+
+impl<T, C: Compare<T>> DerefMut for PeekMut<'_, T, C> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.heap.heap[0]
+    }
+}
+
+impl<T, C: Compare<T>> Drop for PeekMut<'_, T, C> {
+    fn drop(&mut self) {
+        let len = self.heap.len();
+        self.heap.shift_down(0, len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BinaryHeap, MinBinaryHeap, PeekMut};
+
+    #[test]
+    fn test_max_heap() {
+        let mut heap = BinaryHeap::new();
+        for value in [3, 1, 4, 1, 5, 9, 2, 6] {
+            heap.push(value);
+        }
+        assert_eq!(heap.len(), 8);
+        assert_eq!(heap.peek(), Some(&9));
+        let list: Vec<i32> = heap.drain_sorted().collect();
+        assert_eq!(list, [9, 6, 5, 4, 3, 2, 1, 1]);
+        assert!(heap.is_empty());
+        assert_eq!(heap.pop(), None);
+    }
+
+    #[test]
+    fn test_min_heap() {
+        let mut heap = MinBinaryHeap::new_min();
+        heap.extend([3, 1, 4, 1, 5]);
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.pop(), Some(3));
+        assert_eq!(heap.into_sorted_vec(), [5, 4]);
+    }
+
+    #[test]
+    fn test_heapify() {
+        let heap = BinaryHeap::from(vec![5, 8, 1, 9, 3, 7, 2]);
+        assert_eq!(heap.peek(), Some(&9));
+        assert_eq!(heap.into_sorted_vec(), [1, 2, 3, 5, 7, 8, 9]);
+
+        let heap: MinBinaryHeap<i32> = (0..100).rev().collect();
+        assert_eq!(heap.peek(), Some(&0));
+    }
+
+    #[test]
+    fn test_new_by() {
+        let mut heap = BinaryHeap::new_by(|a: &&str, b: &&str| b.len().cmp(&a.len()));
+        heap.extend(["hello", "a", "abc"]);
+        assert_eq!(heap.pop(), Some("a"));
+        assert_eq!(heap.pop(), Some("abc"));
+    }
+
+    #[test]
+    fn test_peek_mut() {
+        let mut heap = BinaryHeap::from([1, 5, 3]);
+        if let Some(mut top) = heap.peek_mut() {
+            *top = 0;
+        }
+        assert_eq!(heap.peek(), Some(&3));
+        let top = heap.peek_mut().map(PeekMut::pop);
+        assert_eq!(top, Some(3));
+        assert_eq!(heap.into_sorted_vec(), [0, 1]);
+    }
+
+    #[test]
+    fn test_push_pop_and_append() {
+        let mut heap = BinaryHeap::from([1, 2]);
+        assert_eq!(heap.push_pop(5), 5);
+        assert_eq!(heap.push_pop(0), 2);
+        let mut other = BinaryHeap::from([7, 3, 4]);
+        heap.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(heap.into_sorted_vec(), [0, 1, 3, 4, 7]);
+    }
+}
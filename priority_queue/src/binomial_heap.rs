@@ -0,0 +1,353 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 二项堆 (binomial heap).
+//!
+//! 二项堆由一组二项树组成, 度为 k 的二项树有 `2^k` 个节点, 每种度数最多只有一棵树,
+//! 这跟整数的二进制表示一一对应. 这里用 `trees[k]` 存放度为 k 的树的根节点,
+//! 插入和合并就像二进制加法一样处理进位.
+//! - 插入: 均摊 O(1), 最坏 O(log(n));
+//! - 合并, 弹出, decrease-key: O(log(n)).
+//!
+//! 节点都单独分配在堆上, 用指针连接起来, 合并时不需要移动节点, 元素被弹出后立即释放.
+//! decrease-key 时节点沿着父节点向上交换元素, 元素的句柄也跟着一起交换,
+//! 并更新句柄中记录的节点地址.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr::NonNull;
+
+use crate::compare::{Compare, MaxComparator, MinComparator};
+use crate::traits::{Handle, HandleSpace, KeyError, PriorityQueue};
+
+type Link<T> = NonNull<Node<T>>;
+
+struct Node<T> {
+    value: T,
+    handle: Handle,
+    parent: Option<Link<T>>,
+    // 度数最高的子节点.
+    child: Option<Link<T>>,
+    // 度数更低的下一个兄弟节点.
+    sibling: Option<Link<T>>,
+    degree: usize,
+}
+
+pub struct BinomialHeap<T, C = MaxComparator> {
+    // `trees[k]` 是度为 k 的二项树的根节点.
+    trees: Vec<Option<Link<T>>>,
+    len: usize,
+    space: HandleSpace,
+    cmp: C,
+    _marker: PhantomData<Box<Node<T>>>,
+}
+
+/// 小顶堆.
+pub type MinBinomialHeap<T> = BinomialHeap<T, MinComparator>;
+
+impl<T: Ord> Default for BinomialHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> BinomialHeap<T> {
+    /// 创建一个空的大顶堆.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_comparator(MaxComparator)
+    }
+}
+
+impl<T: Ord> BinomialHeap<T, MinComparator> {
+    /// 创建一个空的小顶堆.
+    #[must_use]
+    pub fn new_min() -> Self {
+        Self::with_comparator(MinComparator)
+    }
+}
+
+impl<T, C> BinomialHeap<T, C> {
+    /// 清空堆, 之前的句柄全部失效.
+    pub fn clear(&mut self) {
+        for node in self.nodes() {
+            // 每个节点只会被释放一次.
+            let node = unsafe { Box::from_raw(node.as_ptr()) };
+            node.handle.invalidate();
+        }
+        self.trees.clear();
+        self.len = 0;
+    }
+
+    // 返回所有的节点.
+    fn nodes(&self) -> Vec<Link<T>> {
+        let mut nodes = Vec::with_capacity(self.len);
+        let mut stack: Vec<Link<T>> = self.trees.iter().flatten().copied().collect();
+        while let Some(node) = stack.pop() {
+            unsafe {
+                stack.extend((*node.as_ptr()).child);
+                stack.extend((*node.as_ptr()).sibling);
+            }
+            nodes.push(node);
+        }
+        nodes
+    }
+}
+
+impl<T, C: Compare<T>> BinomialHeap<T, C> {
+    #[must_use]
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            trees: Vec::new(),
+            len: 0,
+            space: HandleSpace::new(),
+            cmp,
+            _marker: PhantomData,
+        }
+    }
+
+    // 节点 `a` 的优先级是否比 `b` 高.
+    fn higher(&self, a: Link<T>, b: Link<T>) -> bool {
+        unsafe {
+            self.cmp
+                .compare(&(*a.as_ptr()).value, &(*b.as_ptr()).value)
+                .is_gt()
+        }
+    }
+
+    // 合并两棵度数相同的树, 返回新的根节点.
+    fn link(&self, a: Link<T>, b: Link<T>) -> Link<T> {
+        let (root, child) = if self.higher(b, a) { (b, a) } else { (a, b) };
+        unsafe {
+            (*child.as_ptr()).parent = Some(root);
+            (*child.as_ptr()).sibling = (*root.as_ptr()).child;
+            (*root.as_ptr()).child = Some(child);
+            (*root.as_ptr()).degree += 1;
+        }
+        root
+    }
+
+    // 加入一棵树, 遇到度数相同的树就合并, 并向高位进位.
+    fn add_tree(&mut self, mut tree: Link<T>) {
+        let mut degree = unsafe { (*tree.as_ptr()).degree };
+        loop {
+            if degree >= self.trees.len() {
+                self.trees.resize(degree + 1, None);
+            }
+            match self.trees[degree].take() {
+                None => {
+                    self.trees[degree] = Some(tree);
+                    break;
+                }
+                Some(other) => {
+                    tree = self.link(tree, other);
+                    degree += 1;
+                }
+            }
+        }
+    }
+
+    // 优先级最高的根节点.
+    fn top(&self) -> Option<Link<T>> {
+        self.trees.iter().flatten().copied().reduce(|best, root| {
+            if self.higher(root, best) {
+                root
+            } else {
+                best
+            }
+        })
+    }
+
+    // 交换两个节点上的元素以及句柄.
+    fn swap_items(a: Link<T>, b: Link<T>) {
+        unsafe {
+            let (x, y) = (&mut *a.as_ptr(), &mut *b.as_ptr());
+            mem::swap(&mut x.value, &mut y.value);
+            mem::swap(&mut x.handle, &mut y.handle);
+            x.handle.set_node(a);
+            y.handle.set_node(b);
+        }
+    }
+}
+
+impl<T, C: Compare<T>> PriorityQueue<T> for BinomialHeap<T, C> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn push(&mut self, value: T) -> Handle {
+        let node = NonNull::from(Box::leak(Box::new(Node {
+            value,
+            handle: self.space.handle(0),
+            parent: None,
+            child: None,
+            sibling: None,
+            degree: 0,
+        })));
+        let handle = unsafe { (*node.as_ptr()).handle.clone() };
+        handle.set_node(node);
+        self.add_tree(node);
+        self.len += 1;
+        handle
+    }
+
+    /// 需要遍历所有的根节点, 时间复杂度是 O(log(n)).
+    fn peek(&self) -> Option<&T> {
+        self.top().map(|root| unsafe { &(*root.as_ptr()).value })
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let root = self.top()?;
+        let node = unsafe { Box::from_raw(root.as_ptr()) };
+        self.trees[node.degree] = None;
+
+        // 根节点的各个子树都是二项树, 把它们重新加入进来.
+        let mut next = node.child;
+        while let Some(child) = next {
+            unsafe {
+                next = (*child.as_ptr()).sibling.take();
+                (*child.as_ptr()).parent = None;
+            }
+            self.add_tree(child);
+        }
+        while self.trees.last() == Some(&None) {
+            self.trees.pop();
+        }
+
+        self.len -= 1;
+        node.handle.invalidate();
+        Some(node.value)
+    }
+
+    fn get(&self, handle: &Handle) -> Option<&T> {
+        let node: Link<T> = self.space.resolve_node(handle)?;
+        Some(unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// 像二进制加法一样逐个加入 `other` 的树, O(log(n)).
+    fn meld(&mut self, mut other: Self) {
+        self.space.absorb(&other.space);
+        self.len += other.len;
+        other.len = 0;
+        for tree in mem::take(&mut other.trees).into_iter().flatten() {
+            self.add_tree(tree);
+        }
+    }
+
+    /// 修改元素后, 把它与父节点交换, 直到满足堆的特性.
+    fn decrease_key(&mut self, handle: &Handle, value: T) -> Result<T, KeyError> {
+        let mut node: Link<T> = self
+            .space
+            .resolve_node(handle)
+            .ok_or(KeyError::InvalidHandle)?;
+        let old = unsafe { &mut (*node.as_ptr()).value };
+        if self.cmp.compare(&value, old).is_lt() {
+            return Err(KeyError::PriorityLowered);
+        }
+        let old = mem::replace(old, value);
+
+        while let Some(parent) = unsafe { (*node.as_ptr()).parent } {
+            if !self.higher(node, parent) {
+                break;
+            }
+            Self::swap_items(node, parent);
+            node = parent;
+        }
+        Ok(old)
+    }
+}
+
+impl<T, C> Drop for BinomialHeap<T, C> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: Clone, C: Compare<T> + Clone> Clone for BinomialHeap<T, C> {
+    /// 复制所有元素, 新的堆有自己的句柄.
+    fn clone(&self) -> Self {
+        let mut heap = Self::with_comparator(self.cmp.clone());
+        for node in self.nodes() {
+            heap.push(unsafe { (*node.as_ptr()).value.clone() });
+        }
+        heap
+    }
+}
+
+impl<T: fmt::Debug, C> fmt::Debug for BinomialHeap<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.nodes()
+                    .into_iter()
+                    .map(|node| unsafe { &(*node.as_ptr()).value }),
+            )
+            .finish()
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for BinomialHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BinomialHeap, MinBinomialHeap};
+    use crate::traits::{KeyError, PriorityQueue};
+
+    #[test]
+    fn test_push_pop() {
+        let mut heap = BinomialHeap::new();
+        heap.extend((0..100).map(|i| (i * 37) % 100));
+        assert_eq!(heap.len(), 100);
+        // 100 = 0b110_0100
+        assert_eq!(heap.trees.iter().flatten().count(), 3);
+        assert_eq!(heap.peek(), Some(&99));
+        let values: Vec<i32> = std::iter::from_fn(|| heap.pop()).collect();
+        assert_eq!(values, (0..100).rev().collect::<Vec<_>>());
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_decrease_key() {
+        let mut heap = MinBinomialHeap::new_min();
+        let handles: Vec<_> = (0..50).map(|i| heap.push(i + 100)).collect();
+        assert_eq!(heap.pop(), Some(100));
+        assert_eq!(
+            heap.decrease_key(&handles[0], 1),
+            Err(KeyError::InvalidHandle)
+        );
+        assert_eq!(
+            heap.decrease_key(&handles[30], 200),
+            Err(KeyError::PriorityLowered)
+        );
+        assert_eq!(heap.decrease_key(&handles[30], 5), Ok(130));
+        assert_eq!(heap.decrease_key(&handles[40], 3), Ok(140));
+        assert_eq!(heap.get(&handles[30]), Some(&5));
+        assert_eq!(heap.pop(), Some(3));
+        assert_eq!(heap.decrease_key(&handles[49], 4), Ok(149));
+        assert_eq!(heap.pop(), Some(4));
+        assert_eq!(heap.pop(), Some(5));
+        assert_eq!(heap.pop(), Some(101));
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut a = MinBinomialHeap::new_min();
+        let mut b = MinBinomialHeap::new_min();
+        a.extend([7, 3, 9]);
+        let handle = b.push(20);
+        b.extend([4, 11, 6]);
+        a.meld(b);
+        assert_eq!(a.len(), 7);
+        assert_eq!(a.decrease_key(&handle, 1), Ok(20));
+        let values: Vec<i32> = std::iter::from_fn(|| a.pop()).collect();
+        assert_eq!(values, [1, 3, 4, 6, 7, 9, 11]);
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 堆中元素的比较方式.
+//!
+//! 堆总是把 "最大" 的元素放在堆顶, 至于什么是最大, 由 [`Compare`] 决定:
+//! - [`MaxComparator`] 使用元素本身的顺序, 得到大顶堆;
+//! - [`MinComparator`] 把顺序反过来, 得到小顶堆;
+//! - [`FnComparator`] 使用自定义的比较函数.
+
+use std::cmp::Ordering;
+
+/// 比较两个元素的优先级.
+///
+/// 返回 `Ordering::Greater` 表示 `a` 的优先级更高, 应该更靠近堆顶.
+pub trait Compare<T: ?Sized> {
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+/// 大顶堆, 值越大优先级越高.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MaxComparator;
+
+impl<T: Ord + ?Sized> Compare<T> for MaxComparator {
+    #[inline]
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// 小顶堆, 值越小优先级越高.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MinComparator;
+
+impl<T: Ord + ?Sized> Compare<T> for MinComparator {
+    #[inline]
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+/// 使用闭包作为比较函数.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FnComparator<F>(pub F);
+
+impl<T: ?Sized, F> Compare<T> for FnComparator<F>
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    #[inline]
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{Compare, FnComparator, MaxComparator, MinComparator};
+
+    #[test]
+    fn test_comparators() {
+        assert_eq!(MaxComparator.compare(&1, &2), Ordering::Less);
+        assert_eq!(MinComparator.compare(&1, &2), Ordering::Greater);
+        let by_len = FnComparator(|a: &&str, b: &&str| a.len().cmp(&b.len()));
+        assert_eq!(by_len.compare(&"abc", &"de"), Ordering::Greater);
+    }
+}
@@ -0,0 +1,470 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 斐波那契堆 (Fibonacci heap).
+//!
+//! 斐波那契堆由一组堆有序的树组成, 根节点以及兄弟节点都用循环双向链表串起来.
+//! - 插入和合并: 直接拼接根链表, O(1);
+//! - 弹出: 把堆顶的子节点都移到根链表, 再把度数相同的树两两合并 (consolidate), 均摊 O(log(n));
+//! - decrease-key: 如果违反了堆的特性, 就把节点剪下来放到根链表,
+//!   并对父节点进行级联剪切 (cascading cut), 均摊 O(1).
+//!
+//! 节点都单独分配在堆上, 用指针连接起来, 合并时不需要移动节点, 元素被弹出后立即释放.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use crate::compare::{Compare, MaxComparator, MinComparator};
+use crate::traits::{Handle, HandleSpace, KeyError, PriorityQueue};
+
+type Link<T> = NonNull<Node<T>>;
+
+struct Node<T> {
+    value: T,
+    handle: Handle,
+    parent: Option<Link<T>>,
+    // 子节点链表中的任意一个节点.
+    child: Option<Link<T>>,
+    // 循环链表中的前一个和后一个节点, 只有一个节点时指向自身.
+    left: Link<T>,
+    right: Link<T>,
+    degree: usize,
+    // 成为子节点之后, 是否失去过一个子节点.
+    mark: bool,
+}
+
+pub struct FibonacciHeap<T, C = MaxComparator> {
+    // 优先级最高的根节点.
+    top: Option<Link<T>>,
+    len: usize,
+    space: HandleSpace,
+    cmp: C,
+    _marker: PhantomData<Box<Node<T>>>,
+}
+
+/// 小顶堆.
+pub type MinFibonacciHeap<T> = FibonacciHeap<T, MinComparator>;
+
+impl<T: Ord> Default for FibonacciHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> FibonacciHeap<T> {
+    /// 创建一个空的大顶堆.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_comparator(MaxComparator)
+    }
+}
+
+impl<T: Ord> FibonacciHeap<T, MinComparator> {
+    /// 创建一个空的小顶堆.
+    #[must_use]
+    pub fn new_min() -> Self {
+        Self::with_comparator(MinComparator)
+    }
+}
+
+impl<T, C> FibonacciHeap<T, C> {
+    /// 清空堆, 之前的句柄全部失效.
+    pub fn clear(&mut self) {
+        for node in self.nodes() {
+            // 每个节点只会被释放一次.
+            let node = unsafe { Box::from_raw(node.as_ptr()) };
+            node.handle.invalidate();
+        }
+        self.top = None;
+        self.len = 0;
+    }
+
+    // 遍历以 `start` 开头的循环链表.
+    fn siblings(start: Link<T>) -> Vec<Link<T>> {
+        let mut list = vec![start];
+        let mut next = unsafe { (*start.as_ptr()).right };
+        while next != start {
+            list.push(next);
+            next = unsafe { (*next.as_ptr()).right };
+        }
+        list
+    }
+
+    // 返回所有的节点.
+    fn nodes(&self) -> Vec<Link<T>> {
+        let mut nodes = Vec::with_capacity(self.len);
+        let mut lists: Vec<Link<T>> = self.top.into_iter().collect();
+        while let Some(start) = lists.pop() {
+            for node in Self::siblings(start) {
+                if let Some(child) = unsafe { (*node.as_ptr()).child } {
+                    lists.push(child);
+                }
+                nodes.push(node);
+            }
+        }
+        nodes
+    }
+}
+
+impl<T, C: Compare<T>> FibonacciHeap<T, C> {
+    #[must_use]
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            top: None,
+            len: 0,
+            space: HandleSpace::new(),
+            cmp,
+            _marker: PhantomData,
+        }
+    }
+
+    // 节点 `a` 的优先级是否比 `b` 高.
+    fn higher(&self, a: Link<T>, b: Link<T>) -> bool {
+        unsafe {
+            self.cmp
+                .compare(&(*a.as_ptr()).value, &(*b.as_ptr()).value)
+                .is_gt()
+        }
+    }
+
+    // 把节点 `node` 插入到链表中 `pos` 的后面.
+    fn splice_after(pos: Link<T>, node: Link<T>) {
+        unsafe {
+            let right = (*pos.as_ptr()).right;
+            (*node.as_ptr()).left = pos;
+            (*node.as_ptr()).right = right;
+            (*pos.as_ptr()).right = node;
+            (*right.as_ptr()).left = node;
+        }
+    }
+
+    // 把节点从它所在的链表中移除.
+    fn unlink(node: Link<T>) {
+        unsafe {
+            let left = (*node.as_ptr()).left;
+            let right = (*node.as_ptr()).right;
+            (*left.as_ptr()).right = right;
+            (*right.as_ptr()).left = left;
+            (*node.as_ptr()).left = node;
+            (*node.as_ptr()).right = node;
+        }
+    }
+
+    // 把一个独立的节点加入到根链表.
+    fn add_root(&mut self, node: Link<T>) {
+        unsafe {
+            (*node.as_ptr()).parent = None;
+            (*node.as_ptr()).mark = false;
+        }
+        match self.top {
+            None => {
+                unsafe {
+                    (*node.as_ptr()).left = node;
+                    (*node.as_ptr()).right = node;
+                }
+                self.top = Some(node);
+            }
+            Some(top) => {
+                Self::splice_after(top, node);
+                if self.higher(node, top) {
+                    self.top = Some(node);
+                }
+            }
+        }
+    }
+
+    // 把根节点 `child` 作为 `root` 的子节点.
+    fn link(root: Link<T>, child: Link<T>) {
+        unsafe {
+            (*child.as_ptr()).parent = Some(root);
+            (*child.as_ptr()).mark = false;
+            match (*root.as_ptr()).child {
+                None => {
+                    (*child.as_ptr()).left = child;
+                    (*child.as_ptr()).right = child;
+                    (*root.as_ptr()).child = Some(child);
+                }
+                Some(first) => Self::splice_after(first, child),
+            }
+            (*root.as_ptr()).degree += 1;
+        }
+    }
+
+    // 合并度数相同的树, 直到每种度数最多只有一棵树.
+    fn consolidate(&mut self) {
+        let Some(top) = self.top else {
+            return;
+        };
+        let roots = Self::siblings(top);
+        let mut table: Vec<Option<Link<T>>> = Vec::new();
+        for mut tree in roots {
+            let mut degree = unsafe { (*tree.as_ptr()).degree };
+            loop {
+                if degree >= table.len() {
+                    table.resize(degree + 1, None);
+                }
+                match table[degree].take() {
+                    None => {
+                        table[degree] = Some(tree);
+                        break;
+                    }
+                    Some(other) => {
+                        let (root, child) = if self.higher(other, tree) {
+                            (other, tree)
+                        } else {
+                            (tree, other)
+                        };
+                        Self::link(root, child);
+                        tree = root;
+                        degree += 1;
+                    }
+                }
+            }
+        }
+
+        // 用剩下的树重建根链表.
+        self.top = None;
+        for tree in table.into_iter().flatten() {
+            self.add_root(tree);
+        }
+    }
+
+    // 把节点从父节点的子链表中剪下来, 放到根链表.
+    fn cut(&mut self, node: Link<T>, parent: Link<T>) {
+        unsafe {
+            if (*node.as_ptr()).right == node {
+                (*parent.as_ptr()).child = None;
+            } else {
+                if (*parent.as_ptr()).child == Some(node) {
+                    (*parent.as_ptr()).child = Some((*node.as_ptr()).right);
+                }
+                Self::unlink(node);
+            }
+            (*parent.as_ptr()).degree -= 1;
+        }
+        self.add_root(node);
+    }
+
+    // 级联剪切: 节点第二次失去子节点时, 把它也剪下来.
+    fn cascading_cut(&mut self, mut node: Link<T>) {
+        while let Some(parent) = unsafe { (*node.as_ptr()).parent } {
+            unsafe {
+                if !(*node.as_ptr()).mark {
+                    (*node.as_ptr()).mark = true;
+                    break;
+                }
+            }
+            self.cut(node, parent);
+            node = parent;
+        }
+    }
+}
+
+impl<T, C: Compare<T>> PriorityQueue<T> for FibonacciHeap<T, C> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn push(&mut self, value: T) -> Handle {
+        let mut node = Box::new(Node {
+            value,
+            handle: self.space.handle(0),
+            parent: None,
+            child: None,
+            left: NonNull::dangling(),
+            right: NonNull::dangling(),
+            degree: 0,
+            mark: false,
+        });
+        let ptr = NonNull::from(&mut *node);
+        node.left = ptr;
+        node.right = ptr;
+        node.handle.set_node(ptr);
+        let handle = node.handle.clone();
+        let node = NonNull::from(Box::leak(node));
+        self.add_root(node);
+        self.len += 1;
+        handle
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.top.map(|top| unsafe { &(*top.as_ptr()).value })
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let top = self.top?;
+
+        // 把所有子节点移到根链表.
+        if let Some(child) = unsafe { (*top.as_ptr()).child.take() } {
+            for child in Self::siblings(child) {
+                Self::unlink(child);
+                unsafe {
+                    (*child.as_ptr()).parent = None;
+                    (*child.as_ptr()).mark = false;
+                }
+                Self::splice_after(top, child);
+            }
+        }
+
+        let right = unsafe { (*top.as_ptr()).right };
+        self.top = if right == top { None } else { Some(right) };
+        Self::unlink(top);
+        self.consolidate();
+        self.len -= 1;
+
+        let node = unsafe { Box::from_raw(top.as_ptr()) };
+        node.handle.invalidate();
+        Some(node.value)
+    }
+
+    fn get(&self, handle: &Handle) -> Option<&T> {
+        let node: Link<T> = self.space.resolve_node(handle)?;
+        Some(unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// 直接拼接两个根链表, O(1).
+    fn meld(&mut self, mut other: Self) {
+        self.space.absorb(&other.space);
+        self.len += other.len;
+        other.len = 0;
+
+        let Some(other_top) = other.top.take() else {
+            return;
+        };
+        match self.top {
+            None => self.top = Some(other_top),
+            Some(top) => {
+                unsafe {
+                    let top_right = (*top.as_ptr()).right;
+                    let other_left = (*other_top.as_ptr()).left;
+                    (*top.as_ptr()).right = other_top;
+                    (*other_top.as_ptr()).left = top;
+                    (*other_left.as_ptr()).right = top_right;
+                    (*top_right.as_ptr()).left = other_left;
+                }
+                if self.higher(other_top, top) {
+                    self.top = Some(other_top);
+                }
+            }
+        }
+    }
+
+    fn decrease_key(&mut self, handle: &Handle, value: T) -> Result<T, KeyError> {
+        let node: Link<T> = self
+            .space
+            .resolve_node(handle)
+            .ok_or(KeyError::InvalidHandle)?;
+        let old = unsafe { &mut (*node.as_ptr()).value };
+        if self.cmp.compare(&value, old).is_lt() {
+            return Err(KeyError::PriorityLowered);
+        }
+        let old = std::mem::replace(old, value);
+
+        if let Some(parent) = unsafe { (*node.as_ptr()).parent } {
+            if self.higher(node, parent) {
+                self.cut(node, parent);
+                self.cascading_cut(parent);
+            }
+        } else if let Some(top) = self.top {
+            if self.higher(node, top) {
+                self.top = Some(node);
+            }
+        }
+        Ok(old)
+    }
+}
+
+impl<T, C> Drop for FibonacciHeap<T, C> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: Clone, C: Compare<T> + Clone> Clone for FibonacciHeap<T, C> {
+    /// 复制所有元素, 新的堆有自己的句柄.
+    fn clone(&self) -> Self {
+        let mut heap = Self::with_comparator(self.cmp.clone());
+        for node in self.nodes() {
+            heap.push(unsafe { (*node.as_ptr()).value.clone() });
+        }
+        heap
+    }
+}
+
+impl<T: fmt::Debug, C> fmt::Debug for FibonacciHeap<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.nodes()
+                    .into_iter()
+                    .map(|node| unsafe { &(*node.as_ptr()).value }),
+            )
+            .finish()
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for FibonacciHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FibonacciHeap, MinFibonacciHeap};
+    use crate::traits::{KeyError, PriorityQueue};
+
+    #[test]
+    fn test_push_pop() {
+        let mut heap = FibonacciHeap::new();
+        heap.extend((0..100).map(|i| (i * 37) % 100));
+        assert_eq!(heap.len(), 100);
+        assert_eq!(heap.peek(), Some(&99));
+        let values: Vec<i32> = std::iter::from_fn(|| heap.pop()).collect();
+        assert_eq!(values, (0..100).rev().collect::<Vec<_>>());
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_decrease_key() {
+        let mut heap = MinFibonacciHeap::new_min();
+        let handles: Vec<_> = (0..50).map(|i| heap.push(i + 100)).collect();
+        assert_eq!(heap.pop(), Some(100));
+        assert_eq!(
+            heap.decrease_key(&handles[0], 1),
+            Err(KeyError::InvalidHandle)
+        );
+        assert_eq!(
+            heap.decrease_key(&handles[30], 200),
+            Err(KeyError::PriorityLowered)
+        );
+        assert_eq!(heap.decrease_key(&handles[30], 5), Ok(130));
+        assert_eq!(heap.decrease_key(&handles[40], 3), Ok(140));
+        assert_eq!(heap.pop(), Some(3));
+        // 触发级联剪切.
+        for (i, handle) in handles.iter().enumerate().skip(41) {
+            let value = i32::try_from(i).unwrap() - 40;
+            assert!(heap.decrease_key(handle, value).is_ok());
+        }
+        let values: Vec<i32> = std::iter::from_fn(|| heap.pop()).take(12).collect();
+        assert_eq!(values, [1, 2, 3, 4, 5, 5, 6, 7, 8, 9, 101, 102]);
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut a = MinFibonacciHeap::new_min();
+        let mut b = MinFibonacciHeap::new_min();
+        a.extend([7, 3, 9]);
+        let handle = b.push(20);
+        b.extend([4, 11, 6]);
+        a.meld(b);
+        assert_eq!(a.len(), 7);
+        assert_eq!(a.decrease_key(&handle, 1), Ok(20));
+        let values: Vec<i32> = std::iter::from_fn(|| a.pop()).collect();
+        assert_eq!(values, [1, 3, 4, 6, 7, 9, 11]);
+    }
+}
@@ -0,0 +1,405 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 支持按句柄修改和删除元素的二叉堆 (indexed priority queue).
+//!
+//! 插入元素时返回一个句柄 [`Handle`], 之后可以通过句柄在 O(log(n)) 时间内
+//! 修改元素的键值或者删除该元素, 这正是 Dijkstra 和 Prim 算法所需要的 decrease-key 操作.
+//!
+//! 实现上, 堆数组中存放的是元素以及它的句柄, 句柄中记录了元素在堆数组中的位置,
+//! 每次交换两个节点时同时更新它们的位置. 元素被移除后, 它占用的空间立即被释放.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::mem;
+
+use crate::compare::{Compare, MaxComparator, MinComparator};
+use crate::traits::{Handle, HandleSpace, KeyError, PriorityQueue};
+
+pub struct IndexedBinaryHeap<T, C = MaxComparator> {
+    // 堆数组, 存放元素以及它的句柄.
+    heap: Vec<(Handle, T)>,
+    space: HandleSpace,
+    cmp: C,
+}
+
+/// 小顶堆, 适用于 Dijkstra 之类的最短路径算法.
+pub type IndexedMinHeap<T> = IndexedBinaryHeap<T, MinComparator>;
+
+impl<T: Ord> Default for IndexedBinaryHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> IndexedBinaryHeap<T> {
+    /// 创建一个空的大顶堆.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_comparator(MaxComparator)
+    }
+}
+
+impl<T: Ord> IndexedBinaryHeap<T, MinComparator> {
+    /// 创建一个空的小顶堆.
+    #[must_use]
+    #[inline]
+    pub fn new_min() -> Self {
+        Self::with_comparator(MinComparator)
+    }
+}
+
+impl<T, C> IndexedBinaryHeap<T, C> {
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// 检查句柄对应的元素是否还在堆中.
+    #[must_use]
+    #[inline]
+    pub fn contains(&self, handle: &Handle) -> bool {
+        self.slot(handle).is_ok()
+    }
+
+    #[must_use]
+    pub fn get(&self, handle: &Handle) -> Option<&T> {
+        let pos = self.slot(handle).ok()?;
+        Some(&self.heap[pos].1)
+    }
+
+    /// 返回堆顶元素及其句柄.
+    #[must_use]
+    pub fn peek(&self) -> Option<(&Handle, &T)> {
+        self.heap.first().map(|(handle, value)| (handle, value))
+    }
+
+    /// 清空堆, 之前的句柄全部失效.
+    pub fn clear(&mut self) {
+        for (handle, _value) in self.heap.drain(..) {
+            handle.invalidate();
+        }
+    }
+
+    /// 以任意顺序遍历堆中的元素.
+    pub fn iter(&self) -> impl Iterator<Item = (&Handle, &T)> + '_ {
+        self.heap.iter().map(|(handle, value)| (handle, value))
+    }
+
+    // 返回句柄对应的元素在堆数组中的位置.
+    fn slot(&self, handle: &Handle) -> Result<usize, KeyError> {
+        self.space.resolve(handle).ok_or(KeyError::InvalidHandle)
+    }
+}
+
+impl<T, C: Compare<T>> IndexedBinaryHeap<T, C> {
+    #[must_use]
+    #[inline]
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            heap: Vec::new(),
+            space: HandleSpace::new(),
+            cmp,
+        }
+    }
+
+    /// 插入新元素, 返回它的句柄.
+    pub fn push(&mut self, value: T) -> Handle {
+        let pos = self.heap.len();
+        let handle = self.space.handle(pos);
+        self.heap.push((handle.clone(), value));
+        self.shift_up(pos);
+        handle
+    }
+
+    /// 弹出堆顶元素及其句柄.
+    pub fn pop(&mut self) -> Option<(Handle, T)> {
+        if self.heap.is_empty() {
+            None
+        } else {
+            Some(self.remove_at(0))
+        }
+    }
+
+    /// 修改句柄对应元素的键值, 返回旧的值.
+    ///
+    /// 新的值可以比旧值的优先级高, 也可以低.
+    ///
+    /// # Errors
+    ///
+    /// 句柄无效时返回 `KeyError::InvalidHandle`.
+    pub fn change_key(&mut self, handle: &Handle, value: T) -> Result<T, KeyError> {
+        let pos = self.slot(handle)?;
+        let old = mem::replace(&mut self.heap[pos].1, value);
+        match self.cmp.compare(&self.heap[pos].1, &old) {
+            Ordering::Greater => self.shift_up(pos),
+            Ordering::Less => self.shift_down(pos),
+            Ordering::Equal => (),
+        }
+        Ok(old)
+    }
+
+    /// 提高句柄对应元素的优先级, 返回旧的值.
+    ///
+    /// 对小顶堆来说就是减小键值 (decrease-key), 对大顶堆来说是增大键值.
+    /// 元素只会向堆顶移动, 时间复杂度是 O(log(n)).
+    ///
+    /// # Errors
+    ///
+    /// - 句柄无效时返回 `KeyError::InvalidHandle`;
+    /// - 新值的优先级比旧值低时返回 `KeyError::PriorityLowered`, 此时堆不会被修改.
+    pub fn decrease_key(&mut self, handle: &Handle, value: T) -> Result<T, KeyError> {
+        let pos = self.slot(handle)?;
+        if self.cmp.compare(&value, &self.heap[pos].1).is_lt() {
+            return Err(KeyError::PriorityLowered);
+        }
+        let old = mem::replace(&mut self.heap[pos].1, value);
+        self.shift_up(pos);
+        Ok(old)
+    }
+
+    /// 删除句柄对应的元素.
+    ///
+    /// 句柄无效时返回 `None`.
+    pub fn remove(&mut self, handle: &Handle) -> Option<T> {
+        let pos = self.slot(handle).ok()?;
+        Some(self.remove_at(pos).1)
+    }
+
+    fn compare_at(&self, i: usize, j: usize) -> Ordering {
+        self.cmp.compare(&self.heap[i].1, &self.heap[j].1)
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.heap[i].0.set_key(i);
+        self.heap[j].0.set_key(j);
+    }
+
+    fn remove_at(&mut self, pos: usize) -> (Handle, T) {
+        let last = self.heap.len() - 1;
+        self.swap(pos, last);
+        let (handle, value) = self.heap.pop().expect("heap should not be empty");
+        handle.invalidate();
+        if pos < last {
+            // 被换过来的元素可能需要上移, 也可能需要下沉.
+            self.shift_up(pos);
+            self.shift_down(pos);
+        }
+        (handle, value)
+    }
+
+    fn shift_up(&mut self, mut pos: usize) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if self.compare_at(pos, parent).is_le() {
+                break;
+            }
+            self.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    fn shift_down(&mut self, mut pos: usize) {
+        let len = self.heap.len();
+        while pos * 2 + 1 < len {
+            let left = pos * 2 + 1;
+            let right = left + 1;
+            let larger = if right < len && self.compare_at(right, left).is_gt() {
+                right
+            } else {
+                left
+            };
+            if self.compare_at(pos, larger).is_lt() {
+                self.swap(pos, larger);
+                pos = larger;
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+impl<T, C: Compare<T>> PriorityQueue<T> for IndexedBinaryHeap<T, C> {
+    fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn push(&mut self, value: T) -> Handle {
+        Self::push(self, value)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        Self::peek(self).map(|(_handle, value)| value)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        Self::pop(self).map(|(_handle, value)| value)
+    }
+
+    fn get(&self, handle: &Handle) -> Option<&T> {
+        Self::get(self, handle)
+    }
+
+    /// 把 `other` 的数组追加到尾部, 再逐个上移, 时间复杂度是 O(m log(n + m)).
+    fn meld(&mut self, mut other: Self) {
+        let start = self.heap.len();
+        self.space.absorb(&other.space);
+        self.heap.append(&mut other.heap);
+        for pos in start..self.heap.len() {
+            self.heap[pos].0.set_key(pos);
+        }
+        for pos in start..self.heap.len() {
+            self.shift_up(pos);
+        }
+    }
+
+    fn decrease_key(&mut self, handle: &Handle, value: T) -> Result<T, KeyError> {
+        Self::decrease_key(self, handle, value)
+    }
+}
+
+impl<T, C> Drop for IndexedBinaryHeap<T, C> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: Clone, C: Clone> Clone for IndexedBinaryHeap<T, C> {
+    /// 复制所有元素, 新的堆有自己的句柄.
+    fn clone(&self) -> Self {
+        let space = HandleSpace::new();
+        let heap = self
+            .heap
+            .iter()
+            .enumerate()
+            .map(|(pos, (_handle, value))| (space.handle(pos), value.clone()))
+            .collect();
+        Self {
+            heap,
+            space,
+            cmp: self.cmp.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, C> fmt::Debug for IndexedBinaryHeap<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.heap.iter().map(|(_handle, value)| value))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IndexedBinaryHeap, IndexedMinHeap};
+    use crate::traits::{KeyError, PriorityQueue};
+
+    #[test]
+    fn test_push_pop() {
+        let mut heap = IndexedBinaryHeap::new();
+        let handles: Vec<_> = [3, 1, 4, 1, 5].into_iter().map(|v| heap.push(v)).collect();
+        assert_eq!(heap.peek(), Some((&handles[4], &5)));
+        assert_eq!(heap.pop(), Some((handles[4].clone(), 5)));
+        assert!(!heap.contains(&handles[4]));
+        assert!(handles[4].is_removed());
+        assert_eq!(heap.get(&handles[2]), Some(&4));
+        let values: Vec<i32> = std::iter::from_fn(|| heap.pop().map(|(_, v)| v)).collect();
+        assert_eq!(values, [4, 3, 1, 1]);
+    }
+
+    #[test]
+    fn test_decrease_key() {
+        let mut heap = IndexedMinHeap::new_min();
+        let a = heap.push(10);
+        let b = heap.push(20);
+        let c = heap.push(30);
+        assert_eq!(heap.decrease_key(&c, 5), Ok(30));
+        assert_eq!(heap.peek(), Some((&c, &5)));
+        assert_eq!(heap.decrease_key(&b, 25), Err(KeyError::PriorityLowered));
+        assert_eq!(heap.get(&b), Some(&20));
+        assert_eq!(heap.change_key(&c, 40), Ok(5));
+        assert_eq!(heap.pop(), Some((a.clone(), 10)));
+        assert_eq!(heap.decrease_key(&a, 1), Err(KeyError::InvalidHandle));
+        assert_eq!(heap.pop(), Some((b, 20)));
+        assert_eq!(heap.pop(), Some((c, 40)));
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut a = IndexedMinHeap::new_min();
+        let mut b = IndexedMinHeap::new_min();
+        let ha = a.push(5);
+        let hb = b.push(8);
+        b.push(2);
+        assert_eq!(a.get(&hb), None);
+        PriorityQueue::meld(&mut a, b);
+        assert_eq!(a.len(), 3);
+        assert_eq!(a.decrease_key(&hb, 1), Ok(8));
+        assert_eq!(PriorityQueue::pop(&mut a), Some(1));
+        assert_eq!(PriorityQueue::pop(&mut a), Some(2));
+        assert_eq!(a.get(&ha), Some(&5));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut heap = IndexedMinHeap::new_min();
+        let handles: Vec<_> = (0..20).map(|v| heap.push((v * 7) % 20)).collect();
+        for handle in handles.iter().step_by(3) {
+            assert!(heap.remove(handle).is_some());
+            assert_eq!(heap.remove(handle), None);
+        }
+        let mut expected: Vec<i32> = (0..20)
+            .filter(|v| v % 3 != 0)
+            .map(|v| (v * 7) % 20)
+            .collect();
+        expected.sort_unstable();
+        let values: Vec<i32> = std::iter::from_fn(|| heap.pop().map(|(_, v)| v)).collect();
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // 有向图的邻接表: (终点, 权重)
+        let graph: [&[(usize, u32)]; 5] = [
+            &[(1, 10), (2, 3)],
+            &[(3, 2)],
+            &[(1, 4), (3, 8), (4, 2)],
+            &[(4, 5)],
+            &[],
+        ];
+        let mut dist = [u32::MAX; 5];
+        let mut heap = IndexedMinHeap::new_min();
+        let mut handles = Vec::new();
+        for vertex in 0..graph.len() {
+            let d = if vertex == 0 { 0 } else { u32::MAX };
+            handles.push(heap.push((d, vertex)));
+        }
+        while let Some((_, (d, vertex))) = heap.pop() {
+            dist[vertex] = d;
+            if d == u32::MAX {
+                continue;
+            }
+            for &(next, weight) in graph[vertex] {
+                let handle = &handles[next];
+                if let Some(&(old, _)) = heap.get(handle) {
+                    if d + weight < old {
+                        heap.decrease_key(handle, (d + weight, next)).unwrap();
+                    }
+                }
+            }
+        }
+        assert_eq!(dist, [0, 7, 3, 9, 5]);
+    }
+}
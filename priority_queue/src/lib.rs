@@ -9,3 +9,14 @@
     clippy::nursery,
     clippy::pedantic
 )]
+#![allow(clippy::module_name_repetitions)]
+
+pub mod binary_heap;
+pub mod binomial_heap;
+pub mod compare;
+pub mod fibonacci_heap;
+pub mod indexed_heap;
+pub mod min_max_heap;
+pub mod pairing_heap;
+pub mod skew_heap;
+pub mod traits;
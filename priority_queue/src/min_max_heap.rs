@@ -0,0 +1,413 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 最小-最大堆 (min-max heap), 一种双端优先级队列.
+//!
+//! 它仍然是一棵用数组存储的完全二叉树, 但是偶数层 (根节点是第 0 层) 是最小层,
+//! 奇数层是最大层:
+//! - 最小层的节点, 不大于它的所有子孙节点;
+//! - 最大层的节点, 不小于它的所有子孙节点.
+//!
+//! 所以最小值就是根节点, 最大值是根节点的两个子节点中较大的那个,
+//! 插入和弹出只需要沿着祖父节点或者孙子节点移动, 时间复杂度都是 O(log(n)).
+
+use std::fmt;
+use std::mem;
+use std::slice;
+
+#[derive(Clone)]
+pub struct MinMaxHeap<T> {
+    heap: Vec<T>,
+}
+
+impl<T: Ord> Default for MinMaxHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[inline]
+const fn parent(pos: usize) -> usize {
+    (pos - 1) / 2
+}
+
+// 节点是否在最小层.
+#[inline]
+const fn is_min_level(pos: usize) -> bool {
+    (pos + 1).ilog2().is_multiple_of(2)
+}
+
+impl<T: Ord> MinMaxHeap<T> {
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self { heap: Vec::new() }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            heap: Vec::with_capacity(capacity),
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// 返回最小值.
+    #[must_use]
+    #[inline]
+    pub fn peek_min(&self) -> Option<&T> {
+        self.heap.first()
+    }
+
+    /// 返回最大值.
+    #[must_use]
+    pub fn peek_max(&self) -> Option<&T> {
+        self.max_pos().map(|pos| &self.heap[pos])
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.heap.push(value);
+        self.bubble_up(self.heap.len() - 1);
+    }
+
+    /// 弹出最小值.
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.pop_at(0)
+    }
+
+    /// 弹出最大值.
+    pub fn pop_max(&mut self) -> Option<T> {
+        let pos = self.max_pos()?;
+        self.pop_at(pos)
+    }
+
+    /// 用新的值替换最小值, 返回原来的最小值.
+    ///
+    /// 堆为空时, 直接插入新的值并返回 `None`.
+    pub fn replace_min(&mut self, value: T) -> Option<T> {
+        if self.is_empty() {
+            self.push(value);
+            return None;
+        }
+        let old = mem::replace(&mut self.heap[0], value);
+        self.trickle_down(0);
+        Some(old)
+    }
+
+    /// 用新的值替换最大值, 返回原来的最大值.
+    ///
+    /// 比先 `pop_max()` 再 `push()` 少一次调整, 适合有容量上限的 top-k 缓存:
+    /// 淘汰最差的元素, 同时保留对最好的元素的访问.
+    /// 堆为空时, 直接插入新的值并返回 `None`.
+    pub fn replace_max(&mut self, value: T) -> Option<T> {
+        let Some(pos) = self.max_pos() else {
+            self.push(value);
+            return None;
+        };
+        let old = mem::replace(&mut self.heap[pos], value);
+        if pos > 0 {
+            // 最大层的节点的父节点就是根节点, 新的值可能比它还小.
+            if self.heap[pos] < self.heap[0] {
+                self.heap.swap(pos, 0);
+            }
+            self.trickle_down(pos);
+        }
+        Some(old)
+    }
+
+    /// 以任意顺序遍历堆中的元素.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.heap.iter()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn into_vec(self) -> Vec<T> {
+        self.heap
+    }
+
+    /// 返回升序排列的数组.
+    #[must_use]
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut vec = Vec::with_capacity(self.len());
+        while let Some(value) = self.pop_min() {
+            vec.push(value);
+        }
+        vec
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    fn max_pos(&self) -> Option<usize> {
+        match self.heap.len() {
+            0 => None,
+            1 => Some(0),
+            2 => Some(1),
+            _ => Some(if self.heap[1] >= self.heap[2] { 1 } else { 2 }),
+        }
+    }
+
+    fn pop_at(&mut self, pos: usize) -> Option<T> {
+        if pos >= self.heap.len() {
+            return None;
+        }
+        // 用最后一个元素填补空位, 再让它下沉.
+        let value = self.heap.swap_remove(pos);
+        if pos < self.heap.len() {
+            self.trickle_down(pos);
+        }
+        Some(value)
+    }
+
+    fn bubble_up(&mut self, pos: usize) {
+        if pos == 0 {
+            return;
+        }
+        let parent = parent(pos);
+        if is_min_level(pos) {
+            if self.heap[pos] > self.heap[parent] {
+                // 比最大层的父节点还大, 应该放到最大层中.
+                self.heap.swap(pos, parent);
+                self.bubble_up_by(parent, |a, b| a > b);
+            } else {
+                self.bubble_up_by(pos, |a, b| a < b);
+            }
+        } else if self.heap[pos] < self.heap[parent] {
+            // 比最小层的父节点还小, 应该放到最小层中.
+            self.heap.swap(pos, parent);
+            self.bubble_up_by(parent, |a, b| a < b);
+        } else {
+            self.bubble_up_by(pos, |a, b| a > b);
+        }
+    }
+
+    // 与祖父节点比较, `before(a, b)` 为真时表示 `a` 应该位于 `b` 的上层.
+    fn bubble_up_by<F>(&mut self, mut pos: usize, before: F)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        while pos > 2 {
+            let grandparent = parent(parent(pos));
+            if !before(&self.heap[pos], &self.heap[grandparent]) {
+                break;
+            }
+            self.heap.swap(pos, grandparent);
+            pos = grandparent;
+        }
+    }
+
+    fn trickle_down(&mut self, pos: usize) {
+        if is_min_level(pos) {
+            self.trickle_down_by(pos, |a, b| a < b);
+        } else {
+            self.trickle_down_by(pos, |a, b| a > b);
+        }
+    }
+
+    // 在子节点和孙子节点中找到最应该位于上层的那个, 与它交换.
+    fn trickle_down_by<F>(&mut self, mut pos: usize, before: F)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        let len = self.heap.len();
+        loop {
+            let first_child = 2 * pos + 1;
+            if first_child >= len {
+                break;
+            }
+            let first_grandchild = 2 * first_child + 1;
+            let candidates = (first_child..len.min(first_child + 2))
+                .chain(first_grandchild..len.min(first_grandchild + 4));
+            let mut best = first_child;
+            for candidate in candidates {
+                if before(&self.heap[candidate], &self.heap[best]) {
+                    best = candidate;
+                }
+            }
+
+            if !before(&self.heap[best], &self.heap[pos]) {
+                break;
+            }
+            self.heap.swap(pos, best);
+            if best < first_grandchild {
+                // 子节点位于另一种层, 它没有需要继续调整的子孙.
+                break;
+            }
+            // 孙子节点与它的父节点 (另一种层) 也要保持顺序.
+            let parent = parent(best);
+            if before(&self.heap[parent], &self.heap[best]) {
+                self.heap.swap(parent, best);
+            }
+            pos = best;
+        }
+    }
+}
+
+impl<T: Ord> From<Vec<T>> for MinMaxHeap<T> {
+    /// 从最后一个非叶子节点开始依次下沉, 时间复杂度是 O(n).
+    fn from(vec: Vec<T>) -> Self {
+        let mut heap = Self { heap: vec };
+        for pos in (0..heap.len() / 2).rev() {
+            heap.trickle_down(pos);
+        }
+        heap
+    }
+}
+
+impl<T: Ord> FromIterator<T> for MinMaxHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<T: Ord> Extend<T> for MinMaxHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T> IntoIterator for MinMaxHeap<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    /// 以任意顺序遍历.
+    fn into_iter(self) -> Self::IntoIter {
+        self.heap.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a MinMaxHeap<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.heap.iter()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for MinMaxHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.heap.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_min_level, MinMaxHeap};
+
+    // 检查每个节点与它所有子孙节点的关系.
+    fn check_heap(heap: &MinMaxHeap<i32>) {
+        let data = &heap.heap;
+        for pos in 0..data.len() {
+            let mut stack = vec![2 * pos + 1, 2 * pos + 2];
+            while let Some(child) = stack.pop() {
+                if child >= data.len() {
+                    continue;
+                }
+                if is_min_level(pos) {
+                    assert!(data[pos] <= data[child]);
+                } else {
+                    assert!(data[pos] >= data[child]);
+                }
+                stack.push(2 * child + 1);
+                stack.push(2 * child + 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_levels() {
+        let levels: Vec<bool> = (0..8).map(is_min_level).collect();
+        assert_eq!(levels, [true, false, false, true, true, true, true, false]);
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut heap = MinMaxHeap::new();
+        for i in 0..100 {
+            heap.push((i * 37) % 100);
+            check_heap(&heap);
+        }
+        assert_eq!(heap.peek_min(), Some(&0));
+        assert_eq!(heap.peek_max(), Some(&99));
+        for i in 0..50 {
+            assert_eq!(heap.pop_min(), Some(i));
+            check_heap(&heap);
+            assert_eq!(heap.pop_max(), Some(99 - i));
+            check_heap(&heap);
+        }
+        assert!(heap.is_empty());
+        assert_eq!(heap.pop_min(), None);
+        assert_eq!(heap.pop_max(), None);
+    }
+
+    #[test]
+    fn test_small_heap() {
+        let mut heap = MinMaxHeap::new();
+        heap.push(2);
+        assert_eq!(heap.peek_max(), Some(&2));
+        heap.push(1);
+        assert_eq!(heap.peek_min(), Some(&1));
+        assert_eq!(heap.peek_max(), Some(&2));
+        assert_eq!(heap.pop_max(), Some(2));
+        assert_eq!(heap.pop_max(), Some(1));
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let heap = MinMaxHeap::from((0..200).map(|i| (i * 71) % 200).collect::<Vec<i32>>());
+        check_heap(&heap);
+        assert_eq!(heap.into_sorted_vec(), (0..200).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_replace() {
+        let mut heap: MinMaxHeap<i32> = (0..20).collect();
+        assert_eq!(heap.replace_max(-1), Some(19));
+        check_heap(&heap);
+        assert_eq!(heap.peek_min(), Some(&-1));
+        assert_eq!(heap.peek_max(), Some(&18));
+        assert_eq!(heap.replace_min(100), Some(-1));
+        check_heap(&heap);
+        assert_eq!(heap.peek_max(), Some(&100));
+        assert_eq!(heap.peek_min(), Some(&0));
+
+        let mut empty = MinMaxHeap::new();
+        assert_eq!(empty.replace_max(1), None);
+        assert_eq!(empty.replace_max(2), Some(1));
+        assert_eq!(empty.peek_min(), Some(&2));
+    }
+
+    #[test]
+    fn test_top_k() {
+        // 只保留最小的 5 个元素.
+        let k = 5;
+        let mut heap = MinMaxHeap::with_capacity(k);
+        for value in (0..100).map(|i| (i * 37) % 100) {
+            if heap.len() < k {
+                heap.push(value);
+            } else if heap.peek_max().is_some_and(|max| value < *max) {
+                heap.replace_max(value);
+            }
+            check_heap(&heap);
+        }
+        assert_eq!(heap.into_sorted_vec(), [0, 1, 2, 3, 4]);
+    }
+}
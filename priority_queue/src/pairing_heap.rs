@@ -0,0 +1,343 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 配对堆 (pairing heap).
+//!
+//! 配对堆是一棵多叉树, 每个节点的子节点用单向链表串起来 (左孩子右兄弟表示法).
+//! - 插入和合并: 比较两个根节点, 优先级低的作为另一个的第一个子节点, O(1);
+//! - 弹出: 删除根节点后, 先从左到右两两合并子树, 再从右到左依次合并, 均摊 O(log(n));
+//! - decrease-key: 把节点所在的子树剪下来, 再与根节点合并, 均摊 o(log(n)).
+//!
+//! 节点都单独分配在堆上, 用指针连接起来, 合并时不需要移动节点, 元素被弹出后立即释放.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use crate::compare::{Compare, MaxComparator, MinComparator};
+use crate::traits::{Handle, HandleSpace, KeyError, PriorityQueue};
+
+type Link<T> = NonNull<Node<T>>;
+
+struct Node<T> {
+    value: T,
+    handle: Handle,
+    // 第一个子节点.
+    child: Option<Link<T>>,
+    // 下一个兄弟节点.
+    sibling: Option<Link<T>>,
+    // 如果是第一个子节点, 指向父节点; 否则指向前一个兄弟节点.
+    prev: Option<Link<T>>,
+}
+
+pub struct PairingHeap<T, C = MaxComparator> {
+    root: Option<Link<T>>,
+    len: usize,
+    space: HandleSpace,
+    cmp: C,
+    _marker: PhantomData<Box<Node<T>>>,
+}
+
+/// 小顶堆.
+pub type MinPairingHeap<T> = PairingHeap<T, MinComparator>;
+
+impl<T: Ord> Default for PairingHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> PairingHeap<T> {
+    /// 创建一个空的大顶堆.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_comparator(MaxComparator)
+    }
+}
+
+impl<T: Ord> PairingHeap<T, MinComparator> {
+    /// 创建一个空的小顶堆.
+    #[must_use]
+    pub fn new_min() -> Self {
+        Self::with_comparator(MinComparator)
+    }
+}
+
+impl<T, C> PairingHeap<T, C> {
+    /// 清空堆, 之前的句柄全部失效.
+    pub fn clear(&mut self) {
+        for node in self.nodes() {
+            // 每个节点只会被释放一次.
+            let node = unsafe { Box::from_raw(node.as_ptr()) };
+            node.handle.invalidate();
+        }
+        self.root = None;
+        self.len = 0;
+    }
+
+    // 返回所有的节点.
+    fn nodes(&self) -> Vec<Link<T>> {
+        let mut nodes = Vec::with_capacity(self.len);
+        let mut stack: Vec<Link<T>> = self.root.into_iter().collect();
+        while let Some(node) = stack.pop() {
+            unsafe {
+                stack.extend((*node.as_ptr()).child);
+                stack.extend((*node.as_ptr()).sibling);
+            }
+            nodes.push(node);
+        }
+        nodes
+    }
+}
+
+impl<T, C: Compare<T>> PairingHeap<T, C> {
+    #[must_use]
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            root: None,
+            len: 0,
+            space: HandleSpace::new(),
+            cmp,
+            _marker: PhantomData,
+        }
+    }
+
+    // 节点 `a` 的优先级是否比 `b` 高.
+    fn higher(&self, a: Link<T>, b: Link<T>) -> bool {
+        unsafe {
+            self.cmp
+                .compare(&(*a.as_ptr()).value, &(*b.as_ptr()).value)
+                .is_gt()
+        }
+    }
+
+    // 合并两棵树, 返回新的根节点.
+    fn link(&self, a: Link<T>, b: Link<T>) -> Link<T> {
+        let (root, child) = if self.higher(b, a) { (b, a) } else { (a, b) };
+        unsafe {
+            let first = (*root.as_ptr()).child;
+            if let Some(first) = first {
+                (*first.as_ptr()).prev = Some(child);
+            }
+            (*child.as_ptr()).sibling = first;
+            (*child.as_ptr()).prev = Some(root);
+            (*root.as_ptr()).child = Some(child);
+            (*root.as_ptr()).prev = None;
+            (*root.as_ptr()).sibling = None;
+        }
+        root
+    }
+
+    // 两趟合并所有子树.
+    fn merge_pairs(&self, first: Option<Link<T>>) -> Option<Link<T>> {
+        let mut children = Vec::new();
+        let mut next = first;
+        while let Some(child) = next {
+            unsafe {
+                next = (*child.as_ptr()).sibling;
+                (*child.as_ptr()).sibling = None;
+                (*child.as_ptr()).prev = None;
+            }
+            children.push(child);
+        }
+
+        // 从左到右两两合并.
+        let mut pairs = Vec::with_capacity(children.len().div_ceil(2));
+        for chunk in children.chunks(2) {
+            let tree = if let [a, b] = *chunk {
+                self.link(a, b)
+            } else {
+                chunk[0]
+            };
+            pairs.push(tree);
+        }
+
+        // 再从右到左依次合并.
+        let mut root = pairs.pop()?;
+        while let Some(tree) = pairs.pop() {
+            root = self.link(tree, root);
+        }
+        Some(root)
+    }
+
+    // 把以 `node` 为根的子树从它的父节点中剪下来.
+    fn cut(node: Link<T>) {
+        unsafe {
+            let sibling = (*node.as_ptr()).sibling;
+            let prev = (*node.as_ptr()).prev;
+            if let Some(prev) = prev {
+                if (*prev.as_ptr()).child == Some(node) {
+                    (*prev.as_ptr()).child = sibling;
+                } else {
+                    (*prev.as_ptr()).sibling = sibling;
+                }
+            }
+            if let Some(sibling) = sibling {
+                (*sibling.as_ptr()).prev = prev;
+            }
+            (*node.as_ptr()).sibling = None;
+            (*node.as_ptr()).prev = None;
+        }
+    }
+}
+
+impl<T, C: Compare<T>> PriorityQueue<T> for PairingHeap<T, C> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn push(&mut self, value: T) -> Handle {
+        let node = NonNull::from(Box::leak(Box::new(Node {
+            value,
+            handle: self.space.handle(0),
+            child: None,
+            sibling: None,
+            prev: None,
+        })));
+        let handle = unsafe { (*node.as_ptr()).handle.clone() };
+        handle.set_node(node);
+        self.root = Some(self.root.map_or(node, |root| self.link(root, node)));
+        self.len += 1;
+        handle
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.root.map(|root| unsafe { &(*root.as_ptr()).value })
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let root = self.root?;
+        let node = unsafe { Box::from_raw(root.as_ptr()) };
+        self.root = self.merge_pairs(node.child);
+        self.len -= 1;
+        node.handle.invalidate();
+        Some(node.value)
+    }
+
+    fn get(&self, handle: &Handle) -> Option<&T> {
+        let node: Link<T> = self.space.resolve_node(handle)?;
+        Some(unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// 比较两个根节点, O(1).
+    fn meld(&mut self, mut other: Self) {
+        self.space.absorb(&other.space);
+        self.len += other.len;
+        other.len = 0;
+        self.root = match (self.root, other.root.take()) {
+            (Some(a), Some(b)) => Some(self.link(a, b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    fn decrease_key(&mut self, handle: &Handle, value: T) -> Result<T, KeyError> {
+        let node: Link<T> = self
+            .space
+            .resolve_node(handle)
+            .ok_or(KeyError::InvalidHandle)?;
+        let old = unsafe { &mut (*node.as_ptr()).value };
+        if self.cmp.compare(&value, old).is_lt() {
+            return Err(KeyError::PriorityLowered);
+        }
+        let old = std::mem::replace(old, value);
+        if let Some(root) = self.root {
+            if root != node {
+                Self::cut(node);
+                self.root = Some(self.link(root, node));
+            }
+        }
+        Ok(old)
+    }
+}
+
+impl<T, C> Drop for PairingHeap<T, C> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: Clone, C: Compare<T> + Clone> Clone for PairingHeap<T, C> {
+    /// 复制所有元素, 新的堆有自己的句柄.
+    fn clone(&self) -> Self {
+        let mut heap = Self::with_comparator(self.cmp.clone());
+        for node in self.nodes() {
+            heap.push(unsafe { (*node.as_ptr()).value.clone() });
+        }
+        heap
+    }
+}
+
+impl<T: fmt::Debug, C> fmt::Debug for PairingHeap<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.nodes()
+                    .into_iter()
+                    .map(|node| unsafe { &(*node.as_ptr()).value }),
+            )
+            .finish()
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for PairingHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MinPairingHeap, PairingHeap};
+    use crate::traits::{KeyError, PriorityQueue};
+
+    #[test]
+    fn test_push_pop() {
+        let mut heap = PairingHeap::new();
+        for i in 0..100 {
+            heap.push((i * 37) % 100);
+        }
+        assert_eq!(heap.len(), 100);
+        assert_eq!(heap.peek(), Some(&99));
+        let values: Vec<i32> = std::iter::from_fn(|| heap.pop()).collect();
+        assert_eq!(values, (0..100).rev().collect::<Vec<_>>());
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_decrease_key() {
+        let mut heap = MinPairingHeap::new_min();
+        let handles: Vec<_> = (0..50).map(|i| heap.push(i + 100)).collect();
+        assert_eq!(heap.pop(), Some(100));
+        assert_eq!(
+            heap.decrease_key(&handles[0], 1),
+            Err(KeyError::InvalidHandle)
+        );
+        assert_eq!(
+            heap.decrease_key(&handles[30], 200),
+            Err(KeyError::PriorityLowered)
+        );
+        assert_eq!(heap.decrease_key(&handles[30], 5), Ok(130));
+        assert_eq!(heap.decrease_key(&handles[40], 3), Ok(140));
+        assert_eq!(heap.get(&handles[40]), Some(&3));
+        assert_eq!(heap.pop(), Some(3));
+        assert_eq!(heap.pop(), Some(5));
+        assert_eq!(heap.pop(), Some(101));
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut a = MinPairingHeap::new_min();
+        let mut b = MinPairingHeap::new_min();
+        a.extend([7, 3, 9]);
+        let handle = b.push(20);
+        b.push(4);
+        a.meld(b);
+        assert_eq!(a.len(), 5);
+        assert_eq!(a.decrease_key(&handle, 1), Ok(20));
+        let values: Vec<i32> = std::iter::from_fn(|| a.pop()).collect();
+        assert_eq!(values, [1, 3, 4, 7, 9]);
+    }
+}
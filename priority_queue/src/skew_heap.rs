@@ -0,0 +1,318 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 斜堆 (skew heap).
+//!
+//! 斜堆是自调整的左偏树, 所有操作都基于合并:
+//! 沿着两棵树的最右路径合并, 然后交换路径上每个节点的左右子树.
+//! 合并, 插入和弹出的均摊时间复杂度都是 O(log(n)).
+//!
+//! 最右路径在最坏情况下可能很长, 所以这里用循环代替递归来合并.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use crate::compare::{Compare, MaxComparator, MinComparator};
+use crate::traits::{Handle, HandleSpace, KeyError, PriorityQueue};
+
+type Link<T> = NonNull<Node<T>>;
+
+struct Node<T> {
+    value: T,
+    handle: Handle,
+    left: Option<Link<T>>,
+    right: Option<Link<T>>,
+    parent: Option<Link<T>>,
+}
+
+pub struct SkewHeap<T, C = MaxComparator> {
+    root: Option<Link<T>>,
+    len: usize,
+    space: HandleSpace,
+    cmp: C,
+    _marker: PhantomData<Box<Node<T>>>,
+}
+
+/// 小顶堆.
+pub type MinSkewHeap<T> = SkewHeap<T, MinComparator>;
+
+impl<T: Ord> Default for SkewHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> SkewHeap<T> {
+    /// 创建一个空的大顶堆.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_comparator(MaxComparator)
+    }
+}
+
+impl<T: Ord> SkewHeap<T, MinComparator> {
+    /// 创建一个空的小顶堆.
+    #[must_use]
+    pub fn new_min() -> Self {
+        Self::with_comparator(MinComparator)
+    }
+}
+
+impl<T, C> SkewHeap<T, C> {
+    /// 清空堆, 之前的句柄全部失效.
+    pub fn clear(&mut self) {
+        for node in self.nodes() {
+            // 每个节点只会被释放一次.
+            let node = unsafe { Box::from_raw(node.as_ptr()) };
+            node.handle.invalidate();
+        }
+        self.root = None;
+        self.len = 0;
+    }
+
+    // 返回所有的节点.
+    fn nodes(&self) -> Vec<Link<T>> {
+        let mut nodes = Vec::with_capacity(self.len);
+        let mut stack: Vec<Link<T>> = self.root.into_iter().collect();
+        while let Some(node) = stack.pop() {
+            unsafe {
+                stack.extend((*node.as_ptr()).left);
+                stack.extend((*node.as_ptr()).right);
+            }
+            nodes.push(node);
+        }
+        nodes
+    }
+}
+
+impl<T, C: Compare<T>> SkewHeap<T, C> {
+    #[must_use]
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            root: None,
+            len: 0,
+            space: HandleSpace::new(),
+            cmp,
+            _marker: PhantomData,
+        }
+    }
+
+    // 节点 `a` 的优先级是否比 `b` 高.
+    fn higher(&self, a: Link<T>, b: Link<T>) -> bool {
+        unsafe {
+            self.cmp
+                .compare(&(*a.as_ptr()).value, &(*b.as_ptr()).value)
+                .is_gt()
+        }
+    }
+
+    // 合并两棵树, 返回新的根节点.
+    fn merge(&self, a: Option<Link<T>>, b: Option<Link<T>>) -> Option<Link<T>> {
+        // 先沿着最右路径向下, 记录下每一步优先级较高的节点.
+        let mut path = Vec::new();
+        let (mut x, mut y) = (a, b);
+        while let (Some(p), Some(q)) = (x, y) {
+            let (top, other) = if self.higher(q, p) { (q, p) } else { (p, q) };
+            path.push(top);
+            x = unsafe { (*top.as_ptr()).right };
+            y = Some(other);
+        }
+
+        // 再自底向上, 把合并的结果作为左子树, 原来的左子树作为右子树.
+        let mut merged = x.or(y);
+        while let Some(top) = path.pop() {
+            unsafe {
+                (*top.as_ptr()).right = (*top.as_ptr()).left;
+                (*top.as_ptr()).left = merged;
+                if let Some(child) = merged {
+                    (*child.as_ptr()).parent = Some(top);
+                }
+            }
+            merged = Some(top);
+        }
+        if let Some(root) = merged {
+            unsafe {
+                (*root.as_ptr()).parent = None;
+            }
+        }
+        merged
+    }
+
+    // 把以 `node` 为根的子树从它的父节点中剪下来.
+    fn cut(node: Link<T>) {
+        unsafe {
+            if let Some(parent) = (*node.as_ptr()).parent.take() {
+                if (*parent.as_ptr()).left == Some(node) {
+                    (*parent.as_ptr()).left = None;
+                } else {
+                    (*parent.as_ptr()).right = None;
+                }
+            }
+        }
+    }
+}
+
+impl<T, C: Compare<T>> PriorityQueue<T> for SkewHeap<T, C> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn push(&mut self, value: T) -> Handle {
+        let node = NonNull::from(Box::leak(Box::new(Node {
+            value,
+            handle: self.space.handle(0),
+            left: None,
+            right: None,
+            parent: None,
+        })));
+        let handle = unsafe { (*node.as_ptr()).handle.clone() };
+        handle.set_node(node);
+        self.root = self.merge(self.root, Some(node));
+        self.len += 1;
+        handle
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.root.map(|root| unsafe { &(*root.as_ptr()).value })
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let root = self.root?;
+        let node = unsafe { Box::from_raw(root.as_ptr()) };
+        self.root = self.merge(node.left, node.right);
+        self.len -= 1;
+        node.handle.invalidate();
+        Some(node.value)
+    }
+
+    fn get(&self, handle: &Handle) -> Option<&T> {
+        let node: Link<T> = self.space.resolve_node(handle)?;
+        Some(unsafe { &(*node.as_ptr()).value })
+    }
+
+    /// 合并两棵树, 不需要移动节点.
+    fn meld(&mut self, mut other: Self) {
+        self.space.absorb(&other.space);
+        self.len += other.len;
+        other.len = 0;
+        self.root = self.merge(self.root, other.root.take());
+    }
+
+    /// 提高优先级之后, 以该节点为根的子树仍然满足堆的特性,
+    /// 把它剪下来再与根节点合并即可.
+    fn decrease_key(&mut self, handle: &Handle, value: T) -> Result<T, KeyError> {
+        let node: Link<T> = self
+            .space
+            .resolve_node(handle)
+            .ok_or(KeyError::InvalidHandle)?;
+        let old = unsafe { &mut (*node.as_ptr()).value };
+        if self.cmp.compare(&value, old).is_lt() {
+            return Err(KeyError::PriorityLowered);
+        }
+        let old = std::mem::replace(old, value);
+        if self.root != Some(node) {
+            Self::cut(node);
+            self.root = self.merge(self.root, Some(node));
+        }
+        Ok(old)
+    }
+}
+
+impl<T, C> Drop for SkewHeap<T, C> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: Clone, C: Compare<T> + Clone> Clone for SkewHeap<T, C> {
+    /// 复制所有元素, 新的堆有自己的句柄.
+    fn clone(&self) -> Self {
+        let mut heap = Self::with_comparator(self.cmp.clone());
+        for node in self.nodes() {
+            heap.push(unsafe { (*node.as_ptr()).value.clone() });
+        }
+        heap
+    }
+}
+
+impl<T: fmt::Debug, C> fmt::Debug for SkewHeap<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.nodes()
+                    .into_iter()
+                    .map(|node| unsafe { &(*node.as_ptr()).value }),
+            )
+            .finish()
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for SkewHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MinSkewHeap, SkewHeap};
+    use crate::traits::{KeyError, PriorityQueue};
+
+    #[test]
+    fn test_push_pop() {
+        let mut heap = SkewHeap::new();
+        heap.extend((0..100).map(|i| (i * 37) % 100));
+        assert_eq!(heap.len(), 100);
+        assert_eq!(heap.peek(), Some(&99));
+        let values: Vec<i32> = std::iter::from_fn(|| heap.pop()).collect();
+        assert_eq!(values, (0..100).rev().collect::<Vec<_>>());
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_sorted_input() {
+        // 有序输入会让最右路径变得很长.
+        let mut heap = MinSkewHeap::new_min();
+        heap.extend(0..10_000);
+        let values: Vec<i32> = std::iter::from_fn(|| heap.pop()).collect();
+        assert_eq!(values, (0..10_000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_decrease_key() {
+        let mut heap = MinSkewHeap::new_min();
+        let handles: Vec<_> = (0..50).map(|i| heap.push(i + 100)).collect();
+        assert_eq!(heap.pop(), Some(100));
+        assert_eq!(
+            heap.decrease_key(&handles[0], 1),
+            Err(KeyError::InvalidHandle)
+        );
+        assert_eq!(
+            heap.decrease_key(&handles[30], 200),
+            Err(KeyError::PriorityLowered)
+        );
+        assert_eq!(heap.decrease_key(&handles[30], 5), Ok(130));
+        assert_eq!(heap.decrease_key(&handles[40], 3), Ok(140));
+        assert_eq!(heap.pop(), Some(3));
+        assert_eq!(heap.pop(), Some(5));
+        assert_eq!(heap.pop(), Some(101));
+        assert_eq!(heap.len(), 46);
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut a = MinSkewHeap::new_min();
+        let mut b = MinSkewHeap::new_min();
+        a.extend([7, 3, 9]);
+        let handle = b.push(20);
+        b.push(4);
+        a.meld(b);
+        assert_eq!(a.decrease_key(&handle, 1), Ok(20));
+        let values: Vec<i32> = std::iter::from_fn(|| a.pop()).collect();
+        assert_eq!(values, [1, 3, 4, 7, 9]);
+    }
+}
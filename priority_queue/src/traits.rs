@@ -0,0 +1,420 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 各种堆的公共接口.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ptr::{self, NonNull};
+use std::rc::Rc;
+
+/// 可合并的优先级队列.
+///
+/// 堆顶元素是优先级最高的那个, 优先级由各个堆的比较器决定.
+pub trait PriorityQueue<T> {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 插入新元素, 返回它的句柄.
+    fn push(&mut self, value: T) -> Handle;
+
+    /// 返回堆顶元素.
+    fn peek(&self) -> Option<&T>;
+
+    /// 弹出堆顶元素.
+    fn pop(&mut self) -> Option<T>;
+
+    /// 读取句柄对应的元素, 元素已经被弹出时返回 `None`.
+    fn get(&self, handle: &Handle) -> Option<&T>;
+
+    /// 把 `other` 中的元素全部合并进来.
+    ///
+    /// 合并之后, 之前从 `other` 得到的句柄在当前堆中仍然有效.
+    fn meld(&mut self, other: Self)
+    where
+        Self: Sized;
+
+    /// 提高句柄对应元素的优先级, 返回旧的值.
+    ///
+    /// 对小顶堆来说就是减小键值 (decrease-key), 对大顶堆来说是增大键值.
+    ///
+    /// # Errors
+    ///
+    /// - 句柄无效时返回 `KeyError::InvalidHandle`;
+    /// - 新值的优先级比旧值低时返回 `KeyError::PriorityLowered`, 此时堆不会被修改.
+    fn decrease_key(&mut self, handle: &Handle, value: T) -> Result<T, KeyError>;
+}
+
+/// 堆中元素的句柄.
+///
+/// 句柄是一个由元素和调用者共享的小对象, 记录了元素在堆中的位置以及元素所属的堆.
+/// 元素移动时, 堆会更新句柄中的位置; 元素被弹出或删除后, 句柄就失效了.
+/// 通过句柄查找元素不需要遍历, 时间复杂度是 O(1).
+#[derive(Clone)]
+pub struct Handle(Rc<Slot>);
+
+struct Slot {
+    // 对于基于指针的堆, 是节点的地址; 对于 `IndexedBinaryHeap`, 是元素在堆数组中的位置.
+    // 元素被移除后为 `None`.
+    key: Cell<Option<usize>>,
+    // 创建该元素的堆的所有权标记, 不一定是并查集的根.
+    owner: RefCell<Rc<Owner>>,
+}
+
+impl Handle {
+    /// 元素是否已经被移除.
+    #[must_use]
+    #[inline]
+    pub fn is_removed(&self) -> bool {
+        self.0.key.get().is_none()
+    }
+
+    #[inline]
+    pub(crate) fn set_key(&self, key: usize) {
+        self.0.key.set(Some(key));
+    }
+
+    /// 记录节点的新地址.
+    #[inline]
+    pub(crate) fn set_node<N>(&self, node: NonNull<N>) {
+        self.set_key(node.as_ptr().expose_provenance());
+    }
+
+    /// 元素被移除后调用, 之后该句柄就失效了.
+    #[inline]
+    pub(crate) fn invalidate(&self) {
+        self.0.key.set(None);
+    }
+}
+
+impl PartialEq for Handle {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Handle {}
+
+impl Hash for Handle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        ptr::hash(Rc::as_ptr(&self.0), state);
+    }
+}
+
+impl fmt::Debug for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Handle")
+            .field("removed", &self.is_removed())
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyError {
+    /// 句柄无效, 或者对应的元素已经被移除.
+    InvalidHandle,
+    /// 新的键值使元素的优先级变低了.
+    PriorityLowered,
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHandle => f.write_str("invalid heap handle"),
+            Self::PriorityLowered => f.write_str("new key has lower priority"),
+        }
+    }
+}
+
+impl std::error::Error for KeyError {}
+
+/// 堆的所有权标记.
+///
+/// 合并两个堆时, 被合并的堆的标记指向当前堆的标记, 所有的标记构成一个并查集,
+/// 根节点就是元素当前所在的堆. 查找时进行路径压缩.
+#[derive(Default)]
+struct Owner {
+    parent: RefCell<Option<Rc<Self>>>,
+}
+
+impl Owner {
+    fn root(owner: &Rc<Self>) -> Rc<Self> {
+        let mut root = Rc::clone(owner);
+        loop {
+            let parent = root.parent.borrow().clone();
+            match parent {
+                Some(parent) => root = parent,
+                None => break,
+            }
+        }
+
+        // 路径压缩.
+        let mut current = Rc::clone(owner);
+        while !Rc::ptr_eq(&current, &root) {
+            let parent = current
+                .parent
+                .replace(Some(Rc::clone(&root)))
+                .expect("non-root owner has parent");
+            current = parent;
+        }
+        root
+    }
+}
+
+impl Drop for Owner {
+    // 逐个释放长长的标记链, 避免递归析构导致栈溢出.
+    fn drop(&mut self) {
+        let mut next = self.parent.get_mut().take();
+        while let Some(owner) = next {
+            next = match Rc::try_unwrap(owner) {
+                Ok(mut owner) => owner.parent.get_mut().take(),
+                Err(_shared) => None,
+            };
+        }
+    }
+}
+
+/// 每个堆持有的句柄空间, 用于创建句柄, 以及拒绝其它堆的句柄.
+pub(crate) struct HandleSpace {
+    owner: Rc<Owner>,
+}
+
+impl HandleSpace {
+    pub fn new() -> Self {
+        Self {
+            owner: Rc::default(),
+        }
+    }
+
+    pub fn handle(&self, key: usize) -> Handle {
+        Handle(Rc::new(Slot {
+            key: Cell::new(Some(key)),
+            owner: RefCell::new(Rc::clone(&self.owner)),
+        }))
+    }
+
+    /// 如果句柄属于当前堆, 并且元素还没有被移除, 就返回元素的位置.
+    pub fn resolve(&self, handle: &Handle) -> Option<usize> {
+        let key = handle.0.key.get()?;
+        let root = Owner::root(&handle.0.owner.borrow());
+        let owned = Rc::ptr_eq(&root, &self.owner);
+        *handle.0.owner.borrow_mut() = root;
+        owned.then_some(key)
+    }
+
+    /// 与 `resolve()` 相同, 返回节点的地址.
+    ///
+    /// 元素被移除之前, 堆会让句柄失效, 所以返回的节点一定属于当前堆并且是有效的.
+    pub fn resolve_node<N>(&self, handle: &Handle) -> Option<NonNull<N>> {
+        let key = self.resolve(handle)?;
+        NonNull::new(ptr::with_exposed_provenance_mut(key))
+    }
+
+    /// 合并 `other` 的句柄空间, 之后 `other` 的句柄都属于当前堆.
+    pub fn absorb(&self, other: &Self) {
+        *other.owner.parent.borrow_mut() = Some(Rc::clone(&self.owner));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::cmp::Ordering;
+    use std::collections::BTreeMap;
+    use std::rc::Rc;
+
+    use super::{Handle, HandleSpace, PriorityQueue};
+    use crate::binomial_heap::MinBinomialHeap;
+    use crate::fibonacci_heap::MinFibonacciHeap;
+    use crate::indexed_heap::IndexedMinHeap;
+    use crate::pairing_heap::MinPairingHeap;
+    use crate::skew_heap::MinSkewHeap;
+
+    // 随机执行插入, 弹出, decrease-key 和合并操作, 并与 `BTreeMap` 的结果对比.
+    fn check_queue<Q: PriorityQueue<u64>>(new_queue: impl Fn() -> Q) {
+        let mut seed: u64 = 0x2545_f491;
+        let mut next_random = move |bound: u64| {
+            seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (seed >> 33) % bound
+        };
+
+        let mut queue = new_queue();
+        let mut handles = Vec::new();
+        // 值 -> 个数
+        let mut model: BTreeMap<u64, usize> = BTreeMap::new();
+        let mut len = 0;
+        for _step in 0..5000 {
+            match next_random(10) {
+                0..=3 => {
+                    let value = next_random(100_000);
+                    handles.push(queue.push(value));
+                    *model.entry(value).or_default() += 1;
+                    len += 1;
+                }
+                4..=5 => {
+                    let expected = model.first_key_value().map(|(&value, _)| value);
+                    assert_eq!(queue.pop(), expected);
+                    if let Some(value) = expected {
+                        len -= 1;
+                        let count = model.get_mut(&value).unwrap();
+                        *count -= 1;
+                        if *count == 0 {
+                            model.remove(&value);
+                        }
+                    }
+                }
+                6..=8 => {
+                    if handles.is_empty() {
+                        continue;
+                    }
+                    let handle =
+                        &handles[usize::try_from(next_random(handles.len() as u64)).unwrap()];
+                    if let Some(&old) = queue.get(handle) {
+                        let value = old - next_random(old + 1);
+                        assert_eq!(queue.decrease_key(handle, value), Ok(old));
+                        let count = model.get_mut(&old).unwrap();
+                        *count -= 1;
+                        if *count == 0 {
+                            model.remove(&old);
+                        }
+                        *model.entry(value).or_default() += 1;
+                    }
+                }
+                _ => {
+                    let mut other = new_queue();
+                    for _ in 0..next_random(20) {
+                        let value = next_random(100_000);
+                        handles.push(other.push(value));
+                        *model.entry(value).or_default() += 1;
+                        len += 1;
+                    }
+                    queue.meld(other);
+                }
+            }
+            assert_eq!(queue.len(), len);
+            assert_eq!(
+                queue.peek(),
+                model.first_key_value().map(|(value, _)| value)
+            );
+        }
+    }
+
+    #[test]
+    fn test_priority_queues() {
+        check_queue(IndexedMinHeap::new_min);
+        check_queue(MinBinomialHeap::new_min);
+        check_queue(MinFibonacciHeap::new_min);
+        check_queue(MinPairingHeap::new_min);
+        check_queue(MinSkewHeap::new_min);
+    }
+
+    #[test]
+    fn test_handle_space() {
+        let a = HandleSpace::new();
+        let b = HandleSpace::new();
+        let c = HandleSpace::new();
+        let hb = b.handle(1);
+        let hc = c.handle(2);
+        assert_eq!(a.resolve(&hb), None);
+        c.absorb(&b);
+        a.absorb(&c);
+        assert_eq!(a.resolve(&a.handle(3)), Some(3));
+        assert_eq!(a.resolve(&hc), Some(2));
+        assert_eq!(a.resolve(&hb), Some(1));
+        assert_eq!(b.resolve(&hb), None);
+        hb.invalidate();
+        assert!(hb.is_removed());
+        assert_eq!(a.resolve(&hb), None);
+    }
+
+    // 很长的合并链不会导致析构时栈溢出.
+    #[test]
+    fn test_long_meld_chain() {
+        let mut queue = MinPairingHeap::new_min();
+        let first = queue.push(0_u64);
+        for value in 1..200_000 {
+            let mut other = MinPairingHeap::new_min();
+            other.push(value);
+            other.meld(queue);
+            queue = other;
+        }
+        assert_eq!(queue.get(&first), Some(&0));
+        assert_eq!(queue.len(), 200_000);
+        drop(queue);
+        assert!(first.is_removed());
+    }
+
+    // 每个元素都恰好被释放一次.
+    fn check_drop<Q: PriorityQueue<Counted>>(new_queue: impl Fn() -> Q) {
+        let drops = Rc::new(Cell::new(0));
+        let counted = |value| Counted(value, Rc::clone(&drops));
+        let mut queue = new_queue();
+        let mut handles = Vec::new();
+        for value in 0..100 {
+            handles.push(queue.push(counted(value)));
+        }
+        let mut other = new_queue();
+        for value in 100..150 {
+            handles.push(other.push(counted(value)));
+        }
+        queue.meld(other);
+        for _ in 0..30 {
+            drop(queue.pop());
+        }
+        assert_eq!(drops.get(), 30);
+        let old = queue.decrease_key(&handles[120], counted(-1)).unwrap();
+        drop(old);
+        assert_eq!(drops.get(), 31);
+        assert_eq!(queue.peek().map(|item| item.0), Some(-1));
+        assert!(handles[..30].iter().all(Handle::is_removed));
+        assert!(!handles[30].is_removed());
+        drop(queue);
+        assert_eq!(drops.get(), 151);
+        assert!(handles.iter().all(Handle::is_removed));
+    }
+
+    #[derive(Debug)]
+    struct Counted(i32, Rc<Cell<usize>>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.1.set(self.1.get() + 1);
+        }
+    }
+
+    impl PartialEq for Counted {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl Eq for Counted {}
+
+    impl PartialOrd for Counted {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Counted {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+
+    #[test]
+    fn test_drop() {
+        check_drop(IndexedMinHeap::new_min);
+        check_drop(MinBinomialHeap::new_min);
+        check_drop(MinFibonacciHeap::new_min);
+        check_drop(MinPairingHeap::new_min);
+        check_drop(MinSkewHeap::new_min);
+    }
+}
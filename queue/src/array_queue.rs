@@ -6,6 +6,8 @@ use std::cmp::Ordering;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 
+use crate::traits::{BoundedQueue, Queue};
+
 pub struct ArrayQueue<T> {
     len: usize,
     buf: Box<[Option<T>]>,
@@ -108,6 +110,35 @@ impl<T> ArrayQueue<T> {
     }
 }
 
+impl<T> Queue<T> for ArrayQueue<T> {
+    #[inline]
+    fn push(&mut self, value: T) -> Result<(), T> {
+        Self::push(self, value)
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<T> {
+        Self::pop(self)
+    }
+
+    #[inline]
+    fn front(&self) -> Option<&T> {
+        Self::front(self)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        Self::len(self)
+    }
+}
+
+impl<T> BoundedQueue<T> for ArrayQueue<T> {
+    #[inline]
+    fn capacity(&self) -> usize {
+        Self::capacity(self)
+    }
+}
+
 impl<T: PartialEq> PartialEq for ArrayQueue<T> {
     fn eq(&self, other: &Self) -> bool {
         self.len == other.len && PartialEq::eq(&self.buf, &other.buf)
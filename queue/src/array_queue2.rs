@@ -9,6 +9,8 @@ use std::hash::{Hash, Hasher};
 use std::mem::ManuallyDrop;
 use std::ptr::NonNull;
 
+use crate::traits::{BoundedQueue, Queue};
+
 pub struct ArrayQueue2<T> {
     len: usize,
     buf: Box<[T]>,
@@ -130,6 +132,35 @@ impl<T> ArrayQueue2<T> {
     }
 }
 
+impl<T> Queue<T> for ArrayQueue2<T> {
+    #[inline]
+    fn push(&mut self, value: T) -> Result<(), T> {
+        Self::push(self, value)
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<T> {
+        Self::pop(self)
+    }
+
+    #[inline]
+    fn front(&self) -> Option<&T> {
+        Self::front(self)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        Self::len(self)
+    }
+}
+
+impl<T> BoundedQueue<T> for ArrayQueue2<T> {
+    #[inline]
+    fn capacity(&self) -> usize {
+        Self::capacity(self)
+    }
+}
+
 impl<T: PartialEq> PartialEq for ArrayQueue2<T> {
     fn eq(&self, other: &Self) -> bool {
         self.len == other.len && PartialEq::eq(&self.buf, &other.buf)
@@ -0,0 +1,57 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+use std::hint;
+use std::ops::Deref;
+use std::thread;
+
+/// 自旋次数按 2 的幂增长, 超过这个上限后改为让出 CPU.
+const SPIN_LIMIT: u32 = 6;
+
+/// 阻塞操作的退避策略: 先自旋等待, 之后调用 `thread::yield_now()`.
+pub struct Backoff {
+    step: u32,
+}
+
+impl Backoff {
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self { step: 0 }
+    }
+
+    pub fn snooze(&mut self) {
+        if self.step <= SPIN_LIMIT {
+            for _ in 0..1 << self.step {
+                hint::spin_loop();
+            }
+            self.step += 1;
+        } else {
+            thread::yield_now();
+        }
+    }
+}
+
+/// 让生产者和消费者各自修改的索引位于不同的缓存行, 避免伪共享 (false sharing).
+#[repr(align(64))]
+pub struct CachePadded<T> {
+    value: T,
+}
+
+impl<T> CachePadded<T> {
+    #[must_use]
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self { value }
+    }
+}
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
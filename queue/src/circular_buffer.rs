@@ -3,41 +3,84 @@
 // in the LICENSE file.
 
 use std::alloc::{alloc, dealloc, Layout};
+use std::iter::FusedIterator;
 use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
 use std::ptr::NonNull;
-use std::{mem, ops, ptr, slice};
+use std::{fmt, mem, ptr, slice};
+
+use crate::traits::{BoundedQueue, Queue};
+
+/// 缓冲区已满时, 写入新元素的策略.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// 拒绝写入, `push()` 返回 `Err(value)`.
+    #[default]
+    Reject,
+    /// 覆盖最早写入的元素, 只保留最近的 `capacity` 个元素.
+    Overwrite,
+    /// 容量翻倍, 缓冲区不会变满.
+    Grow,
+}
+
+/// 容量为 0 的缓冲区扩容时的最小容量.
+const MIN_GROW_CAPACITY: usize = 4;
 
 pub struct CircularBuffer<T: Sized> {
     start: usize,
     len: usize,
     cap: usize,
     ptr: NonNull<T>,
+    policy: OverflowPolicy,
     _marker: PhantomData<T>,
 }
 
+// 与 `Vec<T>` 一样, 缓冲区独占它的元素.
+unsafe impl<T: Send> Send for CircularBuffer<T> {}
+unsafe impl<T: Sync> Sync for CircularBuffer<T> {}
+
 impl<T: Sized> CircularBuffer<T> {
+    /// 创建缓冲区, 已满时拒绝写入.
+    ///
     /// # Panics
     ///
     /// 分配内存失败时直接返回 panic
     #[must_use]
     #[inline]
     pub fn new(capacity: usize) -> Self {
-        // 为了方便处理, 我们强制要求 capacity 是正数, 并且目前还没有考虑 ZST (zero sized type).
-        assert!(capacity > 0);
-
-        let layout = Layout::array::<T>(capacity).expect("Layout error");
-        let ptr = unsafe { alloc(layout) };
-        let ptr = NonNull::new(ptr).expect("Failed to alloc");
+        Self::with_policy(capacity, OverflowPolicy::Reject)
+    }
 
+    /// 创建缓冲区, 并指定缓冲区已满时的写入策略.
+    ///
+    /// `capacity` 可以为 0, 也支持 ZST (zero sized type), 这两种情况下都不会分配堆内存.
+    ///
+    /// # Panics
+    ///
+    /// 分配内存失败时直接返回 panic
+    #[must_use]
+    pub fn with_policy(capacity: usize, policy: OverflowPolicy) -> Self {
         Self {
             start: 0,
             len: 0,
             cap: capacity,
-            ptr: ptr.cast(),
+            ptr: allocate(capacity),
+            policy,
             _marker: PhantomData,
         }
     }
 
+    #[must_use]
+    #[inline]
+    pub const fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    #[inline]
+    pub const fn set_policy(&mut self, policy: OverflowPolicy) {
+        self.policy = policy;
+    }
+
     #[must_use]
     #[inline]
     pub const fn as_mut_ptr(&self) -> *mut T {
@@ -50,51 +93,217 @@ impl<T: Sized> CircularBuffer<T> {
         self.ptr.as_ptr()
     }
 
+    /// 以切片的形式返回全部元素, 必要时先调用 `make_contiguous()` 重新排列元素.
     #[must_use]
     #[inline]
-    pub fn as_slice(&self) -> &[T] {
-        self
+    pub const fn as_mut_slice(&mut self) -> &mut [T] {
+        self.make_contiguous()
     }
 
+    /// 元素在内存中是否连续, 即没有回绕到缓冲区的开头.
     #[must_use]
     #[inline]
-    pub fn as_mut_slice(&mut self) -> &mut [T] {
-        self
+    pub const fn is_contiguous(&self) -> bool {
+        self.start + self.len <= self.cap
     }
 
+    /// 把逻辑下标转换为物理下标.
+    #[inline]
+    const fn to_physical(&self, index: usize) -> usize {
+        let index = self.start + index;
+        if index >= self.cap {
+            index - self.cap
+        } else {
+            index
+        }
+    }
+
+    /// 返回前后两段连续的元素, 依次连接起来就是按写入顺序排列的全部元素.
+    #[must_use]
+    pub const fn as_slices(&self) -> (&[T], &[T]) {
+        let (front_len, back_len) = self.slice_lens();
+        unsafe {
+            (
+                slice::from_raw_parts(self.as_ptr().add(self.start), front_len),
+                slice::from_raw_parts(self.as_ptr(), back_len),
+            )
+        }
+    }
+
+    #[must_use]
+    pub const fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (front_len, back_len) = self.slice_lens();
+        unsafe {
+            (
+                slice::from_raw_parts_mut(self.as_mut_ptr().add(self.start), front_len),
+                slice::from_raw_parts_mut(self.as_mut_ptr(), back_len),
+            )
+        }
+    }
+
+    /// 返回 `as_slices()` 中两个切片的长度.
+    const fn slice_lens(&self) -> (usize, usize) {
+        let tail_room = self.cap - self.start;
+        if self.len <= tail_room {
+            (self.len, 0)
+        } else {
+            (tail_room, self.len - tail_room)
+        }
+    }
+
+    /// 重新排列元素, 使它们在内存中是连续的, 并返回这个切片.
+    pub const fn make_contiguous(&mut self) -> &mut [T] {
+        if !self.is_contiguous() {
+            unsafe {
+                // 把整个缓冲区看作 `MaybeUninit<T>` 数组, 旋转它不会读取未初始化的元素.
+                slice::from_raw_parts_mut(
+                    self.as_mut_ptr().cast::<mem::MaybeUninit<T>>(),
+                    self.cap,
+                )
+                .rotate_left(self.start);
+            }
+            self.start = 0;
+        }
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr().add(self.start), self.len) }
+    }
+
+    /// 向缓冲区中写入元素, 缓冲区已满时的行为由 `policy()` 决定.
+    ///
     /// # Errors
     ///
-    /// 当缓冲区已满时返回 `Err(value)`
+    /// 策略为 `OverflowPolicy::Reject` 并且缓冲区已满时返回 `Err(value)`
     pub fn push(&mut self, value: T) -> Result<(), T> {
         if self.is_full() {
-            Err(value)
+            match self.policy {
+                OverflowPolicy::Reject => return Err(value),
+                OverflowPolicy::Overwrite => {
+                    drop(self.push_overwrite(value));
+                    return Ok(());
+                }
+                OverflowPolicy::Grow => self.grow(),
+            }
+        }
+        unsafe {
+            // 计算新元素的指针位置
+            let end_ptr = self.as_mut_ptr().add(self.to_physical(self.len));
+            self.len += 1;
+            ptr::write(end_ptr, value);
+        }
+        Ok(())
+    }
+
+    /// 向缓冲区中写入元素, 缓冲区已满时覆盖最早写入的元素, 并返回被覆盖的元素.
+    ///
+    /// 该方法不受 `policy()` 影响. 容量为 0 时, 直接返回 `Some(value)`.
+    pub const fn push_overwrite(&mut self, value: T) -> Option<T> {
+        if self.cap == 0 {
+            return Some(value);
+        }
+        if self.is_full() {
+            unsafe {
+                let slot = self.as_mut_ptr().add(self.start);
+                self.start = self.to_physical(1);
+                Some(ptr::replace(slot, value))
+            }
         } else {
             unsafe {
-                // 计算新元素的指针位置
-                let end = (self.start + self.len) % self.cap;
-                let end_ptr = self.as_mut_ptr().add(end);
-                self.len += 1;
-                ptr::write(end_ptr, value);
+                ptr::write(self.as_mut_ptr().add(self.to_physical(self.len)), value);
             }
-            Ok(())
+            self.len += 1;
+            None
         }
     }
 
+    /// 批量写入, 缓冲区已满时的行为由 `policy()` 决定, 返回被写入的元素个数.
+    ///
+    /// 元素分成最多两段用 `memcpy` 复制到缓冲区中. 对于 `OverflowPolicy::Overwrite`,
+    /// 被覆盖的元素也计算在内, 所以总是返回 `values.len()`.
+    pub fn extend_from_slice(&mut self, values: &[T]) -> usize
+    where
+        T: Copy,
+    {
+        let mut values = values;
+        let count = match self.policy {
+            OverflowPolicy::Reject => {
+                values = &values[..values.len().min(self.cap - self.len)];
+                values.len()
+            }
+            OverflowPolicy::Overwrite => {
+                let count = values.len();
+                if values.len() >= self.cap {
+                    // 只有最后 `cap` 个元素会被保留.
+                    values = &values[values.len() - self.cap..];
+                    self.start = 0;
+                    self.len = 0;
+                } else {
+                    // `T: Copy`, 被覆盖的元素不需要释放.
+                    let overflow = (self.len + values.len()).saturating_sub(self.cap);
+                    self.start = self.to_physical(overflow);
+                    self.len -= overflow;
+                }
+                count
+            }
+            OverflowPolicy::Grow => {
+                let needed = self.len + values.len();
+                if needed > self.cap {
+                    self.realloc(needed.max(self.cap * 2));
+                }
+                values.len()
+            }
+        };
+
+        let end = self.to_physical(self.len);
+        let first = values.len().min(self.cap - end);
+        unsafe {
+            ptr::copy_nonoverlapping(values.as_ptr(), self.as_mut_ptr().add(end), first);
+            ptr::copy_nonoverlapping(
+                values.as_ptr().add(first),
+                self.as_mut_ptr(),
+                values.len() - first,
+            );
+        }
+        self.len += values.len();
+        count
+    }
+
     /// 从缓冲区消费元素, 如果缓冲区已空, 就返回 `None`
-    pub fn pop(&mut self) -> Option<T> {
+    pub const fn pop(&mut self) -> Option<T> {
         if self.is_empty() {
             None
         } else {
             unsafe {
                 // 计算起始元素的地址
                 let start_ptr = self.as_ptr().add(self.start);
-                self.start = (self.start + 1) % self.cap;
+                self.start = self.to_physical(1);
                 self.len -= 1;
                 Some(ptr::read(start_ptr))
             }
         }
     }
 
+    /// 移除最后写入的元素, 如果缓冲区已空, 就返回 `None`
+    pub const fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            self.len -= 1;
+            unsafe { Some(ptr::read(self.as_ptr().add(self.to_physical(self.len)))) }
+        }
+    }
+
+    /// 移除所有元素.
+    pub fn clear(&mut self) {
+        let (front, back) = self.as_mut_slices();
+        let (front, back): (*mut [T], *mut [T]) = (front, back);
+        // 先重置长度, 即使释放元素时 panic, 也不会重复释放.
+        self.start = 0;
+        self.len = 0;
+        unsafe {
+            ptr::drop_in_place(front);
+            ptr::drop_in_place(back);
+        }
+    }
+
     /// 返回当前缓冲区中的元素个数
     #[must_use]
     #[inline]
@@ -120,72 +329,321 @@ impl<T: Sized> CircularBuffer<T> {
         self.len() == self.cap
     }
 
-    // 计算当前的内存结构
-    fn current_memory(&self) -> (NonNull<u8>, Layout) {
-        assert_eq!(mem::size_of::<T>() % mem::align_of::<T>(), 0);
+    /// 按写入顺序返回第 `index` 个元素.
+    #[must_use]
+    pub const fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            unsafe { Some(&*self.as_ptr().add(self.to_physical(index))) }
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub const fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            unsafe { Some(&mut *self.as_mut_ptr().add(self.to_physical(index))) }
+        } else {
+            None
+        }
+    }
+
+    /// 返回最早写入的元素, 如果缓冲区已空, 就返回 `None`
+    #[must_use]
+    #[inline]
+    pub const fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// 返回最后写入的元素, 如果缓冲区已空, 就返回 `None`
+    #[must_use]
+    #[inline]
+    pub const fn back(&self) -> Option<&T> {
+        if self.is_empty() {
+            None
+        } else {
+            self.get(self.len - 1)
+        }
+    }
+
+    /// 按写入顺序遍历元素.
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (front, back) = self.as_slices();
+        Iter {
+            front: front.iter(),
+            back: back.iter(),
+        }
+    }
+
+    /// 按写入顺序遍历元素, 并可以修改它们.
+    #[must_use]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (front, back) = self.as_mut_slices();
+        IterMut {
+            front: front.iter_mut(),
+            back: back.iter_mut(),
+        }
+    }
+
+    /// 按写入顺序移除所有元素, 并返回包含这些元素的迭代器.
+    ///
+    /// 迭代器被释放时, 没有被迭代的元素也会被移除.
+    pub const fn drain(&mut self) -> Drain<'_, T> {
+        Drain { buf: self }
+    }
+
+    fn grow(&mut self) {
+        let new_cap = (self.cap * 2).max(MIN_GROW_CAPACITY);
+        self.realloc(new_cap);
+    }
+
+    /// 把元素移到容量为 `new_cap` 的新内存的开头.
+    fn realloc(&mut self, new_cap: usize) {
+        debug_assert!(new_cap >= self.len);
+        let new_ptr = allocate::<T>(new_cap);
+        let (front, back) = self.as_slices();
         unsafe {
-            let align = mem::align_of::<T>();
-            let size = mem::size_of::<T>().unchecked_mul(self.cap);
-            let layout = Layout::from_size_align_unchecked(size, align);
-            (self.ptr.cast(), layout)
+            ptr::copy_nonoverlapping(front.as_ptr(), new_ptr.as_ptr(), front.len());
+            ptr::copy_nonoverlapping(back.as_ptr(), new_ptr.as_ptr().add(front.len()), back.len());
+            deallocate(self.ptr, self.cap);
         }
+        self.ptr = new_ptr;
+        self.cap = new_cap;
+        self.start = 0;
+    }
+}
+
+/// 分配能存放 `capacity` 个元素的内存, 如果不需要分配, 就返回悬垂指针.
+fn allocate<T>(capacity: usize) -> NonNull<T> {
+    let layout = Layout::array::<T>(capacity).expect("Layout error");
+    if layout.size() == 0 {
+        NonNull::dangling()
+    } else {
+        let ptr = unsafe { alloc(layout) };
+        NonNull::new(ptr).expect("Failed to alloc").cast()
+    }
+}
+
+/// 释放由 `allocate()` 分配的内存.
+unsafe fn deallocate<T>(ptr: NonNull<T>, capacity: usize) {
+    let layout = Layout::array::<T>(capacity).expect("Layout error");
+    if layout.size() != 0 {
+        dealloc(ptr.as_ptr().cast(), layout);
+    }
+}
+
+impl<T> Queue<T> for CircularBuffer<T> {
+    #[inline]
+    fn push(&mut self, value: T) -> Result<(), T> {
+        Self::push(self, value)
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<T> {
+        Self::pop(self)
+    }
+
+    #[inline]
+    fn front(&self) -> Option<&T> {
+        Self::front(self)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        Self::len(self)
     }
 }
 
-/// 释放堆内存
+impl<T> BoundedQueue<T> for CircularBuffer<T> {
+    #[inline]
+    fn capacity(&self) -> usize {
+        Self::capacity(self)
+    }
+}
+
+/// 释放剩余的元素以及堆内存
 impl<T> Drop for CircularBuffer<T> {
     fn drop(&mut self) {
-        let (ptr, layout) = self.current_memory();
-        unsafe { dealloc(ptr.as_ptr(), layout) }
+        self.clear();
+        unsafe { deallocate(self.ptr, self.cap) }
     }
 }
 
-/// 实现 `Deref` 和 `DerefMut` traits.
-impl<T> ops::Deref for CircularBuffer<T> {
-    type Target = [T];
+/// 按写入顺序访问元素.
+impl<T> Index<usize> for CircularBuffer<T> {
+    type Output = T;
 
     #[inline]
-    fn deref(&self) -> &[T] {
-        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
     }
 }
 
-impl<T> ops::DerefMut for CircularBuffer<T> {
+impl<T> IndexMut<usize> for CircularBuffer<T> {
     #[inline]
-    fn deref_mut(&mut self) -> &mut [T] {
-        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("index out of bounds")
     }
 }
 
-/// 支持从迭代器初始化.
+/// 支持从迭代器初始化, 容量等于元素个数.
 impl<T> FromIterator<T> for CircularBuffer<T> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
         // 为了实现简单, 我们重用了 vec 的 `FromIterator` 实现.
         let vec: Vec<T> = iter.into_iter().collect();
-        let len = vec.len();
-        let cap = vec.capacity();
-        let boxed = vec.into_boxed_slice();
-        let ptr = Box::leak(boxed);
-        let ptr = NonNull::new(ptr.as_mut_ptr()).unwrap();
+        let mut buf = Self::new(vec.len());
+        for value in vec {
+            let _ = buf.push(value);
+        }
+        buf
+    }
+}
+
+impl<T: Clone> Clone for CircularBuffer<T> {
+    fn clone(&self) -> Self {
+        let mut buf = Self::with_policy(self.cap, self.policy);
+        for value in self {
+            let _ = buf.push(value.clone());
+        }
+        buf
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CircularBuffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+pub struct Iter<'a, T> {
+    front: slice::Iter<'a, T>,
+    back: slice::Iter<'a, T>,
+}
+
+// 手动实现, 避免 `#[derive(Clone)]` 要求 `T: Clone`.
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
         Self {
-            start: 0,
-            len,
-            cap,
-            ptr,
-            _marker: PhantomData,
+            front: self.front.clone(),
+            back: self.back.clone(),
         }
     }
 }
 
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    front: slice::IterMut<'a, T>,
+    back: slice::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a CircularBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut CircularBuffer<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// `CircularBuffer::drain()` 返回的迭代器.
+pub struct Drain<'a, T> {
+    buf: &'a mut CircularBuffer<T>,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buf.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.buf.len, Some(self.buf.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.buf.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        self.buf.clear();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::mem::size_of;
+    use std::rc::Rc;
 
-    use super::CircularBuffer;
+    use super::{CircularBuffer, OverflowPolicy};
 
     #[test]
     fn test_size() {
-        assert_eq!(size_of::<CircularBuffer<i32>>(), 32);
+        // 比 `Vec` 多了 `start` 和 `policy` 两个字段.
+        assert_eq!(size_of::<CircularBuffer<i32>>(), 40);
         assert_eq!(size_of::<Vec<i32>>(), 24);
     }
 
@@ -214,4 +672,158 @@ mod tests {
         assert_eq!(ret, Some(3));
         assert!(cb.is_empty());
     }
+
+    #[test]
+    fn test_overwrite() {
+        let mut cb = CircularBuffer::with_policy(3, OverflowPolicy::Overwrite);
+        for i in 0..5 {
+            assert_eq!(cb.push(i), Ok(()));
+        }
+        assert!(cb.iter().copied().eq([2, 3, 4]));
+        assert_eq!(cb.push_overwrite(5), Some(2));
+        assert_eq!(cb.front(), Some(&3));
+        assert_eq!(cb.back(), Some(&5));
+        assert_eq!(cb.pop(), Some(3));
+        assert_eq!(cb.push_overwrite(6), None);
+        assert!(cb.iter().copied().eq([4, 5, 6]));
+
+        let mut empty = CircularBuffer::with_policy(0, OverflowPolicy::Overwrite);
+        assert_eq!(empty.push(1), Ok(()));
+        assert!(empty.is_empty());
+        assert_eq!(empty.push_overwrite(1), Some(1));
+    }
+
+    #[test]
+    fn test_grow() {
+        let mut cb = CircularBuffer::with_policy(2, OverflowPolicy::Grow);
+        assert_eq!(cb.push(0), Ok(()));
+        assert_eq!(cb.push(1), Ok(()));
+        assert_eq!(cb.pop(), Some(0));
+        // 元素回绕到缓冲区开头之后再扩容.
+        for i in 2..10 {
+            assert_eq!(cb.push(i), Ok(()));
+        }
+        assert!(cb.capacity() >= 9);
+        assert!(cb.iter().copied().eq(1..10));
+
+        let mut cb = CircularBuffer::with_policy(0, OverflowPolicy::Grow);
+        assert_eq!(cb.push(1), Ok(()));
+        assert_eq!(cb[0], 1);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut cb = CircularBuffer::new(4);
+        for i in 0..4 {
+            assert_eq!(cb.push(i), Ok(()));
+        }
+        assert_eq!(cb.pop(), Some(0));
+        assert_eq!(cb.pop(), Some(1));
+        assert_eq!(cb.push(4), Ok(()));
+        assert_eq!(cb.push(5), Ok(()));
+
+        assert_eq!(cb.as_slices(), (&[2, 3][..], &[4, 5][..]));
+        assert!(cb.iter().copied().eq([2, 3, 4, 5]));
+        assert!(cb.iter().rev().copied().eq([5, 4, 3, 2]));
+        assert_eq!(cb.iter().len(), 4);
+        for value in &mut cb {
+            *value *= 10;
+        }
+        assert_eq!(cb[0], 20);
+        assert_eq!(cb.get(4), None);
+        assert_eq!(cb.make_contiguous(), &[20, 30, 40, 50]);
+        assert!(cb.as_slices().1.is_empty());
+        assert_eq!(format!("{cb:?}"), "[20, 30, 40, 50]");
+
+        let mut iter = cb.iter();
+        assert_eq!(iter.next(), Some(&20));
+        let rest = iter.clone();
+        assert!(iter.copied().eq([30, 40, 50]));
+        assert!(rest.copied().eq([30, 40, 50]));
+    }
+
+    #[test]
+    fn test_slice() {
+        let mut cb = CircularBuffer::new(4);
+        for i in 0..4 {
+            assert_eq!(cb.push(i), Ok(()));
+        }
+        assert!(cb.is_contiguous());
+        assert_eq!(cb.as_slices(), (&[0, 1, 2, 3][..], &[][..]));
+        assert_eq!(cb.pop(), Some(0));
+        assert_eq!(cb.push(4), Ok(()));
+        assert!(!cb.is_contiguous());
+        assert_eq!(cb.as_slices(), (&[1, 2, 3][..], &[4][..]));
+
+        // `as_mut_slice()` 会先重新排列元素.
+        cb.as_mut_slice().sort_unstable_by(|a, b| b.cmp(a));
+        assert!(cb.is_contiguous());
+        assert_eq!(cb.as_slices(), (&[4, 3, 2, 1][..], &[][..]));
+        assert_eq!(cb.pop(), Some(4));
+        assert_eq!(cb.as_mut_slice(), &mut [3, 2, 1]);
+    }
+
+    #[test]
+    fn test_drain() {
+        let value = Rc::new(());
+        let mut cb = CircularBuffer::new(4);
+        for _ in 0..4 {
+            assert!(cb.push(Rc::clone(&value)).is_ok());
+        }
+        drop(cb.pop());
+        assert!(cb.push(Rc::clone(&value)).is_ok());
+        let mut drain = cb.drain();
+        assert_eq!(drain.len(), 4);
+        drop(drain.next());
+        drop(drain.next_back());
+        drop(drain);
+        assert!(cb.is_empty());
+        assert_eq!(Rc::strong_count(&value), 1);
+
+        // 缓冲区被释放时, 剩余的元素也会被释放.
+        for _ in 0..3 {
+            assert!(cb.push(Rc::clone(&value)).is_ok());
+        }
+        drop(cb);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn test_extend_from_slice() {
+        let mut cb = CircularBuffer::new(5);
+        assert_eq!(cb.extend_from_slice(&[1, 2, 3]), 3);
+        assert_eq!(cb.pop(), Some(1));
+        assert_eq!(cb.pop(), Some(2));
+        // 分成两段复制.
+        assert_eq!(cb.extend_from_slice(&[4, 5, 6, 7, 8]), 4);
+        assert!(cb.iter().copied().eq([3, 4, 5, 6, 7]));
+
+        cb.set_policy(OverflowPolicy::Overwrite);
+        assert_eq!(cb.extend_from_slice(&[8, 9]), 2);
+        assert!(cb.iter().copied().eq([5, 6, 7, 8, 9]));
+        assert_eq!(cb.extend_from_slice(&[0, 1, 2, 3, 4, 5, 6]), 7);
+        assert!(cb.iter().copied().eq([2, 3, 4, 5, 6]));
+
+        cb.set_policy(OverflowPolicy::Grow);
+        assert_eq!(cb.extend_from_slice(&[7, 8, 9]), 3);
+        assert!(cb.iter().copied().eq(2..10));
+    }
+
+    #[test]
+    fn test_zero_sized_type() {
+        let mut cb = CircularBuffer::new(3);
+        assert_eq!(cb.push(()), Ok(()));
+        assert_eq!(cb.push(()), Ok(()));
+        assert_eq!(cb.push(()), Ok(()));
+        assert_eq!(cb.push(()), Err(()));
+        assert_eq!(cb.iter().count(), 3);
+        assert_eq!(cb.pop(), Some(()));
+
+        let mut cb = CircularBuffer::with_policy(1, OverflowPolicy::Grow);
+        for _ in 0..100 {
+            assert_eq!(cb.push(()), Ok(()));
+        }
+        assert_eq!(cb.len(), 100);
+        assert_eq!(cb.drain().count(), 100);
+    }
 }
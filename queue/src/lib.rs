@@ -10,7 +10,16 @@
     clippy::pedantic
 )]
 
-pub mod circular_buffer;
 pub mod array_queue;
 pub mod array_queue2;
+pub mod circular_buffer;
 pub mod list_queue;
+pub mod monotonic_queue;
+pub mod mpmc_ring_buffer;
+pub mod spsc_ring_buffer;
+pub mod traits;
+
+pub use monotonic_queue::MonotonicQueue;
+pub use traits::{BoundedQueue, Queue};
+
+mod backoff;
@@ -7,6 +7,8 @@ use std::collections::LinkedList;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 
+use crate::traits::Queue;
+
 #[allow(clippy::linkedlist)]
 pub struct ListQueue<T> (LinkedList<T>);
 
@@ -72,6 +74,29 @@ impl<T> ListQueue<T> {
     }
 }
 
+impl<T> Queue<T> for ListQueue<T> {
+    #[inline]
+    fn push(&mut self, value: T) -> Result<(), T> {
+        Self::push(self, value);
+        Ok(())
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<T> {
+        Self::pop(self)
+    }
+
+    #[inline]
+    fn front(&self) -> Option<&T> {
+        Self::front(self)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        Self::len(self)
+    }
+}
+
 impl<T: PartialEq> PartialEq for ListQueue<T> {
     fn eq(&self, other: &Self) -> bool {
         PartialEq::eq(&self.0, &other.0)
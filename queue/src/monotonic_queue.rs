@@ -0,0 +1,246 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 单调队列, 可以在 O(1) 的均摊时间内得到队列中的最大值或者最小值.
+//!
+//! 队列中只保存可能成为最值的元素: 新元素入队时, 队尾所有不比它更优的元素都会被移除,
+//! 因为它们比新元素更早离开队列, 不可能再成为最值. 这样剩下的元素从队首到队尾是单调的,
+//! 队首就是当前的最值.
+//!
+//! 被移除的元素在逻辑上仍然在队列中, 每个元素都有一个递增的序号, 出队时只有当队首元素的序号
+//! 等于最早入队元素的序号时, 才真正移除它.
+
+use crate::circular_buffer::{CircularBuffer, OverflowPolicy};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Max,
+    Min,
+}
+
+pub struct MonotonicQueue<T> {
+    /// 保存可能成为最值的元素以及它们的序号.
+    buf: CircularBuffer<(usize, T)>,
+    kind: Kind,
+    /// 最早入队的元素的序号.
+    head: usize,
+    /// 下一个入队的元素的序号.
+    tail: usize,
+}
+
+impl<T: Ord> MonotonicQueue<T> {
+    /// 创建一个队首为最大值的单调队列.
+    #[must_use]
+    pub fn new_max() -> Self {
+        Self::new(Kind::Max)
+    }
+
+    /// 创建一个队首为最小值的单调队列.
+    #[must_use]
+    pub fn new_min() -> Self {
+        Self::new(Kind::Min)
+    }
+
+    fn new(kind: Kind) -> Self {
+        Self {
+            buf: CircularBuffer::with_policy(0, OverflowPolicy::Grow),
+            kind,
+            head: 0,
+            tail: 0,
+        }
+    }
+
+    /// `new` 入队之后, 队尾元素 `old` 是否不可能再成为最值.
+    fn is_dominated(&self, old: &T, new: &T) -> bool {
+        match self.kind {
+            Kind::Max => old < new,
+            Kind::Min => old > new,
+        }
+    }
+
+    /// 在队尾添加元素.
+    pub fn push(&mut self, value: T) {
+        while let Some((_, back)) = self.buf.back() {
+            if !self.is_dominated(back, &value) {
+                break;
+            }
+            drop(self.buf.pop_back());
+        }
+        // 扩容策略下写入总是成功的.
+        let _ = self.buf.push((self.tail, value));
+        self.tail += 1;
+    }
+
+    /// 移除最早入队的元素, 如果队列为空, 就返回 `false`.
+    pub fn pop(&mut self) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        if matches!(self.buf.front(), Some((id, _)) if *id == self.head) {
+            drop(self.buf.pop());
+        }
+        self.head += 1;
+        true
+    }
+
+    /// 返回队列中的最大值或者最小值, 它总是位于队首.
+    #[must_use]
+    pub fn front(&self) -> Option<&T> {
+        self.buf.front().map(|(_, value)| value)
+    }
+
+    /// 返回逻辑上队列中的元素个数, 包括已经不可能成为最值的元素.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.tail - self.head
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.buf.clear();
+        self.head = self.tail;
+    }
+}
+
+/// 依次返回每个滑动窗口中的最大值或者最小值.
+///
+/// 由 `sliding_window_max()` 和 `sliding_window_min()` 创建.
+pub struct SlidingWindow<I: Iterator> {
+    iter: I,
+    queue: MonotonicQueue<I::Item>,
+    size: usize,
+}
+
+impl<I> Iterator for SlidingWindow<I>
+where
+    I: Iterator,
+    I::Item: Ord + Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for value in self.iter.by_ref() {
+            self.queue.push(value);
+            if self.queue.len() > self.size {
+                self.queue.pop();
+            }
+            if self.queue.len() == self.size {
+                return self.queue.front().cloned();
+            }
+        }
+        None
+    }
+}
+
+/// 返回大小为 `size` 的滑动窗口依次经过 `values` 时, 每个窗口中的最大值.
+///
+/// 元素个数少于 `size` 时, 不返回任何值.
+///
+/// # Panics
+///
+/// `size` 为 0 时 panic.
+pub fn sliding_window_max<I>(values: I, size: usize) -> SlidingWindow<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: Ord,
+{
+    assert!(size > 0, "window size must be positive");
+    SlidingWindow {
+        iter: values.into_iter(),
+        queue: MonotonicQueue::new_max(),
+        size,
+    }
+}
+
+/// 返回大小为 `size` 的滑动窗口依次经过 `values` 时, 每个窗口中的最小值.
+///
+/// 元素个数少于 `size` 时, 不返回任何值.
+///
+/// # Panics
+///
+/// `size` 为 0 时 panic.
+pub fn sliding_window_min<I>(values: I, size: usize) -> SlidingWindow<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: Ord,
+{
+    assert!(size > 0, "window size must be positive");
+    SlidingWindow {
+        iter: values.into_iter(),
+        queue: MonotonicQueue::new_min(),
+        size,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sliding_window_max, sliding_window_min, MonotonicQueue};
+
+    #[test]
+    fn test_monotonic_queue() {
+        let mut queue = MonotonicQueue::new_max();
+        assert_eq!(queue.front(), None);
+        assert!(!queue.pop());
+        for value in [3, 1, 3, 2] {
+            queue.push(value);
+        }
+        assert_eq!(queue.len(), 4);
+        assert_eq!(queue.front(), Some(&3));
+        // 移除第一个 3 之后, 第二个 3 仍然在队列中.
+        assert!(queue.pop());
+        assert_eq!(queue.front(), Some(&3));
+        assert!(queue.pop());
+        assert_eq!(queue.front(), Some(&3));
+        assert!(queue.pop());
+        assert_eq!(queue.front(), Some(&2));
+        assert!(queue.pop());
+        assert!(queue.is_empty());
+
+        let mut queue = MonotonicQueue::new_min();
+        for value in [5, 2, 4, 1, 3] {
+            queue.push(value);
+        }
+        assert_eq!(queue.front(), Some(&1));
+        queue.clear();
+        assert!(queue.is_empty());
+        queue.push(7);
+        assert_eq!(queue.front(), Some(&7));
+    }
+
+    #[test]
+    fn test_sliding_window() {
+        let values = [1, 3, -1, -3, 5, 3, 6, 7];
+        assert!(sliding_window_max(values, 3).eq([3, 3, 5, 5, 6, 7]));
+        assert!(sliding_window_min(values, 3).eq([-1, -3, -3, -3, 3, 3]));
+        assert!(sliding_window_max(values, 1).eq(values));
+        assert_eq!(sliding_window_max(values, 9).count(), 0);
+
+        // 与暴力求解比较.
+        let values: Vec<i32> = (0..200).map(|i| (i * 7919 % 101) - 50).collect();
+        for size in 1..20 {
+            let expected_max: Vec<i32> = values
+                .windows(size)
+                .map(|w| *w.iter().max().unwrap())
+                .collect();
+            let expected_min: Vec<i32> = values
+                .windows(size)
+                .map(|w| *w.iter().min().unwrap())
+                .collect();
+            assert!(sliding_window_max(values.iter().copied(), size).eq(expected_max));
+            assert!(sliding_window_min(&values, size).eq(expected_min.iter()));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "window size must be positive")]
+    fn test_empty_window() {
+        let _ = sliding_window_max([1, 2, 3], 0);
+    }
+}
@@ -0,0 +1,441 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 多生产者多消费者 (MPMC) 的有界环形缓冲区, 基于 Dmitry Vyukov 的算法.
+//!
+//! 每个槽位都有一个序号 `seq`, 用于标记该槽位当前的状态. 对于位置 `pos`:
+//!
+//! - `seq == pos`, 槽位是空的, 可以被位置 `pos` 的生产者写入, 写入后设置为 `pos + 1`;
+//! - `seq == pos + 1`, 槽位中有数据, 可以被位置 `pos` 的消费者读取,
+//!   读取后设置为 `pos + slots`, 即下一轮的生产者位置.
+//!
+//! 槽位的个数是不小于容量的最小的 2 的幂, 位置与 `slots - 1` 按位与得到槽位的下标,
+//! 位置溢出回绕之后, 每个槽位对应的位置序列仍然是连续的, 序号也就能正确地比较.
+//!
+//! 生产者和消费者先通过 CAS 抢占 `tail` 或者 `head` 中的一个位置, 然后独占地访问对应的槽位.
+//! 槽位的个数可能大于容量, 所以生产者抢占位置之前还要检查 `pos - head` 是否已达到容量.
+
+use std::cell::UnsafeCell;
+use std::cmp::Ordering as CmpOrdering;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::backoff::{Backoff, CachePadded};
+
+pub struct MpmcRingBuffer<T> {
+    /// 下一个要读取的位置.
+    head: CachePadded<AtomicUsize>,
+    /// 下一个要写入的位置.
+    tail: CachePadded<AtomicUsize>,
+    slots: Box<[Slot<T>]>,
+    /// 最多能容纳的元素个数.
+    capacity: usize,
+    /// `slots.len() - 1`, 用于计算槽位的下标.
+    mask: usize,
+}
+
+struct Slot<T> {
+    seq: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// 槽位中的值只被抢占到该位置的线程访问, 并通过 `seq` 的 Release/Acquire 操作转移所有权.
+unsafe impl<T: Send> Sync for MpmcRingBuffer<T> {}
+
+impl<T> MpmcRingBuffer<T> {
+    /// # Panics
+    ///
+    /// `capacity` 小于 2 或者分配内存失败时 panic.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self::with_start(capacity, 0)
+    }
+
+    /// `head` 和 `tail` 都从 `start` 开始.
+    fn with_start(capacity: usize, start: usize) -> Self {
+        // 容量为 1 时, "位置 pos 有数据" 与 "位置 pos + 1 可写入" 的序号相同, 无法区分.
+        assert!(capacity >= 2, "capacity must be at least 2");
+        let slot_count = capacity
+            .checked_next_power_of_two()
+            .expect("capacity overflow");
+        let mask = slot_count - 1;
+
+        let slots = (0..slot_count)
+            .map(|index| Slot {
+                // 槽位 `index` 对应 `[start, start + slots)` 中与它同余的那个位置.
+                seq: AtomicUsize::new(start.wrapping_add(index.wrapping_sub(start) & mask)),
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+            })
+            .collect();
+        Self {
+            head: CachePadded::new(AtomicUsize::new(start)),
+            tail: CachePadded::new(AtomicUsize::new(start)),
+            slots,
+            capacity,
+            mask,
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 返回缓冲区中的元素个数, 其它线程可能同时在修改, 所以该值只是一个估计.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        loop {
+            let tail = self.tail.load(Ordering::SeqCst);
+            let head = self.head.load(Ordering::SeqCst);
+            // 确保读取 `head` 期间 `tail` 没有变化, 否则可能得到一个超出容量的值.
+            if self.tail.load(Ordering::SeqCst) == tail {
+                return tail.wrapping_sub(head).min(self.capacity());
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    #[inline]
+    fn slot(&self, pos: usize) -> &Slot<T> {
+        &self.slots[pos & self.mask]
+    }
+
+    /// 非阻塞写入.
+    ///
+    /// # Errors
+    ///
+    /// 当缓冲区已满时返回 `Err(value)`
+    pub fn try_push(&self, value: T) -> Result<(), T> {
+        let mut pos = self.tail.load(Ordering::Relaxed);
+        loop {
+            let slot = self.slot(pos);
+            let seq = slot.seq.load(Ordering::Acquire);
+            #[allow(clippy::cast_possible_wrap)]
+            let diff = seq.wrapping_sub(pos) as isize;
+            match diff.cmp(&0) {
+                CmpOrdering::Equal => {
+                    // `head` 只会增大, 所以这里没有超出容量, 抢占成功时也不会超出.
+                    if pos.wrapping_sub(self.head.load(Ordering::Acquire)) >= self.capacity {
+                        return Err(value);
+                    }
+                    match self.tail.compare_exchange_weak(
+                        pos,
+                        pos.wrapping_add(1),
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    ) {
+                        Ok(_) => {
+                            unsafe {
+                                (*slot.value.get()).write(value);
+                            }
+                            slot.seq.store(pos.wrapping_add(1), Ordering::Release);
+                            return Ok(());
+                        }
+                        Err(current) => pos = current,
+                    }
+                }
+                CmpOrdering::Less => {
+                    // 该槽位上一轮的数据还没有被读取.
+                    return Err(value);
+                }
+                CmpOrdering::Greater => {
+                    // 其它生产者已经抢占了这个位置.
+                    pos = self.tail.load(Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// 阻塞写入, 缓冲区已满时等待消费者读取.
+    pub fn push(&self, mut value: T) {
+        let mut backoff = Backoff::new();
+        while let Err(v) = self.try_push(value) {
+            value = v;
+            backoff.snooze();
+        }
+    }
+
+    /// 非阻塞读取, 如果缓冲区已空, 就返回 `None`
+    pub fn try_pop(&self) -> Option<T> {
+        let mut pos = self.head.load(Ordering::Relaxed);
+        loop {
+            let slot = self.slot(pos);
+            let seq = slot.seq.load(Ordering::Acquire);
+            #[allow(clippy::cast_possible_wrap)]
+            let diff = seq.wrapping_sub(pos.wrapping_add(1)) as isize;
+            match diff.cmp(&0) {
+                CmpOrdering::Equal => {
+                    match self.head.compare_exchange_weak(
+                        pos,
+                        pos.wrapping_add(1),
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    ) {
+                        Ok(_) => {
+                            let value = unsafe { (*slot.value.get()).assume_init_read() };
+                            slot.seq.store(pos.wrapping_add(self.mask + 1), Ordering::Release);
+                            return Some(value);
+                        }
+                        Err(current) => pos = current,
+                    }
+                }
+                CmpOrdering::Less => {
+                    // 该槽位还没有被写入.
+                    return None;
+                }
+                CmpOrdering::Greater => {
+                    // 其它消费者已经抢占了这个位置.
+                    pos = self.head.load(Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// 阻塞读取, 缓冲区已空时等待生产者写入.
+    pub fn pop(&self) -> T {
+        let mut backoff = Backoff::new();
+        loop {
+            if let Some(value) = self.try_pop() {
+                return value;
+            }
+            backoff.snooze();
+        }
+    }
+
+    /// 非阻塞地批量写入, 遇到缓冲区已满时停止, 返回实际写入的元素个数.
+    ///
+    /// 其它生产者的元素可能与这一批元素交错.
+    pub fn push_slice(&self, values: &[T]) -> usize
+    where
+        T: Clone,
+    {
+        values
+            .iter()
+            .take_while(|value| self.try_push((*value).clone()).is_ok())
+            .count()
+    }
+
+    /// 阻塞地批量写入, 直到所有元素都被写入.
+    pub fn push_all(&self, values: &[T])
+    where
+        T: Clone,
+    {
+        for value in values {
+            self.push(value.clone());
+        }
+    }
+
+    /// 非阻塞地批量读取到 `dst` 中, 遇到缓冲区已空时停止, 返回实际读取的元素个数.
+    pub fn pop_slice(&self, dst: &mut [T]) -> usize {
+        for (count, item) in dst.iter_mut().enumerate() {
+            match self.try_pop() {
+                Some(value) => *item = value,
+                None => return count,
+            }
+        }
+        dst.len()
+    }
+
+    /// 阻塞地批量读取, 直到 `dst` 被填满.
+    pub fn pop_exact(&self, dst: &mut [T]) {
+        for item in dst {
+            *item = self.pop();
+        }
+    }
+}
+
+impl<T> Drop for MpmcRingBuffer<T> {
+    fn drop(&mut self) {
+        while self.try_pop().is_some() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    use super::MpmcRingBuffer;
+
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let buf = MpmcRingBuffer::new(3);
+        assert_eq!(buf.capacity(), 3);
+        assert!(buf.is_empty());
+        assert_eq!(buf.try_pop(), None);
+
+        for round in 0..5 {
+            for i in 0..3 {
+                assert_eq!(buf.try_push(round * 3 + i), Ok(()));
+            }
+            assert!(buf.is_full());
+            assert_eq!(buf.try_push(100), Err(100));
+            for i in 0..3 {
+                assert_eq!(buf.try_pop(), Some(round * 3 + i));
+            }
+            assert!(buf.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_position_wraparound() {
+        // 位置跨过 `usize::MAX` 时, 序号仍然匹配, 不会误判为已满或者已空.
+        let buf = MpmcRingBuffer::with_start(5, usize::MAX - 2);
+        for round in 0..4 {
+            for i in 0..5 {
+                assert_eq!(buf.try_push(round * 5 + i), Ok(()));
+            }
+            assert_eq!(buf.try_push(100), Err(100));
+            assert_eq!(buf.len(), 5);
+            for i in 0..5 {
+                assert_eq!(buf.try_pop(), Some(round * 5 + i));
+            }
+            assert_eq!(buf.try_pop(), None);
+        }
+
+        // 部分填充时跨过回绕点.
+        let buf = MpmcRingBuffer::with_start(4, usize::MAX);
+        for i in 0..10 {
+            assert_eq!(buf.try_push(i), Ok(()));
+            assert_eq!(buf.try_push(i + 100), Ok(()));
+            assert_eq!(buf.try_pop(), Some(i));
+            assert_eq!(buf.try_pop(), Some(i + 100));
+        }
+
+        let drops = Arc::new(AtomicUsize::new(0));
+        let buf = MpmcRingBuffer::with_start(4, usize::MAX - 1);
+        for _ in 0..4 {
+            assert!(buf.try_push(Counted(Arc::clone(&drops))).is_ok());
+        }
+        drop(buf);
+        assert_eq!(drops.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity must be at least 2")]
+    fn test_capacity_one() {
+        let _buf = MpmcRingBuffer::<i32>::new(1);
+    }
+
+    #[test]
+    fn test_exact_capacity() {
+        // 槽位有 8 个, 但只能写入 5 个元素.
+        let buf = MpmcRingBuffer::new(5);
+        for i in 0..5 {
+            assert_eq!(buf.try_push(i), Ok(()));
+        }
+        assert!(buf.is_full());
+        assert_eq!(buf.try_push(5), Err(5));
+        assert_eq!(buf.push_slice(&[5, 6]), 0);
+        assert_eq!(buf.try_pop(), Some(0));
+        assert_eq!(buf.push_slice(&[5, 6]), 1);
+        assert_eq!(buf.len(), 5);
+    }
+
+    #[test]
+    fn test_batch() {
+        let buf = MpmcRingBuffer::new(4);
+        assert_eq!(buf.push_slice(&[1, 2, 3]), 3);
+        assert_eq!(buf.push_slice(&[4, 5, 6]), 1);
+        assert_eq!(buf.len(), 4);
+
+        let mut dst = [0; 3];
+        assert_eq!(buf.pop_slice(&mut dst), 3);
+        assert_eq!(dst, [1, 2, 3]);
+        buf.push_all(&[5, 6]);
+        buf.pop_exact(&mut dst);
+        assert_eq!(dst, [4, 5, 6]);
+        assert_eq!(buf.pop_slice(&mut dst), 0);
+    }
+
+    #[test]
+    fn test_drop_remaining() {
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let buf = MpmcRingBuffer::new(8);
+        for _ in 0..6 {
+            assert!(buf.try_push(Counted(Arc::clone(&dropped))).is_ok());
+        }
+        drop(buf.try_pop());
+        assert_eq!(dropped.load(Ordering::SeqCst), 1);
+        drop(buf);
+        assert_eq!(dropped.load(Ordering::SeqCst), 6);
+    }
+
+    #[test]
+    fn test_mpmc() {
+        const PRODUCERS: usize = 4;
+        const CONSUMERS: usize = 4;
+        const PER_PRODUCER: usize = 20_000;
+        const PER_CONSUMER: usize = PRODUCERS * PER_PRODUCER / CONSUMERS;
+
+        let buf = Arc::new(MpmcRingBuffer::new(16));
+        let barrier = Arc::new(Barrier::new(PRODUCERS + CONSUMERS));
+
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|id| {
+                let buf = Arc::clone(&buf);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..PER_PRODUCER {
+                        buf.push((id, i));
+                    }
+                })
+            })
+            .collect();
+        // Threads must all be spawned before joining any of them.
+        #[allow(clippy::needless_collect)]
+        let consumers: Vec<_> = (0..CONSUMERS)
+            .map(|_| {
+                let buf = Arc::clone(&buf);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    let mut received = vec![(0, 0); PER_CONSUMER];
+                    buf.pop_exact(&mut received);
+                    received
+                })
+            })
+            .collect();
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let mut all = vec![Vec::new(); PRODUCERS];
+        for consumer in consumers {
+            let received = consumer.join().unwrap();
+            // 同一个生产者的元素按 FIFO 顺序被读取.
+            let mut last = [None; PRODUCERS];
+            for (id, i) in received {
+                assert!(last[id] < Some(i));
+                last[id] = Some(i);
+                all[id].push(i);
+            }
+        }
+        for mut items in all {
+            items.sort_unstable();
+            assert_eq!(items, (0..PER_PRODUCER).collect::<Vec<_>>());
+        }
+        assert!(buf.is_empty());
+    }
+}
@@ -0,0 +1,507 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 单生产者单消费者 (SPSC) 的有界环形缓冲区.
+//!
+//! 存储空间是一个槽位数组, `head` 和 `tail` 是单调递增的位置, 溢出后回绕到 0.
+//! 槽位的个数是不小于容量的最小的 2 的幂, 用位置与 `slots - 1` 按位与得到槽位的下标,
+//! 这样即使位置回绕, 连续的 `slots` 个位置也总是对应不同的槽位. 缓冲区是否已满则用 `tail - head`
+//! 与容量比较, 所以多出来的槽位不会被使用. `tail` 只被生产者修改, `head` 只被消费者修改,
+//! 所以 `try_push()` 和 `try_pop()` 都不需要 CAS, 是无等待 (wait-free) 的.
+//!
+//! 生产者和消费者还各自缓存了对方的位置, 只有在缓存的值显示缓冲区已满或者已空时,
+//! 才重新读取对方的原子变量, 这样可以减少缓存行在两个 CPU 核之间的来回传递.
+
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{self, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::backoff::{Backoff, CachePadded};
+
+struct Shared<T> {
+    /// 下一个要读取的位置, 只被消费者修改.
+    head: CachePadded<AtomicUsize>,
+    /// 下一个要写入的位置, 只被生产者修改.
+    tail: CachePadded<AtomicUsize>,
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    /// 最多能容纳的元素个数.
+    capacity: usize,
+    /// `slots.len() - 1`, 用于计算槽位的下标.
+    mask: usize,
+}
+
+/// 生产者一端, 只能有一个.
+pub struct Producer<T> {
+    shared: Arc<Shared<T>>,
+    tail: usize,
+    cached_head: usize,
+}
+
+/// 消费者一端, 只能有一个.
+pub struct Consumer<T> {
+    shared: Arc<Shared<T>>,
+    head: usize,
+    cached_tail: usize,
+}
+
+// 每个槽位在同一时刻只被一端访问, 槽位的所有权通过 `head` 和 `tail` 的
+// Release/Acquire 操作在两端之间转移.
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+/// 创建环形缓冲区, 返回生产者和消费者两端.
+///
+/// # Panics
+///
+/// `capacity` 为 0 或者分配内存失败时 panic.
+#[must_use]
+pub fn ring_buffer<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    with_start(capacity, 0)
+}
+
+/// 创建环形缓冲区, `head` 和 `tail` 都从 `start` 开始.
+fn with_start<T>(capacity: usize, start: usize) -> (Producer<T>, Consumer<T>) {
+    assert!(capacity > 0, "capacity must be positive");
+    let slot_count = capacity
+        .checked_next_power_of_two()
+        .expect("capacity overflow");
+
+    let slots = (0..slot_count)
+        .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+        .collect();
+    let shared = Arc::new(Shared {
+        head: CachePadded::new(AtomicUsize::new(start)),
+        tail: CachePadded::new(AtomicUsize::new(start)),
+        slots,
+        capacity,
+        mask: slot_count - 1,
+    });
+    let producer = Producer {
+        shared: Arc::clone(&shared),
+        tail: start,
+        cached_head: start,
+    };
+    let consumer = Consumer {
+        shared,
+        head: start,
+        cached_tail: start,
+    };
+    (producer, consumer)
+}
+
+impl<T> Shared<T> {
+    #[inline]
+    fn slot(&self, pos: usize) -> *mut MaybeUninit<T> {
+        self.slots[pos & self.mask].get()
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Relaxed);
+        let mut pos = head;
+        while pos != tail {
+            unsafe {
+                (*self.slot(pos)).assume_init_drop();
+            }
+            pos = pos.wrapping_add(1);
+        }
+    }
+}
+
+impl<T> Producer<T> {
+    #[must_use]
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+
+    /// 返回缓冲区中的元素个数, 消费者可能同时在读取, 所以该值只是一个估计.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tail
+            .wrapping_sub(self.shared.head.load(Ordering::Acquire))
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// 消费者一端是否已被释放.
+    #[must_use]
+    pub fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.shared) == 1
+    }
+
+    /// 返回当前可以写入的槽位个数, 必要时重新读取消费者的位置.
+    fn free_slots(&mut self, wanted: usize) -> usize {
+        let cap = self.capacity();
+        let mut free = cap - self.tail.wrapping_sub(self.cached_head);
+        if free < wanted {
+            self.cached_head = self.shared.head.load(Ordering::Acquire);
+            free = cap - self.tail.wrapping_sub(self.cached_head);
+        }
+        free
+    }
+
+    /// 非阻塞写入.
+    ///
+    /// # Errors
+    ///
+    /// 当缓冲区已满时返回 `Err(value)`
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        if self.free_slots(1) == 0 {
+            return Err(value);
+        }
+        unsafe {
+            (*self.shared.slot(self.tail)).write(value);
+        }
+        self.tail = self.tail.wrapping_add(1);
+        self.shared.tail.store(self.tail, Ordering::Release);
+        Ok(())
+    }
+
+    /// 阻塞写入, 缓冲区已满时等待消费者读取.
+    ///
+    /// # Errors
+    ///
+    /// 消费者一端已被释放时, 返回 `Err(value)`
+    pub fn push(&mut self, mut value: T) -> Result<(), T> {
+        let mut backoff = Backoff::new();
+        loop {
+            match self.try_push(value) {
+                Ok(()) => return Ok(()),
+                Err(v) if self.is_abandoned() => return Err(v),
+                Err(v) => value = v,
+            }
+            backoff.snooze();
+        }
+    }
+
+    /// 非阻塞地批量写入, 返回实际写入的元素个数.
+    ///
+    /// 所有元素写入之后才更新一次 `tail`.
+    pub fn push_slice(&mut self, values: &[T]) -> usize
+    where
+        T: Clone,
+    {
+        let count = self.free_slots(values.len()).min(values.len());
+        for (i, value) in values[..count].iter().enumerate() {
+            unsafe {
+                (*self.shared.slot(self.tail.wrapping_add(i))).write(value.clone());
+            }
+        }
+        self.tail = self.tail.wrapping_add(count);
+        self.shared.tail.store(self.tail, Ordering::Release);
+        count
+    }
+
+    /// 阻塞地批量写入, 直到所有元素都被写入, 或者消费者一端被释放.
+    ///
+    /// 返回实际写入的元素个数.
+    pub fn push_all(&mut self, values: &[T]) -> usize
+    where
+        T: Clone,
+    {
+        let mut written = 0;
+        let mut backoff = Backoff::new();
+        while written < values.len() {
+            let count = self.push_slice(&values[written..]);
+            if count > 0 {
+                written += count;
+                backoff = Backoff::new();
+            } else if self.is_abandoned() {
+                break;
+            } else {
+                backoff.snooze();
+            }
+        }
+        written
+    }
+}
+
+impl<T> Consumer<T> {
+    #[must_use]
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+
+    /// 返回缓冲区中的元素个数, 生产者可能同时在写入, 所以该值只是一个估计.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.shared
+            .tail
+            .load(Ordering::Acquire)
+            .wrapping_sub(self.head)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// 生产者一端是否已被释放.
+    #[must_use]
+    pub fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.shared) == 1
+    }
+
+    /// 返回当前可以读取的元素个数, 必要时重新读取生产者的位置.
+    fn ready_slots(&mut self, wanted: usize) -> usize {
+        let mut ready = self.cached_tail.wrapping_sub(self.head);
+        if ready < wanted {
+            self.cached_tail = self.shared.tail.load(Ordering::Acquire);
+            ready = self.cached_tail.wrapping_sub(self.head);
+        }
+        ready
+    }
+
+    /// 非阻塞读取, 如果缓冲区已空, 就返回 `None`
+    pub fn try_pop(&mut self) -> Option<T> {
+        if self.ready_slots(1) == 0 {
+            return None;
+        }
+        let value = unsafe { (*self.shared.slot(self.head)).assume_init_read() };
+        self.head = self.head.wrapping_add(1);
+        self.shared.head.store(self.head, Ordering::Release);
+        Some(value)
+    }
+
+    /// 阻塞读取, 缓冲区已空时等待生产者写入.
+    ///
+    /// 生产者一端已被释放, 并且缓冲区已空时, 返回 `None`.
+    pub fn pop(&mut self) -> Option<T> {
+        let mut backoff = Backoff::new();
+        loop {
+            if let Some(value) = self.try_pop() {
+                return Some(value);
+            }
+            if self.is_abandoned() {
+                // 与生产者释放 `Arc` 时的 Release 操作同步, 确保能看到它最后写入的元素.
+                atomic::fence(Ordering::Acquire);
+                return self.try_pop();
+            }
+            backoff.snooze();
+        }
+    }
+
+    /// 非阻塞地批量读取到 `dst` 中, 返回实际读取的元素个数.
+    ///
+    /// 所有元素读取之后才更新一次 `head`.
+    pub fn pop_slice(&mut self, dst: &mut [T]) -> usize {
+        let count = self.ready_slots(dst.len()).min(dst.len());
+        for (i, item) in dst[..count].iter_mut().enumerate() {
+            *item = unsafe { (*self.shared.slot(self.head.wrapping_add(i))).assume_init_read() };
+        }
+        self.head = self.head.wrapping_add(count);
+        self.shared.head.store(self.head, Ordering::Release);
+        count
+    }
+
+    /// 阻塞地批量读取, 直到 `dst` 被填满, 或者生产者一端被释放并且缓冲区已空.
+    ///
+    /// 返回实际读取的元素个数.
+    pub fn pop_exact(&mut self, dst: &mut [T]) -> usize {
+        let mut read = 0;
+        let mut backoff = Backoff::new();
+        while read < dst.len() {
+            let count = self.pop_slice(&mut dst[read..]);
+            if count > 0 {
+                read += count;
+                backoff = Backoff::new();
+            } else if self.is_abandoned() {
+                atomic::fence(Ordering::Acquire);
+                read += self.pop_slice(&mut dst[read..]);
+                break;
+            } else {
+                backoff.snooze();
+            }
+        }
+        read
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::{ring_buffer, with_start};
+
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let (mut producer, mut consumer) = ring_buffer(3);
+        assert_eq!(producer.capacity(), 3);
+        assert!(consumer.is_empty());
+        assert_eq!(consumer.try_pop(), None);
+
+        // 多次绕过缓冲区的末尾.
+        for round in 0..5 {
+            for i in 0..3 {
+                assert_eq!(producer.try_push(round * 3 + i), Ok(()));
+            }
+            assert!(producer.is_full());
+            assert_eq!(producer.try_push(100), Err(100));
+            assert_eq!(consumer.len(), 3);
+            for i in 0..3 {
+                assert_eq!(consumer.try_pop(), Some(round * 3 + i));
+            }
+            assert!(consumer.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_position_wraparound() {
+        // 位置跨过 `usize::MAX` 时, 缓冲区中的元素仍然保持先进先出.
+        let (mut producer, mut consumer) = with_start(5, usize::MAX - 2);
+        for round in 0..4 {
+            for i in 0..5 {
+                assert_eq!(producer.try_push(round * 5 + i), Ok(()));
+            }
+            assert_eq!(producer.try_push(100), Err(100));
+            assert_eq!(producer.len(), 5);
+            for i in 0..5 {
+                assert_eq!(consumer.try_pop(), Some(round * 5 + i));
+            }
+            assert_eq!(consumer.try_pop(), None);
+        }
+
+        // 释放时, 跨过回绕点的剩余元素都被析构.
+        let drops = Arc::new(AtomicUsize::new(0));
+        let (mut producer, consumer) = with_start(4, usize::MAX - 1);
+        for _ in 0..4 {
+            assert!(producer.try_push(Counted(Arc::clone(&drops))).is_ok());
+        }
+        drop(producer);
+        drop(consumer);
+        assert_eq!(drops.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_exact_capacity() {
+        // 槽位有 8 个, 但只能写入 5 个元素.
+        let (mut producer, mut consumer) = ring_buffer(5);
+        for i in 0..5 {
+            assert_eq!(producer.try_push(i), Ok(()));
+        }
+        assert!(producer.is_full());
+        assert_eq!(producer.try_push(5), Err(5));
+        assert_eq!(producer.push_slice(&[5, 6]), 0);
+        assert_eq!(consumer.try_pop(), Some(0));
+        assert_eq!(producer.push_slice(&[5, 6]), 1);
+        assert_eq!(consumer.len(), 5);
+    }
+
+    #[test]
+    fn test_batch() {
+        let (mut producer, mut consumer) = ring_buffer(4);
+        assert_eq!(producer.push_slice(&[1, 2, 3]), 3);
+        assert_eq!(producer.push_slice(&[4, 5, 6]), 1);
+        assert_eq!(producer.push_slice(&[7]), 0);
+
+        let mut dst = [0; 3];
+        assert_eq!(consumer.pop_slice(&mut dst), 3);
+        assert_eq!(dst, [1, 2, 3]);
+        assert_eq!(producer.push_slice(&[5, 6]), 2);
+        assert_eq!(consumer.pop_slice(&mut dst), 3);
+        assert_eq!(dst, [4, 5, 6]);
+        assert_eq!(consumer.pop_slice(&mut dst), 0);
+    }
+
+    #[test]
+    fn test_abandoned() {
+        let (mut producer, mut consumer) = ring_buffer(2);
+        assert_eq!(producer.push(1), Ok(()));
+        assert_eq!(producer.push(2), Ok(()));
+        drop(producer);
+        assert!(consumer.is_abandoned());
+        assert_eq!(consumer.pop(), Some(1));
+        let mut dst = [0; 4];
+        assert_eq!(consumer.pop_exact(&mut dst), 1);
+        assert_eq!(dst[0], 2);
+        assert_eq!(consumer.pop(), None);
+
+        let (mut producer, consumer) = ring_buffer(1);
+        drop(consumer);
+        assert_eq!(producer.push(1), Ok(()));
+        assert_eq!(producer.push(2), Err(2));
+        assert_eq!(producer.push_all(&[3, 4]), 0);
+    }
+
+    #[test]
+    fn test_drop_remaining() {
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let (mut producer, mut consumer) = ring_buffer(8);
+        for _ in 0..6 {
+            assert!(producer.try_push(Counted(Arc::clone(&dropped))).is_ok());
+        }
+        drop(consumer.try_pop());
+        drop(consumer.try_pop());
+        assert_eq!(dropped.load(Ordering::SeqCst), 2);
+        drop(producer);
+        drop(consumer);
+        assert_eq!(dropped.load(Ordering::SeqCst), 6);
+    }
+
+    #[test]
+    fn test_threads() {
+        const COUNT: usize = 100_000;
+
+        let (mut producer, mut consumer) = ring_buffer(64);
+        let handle = thread::spawn(move || {
+            let values: Vec<usize> = (0..COUNT).collect();
+            // 交替使用单个写入和批量写入.
+            for chunk in values.chunks(100) {
+                if chunk[0] % 200 == 0 {
+                    assert_eq!(producer.push_all(chunk), chunk.len());
+                } else {
+                    for &value in chunk {
+                        assert_eq!(producer.push(value), Ok(()));
+                    }
+                }
+            }
+        });
+
+        let mut expected = 0;
+        let mut dst = [0; 37];
+        while expected < COUNT {
+            if expected % 2 == 0 {
+                assert_eq!(consumer.pop(), Some(expected));
+                expected += 1;
+            } else {
+                let count = consumer.pop_exact(&mut dst);
+                assert!(count > 0);
+                for &value in &dst[..count] {
+                    assert_eq!(value, expected);
+                    expected += 1;
+                }
+            }
+        }
+        handle.join().unwrap();
+        assert_eq!(consumer.pop(), None);
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 各种队列的公共接口.
+//!
+//! 基于这些 trait, 广度优先搜索这类算法只需要实现一次, 就可以用于所有的队列.
+
+/// 先进先出 (FIFO) 的队列.
+pub trait Queue<T> {
+    /// 在队尾添加元素
+    ///
+    /// # Errors
+    ///
+    /// 当队列已满时返回 `Err(value)`, 容量不受限制的队列总是返回 `Ok(())`.
+    fn push(&mut self, value: T) -> Result<(), T>;
+
+    /// 从队首弹出元素, 如果队列已空, 就返回 `None`
+    fn pop(&mut self) -> Option<T>;
+
+    /// 返回队首元素
+    fn front(&self) -> Option<&T>;
+
+    /// 返回当前队列中的元素个数
+    fn len(&self) -> usize;
+
+    /// 检查队列是否为空
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 容量固定的队列.
+pub trait BoundedQueue<T>: Queue<T> {
+    /// 返回队列的容量
+    fn capacity(&self) -> usize;
+
+    /// 检查队列是否已满
+    fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundedQueue, Queue};
+    use crate::array_queue::ArrayQueue;
+    use crate::array_queue2::ArrayQueue2;
+    use crate::circular_buffer::CircularBuffer;
+    use crate::list_queue::ListQueue;
+
+    /// 在无向图中做广度优先搜索, 返回从 `start` 到各个顶点的距离, 适用于任意的队列.
+    fn bfs<Q: Queue<usize>>(mut queue: Q, edges: &[(usize, usize)], start: usize) -> Vec<usize> {
+        let n = edges.iter().map(|&(u, v)| u.max(v) + 1).max().unwrap_or(0);
+        let mut adj = vec![Vec::new(); n];
+        for &(u, v) in edges {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+        let mut dist = vec![usize::MAX; n];
+        dist[start] = 0;
+        assert!(queue.push(start).is_ok());
+        while let Some(u) = queue.pop() {
+            for &v in &adj[u] {
+                if dist[v] == usize::MAX {
+                    dist[v] = dist[u] + 1;
+                    assert!(queue.push(v).is_ok());
+                }
+            }
+        }
+        dist
+    }
+
+    /// 交替入队和出队, 元素按照入队的顺序出队, 队首总是最早入队的元素.
+    fn check_fifo<Q: Queue<i32>>(mut queue: Q) {
+        assert!(queue.is_empty());
+        assert_eq!(queue.front(), None);
+        assert_eq!(queue.pop(), None);
+        assert_eq!(queue.push(1), Ok(()));
+        assert_eq!(queue.push(2), Ok(()));
+        assert_eq!(queue.front(), Some(&1));
+        assert_eq!(queue.pop(), Some(1));
+        assert_eq!(queue.push(3), Ok(()));
+        assert_eq!(queue.front(), Some(&2));
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.pop(), Some(2));
+        assert_eq!(queue.pop(), Some(3));
+        assert!(queue.is_empty());
+        assert_eq!(queue.pop(), None);
+    }
+
+    /// 队列满了之后, 每出队一个元素就再入队一个, 重复多轮,
+    /// 让队首和队尾多次绕过底层数组的末尾, 元素顺序保持不变.
+    fn check_wraparound<Q: BoundedQueue<i32>>(mut queue: Q) {
+        let capacity = i32::try_from(queue.capacity()).unwrap();
+        for i in 0..capacity {
+            assert!(!queue.is_full());
+            assert_eq!(queue.push(i), Ok(()));
+        }
+        assert!(queue.is_full());
+        assert_eq!(queue.push(-1), Err(-1));
+
+        for i in 0..capacity * 3 {
+            assert_eq!(queue.front(), Some(&i));
+            assert_eq!(queue.pop(), Some(i));
+            assert!(!queue.is_full());
+            assert_eq!(queue.push(i + capacity), Ok(()));
+            assert!(queue.is_full());
+            assert_eq!(queue.push(-1), Err(-1));
+        }
+        for i in capacity * 3..capacity * 4 {
+            assert_eq!(queue.pop(), Some(i));
+        }
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_fifo_order() {
+        check_fifo(ArrayQueue::new(4));
+        check_fifo(ArrayQueue2::new(4));
+        check_fifo(CircularBuffer::new(4));
+        check_fifo(ListQueue::new());
+    }
+
+    #[test]
+    fn test_wraparound() {
+        check_wraparound(ArrayQueue::new(5));
+        check_wraparound(ArrayQueue2::new(5));
+        check_wraparound(CircularBuffer::new(5));
+    }
+
+    #[test]
+    fn test_generic_algorithm() {
+        let edges = [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (5, 6)];
+        let expected = [0, 1, 1, 2, 3, usize::MAX, usize::MAX];
+        assert_eq!(bfs(ArrayQueue::new(8), &edges, 0), expected);
+        assert_eq!(bfs(ArrayQueue2::new(8), &edges, 0), expected);
+        assert_eq!(bfs(CircularBuffer::new(8), &edges, 0), expected);
+        assert_eq!(bfs(ListQueue::new(), &edges, 0), expected);
+    }
+}
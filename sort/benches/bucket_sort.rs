@@ -7,13 +7,14 @@ use std::time::Duration;
 use criterion::{Criterion, criterion_group, criterion_main};
 
 use sort::bucket_sort::{bucket_sort, generic_bucket_sort, shell_bucket_sort};
+use sort::dataset::DEFAULT_SEED;
 use sort::util::random_ints_in_range;
 
 fn criterion_benchmark(c: &mut Criterion) {
     for exp in 1..7 {
         let len: usize = 2 * 10_usize.pow(exp);
         let max = (len as i32) * 2;
-        let arr = random_ints_in_range(len, 0, max).expect("Failed to generate random integers");
+        let arr = random_ints_in_range(len, 0, max, DEFAULT_SEED).expect("Failed to generate random integers");
         let title1 = format!("std_sort_for_bucket_sort {len}");
         let title2 = format!("bucket_sort {len}");
         let title3 = format!("shell_bucket_sort {len}");
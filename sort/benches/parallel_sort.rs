@@ -0,0 +1,60 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+use std::time::Duration;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+
+use sort::dataset::DEFAULT_SEED;
+use sort::parallel_sort::{parallel_merge_sort, parallel_quicksort, parallel_sample_sort};
+use sort::util::random_ints;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    for exp in 4..7 {
+        let len: usize = 2 * 10_usize.pow(exp);
+        let arr: Vec<i64> = random_ints(len)
+            .expect("Failed to generate random integers")
+            .into_iter()
+            .map(i64::from)
+            .collect();
+        let mut arr_sorted = arr.clone();
+        arr_sorted.sort_unstable();
+
+        c.bench_function(&format!("std_sort_unstable {len}"), |b| {
+            b.iter(|| {
+                let mut arr1 = arr.clone();
+                arr1.sort_unstable();
+                assert_eq!(arr1, arr_sorted);
+            })
+        });
+        c.bench_function(&format!("parallel_merge_sort {len}"), |b| {
+            b.iter(|| {
+                let mut arr2 = arr.clone();
+                parallel_merge_sort(&mut arr2, 0);
+                assert_eq!(arr2, arr_sorted);
+            })
+        });
+        c.bench_function(&format!("parallel_quicksort {len}"), |b| {
+            b.iter(|| {
+                let mut arr3 = arr.clone();
+                parallel_quicksort(&mut arr3, 0);
+                assert_eq!(arr3, arr_sorted);
+            })
+        });
+        c.bench_function(&format!("parallel_sample_sort {len}"), |b| {
+            b.iter(|| {
+                let mut arr4 = arr.clone();
+                parallel_sample_sort(&mut arr4, 0, DEFAULT_SEED);
+                assert_eq!(arr4, arr_sorted);
+            })
+        });
+    }
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().measurement_time(Duration::from_secs(10));
+    targets = criterion_benchmark
+);
+criterion_main!(benches);
@@ -0,0 +1,83 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+use std::time::Duration;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+
+use sort::introsort::introsort;
+use sort::pdqsort::pdqsort;
+use sort::quicksort::insertion_quicksort;
+use sort::util::random_ints;
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+fn patterns(len: usize) -> Vec<(&'static str, Vec<i32>)> {
+    let random = random_ints(len).expect("Failed to generate random integers");
+    let organ_pipe: Vec<i32> = (0..len / 2)
+        .chain((0..len - len / 2).rev())
+        .map(|num| num as i32)
+        .collect();
+    let sawtooth: Vec<i32> = (0..len).map(|num| (num % 256) as i32).collect();
+    let all_equal: Vec<i32> = vec![42; len];
+    vec![
+        ("random", random),
+        ("organ_pipe", organ_pipe),
+        ("sawtooth", sawtooth),
+        ("all_equal", all_equal),
+    ]
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    for exp in 1..7 {
+        let len: usize = 2 * 10_usize.pow(exp);
+        for (pattern, arr) in patterns(len) {
+            let title1 = format!("std_sort_unstable_for_pdqsort {pattern} {len}");
+            let title2 = format!("introsort {pattern} {len}");
+            let title3 = format!("pdqsort {pattern} {len}");
+            let title4 = format!("insertion_quicksort_for_pdqsort {pattern} {len}");
+            let mut arr_sorted = arr.clone();
+            arr_sorted.sort_unstable();
+
+            c.bench_function(&title1, |b| {
+                b.iter(|| {
+                    let mut arr1 = arr.clone();
+                    arr1.sort_unstable();
+                    assert_eq!(arr1, arr_sorted);
+                })
+            });
+            c.bench_function(&title2, |b| {
+                b.iter(|| {
+                    let mut arr2 = arr.clone();
+                    introsort(&mut arr2);
+                    assert_eq!(arr2, arr_sorted);
+                })
+            });
+            c.bench_function(&title3, |b| {
+                b.iter(|| {
+                    let mut arr3 = arr.clone();
+                    pdqsort(&mut arr3);
+                    assert_eq!(arr3, arr_sorted);
+                })
+            });
+
+            // 普通的快速排序在这些模式下是 `O(n^2)` 的, 只在随机数据上比较.
+            if pattern == "random" {
+                c.bench_function(&title4, |b| {
+                    b.iter(|| {
+                        let mut arr4 = arr.clone();
+                        insertion_quicksort(&mut arr4);
+                        assert_eq!(arr4, arr_sorted);
+                    })
+                });
+            }
+        }
+    }
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().measurement_time(Duration::from_secs(10));
+    targets = criterion_benchmark
+);
+criterion_main!(benches);
@@ -6,7 +6,7 @@ use std::time::Duration;
 
 use criterion::{Criterion, criterion_group, criterion_main};
 
-use sort::timsort::{shell_timsort, timsort};
+use sort::timsort::{natural_timsort, shell_timsort, timsort};
 use sort::util::random_ints;
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -16,6 +16,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         let title1 = format!("std_sort_for_timsort {len}");
         let title2 = format!("timsort {len}");
         let title3 = format!("shell_timsort {len}");
+        let title4 = format!("natural_timsort {len}");
         let mut arr_sorted = arr.clone();
         arr_sorted.sort();
 
@@ -40,6 +41,13 @@ fn criterion_benchmark(c: &mut Criterion) {
                 assert_eq!(arr3, arr_sorted);
             })
         });
+        c.bench_function(&title4, |b| {
+            b.iter(|| {
+                let mut arr4 = arr.clone();
+                natural_timsort(&mut arr4);
+                assert_eq!(arr4, arr_sorted);
+            })
+        });
     }
 }
 
@@ -0,0 +1,42 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+use std::env::args;
+use std::io::{self, Write};
+use std::process;
+
+use sort::dataset::{generate, save, Distribution, DEFAULT_SEED, DISTRIBUTION_NAMES};
+
+fn print_usage() -> ! {
+    eprintln!("Usage: gen_dataset <distribution> <len> [seed]");
+    eprintln!("Supported distributions:");
+    for (index, name) in DISTRIBUTION_NAMES.iter().enumerate() {
+        eprintln!("  {:2}: {name}", index + 1);
+    }
+    process::exit(1);
+}
+
+fn main() -> io::Result<()> {
+    let mut args = args();
+    let _app_name = args.next();
+    let name = args.next().unwrap_or_else(|| print_usage());
+    let len: usize = match args.next().map(|len| len.parse()) {
+        Some(Ok(len)) => len,
+        _ => print_usage(),
+    };
+    let seed: u64 = match args.next().map(|seed| seed.parse()) {
+        None => DEFAULT_SEED,
+        Some(Ok(seed)) => seed,
+        Some(Err(_)) => print_usage(),
+    };
+    let Some(distribution) = Distribution::by_name(&name, len) else {
+        eprintln!("Unknown distribution: {name}");
+        print_usage();
+    };
+
+    let data: Vec<i32> = generate(&distribution, len, seed);
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "# {name}, len: {len}, seed: {seed}")?;
+    save(&data, stdout)
+}
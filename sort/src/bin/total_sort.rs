@@ -3,27 +3,38 @@
 // in the LICENSE file.
 
 use std::env::args;
+use std::fmt;
 use std::process;
+use std::time::Instant;
 
 use sort::bubble_sort::{bubble_sort, recursive_bubble_sort};
 use sort::bucket_sort::{bucket_sort, shell_bucket_sort};
 use sort::counting_sort::{counting_sort, counting_sort_with_map};
+use sort::dataset::DEFAULT_SEED;
 use sort::gnome_sort::gnome_sort;
 use sort::heap_sort::heap_sort;
 use sort::insertion_sort::{binary_insertion_sort, insertion_sort, recursive_insertion_sort};
+use sort::introsort::introsort;
 use sort::merge_sort::{
     bottom_up_merge_sort, in_place_merge_sort, in_place_shell_merge_sort, insertion_merge_sort,
     merge_sort, shell_merge_sort, three_way_merge_sort,
 };
+use sort::parallel_sort::{parallel_merge_sort, parallel_quicksort, parallel_sample_sort};
+use sort::pdqsort::pdqsort;
 use sort::quicksort::{
     head_quicksort, insertion_quicksort, iterative_quicksort, quicksort, two_pointer_quicksort,
 };
+use sort::radix_sort::radix_sort;
 use sort::selection_sort::{recursive_selection_sort, selection_sort, two_way_selection_sort};
 use sort::shell_sort::shell_sort;
-use sort::timsort::{shell_timsort, timsort};
+use sort::stats::{CountingAllocator, SortStats, SwapCounter};
+use sort::timsort::{natural_timsort, shell_timsort, timsort};
 use sort::util::{is_sorted, read_ints, show_brief};
 
-const SORTING_METHODS: [&str; 29] = [
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const SORTING_METHODS: [&str; 36] = [
     "binary-insertion-sort",
     "bottom-up-merge-sort",
     "bubble-sort",
@@ -38,10 +49,16 @@ const SORTING_METHODS: [&str; 29] = [
     "insertion-merge-sort",
     "insertion-quicksort",
     "insertion-sort",
+    "introsort",
     "iterative-quicksort",
     "merge-sort",
+    "natural-timsort",
+    "parallel-merge-sort",
+    "parallel-quicksort",
+    "parallel-sample-sort",
+    "pdqsort",
     "quicksort",
-    //"radix-sort" ,
+    "radix-sort",
     "recursive-bubble-sort",
     "recursive-insertion-sort",
     "recursive-selection-sort",
@@ -56,14 +73,15 @@ const SORTING_METHODS: [&str; 29] = [
     "two-way-selection-sort",
 ];
 
-fn sort_list(sort_method: &str, list: &mut [i32]) {
+/// 使用基于比较的排序算法, 返回 `false` 表示 `sort_method` 不是基于比较的排序算法.
+fn sort_generic<T>(sort_method: &str, list: &mut [T]) -> bool
+where
+    T: PartialOrd + Clone + Send + Sync + fmt::Debug,
+{
     match sort_method {
         "binary-insertion-sort" => binary_insertion_sort(list),
         "bottom-up-merge-sort" => bottom_up_merge_sort(list),
         "bubble-sort" => bubble_sort(list),
-        "bucket-sort" => bucket_sort(list),
-        "counting-sort" => counting_sort(list),
-        "counting-sort-with-map" => counting_sort_with_map(list),
         "gnome-sort" => gnome_sort(list),
         "head-quicksort" => head_quicksort(list),
         "heap-sort" => heap_sort(list),
@@ -72,15 +90,19 @@ fn sort_list(sort_method: &str, list: &mut [i32]) {
         "insertion-merge-sort" => insertion_merge_sort(list),
         "insertion-quicksort" => insertion_quicksort(list),
         "insertion-sort" => insertion_sort(list),
+        "introsort" => introsort(list),
         "iterative-quicksort" => iterative_quicksort(list),
         "merge-sort" => merge_sort(list),
+        "natural-timsort" => natural_timsort(list),
+        "parallel-merge-sort" => parallel_merge_sort(list, 0),
+        "parallel-quicksort" => parallel_quicksort(list, 0),
+        "parallel-sample-sort" => parallel_sample_sort(list, 0, DEFAULT_SEED),
+        "pdqsort" => pdqsort(list),
         "quicksort" => quicksort(list),
-        //"radix-sort" => radix_sort(list),
         "recursive-bubble-sort" => recursive_bubble_sort(list),
         "recursive-insertion-sort" => recursive_insertion_sort(list),
         "recursive-selection-sort" => recursive_selection_sort(list),
         "selection-sort" => selection_sort(list),
-        "shell-bucket-sort" => shell_bucket_sort(list),
         "shell-merge-sort" => shell_merge_sort(list),
         "shell-sort" => shell_sort(list),
         "shell-timsort" => shell_timsort(list),
@@ -88,11 +110,86 @@ fn sort_list(sort_method: &str, list: &mut [i32]) {
         "timsort" => timsort(list),
         "two-pointer-quicksort" => two_pointer_quicksort(list),
         "two-way-selection-sort" => two_way_selection_sort(list),
-        _ => print_sorting_methods(),
+        _ => return false,
+    }
+    true
+}
+
+/// 只适用于整数的排序算法.
+fn sort_ints(sort_method: &str, list: &mut [i32]) -> bool {
+    match sort_method {
+        "bucket-sort" => bucket_sort(list),
+        "counting-sort" => counting_sort(list),
+        "counting-sort-with-map" => counting_sort_with_map(list),
+        "radix-sort" => radix_sort(list),
+        "shell-bucket-sort" => shell_bucket_sort(list),
+        _ => return false,
+    }
+    true
+}
+
+fn sort_list(sort_method: &str, list: &mut [i32]) {
+    if !sort_generic(sort_method, list) && !sort_ints(sort_method, list) {
+        print_sorting_methods();
+    }
+}
+
+/// 统计排序算法的比较次数, 交换次数, 复制次数, 内存分配次数, 辅助内存峰值以及耗时.
+///
+/// 只适用于整数的排序算法无法统计比较, 交换和复制次数, 显示为 `-`.
+///
+/// 交换次数包括 `reverse()` 这类批量移动元素的操作, 复制次数是元素被克隆的次数,
+/// 对于使用辅助数组的算法, 它近似于元素移动的次数.
+fn print_stats(sort_methods: &[&str], list: &[i32]) {
+    println!(
+        "| {:<26} | {:>14} | {:>14} | {:>12} | {:>11} | {:>12} | {:>10} |",
+        "method", "comparisons", "swaps", "clones", "allocations", "peak bytes", "time (ms)"
+    );
+    println!(
+        "|{:-<28}|{:->16}|{:->16}|{:->14}|{:->13}|{:->14}|{:->12}|",
+        ":", ":", ":", ":", ":", ":", ":"
+    );
+
+    for &sort_method in sort_methods {
+        let stats = SortStats::new();
+        let mut counted = stats.wrap(list.iter().copied());
+        let mut ints = list.to_vec();
+
+        SwapCounter::reset();
+        let baseline = CountingAllocator::reset();
+        let start = Instant::now();
+        let is_generic = sort_generic(sort_method, &mut counted);
+        if !is_generic {
+            assert!(sort_ints(sort_method, &mut ints));
+        }
+        let elapsed = start.elapsed();
+        let alloc_stats = CountingAllocator::stats(baseline);
+
+        // 先读取计数器, 再检查结果, 否则检查时的比较也会被计入.
+        let (comparisons, swaps, clones) = if is_generic {
+            let counters = (
+                stats.comparisons().to_string(),
+                SwapCounter::count().to_string(),
+                stats.clones().to_string(),
+            );
+            let values: Vec<i32> = counted.iter().map(|item| *item.value()).collect();
+            assert!(is_sorted(&values));
+            counters
+        } else {
+            assert!(is_sorted(&ints));
+            ("-".to_owned(), "-".to_owned(), "-".to_owned())
+        };
+        println!(
+            "| {sort_method:<26} | {comparisons:>14} | {swaps:>14} | {clones:>12} | {:>11} | {:>12} | {:>10.3} |",
+            alloc_stats.allocations,
+            alloc_stats.peak_bytes,
+            elapsed.as_secs_f64() * 1000.0
+        );
     }
 }
 
 fn print_sorting_methods() -> ! {
+    eprintln!("Usage: total_sort [--stats] <sort-method>");
     eprintln!("Supported sorting methods:");
     for (index, name) in SORTING_METHODS.iter().enumerate() {
         println!("  {:2}: {name}", index + 1);
@@ -102,8 +199,28 @@ fn print_sorting_methods() -> ! {
 }
 
 fn main() {
-    let mut args = args();
+    let mut args = args().peekable();
     let _app_name = args.next();
+
+    // `total_sort --stats [sort-method]` 统计所有 (或者指定的) 排序算法.
+    if args.peek().map(String::as_str) == Some("--stats") {
+        let _flag = args.next();
+        let sort_methods: Vec<&str> = match args.next() {
+            None => SORTING_METHODS.to_vec(),
+            Some(sort_method) => match SORTING_METHODS.iter().find(|name| **name == sort_method) {
+                Some(name) => vec![*name],
+                None => {
+                    eprintln!("Unknown sorting method: {sort_method}");
+                    print_sorting_methods();
+                }
+            },
+        };
+        let list = read_ints();
+        println!("Length of list: {}", list.len());
+        print_stats(&sort_methods, &list);
+        return;
+    }
+
     let sort_method: String = match args.next() {
         None => print_sorting_methods(),
         Some(sort_method) => if !SORTING_METHODS.contains(&sort_method.as_str()) {
@@ -2,20 +2,33 @@
 // Use of this source is governed by General Public License that can be
 // found in the LICENSE file.
 
+use std::cmp::Ordering;
+
+use crate::sorter::{partial_compare, Sorter};
+use crate::stats::swap;
+
 /// 如果传入的数据是增序排好的, 那么只需要 `n-1` 次的比较, 以及 0 次的交换;
 /// 平珓情况以及最坏情况下, 使用 `n^2 / 2` 次比较以及 `n^2 / 2` 次交换.
 pub fn bubble_sort<T>(arr: &mut [T])
 where
     T: PartialOrd,
+{
+    bubble_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的冒泡排序.
+pub fn bubble_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     let len = arr.len();
     for i in 0..len {
         let mut swapped = false;
         // 以 (len - i - 1) 为分隔点, 左侧部分是无序的, 右侧部分是有序的
         for j in 0..(len - i - 1) {
-            if arr[j] > arr[j + 1] {
+            if compare(&arr[j], &arr[j + 1]) == Ordering::Greater {
                 swapped = true;
-                arr.swap(j, j + 1);
+                swap(arr, j, j + 1);
             }
         }
 
@@ -26,12 +39,45 @@ where
     }
 }
 
+/// 按照 `f` 提取出的键值进行冒泡排序.
+pub fn bubble_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    bubble_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
 /// 递归形式的冒泡排序算法.
 ///
 /// 与迭代形式的算法相比, 递归形式实现的算法, 并没有性能上的优势.
 pub fn recursive_bubble_sort<T>(list: &mut [T])
 where
     T: PartialOrd,
+{
+    recursive_bubble_sort_by(list, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的递归形式的冒泡排序.
+pub fn recursive_bubble_sort_by<T, F>(list: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    recursive_bubble_sort_helper(list, &mut compare);
+}
+
+/// 按照 `f` 提取出的键值进行递归形式的冒泡排序.
+pub fn recursive_bubble_sort_by_key<T, K, F>(list: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    recursive_bubble_sort_by(list, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+fn recursive_bubble_sort_helper<T, F>(list: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     let len = list.len();
     if len < 2 {
@@ -40,9 +86,9 @@ where
 
     let mut swapped = false;
     for j in 0..(len - 1) {
-        if list[j] > list[j + 1] {
+        if compare(&list[j], &list[j + 1]) == Ordering::Greater {
             swapped = true;
-            list.swap(j, j + 1);
+            swap(list, j, j + 1);
         }
     }
 
@@ -51,12 +97,43 @@ where
         return;
     }
 
-    recursive_bubble_sort(&mut list[..(len - 1)]);
+    recursive_bubble_sort_helper(&mut list[..(len - 1)], compare);
+}
+
+/// 冒泡排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BubbleSort;
+
+impl<T> Sorter<T> for BubbleSort {
+    fn name(&self) -> &'static str {
+        "bubble-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        bubble_sort_by(arr, compare);
+    }
+}
+
+/// 递归实现的冒泡排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RecursiveBubbleSort;
+
+impl<T> Sorter<T> for RecursiveBubbleSort {
+    fn name(&self) -> &'static str {
+        "recursive-bubble-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        recursive_bubble_sort_by(arr, compare);
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{bubble_sort, recursive_bubble_sort};
+    use super::{
+        bubble_sort, bubble_sort_by, bubble_sort_by_key, recursive_bubble_sort,
+        recursive_bubble_sort_by_key,
+    };
 
     #[test]
     fn test_bubble_sort() {
@@ -119,4 +196,19 @@ mod tests {
             ['A', 'E', 'E', 'I', 'N', 'O', 'Q', 'S', 'S', 'T', 'U', 'Y']
         );
     }
+
+    #[test]
+    fn test_bubble_sort_by() {
+        let mut list = [0, 5, 3, 2, 2];
+        bubble_sort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd')];
+        bubble_sort_by_key(&mut list, |pair| pair.0);
+        assert_eq!(list, [(1, 'b'), (1, 'd'), (3, 'a'), (3, 'c')]);
+
+        let mut list = [(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd')];
+        recursive_bubble_sort_by_key(&mut list, |pair| pair.0);
+        assert_eq!(list, [(1, 'b'), (1, 'd'), (3, 'a'), (3, 'c')]);
+    }
 }
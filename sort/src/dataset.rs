@@ -0,0 +1,435 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 可重现的测试数据集.
+//!
+//! 使用相同的种子 (seed) 总是生成相同的数据, 这样基准测试的结果才可以比较.
+//! 随机数生成器使用 `ChaCha8Rng`, 它的输出序列在不同的平台和版本之间保持不变;
+//! 而 `StdRng` 的算法可能随着 `rand` 的版本而改变, 不能用来生成需要保存下来的数据集.
+//! 生成的数据可以保存到文本文件中, 每行一个数值, 以 `#` 开头的行是注释.
+
+use std::f64::consts::PI;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use rand::distributions::uniform::SampleUniform;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// 基准测试默认使用的种子.
+pub const DEFAULT_SEED: u64 = 0x5eed_2024;
+
+/// 数据集中的数值类型, 包括所有的整数和浮点数.
+pub trait DataValue: Copy + PartialOrd + SampleUniform + fmt::Display + FromStr {
+    /// 把浮点数转换为该类型, 整数会四舍五入, 超出范围时取最大值或最小值.
+    fn from_f64(value: f64) -> Self;
+}
+
+macro_rules! impl_data_value_int {
+    ($($t:ty),*) => {
+        $(
+            impl DataValue for $t {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                #[inline]
+                fn from_f64(value: f64) -> Self {
+                    value.round() as Self
+                }
+            }
+        )*
+    };
+}
+
+impl_data_value_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl DataValue for f32 {
+    #[allow(clippy::cast_possible_truncation)]
+    #[inline]
+    fn from_f64(value: f64) -> Self {
+        value as Self
+    }
+}
+
+impl DataValue for f64 {
+    #[inline]
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+/// 数据的分布.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution<T> {
+    /// 在 `[min, max]` 范围内均匀分布.
+    Uniform { min: T, max: T },
+    /// 正态分布 (高斯分布).
+    Gaussian { mean: f64, std_dev: f64 },
+    /// 齐普夫分布 (Zipf), 取值为 `1..=ranks`, 取值 `k` 的概率与 `1 / k^exponent` 成正比.
+    ///
+    /// 少数几个值出现得非常频繁, 常用来模拟单词频率等真实数据.
+    Zipf { ranks: usize, exponent: f64 },
+    /// 基本有序, 由 `0..len` 经过交换得到, 恰好包含 `inversions` 个逆序对.
+    NearlySorted { inversions: usize },
+    /// 大量重复, 取值为 `0..distinct` 中的随机数.
+    DuplicateHeavy { distinct: usize },
+}
+
+/// `gen_dataset` 中使用的各种分布的名称.
+pub const DISTRIBUTION_NAMES: [&str; 5] = [
+    "duplicate-heavy",
+    "gaussian",
+    "nearly-sorted",
+    "uniform",
+    "zipf",
+];
+
+impl Distribution<i32> {
+    /// 根据名称返回一个整数分布, 参数使用 `datasets/` 目录中的数据集所用的默认值.
+    #[must_use]
+    pub fn by_name(name: &str, len: usize) -> Option<Self> {
+        let distribution = match name {
+            "duplicate-heavy" => Self::DuplicateHeavy { distinct: 16 },
+            "gaussian" => Self::Gaussian {
+                mean: 0.0,
+                std_dev: 10_000.0,
+            },
+            "nearly-sorted" => Self::NearlySorted {
+                inversions: len / 10,
+            },
+            "uniform" => Self::Uniform {
+                min: 0,
+                max: 1_000_000,
+            },
+            "zipf" => Self::Zipf {
+                ranks: 1000,
+                exponent: 1.0,
+            },
+            _ => return None,
+        };
+        Some(distribution)
+    }
+}
+
+/// 数据集生成器.
+#[derive(Debug, Clone)]
+pub struct DatasetGenerator {
+    rng: ChaCha8Rng,
+}
+
+impl DatasetGenerator {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// 按照分布 `distribution` 生成 `len` 个数值.
+    ///
+    /// # Panics
+    /// Raise panic if `min > max` in `Uniform`, `std_dev` is negative in `Gaussian`,
+    /// `ranks` is 0 in `Zipf`, or `distinct` is 0 in `DuplicateHeavy`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn generate<T: DataValue>(&mut self, distribution: &Distribution<T>, len: usize) -> Vec<T> {
+        match *distribution {
+            Distribution::Uniform { min, max } => {
+                assert!(min <= max, "Invalid range");
+                (0..len).map(|_| self.rng.gen_range(min..=max)).collect()
+            }
+            Distribution::Gaussian { mean, std_dev } => {
+                assert!(std_dev >= 0.0, "Invalid standard deviation");
+                (0..len)
+                    .map(|_| T::from_f64(self.standard_normal().mul_add(std_dev, mean)))
+                    .collect()
+            }
+            Distribution::Zipf { ranks, exponent } => {
+                assert!(ranks > 0, "Zipf distribution needs at least one rank");
+                let cdf = zipf_cdf(ranks, exponent);
+                (0..len)
+                    .map(|_| {
+                        let u: f64 = self.rng.gen();
+                        let rank = cdf.partition_point(|&p| p < u).min(ranks - 1) + 1;
+                        T::from_f64(rank as f64)
+                    })
+                    .collect()
+            }
+            Distribution::NearlySorted { inversions } => {
+                let mut order: Vec<usize> = (0..len).collect();
+                self.add_inversions(&mut order, inversions);
+                order.into_iter().map(|i| T::from_f64(i as f64)).collect()
+            }
+            Distribution::DuplicateHeavy { distinct } => {
+                assert!(distinct > 0, "Invalid number of distinct values");
+                (0..len)
+                    .map(|_| T::from_f64(self.rng.gen_range(0..distinct) as f64))
+                    .collect()
+            }
+        }
+    }
+
+    /// 使用 Box-Muller 变换生成标准正态分布的随机数.
+    fn standard_normal(&mut self) -> f64 {
+        // `gen()` 返回 `[0, 1)` 中的数, 这里需要 `(0, 1]`, 以避免 `ln(0)`.
+        let u1: f64 = 1.0 - self.rng.gen::<f64>();
+        let u2: f64 = self.rng.gen();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+    }
+
+    /// 交换相邻的一对升序元素, 每次交换恰好增加一个逆序对.
+    ///
+    /// 逆序对的个数最多为 `len * (len - 1) / 2`, 超出时就得到完全逆序的数组.
+    fn add_inversions(&mut self, arr: &mut [usize], inversions: usize) {
+        let len = arr.len();
+        if len < 2 {
+            return;
+        }
+        let max_inversions = len * (len - 1) / 2;
+        for _ in 0..inversions.min(max_inversions) {
+            // 从随机的位置开始, 向后寻找一对升序的相邻元素, 它一定存在.
+            let start = self.rng.gen_range(0..len - 1);
+            let index = (start..len - 1)
+                .chain(0..start)
+                .find(|&i| arr[i] < arr[i + 1])
+                .unwrap_or(start);
+            arr.swap(index, index + 1);
+        }
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn zipf_cdf(ranks: usize, exponent: f64) -> Vec<f64> {
+    let mut cdf: Vec<f64> = Vec::with_capacity(ranks);
+    let mut sum = 0.0;
+    for k in 1..=ranks {
+        sum += 1.0 / (k as f64).powf(exponent);
+        cdf.push(sum);
+    }
+    for p in &mut cdf {
+        *p /= sum;
+    }
+    cdf
+}
+
+/// 使用种子 `seed`, 按照分布 `distribution` 生成 `len` 个数值.
+///
+/// # Panics
+/// Raise panic if parameters of `distribution` are invalid.
+#[must_use]
+pub fn generate<T: DataValue>(distribution: &Distribution<T>, len: usize, seed: u64) -> Vec<T> {
+    DatasetGenerator::new(seed).generate(distribution, len)
+}
+
+/// 把数据集写入 `writer`, 每行一个数值.
+///
+/// # Errors
+/// Returns error if failed to write.
+pub fn save<T, W>(data: &[T], writer: W) -> io::Result<()>
+where
+    T: fmt::Display,
+    W: Write,
+{
+    let mut writer = BufWriter::new(writer);
+    for value in data {
+        writeln!(writer, "{value}")?;
+    }
+    writer.flush()
+}
+
+/// 从 `reader` 读取数据集, 忽略空行以及以 `#` 开头的注释行.
+///
+/// # Errors
+/// Returns error if failed to read or invalid value found.
+pub fn load<T, R>(reader: R) -> io::Result<Vec<T>>
+where
+    T: FromStr,
+    R: Read,
+{
+    let mut data = Vec::new();
+    for (index, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let value = line.parse::<T>().map_err(|_err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid value at line {}: {line}", index + 1),
+            )
+        })?;
+        data.push(value);
+    }
+    Ok(data)
+}
+
+/// 把数据集保存到文件.
+///
+/// # Errors
+/// Returns error if failed to create or write file.
+pub fn save_to_file<T, P>(data: &[T], path: P) -> io::Result<()>
+where
+    T: fmt::Display,
+    P: AsRef<Path>,
+{
+    save(data, File::create(path)?)
+}
+
+/// 从文件中读取数据集.
+///
+/// # Errors
+/// Returns error if failed to open or read file, or invalid value found.
+pub fn load_from_file<T, P>(path: P) -> io::Result<Vec<T>>
+where
+    T: FromStr,
+    P: AsRef<Path>,
+{
+    load(File::open(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        generate, load, load_from_file, save, Distribution, DEFAULT_SEED, DISTRIBUTION_NAMES,
+    };
+
+    fn count_inversions(arr: &[i32]) -> usize {
+        let mut count = 0;
+        for i in 0..arr.len() {
+            for j in i + 1..arr.len() {
+                if arr[i] > arr[j] {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let distribution = Distribution::Uniform {
+            min: -100,
+            max: 100,
+        };
+        let first: Vec<i32> = generate(&distribution, 1000, DEFAULT_SEED);
+        let second: Vec<i32> = generate(&distribution, 1000, DEFAULT_SEED);
+        assert_eq!(first, second);
+        let third: Vec<i32> = generate(&distribution, 1000, DEFAULT_SEED + 1);
+        assert_ne!(first, third);
+    }
+
+    #[test]
+    fn test_uniform() {
+        let data: Vec<u16> = generate(&Distribution::Uniform { min: 10, max: 20 }, 10_000, 1);
+        assert!(data.iter().all(|&num| (10..=20).contains(&num)));
+        // 每个值都应该出现, 而不是都集中在边界上.
+        for num in 10..=20 {
+            let count = data.iter().filter(|&&value| value == num).count();
+            assert!(count > 500, "{num} appears {count} times");
+        }
+
+        let data: Vec<f64> = generate(&Distribution::Uniform { min: 0.0, max: 1.0 }, 1000, 1);
+        assert!(data.iter().all(|&num| (0.0..=1.0).contains(&num)));
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn test_gaussian() {
+        let len = 10_000;
+        let data: Vec<f64> = generate(
+            &Distribution::Gaussian {
+                mean: 50.0,
+                std_dev: 5.0,
+            },
+            len,
+            2,
+        );
+        let mean = data.iter().sum::<f64>() / len as f64;
+        let variance = data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / len as f64;
+        assert!((mean - 50.0).abs() < 0.5);
+        assert!((variance.sqrt() - 5.0).abs() < 0.5);
+
+        let data: Vec<i16> = generate(
+            &Distribution::Gaussian {
+                mean: 0.0,
+                std_dev: 3.0,
+            },
+            len,
+            2,
+        );
+        assert!(data.iter().all(|num| num.abs() < 30));
+    }
+
+    #[test]
+    fn test_zipf() {
+        let data: Vec<u32> = generate(
+            &Distribution::Zipf {
+                ranks: 100,
+                exponent: 1.0,
+            },
+            10_000,
+            3,
+        );
+        assert!(data.iter().all(|&num| (1..=100).contains(&num)));
+        let ones = data.iter().filter(|&&num| num == 1).count();
+        let twos = data.iter().filter(|&&num| num == 2).count();
+        let hundreds = data.iter().filter(|&&num| num == 100).count();
+        assert!(ones > twos && twos > hundreds);
+    }
+
+    #[test]
+    fn test_nearly_sorted() {
+        for inversions in [0, 1, 10, 100] {
+            let data: Vec<i32> = generate(&Distribution::NearlySorted { inversions }, 200, 4);
+            assert_eq!(count_inversions(&data), inversions);
+        }
+        // 超过最大值时, 得到完全逆序的数组.
+        let data: Vec<i32> = generate(&Distribution::NearlySorted { inversions: 100 }, 10, 4);
+        assert_eq!(data, (0..10).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_duplicate_heavy() {
+        let mut data: Vec<i64> = generate(&Distribution::DuplicateHeavy { distinct: 5 }, 1000, 5);
+        data.sort_unstable();
+        data.dedup();
+        assert_eq!(data, [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let data: Vec<f32> = generate(
+            &Distribution::Uniform {
+                min: -1.0,
+                max: 1.0,
+            },
+            100,
+            6,
+        );
+        let mut buf = Vec::new();
+        save(&data, &mut buf).unwrap();
+        let loaded: Vec<f32> = load(&buf[..]).unwrap();
+        assert_eq!(loaded, data);
+
+        let loaded: Vec<i32> = load(&b"# header\n1\n\n-2\n"[..]).unwrap();
+        assert_eq!(loaded, [1, -2]);
+        assert!(load::<i32, _>(&b"1\nx\n"[..]).is_err());
+    }
+
+    #[test]
+    fn test_checked_in_datasets() {
+        // `datasets/` 目录中的文件是用 `gen_dataset <name> 1000` 生成的.
+        for name in DISTRIBUTION_NAMES {
+            let path = format!("{}/datasets/{name}-1k.txt", env!("CARGO_MANIFEST_DIR"));
+            let loaded: Vec<i32> = load_from_file(&path).unwrap();
+            let distribution = Distribution::by_name(name, 1000).unwrap();
+            assert_eq!(
+                loaded,
+                generate(&distribution, 1000, DEFAULT_SEED),
+                "{path}"
+            );
+        }
+    }
+}
@@ -2,6 +2,11 @@
 // Use of this source is governed by General Public License that can be
 // found in the LICENSE file.
 
+use std::cmp::Ordering;
+
+use crate::sorter::{partial_compare, Sorter};
+use crate::stats::swap;
+
 /// This sorting algorithm sorts an array using the principle of bubble sort,
 /// but does it both from left to right and right to left.
 ///
@@ -9,27 +14,61 @@
 pub fn double_sort<T>(arr: &mut [T])
 where
     T: PartialOrd,
+{
+    double_sort_by(arr, partial_compare);
+}
+
+/// Double sort with a comparator function.
+pub fn double_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     let len = arr.len();
+    if len < 2 {
+        return;
+    }
 
     for _ in 0..=(len - 1) / 2 {
         // No need to traverse to end of list
         for i in 0..(len - 1) {
-            if arr[i + 1] < arr[i] {
+            if compare(&arr[i + 1], &arr[i]) == Ordering::Less {
                 // Apply bubble sort from left to right (forward)
-                arr.swap(i + 1, i);
+                swap(arr, i + 1, i);
             }
-            if arr[len - 1 - i] < arr[len - 2 - i] {
+            if compare(&arr[len - 1 - i], &arr[len - 2 - i]) == Ordering::Less {
                 // Apply bubble sort from right to left (backward)
-                arr.swap(len - 1 - i, len - 2 - i);
+                swap(arr, len - 1 - i, len - 2 - i);
             }
         }
     }
 }
 
+/// Double sort with a key extraction function.
+pub fn double_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    double_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+/// Double sort.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DoubleSort;
+
+impl<T> Sorter<T> for DoubleSort {
+    fn name(&self) -> &'static str {
+        "double-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        double_sort_by(arr, compare);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::double_sort;
+    use super::{double_sort, double_sort_by, double_sort_by_key};
 
     #[test]
     fn test_double_sort() {
@@ -61,4 +100,18 @@ mod tests {
             ['A', 'E', 'E', 'I', 'N', 'O', 'Q', 'S', 'S', 'T', 'U', 'Y']
         );
     }
+
+    #[test]
+    fn test_double_sort_by() {
+        let mut list = [0, 5, 3, 2, 2];
+        double_sort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd')];
+        double_sort_by_key(&mut list, |pair| pair.0);
+        assert_eq!(list[0].0, 1);
+        assert_eq!(list[1].0, 1);
+        assert_eq!(list[2].0, 3);
+        assert_eq!(list[3].0, 3);
+    }
 }
@@ -0,0 +1,647 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 外部排序 (external sort), 用于对超出内存容量的数据进行排序.
+//!
+//! 分为两个阶段:
+//! 1. 生成顺串 (run): 不断读入记录, 直到占用的内存超出预算, 用内存排序算法排序后写入临时文件;
+//! 2. 多路归并 (multiway merge): 使用败者树 (loser tree) 把所有的顺串归并成一个有序的输出.
+//!    如果顺串的个数超过了归并的路数, 就分多趟进行归并.
+//!
+//! 记录的格式由 [`RecordFormat`] 描述, 目前支持定长的二进制记录和以换行符分隔的文本行.
+
+use std::cmp::Ordering;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+use crate::sorter::{partial_compare, Sorter};
+use crate::timsort::NaturalTimSort;
+
+/// 默认的内存预算, 64 MiB.
+pub const DEFAULT_MEMORY_BUDGET: usize = 64 << 20;
+
+/// 默认的归并路数, 也就是同时打开的临时文件数目的上限.
+pub const DEFAULT_FAN_IN: usize = 64;
+
+/// 记录的存储格式.
+pub trait RecordFormat {
+    type Record;
+
+    /// 读取下一条记录, 读到文件末尾时返回 `Ok(None)`.
+    ///
+    /// # Errors
+    /// 读取失败或者记录不完整时返回错误.
+    fn read_record<R: BufRead>(&self, reader: &mut R) -> io::Result<Option<Self::Record>>;
+
+    /// 写入一条记录.
+    ///
+    /// # Errors
+    /// 写入失败时返回错误.
+    fn write_record<W: Write>(&self, writer: &mut W, record: &Self::Record) -> io::Result<()>;
+
+    /// 记录在内存中大约占用的字节数, 用于计算内存预算.
+    fn memory_size(&self, record: &Self::Record) -> usize;
+}
+
+/// 定长的二进制记录, 每条记录都是 `width` 个字节.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedWidthRecords {
+    width: usize,
+}
+
+impl FixedWidthRecords {
+    /// # Panics
+    /// Raise panic if `width` is 0.
+    #[must_use]
+    pub const fn new(width: usize) -> Self {
+        assert!(width > 0, "Record width must be positive");
+        Self { width }
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+}
+
+impl RecordFormat for FixedWidthRecords {
+    type Record = Vec<u8>;
+
+    fn read_record<R: BufRead>(&self, reader: &mut R) -> io::Result<Option<Self::Record>> {
+        let mut record = vec![0; self.width];
+        let mut filled = 0;
+        while filled < self.width {
+            match reader.read(&mut record[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        match filled {
+            0 => Ok(None),
+            n if n == self.width => Ok(Some(record)),
+            n => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("Incomplete record: expected {} bytes, got {n}", self.width),
+            )),
+        }
+    }
+
+    fn write_record<W: Write>(&self, writer: &mut W, record: &Self::Record) -> io::Result<()> {
+        writer.write_all(record)
+    }
+
+    fn memory_size(&self, record: &Self::Record) -> usize {
+        mem::size_of::<Self::Record>() + record.capacity()
+    }
+}
+
+/// 以换行符 `\n` 分隔的文本行, 记录中不包含换行符.
+///
+/// 最后一行即使没有换行符也会被当作一条记录, 输出时每条记录后面都会加上换行符.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LineRecords;
+
+impl RecordFormat for LineRecords {
+    type Record = Vec<u8>;
+
+    fn read_record<R: BufRead>(&self, reader: &mut R) -> io::Result<Option<Self::Record>> {
+        let mut line = Vec::new();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(None);
+        }
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+        Ok(Some(line))
+    }
+
+    fn write_record<W: Write>(&self, writer: &mut W, record: &Self::Record) -> io::Result<()> {
+        writer.write_all(record)?;
+        writer.write_all(b"\n")
+    }
+
+    fn memory_size(&self, record: &Self::Record) -> usize {
+        mem::size_of::<Self::Record>() + record.capacity()
+    }
+}
+
+/// 一次外部排序的统计信息.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExternalSortStats {
+    /// 记录总数.
+    pub records: usize,
+    /// 生成的初始顺串的个数, 为 1 时表示所有记录都是在内存中排序的.
+    pub runs: usize,
+    /// 归并的趟数.
+    pub merge_passes: usize,
+}
+
+/// 外部排序器.
+///
+/// ```
+/// use sort::external_sort::{ExternalSorter, LineRecords};
+///
+/// let input = b"pear\napple\nfig\n";
+/// let mut output = Vec::new();
+/// ExternalSorter::new()
+///     .memory_budget(1024)
+///     .sort(&input[..], &mut output, &LineRecords)
+///     .unwrap();
+/// assert_eq!(output, b"apple\nfig\npear\n");
+/// ```
+pub struct ExternalSorter<'a, T> {
+    memory_budget: usize,
+    fan_in: usize,
+    temp_dir: PathBuf,
+    sorter: &'a dyn Sorter<T>,
+}
+
+impl<T: Clone> Default for ExternalSorter<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> ExternalSorter<'_, T> {
+    /// 使用默认的内存预算和归并路数, 临时文件存放在系统的临时目录中,
+    /// 顺串使用稳定的 [`NaturalTimSort`] 排序.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+            fan_in: DEFAULT_FAN_IN,
+            temp_dir: std::env::temp_dir(),
+            sorter: &NaturalTimSort,
+        }
+    }
+}
+
+impl<'a, T> ExternalSorter<'a, T> {
+    /// 设置内存预算, 单位是字节.
+    #[must_use]
+    pub const fn memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = bytes;
+        self
+    }
+
+    /// 设置归并路数, 最小为 2.
+    #[must_use]
+    pub fn fan_in(mut self, fan_in: usize) -> Self {
+        self.fan_in = fan_in.max(2);
+        self
+    }
+
+    /// 设置存放临时文件的目录.
+    #[must_use]
+    pub fn temp_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.temp_dir = dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// 设置生成顺串时使用的内存排序算法.
+    ///
+    /// 只有当它是稳定排序时, 整个外部排序才是稳定的.
+    #[must_use]
+    pub fn sorter(mut self, sorter: &'a dyn Sorter<T>) -> Self {
+        self.sorter = sorter;
+        self
+    }
+
+    /// 按照增序排序.
+    ///
+    /// # Errors
+    /// 读写输入输出或者临时文件失败时返回错误.
+    pub fn sort<R, W, P>(&self, reader: R, writer: W, format: &P) -> io::Result<ExternalSortStats>
+    where
+        R: Read,
+        W: Write,
+        P: RecordFormat<Record = T>,
+        T: PartialOrd,
+    {
+        self.sort_by(reader, writer, format, partial_compare)
+    }
+
+    /// 按照 `f` 提取出的键值进行增序排序.
+    ///
+    /// # Errors
+    /// 读写输入输出或者临时文件失败时返回错误.
+    pub fn sort_by_key<R, W, P, K, F>(
+        &self,
+        reader: R,
+        writer: W,
+        format: &P,
+        mut f: F,
+    ) -> io::Result<ExternalSortStats>
+    where
+        R: Read,
+        W: Write,
+        P: RecordFormat<Record = T>,
+        K: PartialOrd,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(reader, writer, format, |a, b| partial_compare(&f(a), &f(b)))
+    }
+
+    /// 使用比较函数 `compare` 进行排序.
+    ///
+    /// # Errors
+    /// 读写输入输出或者临时文件失败时返回错误.
+    pub fn sort_by<R, W, P, F>(
+        &self,
+        reader: R,
+        writer: W,
+        format: &P,
+        mut compare: F,
+    ) -> io::Result<ExternalSortStats>
+    where
+        R: Read,
+        W: Write,
+        P: RecordFormat<Record = T>,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut reader = BufReader::new(reader);
+        let mut writer = BufWriter::new(writer);
+        let mut stats = ExternalSortStats::default();
+
+        // 第一阶段, 生成顺串.
+        let mut runs: Vec<TempRun> = Vec::new();
+        let mut buffer: Vec<T> = Vec::new();
+        let mut used: usize = 0;
+        while let Some(record) = format.read_record(&mut reader)? {
+            let size = format.memory_size(&record);
+            if !buffer.is_empty() && used + size > self.memory_budget {
+                self.sorter.sort_by(&mut buffer, &mut compare);
+                runs.push(self.write_run(&buffer, format)?);
+                buffer.clear();
+                used = 0;
+            }
+            used += size;
+            stats.records += 1;
+            buffer.push(record);
+        }
+        self.sorter.sort_by(&mut buffer, &mut compare);
+
+        // 所有记录都能放到内存里, 不需要临时文件.
+        if runs.is_empty() {
+            stats.runs = 1;
+            for record in &buffer {
+                format.write_record(&mut writer, record)?;
+            }
+            writer.flush()?;
+            return Ok(stats);
+        }
+        if !buffer.is_empty() {
+            runs.push(self.write_run(&buffer, format)?);
+        }
+        drop(buffer);
+        stats.runs = runs.len();
+
+        // 第二阶段, 多路归并.
+        // 每一趟都按顺序把相邻的 `fan_in` 个顺串归并成一个, 这样可以保持稳定性.
+        while runs.len() > self.fan_in {
+            let mut merged = Vec::with_capacity(runs.len().div_ceil(self.fan_in));
+            let mut rest = runs.into_iter();
+            loop {
+                let group: Vec<TempRun> = rest.by_ref().take(self.fan_in).collect();
+                if group.len() <= 1 {
+                    merged.extend(group);
+                    break;
+                }
+                let run = self.create_run()?;
+                let mut run_writer = BufWriter::new(run.open_write()?);
+                merge_runs(&group, &mut run_writer, format, &mut compare)?;
+                run_writer.flush()?;
+                merged.push(run);
+            }
+            runs = merged;
+            stats.merge_passes += 1;
+        }
+
+        merge_runs(&runs, &mut writer, format, &mut compare)?;
+        writer.flush()?;
+        stats.merge_passes += 1;
+        Ok(stats)
+    }
+
+    fn create_run(&self) -> io::Result<TempRun> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        loop {
+            let id = COUNTER.fetch_add(1, AtomicOrdering::Relaxed);
+            let path = self
+                .temp_dir
+                .join(format!("external-sort-{}-{id}.run", process::id()));
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_file) => return Ok(TempRun { path }),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn write_run<P>(&self, records: &[T], format: &P) -> io::Result<TempRun>
+    where
+        P: RecordFormat<Record = T>,
+    {
+        let run = self.create_run()?;
+        let mut writer = BufWriter::new(run.open_write()?);
+        for record in records {
+            format.write_record(&mut writer, record)?;
+        }
+        writer.flush()?;
+        Ok(run)
+    }
+}
+
+/// 保存在临时文件中的顺串, 离开作用域时删除该文件.
+struct TempRun {
+    path: PathBuf,
+}
+
+impl TempRun {
+    fn open_write(&self) -> io::Result<File> {
+        OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .open(&self.path)
+    }
+
+    fn open_read(&self) -> io::Result<BufReader<File>> {
+        File::open(&self.path).map(BufReader::new)
+    }
+}
+
+impl Drop for TempRun {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// 使用败者树把多个顺串归并到 `writer` 中.
+fn merge_runs<T, W, P, F>(
+    runs: &[TempRun],
+    writer: &mut W,
+    format: &P,
+    compare: &mut F,
+) -> io::Result<()>
+where
+    W: Write,
+    P: RecordFormat<Record = T>,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut readers = runs
+        .iter()
+        .map(TempRun::open_read)
+        .collect::<io::Result<Vec<_>>>()?;
+    let mut heads = readers
+        .iter_mut()
+        .map(|reader| format.read_record(reader))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let mut tree = LoserTree::new(&heads, compare);
+    while let Some(record) = heads[tree.winner()].take() {
+        let winner = tree.winner();
+        format.write_record(writer, &record)?;
+        heads[winner] = format.read_record(&mut readers[winner])?;
+        tree.replay(&heads, compare);
+    }
+    Ok(())
+}
+
+/// 败者树.
+///
+/// 它是一棵完全二叉树, `k` 个叶子节点 `k..2k` 对应 `k` 路输入, 内部节点 `1..k` 记录了
+/// 在该节点比赛中失败的输入, `tree[0]` 记录最终的胜者. 每次取出胜者之后,
+/// 只需要沿着它的叶子节点到根节点的路径重新比赛, 比较次数是 `O(log(k))`.
+///
+/// 已经读完的输入视为无穷大; 两个输入的记录相等时, 序号较小的胜出, 以保持稳定性.
+struct LoserTree {
+    tree: Vec<usize>,
+}
+
+impl LoserTree {
+    fn new<T, F>(heads: &[Option<T>], compare: &mut F) -> Self
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut tree = Self {
+            tree: vec![0; heads.len().max(1)],
+        };
+        if heads.len() > 1 {
+            tree.tree[0] = tree.build(1, heads, compare);
+        }
+        tree
+    }
+
+    fn build<T, F>(&mut self, node: usize, heads: &[Option<T>], compare: &mut F) -> usize
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let k = heads.len();
+        if node >= k {
+            return node - k;
+        }
+        let left = self.build(2 * node, heads, compare);
+        let right = self.build(2 * node + 1, heads, compare);
+        if Self::beats(left, right, heads, compare) {
+            self.tree[node] = right;
+            left
+        } else {
+            self.tree[node] = left;
+            right
+        }
+    }
+
+    fn winner(&self) -> usize {
+        self.tree[0]
+    }
+
+    /// 胜者的输入更新之后, 重新进行比赛.
+    fn replay<T, F>(&mut self, heads: &[Option<T>], compare: &mut F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut winner = self.tree[0];
+        let leaf = winner + heads.len();
+        let mut node = leaf / 2;
+        while node > 0 {
+            if Self::beats(self.tree[node], winner, heads, compare) {
+                mem::swap(&mut self.tree[node], &mut winner);
+            }
+            node /= 2;
+        }
+        self.tree[0] = winner;
+    }
+
+    fn beats<T, F>(a: usize, b: usize, heads: &[Option<T>], compare: &mut F) -> bool
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        match (&heads[a], &heads[b]) {
+            (Some(x), Some(y)) => match compare(x, y) {
+                Ordering::Less => true,
+                Ordering::Equal => a < b,
+                Ordering::Greater => false,
+            },
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => a < b,
+        }
+    }
+}
+
+/// 对以换行符分隔的文本行进行排序.
+///
+/// # Errors
+/// 读写输入输出或者临时文件失败时返回错误.
+pub fn sort_lines<R, W>(reader: R, writer: W, memory_budget: usize) -> io::Result<ExternalSortStats>
+where
+    R: Read,
+    W: Write,
+{
+    ExternalSorter::new()
+        .memory_budget(memory_budget)
+        .sort(reader, writer, &LineRecords)
+}
+
+/// 对每条 `width` 字节的定长二进制记录按字节序进行排序.
+///
+/// # Errors
+/// 读写输入输出或者临时文件失败, 或者最后一条记录不完整时返回错误.
+///
+/// # Panics
+/// Raise panic if `width` is 0.
+pub fn sort_fixed_width<R, W>(
+    reader: R,
+    writer: W,
+    width: usize,
+    memory_budget: usize,
+) -> io::Result<ExternalSortStats>
+where
+    R: Read,
+    W: Write,
+{
+    ExternalSorter::new().memory_budget(memory_budget).sort(
+        reader,
+        writer,
+        &FixedWidthRecords::new(width),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sort_fixed_width, sort_lines, ExternalSorter, FixedWidthRecords, LineRecords};
+    use crate::pdqsort::PdqSort;
+
+    fn random_numbers(len: usize) -> Vec<u32> {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+        (0..len).map(|_| rng.gen_range(0..1000)).collect()
+    }
+
+    #[test]
+    fn test_sort_lines_in_memory() {
+        let input = b"pear\napple\n\nfig";
+        let mut output = Vec::new();
+        let stats = sort_lines(&input[..], &mut output, 1 << 20).unwrap();
+        assert_eq!(output, b"\napple\nfig\npear\n");
+        assert_eq!(stats.records, 4);
+        assert_eq!(stats.runs, 1);
+        assert_eq!(stats.merge_passes, 0);
+
+        let mut output = Vec::new();
+        let stats = sort_lines(&b""[..], &mut output, 1 << 20).unwrap();
+        assert!(output.is_empty());
+        assert_eq!(stats.records, 0);
+    }
+
+    #[test]
+    fn test_sort_lines_with_runs() {
+        let numbers = random_numbers(5000);
+        let mut expected: Vec<String> = numbers.iter().map(|num| format!("{num:04}")).collect();
+        let input: String = expected.join("\n");
+        expected.sort();
+
+        let mut output = Vec::new();
+        let stats = ExternalSorter::new()
+            .memory_budget(4096)
+            .fan_in(4)
+            .sort(input.as_bytes(), &mut output, &LineRecords)
+            .unwrap();
+        assert_eq!(stats.records, numbers.len());
+        assert!(stats.runs > 16);
+        assert!(stats.merge_passes > 1);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.lines().eq(expected.iter().map(String::as_str)));
+    }
+
+    #[test]
+    fn test_sort_fixed_width() {
+        let numbers = random_numbers(3000);
+        let input: Vec<u8> = numbers.iter().flat_map(|num| num.to_be_bytes()).collect();
+        let mut expected = numbers;
+        expected.sort_unstable();
+
+        let mut output = Vec::new();
+        let stats = sort_fixed_width(&input[..], &mut output, 4, 2048).unwrap();
+        assert!(stats.runs > 1);
+        let result: Vec<u32> = output
+            .chunks_exact(4)
+            .map(|chunk| u32::from_be_bytes(chunk.try_into().unwrap()))
+            .collect();
+        assert_eq!(result, expected);
+
+        // 最后一条记录不完整.
+        let mut output = Vec::new();
+        assert!(sort_fixed_width(&input[..7], &mut output, 4, 2048).is_err());
+    }
+
+    #[test]
+    fn test_external_sort_stable() {
+        // 每条记录 8 个字节, 前 4 个字节是键值, 后 4 个字节是原始位置.
+        let numbers = random_numbers(2000);
+        let input: Vec<u8> = numbers
+            .iter()
+            .zip(0_u32..)
+            .flat_map(|(num, index)| {
+                let mut record = (num % 10).to_be_bytes().to_vec();
+                record.extend_from_slice(&index.to_be_bytes());
+                record
+            })
+            .collect();
+        let mut expected: Vec<Vec<u8>> = input.chunks(8).map(<[u8]>::to_vec).collect();
+        expected.sort_by(|a, b| a[..4].cmp(&b[..4]));
+
+        let mut output = Vec::new();
+        ExternalSorter::new()
+            .memory_budget(1024)
+            .fan_in(3)
+            .sort_by_key(
+                &input[..],
+                &mut output,
+                &FixedWidthRecords::new(8),
+                |record| record[..4].to_vec(),
+            )
+            .unwrap();
+        assert_eq!(output, expected.concat());
+    }
+
+    #[test]
+    fn test_external_sort_with_sorter() {
+        let input = b"3\n1\n2\n5\n4\n";
+        let mut output = Vec::new();
+        ExternalSorter::new()
+            .memory_budget(64)
+            .sorter(&PdqSort)
+            .sort_by(&input[..], &mut output, &LineRecords, |a, b| b.cmp(a))
+            .unwrap();
+        assert_eq!(output, b"5\n4\n3\n2\n1\n");
+    }
+}
@@ -2,6 +2,11 @@
 // Use of this source is governed by General Public License that can be
 // found in the LICENSE file.
 
+use std::cmp::Ordering;
+
+use crate::sorter::{partial_compare, Sorter};
+use crate::stats::swap;
+
 /// Gnome sort is a variation of the insertion sort sorting algorithm
 /// that does not use nested loops.
 ///
@@ -9,23 +14,54 @@
 pub fn gnome_sort<T>(arr: &mut [T])
 where
     T: PartialOrd,
+{
+    gnome_sort_by(arr, partial_compare);
+}
+
+/// Gnome sort with a comparator function.
+pub fn gnome_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     let mut index = 0;
     while index < arr.len() {
         // 当前元素比左侧元素大, 是有序的
-        if index == 0 || arr[index] >= arr[index - 1] {
+        if index == 0 || compare(&arr[index], &arr[index - 1]) != Ordering::Less {
             index += 1;
         } else {
             // 当前元素比左侧元素小, 交换它们
-            arr.swap(index, index - 1);
+            swap(arr, index, index - 1);
             index -= 1;
         }
     }
 }
 
+/// Gnome sort with a key extraction function.
+pub fn gnome_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    gnome_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+/// Gnome sort.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GnomeSort;
+
+impl<T> Sorter<T> for GnomeSort {
+    fn name(&self) -> &'static str {
+        "gnome-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        gnome_sort_by(arr, compare);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::gnome_sort;
+    use super::{gnome_sort, gnome_sort_by, gnome_sort_by_key};
 
     #[test]
     fn test_gnome_sort() {
@@ -57,4 +93,15 @@ mod tests {
             ['A', 'E', 'E', 'I', 'N', 'O', 'Q', 'S', 'S', 'T', 'U', 'Y']
         );
     }
+
+    #[test]
+    fn test_gnome_sort_by() {
+        let mut list = [0, 5, 3, 2, 2];
+        gnome_sort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd')];
+        gnome_sort_by_key(&mut list, |pair| pair.0);
+        assert_eq!(list, [(1, 'b'), (1, 'd'), (3, 'a'), (3, 'c')]);
+    }
 }
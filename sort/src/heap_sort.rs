@@ -2,34 +2,62 @@
 // Use of this source is governed by General Public License that can be found
 // in the LICENSE file.
 
+use std::cmp::Ordering;
+
+use crate::sorter::{partial_compare, Sorter};
+use crate::stats::swap;
+
 pub fn heap_sort<T: PartialOrd>(arr: &mut [T]) {
+    heap_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的堆排序.
+pub fn heap_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     let max_heap = arr;
+    let len = max_heap.len();
+    if len < 2 {
+        return;
+    }
 
     // 建立初始堆.
     // 从最后一个非叶节点开始, 进行下移操作.
-    let len = max_heap.len();
     let parent = (len - 1) / 2;
     for i in (0..=parent).rev() {
-        shift_down(max_heap, i, len);
+        shift_down(max_heap, i, len, &mut compare);
     }
 
     // 每个循环, 将最大元素下沉到数组尾部, 每次循环, 数组长度减少1,
     // 直到最后只剩下 1 个元素.
     for i in (0..len).rev() {
         // 交换根节点与当前堆的最后一个节点, 把最大的元素放到数组尾部.
-        max_heap.swap(0, i);
+        swap(max_heap, 0, i);
 
         // 重新调整堆顶, 从根节点开始, 进行下移操作.
         // 这里, 忽略了刚刚的最大的那个元素.
-        shift_down(max_heap, 0, i);
+        shift_down(max_heap, 0, i, &mut compare);
     }
 }
 
+/// 按照 `f` 提取出的键值进行堆排序.
+pub fn heap_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    heap_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
 // 将该节点与子节点进行比较, 如果比子节点大, 就让它跟较大的那个子节点交换,
 // 一直重复这个过 程, 直到找到合适的位置.
 //
 // 时间复杂度是 O(log(n)), 是树的高度.
-fn shift_down<T: PartialOrd>(heap: &mut [T], mut pos: usize, len: usize) {
+fn shift_down<T, F>(heap: &mut [T], mut pos: usize, len: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     while pos * 2 + 1 < len {
         let left = pos * 2 + 1;
         let right = pos * 2 + 2;
@@ -37,7 +65,7 @@ fn shift_down<T: PartialOrd>(heap: &mut [T], mut pos: usize, len: usize) {
         let larger = if right >= len {
             // 右子树不存在, 它超出了数组的范围.
             left
-        } else if heap[left] >= heap[right] {
+        } else if compare(&heap[left], &heap[right]) != Ordering::Less {
             // 左侧子树较大
             left
         } else {
@@ -46,8 +74,8 @@ fn shift_down<T: PartialOrd>(heap: &mut [T], mut pos: usize, len: usize) {
         };
 
         // 当前节点与较大的子节点进行比较, 如果比它小就进行交换.
-        if heap[pos] < heap[larger] {
-            heap.swap(pos, larger);
+        if compare(&heap[pos], &heap[larger]) == Ordering::Less {
+            swap(heap, pos, larger);
             pos = larger;
         } else {
             // 当前节点比最大的子节点还大
@@ -56,9 +84,23 @@ fn shift_down<T: PartialOrd>(heap: &mut [T], mut pos: usize, len: usize) {
     }
 }
 
+/// 堆排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HeapSort;
+
+impl<T> Sorter<T> for HeapSort {
+    fn name(&self) -> &'static str {
+        "heap-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        heap_sort_by(arr, compare);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::heap_sort;
+    use super::{heap_sort, heap_sort_by, heap_sort_by_key};
 
     #[test]
     fn test_heap_sort() {
@@ -90,4 +132,19 @@ mod tests {
             ['A', 'E', 'E', 'I', 'N', 'O', 'Q', 'S', 'S', 'T', 'U', 'Y']
         );
     }
+
+    #[test]
+    fn test_heap_sort_by() {
+        let mut list = [0, 5, 3, 2, 2];
+        heap_sort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [1.5, -0.5, 3.25, 0.0];
+        heap_sort_by(&mut list, f64::total_cmp);
+        assert!(list.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let mut list = ["apple", "fig", "banana"];
+        heap_sort_by_key(&mut list, |s| s.len());
+        assert_eq!(list, ["fig", "apple", "banana"]);
+    }
 }
@@ -2,16 +2,29 @@
 // Use of this source is governed by General Public License that can be
 // found in the LICENSE file.
 
+use std::cmp::Ordering;
+
+use crate::sorter::{partial_compare, Sorter};
+use crate::stats::swap;
+
 /// 其思路是, 先将前 i 个元素调整为增序的, 随着 i 从 0 增大到 n, 整个序列就变得是增序了.
 pub fn insertion_sort<T>(arr: &mut [T])
 where
     T: PartialOrd,
+{
+    insertion_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的插入排序.
+pub fn insertion_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     let len = arr.len();
     for i in 1..len {
         for j in (1..=i).rev() {
-            if arr[j - 1] > arr[j] {
-                arr.swap(j - 1, j);
+            if compare(&arr[j - 1], &arr[j]) == Ordering::Greater {
+                swap(arr, j - 1, j);
             } else {
                 break;
             }
@@ -19,10 +32,43 @@ where
     }
 }
 
+/// 按照 `f` 提取出的键值进行插入排序.
+pub fn insertion_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    insertion_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
 /// 递归风格的插入排序算法
 pub fn recursive_insertion_sort<T>(arr: &mut [T])
 where
     T: PartialOrd,
+{
+    recursive_insertion_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的递归风格的插入排序.
+pub fn recursive_insertion_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    recursive_insertion_sort_helper(arr, &mut compare);
+}
+
+/// 按照 `f` 提取出的键值进行递归风格的插入排序.
+pub fn recursive_insertion_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    recursive_insertion_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+fn recursive_insertion_sort_helper<T, F>(arr: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     let len = arr.len();
     if len < 2 {
@@ -30,12 +76,12 @@ where
     }
 
     // 先将 list[..(len-1)] 中的元素排序.
-    recursive_insertion_sort(&mut arr[..len - 1]);
+    recursive_insertion_sort_helper(&mut arr[..len - 1], compare);
 
     // 然后将最后一个元素插入到合适的位置.
     for i in (1..len).rev() {
-        if arr[i - 1] > arr[i] {
-            arr.swap(i - 1, i);
+        if compare(&arr[i - 1], &arr[i]) == Ordering::Greater {
+            swap(arr, i - 1, i);
         } else {
             break;
         }
@@ -55,31 +101,30 @@ where
     0
 }
 
+#[allow(dead_code)]
 fn binary_search<T>(arr: &[T], target: &T) -> usize
 where
     T: PartialOrd,
 {
+    binary_search_by(arr, target, &mut partial_compare)
+}
+
+fn binary_search_by<T, F>(arr: &[T], target: &T, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // 返回第一个大于 target 的元素的位置, 这样 target 会被插入到与它相等的元素之后,
+    // 二分插入排序才是稳定的.
     let mut left = 0;
-    let mut right = arr.len() - 1;
+    let mut right = arr.len();
     while left < right {
         let middle = left + (right - left) / 2;
-        // 找到了相等的元素, 就返回该位置的下一个位置
-        if arr[middle] == *target {
-            return middle + 1;
-        } else if arr[middle] < *target {
-            left = middle + 1;
-        } else {
+        if compare(&arr[middle], target) == Ordering::Greater {
             right = middle;
+        } else {
+            left = middle + 1;
         }
     }
-
-    // 没有找到相等的元素, 就返回期望的位置.
-    if arr[arr.len() - 1] < *target {
-        return arr.len();
-    }
-    if arr[0] > *target {
-        return 0;
-    }
     left
 }
 
@@ -87,6 +132,14 @@ where
 pub fn binary_insertion_sort<T>(arr: &mut [T])
 where
     T: PartialOrd,
+{
+    binary_insertion_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的二分插入排序.
+pub fn binary_insertion_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     let len = arr.len();
     if len < 2 {
@@ -94,16 +147,71 @@ where
     }
 
     for i in 1..len {
-        let target_pos = binary_search(&arr[..i], &arr[i]);
+        let target_pos = binary_search_by(&arr[..i], &arr[i], &mut compare);
         for j in (target_pos..i).rev() {
-            arr.swap(j, j + 1);
+            swap(arr, j, j + 1);
         }
     }
 }
 
+/// 按照 `f` 提取出的键值进行二分插入排序.
+pub fn binary_insertion_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    binary_insertion_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+/// 插入排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InsertionSort;
+
+impl<T> Sorter<T> for InsertionSort {
+    fn name(&self) -> &'static str {
+        "insertion-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        insertion_sort_by(arr, compare);
+    }
+}
+
+/// 二分插入排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BinaryInsertionSort;
+
+impl<T> Sorter<T> for BinaryInsertionSort {
+    fn name(&self) -> &'static str {
+        "binary-insertion-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        binary_insertion_sort_by(arr, compare);
+    }
+}
+
+/// 递归实现的插入排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RecursiveInsertionSort;
+
+impl<T> Sorter<T> for RecursiveInsertionSort {
+    fn name(&self) -> &'static str {
+        "recursive-insertion-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        recursive_insertion_sort_by(arr, compare);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{binary_insertion_sort, binary_search, binary_search_fake, insertion_sort, recursive_insertion_sort};
+    use super::{
+        binary_insertion_sort, binary_insertion_sort_by, binary_insertion_sort_by_key,
+        binary_search, binary_search_fake, insertion_sort, insertion_sort_by,
+        insertion_sort_by_key, recursive_insertion_sort, recursive_insertion_sort_by,
+    };
 
     #[test]
     fn test_insertion_sort() {
@@ -203,6 +311,10 @@ mod tests {
         let list = [-5, -2, -45];
         let pos = binary_search(&list[0..2], &(-45));
         assert_eq!(pos, 0);
+
+        let list = [1, 2, 2, 2, 3];
+        let pos = binary_search(&list, &2);
+        assert_eq!(pos, 4);
     }
 
     #[test]
@@ -235,4 +347,27 @@ mod tests {
             ['A', 'E', 'E', 'I', 'N', 'O', 'Q', 'S', 'S', 'T', 'U', 'Y']
         );
     }
+
+    #[test]
+    fn test_insertion_sort_by() {
+        let mut list = [0, 5, 3, 2, 2];
+        insertion_sort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [0, 5, 3, 2, 2];
+        recursive_insertion_sort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [0, 5, 3, 2, 2];
+        binary_insertion_sort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd')];
+        insertion_sort_by_key(&mut list, |pair| pair.0);
+        assert_eq!(list, [(1, 'b'), (1, 'd'), (3, 'a'), (3, 'c')]);
+
+        let mut list = ["apple", "fig", "banana"];
+        binary_insertion_sort_by_key(&mut list, |s| s.len());
+        assert_eq!(list, ["fig", "apple", "banana"]);
+    }
 }
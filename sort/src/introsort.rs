@@ -0,0 +1,225 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+use std::cmp::Ordering;
+
+use crate::heap_sort::heap_sort_by;
+use crate::insertion_sort::insertion_sort_by;
+use crate::sorter::{partial_compare, Sorter};
+use crate::stats::swap;
+
+/// 内省排序 (introspective sort).
+///
+/// 它以快速排序为主, 并记录递归深度, 当递归深度超过 `2 * log(n)` 时, 说明基准值选得很差,
+/// 就改用堆排序, 这样最坏情况下的时间复杂度也是 `O(n log(n))`.
+/// 对于较短的子数组, 使用插入排序.
+///
+/// 它不是稳定排序.
+pub fn introsort<T>(arr: &mut [T])
+where
+    T: PartialOrd,
+{
+    introsort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的内省排序.
+pub fn introsort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+    let depth_limit = 2 * len.ilog2();
+    introsort_helper(arr, depth_limit, &mut compare);
+}
+
+/// 按照 `f` 提取出的键值进行内省排序.
+pub fn introsort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    introsort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+fn introsort_helper<T, F>(mut arr: &mut [T], mut depth_limit: u32, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // 数组中的元数个数低于一个阈值时, 使用插入排序
+    const CUTOFF: usize = 16;
+
+    loop {
+        let len = arr.len();
+        if len <= CUTOFF {
+            insertion_sort_by(arr, &mut *compare);
+            return;
+        }
+
+        // 递归太深了, 改用堆排序.
+        if depth_limit == 0 {
+            heap_sort_by(arr, &mut *compare);
+            return;
+        }
+        depth_limit -= 1;
+
+        let pivot_index = partition(arr, compare);
+
+        // 先递归处理较短的那一侧, 较长的那一侧在循环中继续处理,
+        // 这样可以保证栈的深度不超过 `O(log(n))`.
+        let (left, right) = std::mem::take(&mut arr).split_at_mut(pivot_index);
+        let right = &mut right[1..];
+        if left.len() < right.len() {
+            introsort_helper(left, depth_limit, compare);
+            arr = right;
+        } else {
+            introsort_helper(right, depth_limit, compare);
+            arr = left;
+        }
+    }
+}
+
+/// 选择首, 中, 尾三个元素的中位数作为基准值, 并把它放到 `arr[0]`.
+fn median_of_three<T, F>(arr: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = arr.len();
+    let middle = len / 2;
+    let last = len - 1;
+
+    // 将三个元素排序, 使得 arr[0] <= arr[middle] <= arr[last]
+    if compare(&arr[middle], &arr[0]) == Ordering::Less {
+        swap(arr, middle, 0);
+    }
+    if compare(&arr[last], &arr[middle]) == Ordering::Less {
+        swap(arr, last, middle);
+        if compare(&arr[middle], &arr[0]) == Ordering::Less {
+            swap(arr, middle, 0);
+        }
+    }
+
+    swap(arr, 0, middle);
+}
+
+/// 使用双指针法 (Hoare partition) 进行分区, 返回基准值所在的位置.
+///
+/// 左右两个指针遇到与基准值相等的元素时都会停下来并交换, 这样当数组中的元素都相同时,
+/// 分区依然是平衡的.
+fn partition<T, F>(arr: &mut [T], compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    median_of_three(arr, compare);
+
+    let mut left = 1;
+    let mut right = arr.len() - 1;
+    loop {
+        // left 的位置右移, 直到 arr[left] 大于等于 pivot
+        while left <= right && compare(&arr[left], &arr[0]) == Ordering::Less {
+            left += 1;
+        }
+        // right 的位置左移, 直到 arr[right] 小于等于 pivot
+        while left <= right && compare(&arr[right], &arr[0]) == Ordering::Greater {
+            right -= 1;
+        }
+        if left >= right {
+            break;
+        }
+        swap(arr, left, right);
+        left += 1;
+        right -= 1;
+    }
+
+    // 最后把基准值 pivot 移到合适的位置.
+    swap(arr, 0, right);
+    right
+}
+
+/// 内省排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IntroSort;
+
+impl<T> Sorter<T> for IntroSort {
+    fn name(&self) -> &'static str {
+        "introsort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        introsort_by(arr, compare);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{introsort, introsort_by, introsort_by_key};
+
+    #[test]
+    fn test_introsort() {
+        let mut list = [0, 5, 3, 2, 2];
+        introsort(&mut list);
+        assert_eq!(list, [0, 2, 2, 3, 5]);
+
+        let mut list = [-2, -5, -45];
+        introsort(&mut list);
+        assert_eq!(list, [-45, -5, -2]);
+
+        let mut list = [
+            -998_166, -996_360, -995_703, -995_238, -995_066, -994_740, -992_987, -983_833,
+            -987_905, -980_069, -977_640,
+        ];
+        introsort(&mut list);
+        assert_eq!(
+            list,
+            [
+                -998_166, -996_360, -995_703, -995_238, -995_066, -994_740, -992_987, -987_905,
+                -983_833, -980_069, -977_640,
+            ]
+        );
+
+        let mut list = "EASYQUESTION".chars().collect::<Vec<_>>();
+        introsort(&mut list);
+        assert_eq!(
+            list,
+            ['A', 'E', 'E', 'I', 'N', 'O', 'Q', 'S', 'S', 'T', 'U', 'Y']
+        );
+    }
+
+    #[test]
+    fn test_introsort_by() {
+        let mut list = [0, 5, 3, 2, 2];
+        introsort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = ["apple", "fig", "banana"];
+        introsort_by_key(&mut list, |s| s.len());
+        assert_eq!(list, ["fig", "apple", "banana"]);
+    }
+
+    #[test]
+    fn test_introsort_patterns() {
+        let len: usize = 10_000;
+        let organ_pipe: Vec<usize> = (0..len / 2).chain((0..len / 2).rev()).collect();
+        let sawtooth: Vec<usize> = (0..len).map(|i| i % 64).collect();
+        let all_equal: Vec<usize> = vec![7; len];
+        let descending: Vec<usize> = (0..len).rev().collect();
+
+        for input in [organ_pipe, sawtooth, all_equal, descending] {
+            let mut expected = input.clone();
+            expected.sort_unstable();
+
+            // 比较次数应该是 `O(n log(n))` 的, 而不是 `O(n^2)`.
+            let mut comparisons: usize = 0;
+            let mut list = input;
+            introsort_by(&mut list, |a, b| {
+                comparisons += 1;
+                a.cmp(b)
+            });
+            assert_eq!(list, expected);
+            assert!(comparisons < 4 * len * len.ilog2() as usize);
+        }
+    }
+}
@@ -14,16 +14,24 @@
 pub mod bubble_sort;
 pub mod bucket_sort;
 pub mod counting_sort;
+pub mod dataset;
 pub mod double_sort;
+pub mod external_sort;
 pub mod gnome_sort;
 pub mod heap_sort;
 pub mod insertion_sort;
+pub mod introsort;
 pub mod merge_sort;
 pub mod odd_even_sort;
+pub mod parallel_sort;
+pub mod pdqsort;
 pub mod quicksort;
 pub mod radix_sort;
 pub mod selection_sort;
 pub mod shaker_sort;
 pub mod shell_sort;
+pub mod sorter;
+pub mod stats;
 pub mod timsort;
 pub mod util;
+pub mod verify;
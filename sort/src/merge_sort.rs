@@ -2,8 +2,12 @@
 // Use of this source is governed by General Public License that can be
 // found in the LICENSE file.
 
-use crate::insertion_sort::insertion_sort;
-use crate::shell_sort::shell_sort;
+use std::cmp::Ordering;
+
+use crate::insertion_sort::insertion_sort_by;
+use crate::shell_sort::shell_sort_by;
+use crate::sorter::{partial_compare, Sorter};
+use crate::stats::swap;
 
 #[inline]
 pub fn merge_sort<T>(arr: &mut [T])
@@ -13,23 +17,63 @@ where
     topdown_merge_sort(arr);
 }
 
+/// 使用比较函数 `compare` 的归并排序.
+#[inline]
+pub fn merge_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    topdown_merge_sort_by(arr, compare);
+}
+
+/// 按照 `f` 提取出的键值进行归并排序.
+pub fn merge_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    T: Clone,
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    merge_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
 /// 对于元素个数为 `N` 的数组, 自顶向下的归并排序 (top-down merge sort)
 /// 最多使用 `N log(N)` 次比较以及 `6N log(N)` 次元素访问操作.
 #[inline]
 pub fn topdown_merge_sort<T>(arr: &mut [T])
 where
     T: PartialOrd + Clone,
+{
+    topdown_merge_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的自顶向下的归并排序.
+pub fn topdown_merge_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     if arr.is_empty() {
         return;
     }
-    sort(arr, 0, arr.len() - 1);
+    sort(arr, 0, arr.len() - 1, &mut compare);
+}
+
+/// 按照 `f` 提取出的键值进行自顶向下的归并排序.
+pub fn topdown_merge_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    T: Clone,
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    topdown_merge_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
 }
 
 /// 排序 `arr[low..=high]` 部分.
-fn sort<T>(arr: &mut [T], low: usize, high: usize)
+fn sort<T, F>(arr: &mut [T], low: usize, high: usize, compare: &mut F)
 where
-    T: PartialOrd + Clone,
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     if low >= high {
         return;
@@ -38,13 +82,13 @@ where
     let middle = low + (high - low) / 2;
 
     // 递归排序左侧部分数组
-    sort(arr, low, middle);
+    sort(arr, low, middle, compare);
     // 递归排序右侧部分数组
-    sort(arr, middle + 1, high);
+    sort(arr, middle + 1, high, compare);
 
     // 合并左右两侧部分数组
-    if arr[middle] > arr[middle + 1] {
-        merge(arr, low, middle, high);
+    if compare(&arr[middle], &arr[middle + 1]) == Ordering::Greater {
+        merge(arr, low, middle, high, compare);
     }
 }
 
@@ -52,9 +96,10 @@ where
 ///
 /// 它不是原地合并.
 #[allow(clippy::needless_range_loop)]
-fn merge<T>(arr: &mut [T], low: usize, middle: usize, high: usize)
+fn merge<T, F>(arr: &mut [T], low: usize, middle: usize, high: usize, compare: &mut F)
 where
-    T: PartialOrd + Clone,
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     // 辅助数组, 先将数组复制一份.
     let aux = arr[low..=high].to_vec();
@@ -70,7 +115,7 @@ where
         } else if j > high {
             arr[k] = aux[i - low].clone();
             i += 1;
-        } else if aux[j - low] < aux[i - low] {
+        } else if compare(&aux[j - low], &aux[i - low]) == Ordering::Less {
             arr[k] = aux[j - low].clone();
             j += 1;
         } else {
@@ -84,44 +129,65 @@ where
 pub fn insertion_merge_sort<T>(arr: &mut [T])
 where
     T: PartialOrd + Clone,
+{
+    insertion_merge_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare`, 对于元素数较少的数组, 使用插入排序.
+pub fn insertion_merge_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     if arr.is_empty() {
         return;
     }
     let cutoff: usize = 24;
     let mut aux = arr.to_vec();
-    sort_cutoff_with_insertion(arr, 0, arr.len() - 1, cutoff, &mut aux);
+    sort_cutoff_with_insertion(arr, 0, arr.len() - 1, cutoff, &mut aux, &mut compare);
+}
+
+/// 按照 `f` 提取出的键值, 对于元素数较少的数组, 使用插入排序.
+pub fn insertion_merge_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    T: Clone,
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    insertion_merge_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
 }
 
 /// 排序 `arr[low..=high]` 部分, 如果元数较少, 就使用插入排序.
-fn sort_cutoff_with_insertion<T>(
+fn sort_cutoff_with_insertion<T, F>(
     arr: &mut [T],
     low: usize,
     high: usize,
     cutoff: usize,
     aux: &mut Vec<T>,
+    compare: &mut F,
 ) where
-    T: PartialOrd + Clone,
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     if low >= high {
         return;
     }
 
     if high - low <= cutoff {
-        insertion_sort(&mut arr[low..=high]);
+        insertion_sort_by(&mut arr[low..=high], &mut *compare);
         return;
     }
 
     let middle = low + (high - low) / 2;
 
     // 递归排序左侧部分数组
-    sort_cutoff_with_insertion(arr, low, middle, cutoff, aux);
+    sort_cutoff_with_insertion(arr, low, middle, cutoff, aux, compare);
     // 递归排序右侧部分数组
-    sort_cutoff_with_insertion(arr, middle + 1, high, cutoff, aux);
+    sort_cutoff_with_insertion(arr, middle + 1, high, cutoff, aux, compare);
 
     // 合并左右两侧部分数组
-    if arr[middle] > arr[middle + 1] {
-        merge_with_aux(arr, low, middle, high, aux);
+    if compare(&arr[middle], &arr[middle + 1]) == Ordering::Greater {
+        merge_with_aux(arr, low, middle, high, aux, compare);
     }
 }
 
@@ -129,9 +195,16 @@ fn sort_cutoff_with_insertion<T>(
 ///
 /// 它不是原地合并.
 #[allow(clippy::needless_range_loop)]
-fn merge_with_aux<T>(arr: &mut [T], low: usize, middle: usize, high: usize, aux: &mut [T])
-where
-    T: PartialOrd + Clone,
+fn merge_with_aux<T, F>(
+    arr: &mut [T],
+    low: usize,
+    middle: usize,
+    high: usize,
+    aux: &mut [T],
+    compare: &mut F,
+) where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     // 辅助数组, 先将数组复制一份.
     for index in low..=high {
@@ -149,7 +222,7 @@ where
         } else if j > high {
             arr[k] = aux[i].clone();
             i += 1;
-        } else if aux[j] < aux[i] {
+        } else if compare(&aux[j], &aux[i]) == Ordering::Less {
             arr[k] = aux[j].clone();
             j += 1;
         } else {
@@ -163,6 +236,15 @@ where
 pub fn shell_merge_sort<T>(arr: &mut [T])
 where
     T: PartialOrd + Clone,
+{
+    shell_merge_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare`, 对于元素数较少的数组, 使用希尔排序.
+pub fn shell_merge_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     if arr.is_empty() {
         return;
@@ -170,38 +252,50 @@ where
 
     let cutoff: usize = 72;
     let mut aux = arr.to_vec();
-    sort_cutoff_with_shell(arr, 0, arr.len() - 1, cutoff, &mut aux);
+    sort_cutoff_with_shell(arr, 0, arr.len() - 1, cutoff, &mut aux, &mut compare);
+}
+
+/// 按照 `f` 提取出的键值, 对于元素数较少的数组, 使用希尔排序.
+pub fn shell_merge_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    T: Clone,
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    shell_merge_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
 }
 
 /// 排序 `arr[low..=high]` 部分, 如果元数较少, 就使用希尔排序.
-fn sort_cutoff_with_shell<T>(
+fn sort_cutoff_with_shell<T, F>(
     arr: &mut [T],
     low: usize,
     high: usize,
     cutoff: usize,
     aux: &mut Vec<T>,
+    compare: &mut F,
 ) where
-    T: PartialOrd + Clone,
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     if low >= high {
         return;
     }
 
     if high - low <= cutoff {
-        shell_sort(&mut arr[low..=high]);
+        shell_sort_by(&mut arr[low..=high], &mut *compare);
         return;
     }
 
     let middle = low + (high - low) / 2;
 
     // 递归排序左侧部分数组
-    sort_cutoff_with_shell(arr, low, middle, cutoff, aux);
+    sort_cutoff_with_shell(arr, low, middle, cutoff, aux, compare);
     // 递归排序右侧部分数组
-    sort_cutoff_with_shell(arr, middle + 1, high, cutoff, aux);
+    sort_cutoff_with_shell(arr, middle + 1, high, cutoff, aux, compare);
 
     // 合并左右两侧部分数组
-    if arr[middle] > arr[middle + 1] {
-        merge_with_aux(arr, low, middle, high, aux);
+    if compare(&arr[middle], &arr[middle + 1]) == Ordering::Greater {
+        merge_with_aux(arr, low, middle, high, aux, compare);
     }
 }
 
@@ -209,6 +303,15 @@ fn sort_cutoff_with_shell<T>(
 pub fn bottom_up_merge_sort<T>(arr: &mut [T])
 where
     T: PartialOrd + Clone,
+{
+    bottom_up_merge_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的自底向上的归并排序.
+pub fn bottom_up_merge_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     let len = arr.len();
     if len < 2 {
@@ -233,7 +336,7 @@ where
             let right_end = (left_start + 2 * current_size - 1).min(len - 1);
 
             // 合并左右两侧部分数组
-            merge_with_aux(arr, left_start, middle, right_end, &mut aux);
+            merge_with_aux(arr, left_start, middle, right_end, &mut aux, &mut compare);
 
             left_start += 2 * current_size;
         }
@@ -242,22 +345,52 @@ where
     }
 }
 
+/// 按照 `f` 提取出的键值进行自底向上的归并排序.
+pub fn bottom_up_merge_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    T: Clone,
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    bottom_up_merge_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
 /// 三路归并排序
 pub fn three_way_merge_sort<T>(arr: &mut [T])
 where
     T: PartialOrd + Clone,
+{
+    three_way_merge_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的三路归并排序.
+pub fn three_way_merge_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     if arr.is_empty() {
         return;
     }
     let mut aux = arr.to_vec();
-    three_way_sort(arr, 0, arr.len() - 1, &mut aux);
+    three_way_sort(arr, 0, arr.len() - 1, &mut aux, &mut compare);
+}
+
+/// 按照 `f` 提取出的键值进行三路归并排序.
+pub fn three_way_merge_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    T: Clone,
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    three_way_merge_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
 }
 
 /// 三路排序 `arr[low..=high]`
-fn three_way_sort<T>(arr: &mut [T], low: usize, high: usize, aux: &mut Vec<T>)
+fn three_way_sort<T, F>(arr: &mut [T], low: usize, high: usize, aux: &mut Vec<T>, compare: &mut F)
 where
-    T: PartialOrd + Clone,
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     // 如果数组长度小于2, 就返回.
     if low + 1 > high {
@@ -269,27 +402,30 @@ where
     let middle2 = low + 2 * ((high - low) / 3);
 
     // 递归排序各部分数组
-    three_way_sort(arr, low, middle1, aux);
-    three_way_sort(arr, middle1 + 1, middle2, aux);
-    three_way_sort(arr, middle2 + 1, high, aux);
+    three_way_sort(arr, low, middle1, aux, compare);
+    three_way_sort(arr, middle1 + 1, middle2, aux, compare);
+    three_way_sort(arr, middle2 + 1, high, aux, compare);
 
     // 合并三部分数组
-    three_way_merge(arr, low, middle1, middle2, high, aux);
+    three_way_merge(arr, low, middle1, middle2, high, aux, compare);
 }
 
 /// 合并 `arr[low..=middle1]`, `arr[middle1+1..=middle2]` 以及 `arr[middle2+1..=high]` 三个子数组.
 ///
 /// 它不是原地合并.
 #[allow(clippy::needless_range_loop)]
-fn three_way_merge<T>(
+#[allow(clippy::too_many_arguments)]
+fn three_way_merge<T, F>(
     arr: &mut [T],
     low: usize,
     middle1: usize,
     middle2: usize,
     high: usize,
     aux: &mut [T],
+    compare: &mut F,
 ) where
-    T: PartialOrd + Clone,
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     // 辅助数组, 先将数组复制一份.
     for index in low..=high {
@@ -304,9 +440,11 @@ fn three_way_merge<T>(
 
     // 首先合并较小的子数组
     while i <= middle1 && j <= middle2 && k <= high {
-        let curr_index = if aux[i] < aux[j] && aux[i] < aux[k] {
+        let curr_index = if compare(&aux[i], &aux[j]) == Ordering::Less
+            && compare(&aux[i], &aux[k]) == Ordering::Less
+        {
             &mut i
-        } else if aux[j] < aux[k] {
+        } else if compare(&aux[j], &aux[k]) == Ordering::Less {
             &mut j
         } else {
             &mut k
@@ -318,7 +456,7 @@ fn three_way_merge<T>(
 
     // 然后合并剩余部分的子数组
     while i <= middle1 && j <= middle2 {
-        let curr_index = if aux[i] < aux[j] {
+        let curr_index = if compare(&aux[i], &aux[j]) == Ordering::Less {
             &mut i
         } else {
             &mut j
@@ -329,7 +467,7 @@ fn three_way_merge<T>(
     }
 
     while j <= middle2 && k <= high {
-        let curr_index = if aux[j] < aux[k] {
+        let curr_index = if compare(&aux[j], &aux[k]) == Ordering::Less {
             &mut j
         } else {
             &mut k
@@ -340,7 +478,7 @@ fn three_way_merge<T>(
     }
 
     while i <= middle1 && k <= high {
-        let curr_index = if aux[i] < aux[k] {
+        let curr_index = if compare(&aux[i], &aux[k]) == Ordering::Less {
             &mut i
         } else {
             &mut k
@@ -374,52 +512,68 @@ fn three_way_merge<T>(
 pub fn in_place_merge_sort<T>(arr: &mut [T])
 where
     T: PartialOrd,
+{
+    in_place_merge_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的原地归并排序.
+pub fn in_place_merge_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     if arr.is_empty() {
         return;
     }
-    sort_in_place(arr, 0, arr.len() - 1);
+    sort_in_place(arr, 0, arr.len() - 1, &mut compare);
+}
+
+/// 按照 `f` 提取出的键值进行原地归并排序.
+pub fn in_place_merge_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    in_place_merge_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
 }
 
 /// 原地排序 `arr[low..=high]`
-fn sort_in_place<T>(arr: &mut [T], low: usize, high: usize)
+fn sort_in_place<T, F>(arr: &mut [T], low: usize, high: usize, compare: &mut F)
 where
-    T: PartialOrd,
+    F: FnMut(&T, &T) -> Ordering,
 {
     if low >= high {
         return;
     }
 
     let middle = low + (high - low) / 2;
-    sort_in_place(arr, low, middle);
-    sort_in_place(arr, middle + 1, high);
+    sort_in_place(arr, low, middle, compare);
+    sort_in_place(arr, middle + 1, high, compare);
 
-    if arr[middle] > arr[middle + 1] {
-        merge_in_place(arr, low, middle, high);
+    if compare(&arr[middle], &arr[middle + 1]) == Ordering::Greater {
+        merge_in_place(arr, low, middle, high, compare);
     }
 }
 
 /// 原地合并 `arr[low..=middle]` 以及 `arr[middle+1..=high]` 两个子数组.
-fn merge_in_place<T>(arr: &mut [T], mut low: usize, mut middle: usize, high: usize)
+fn merge_in_place<T, F>(arr: &mut [T], mut low: usize, mut middle: usize, high: usize, compare: &mut F)
 where
-    T: PartialOrd,
+    F: FnMut(&T, &T) -> Ordering,
 {
     let mut low2 = middle + 1;
-    debug_assert!(arr[middle] > arr[low2]);
 
     while low <= middle && low2 <= high {
-        if arr[low] <= arr[low2] {
-            low += 1;
-        } else {
+        if compare(&arr[low], &arr[low2]) == Ordering::Greater {
             // 将所有元素右移, 并将 arr[low2] 插入到 arr[low] 所在位置. 这一步很慢.
             for index in (low..low2).rev() {
-                arr.swap(index, index + 1);
+                swap(arr, index, index + 1);
             }
 
             // 更新所有的索引
             low += 1;
             middle += 1;
             low2 += 1;
+        } else {
+            low += 1;
         }
     }
 }
@@ -430,35 +584,52 @@ where
 pub fn in_place_shell_merge_sort<T>(arr: &mut [T])
 where
     T: PartialOrd,
+{
+    in_place_shell_merge_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的原地希尔归并排序.
+pub fn in_place_shell_merge_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     if arr.is_empty() {
         return;
     }
-    sort_in_place_with_shell(arr, 0, arr.len() - 1);
+    sort_in_place_with_shell(arr, 0, arr.len() - 1, &mut compare);
+}
+
+/// 按照 `f` 提取出的键值进行原地希尔归并排序.
+pub fn in_place_shell_merge_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    in_place_shell_merge_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
 }
 
 /// 原地排序 `arr[low..=high]`
-fn sort_in_place_with_shell<T>(arr: &mut [T], low: usize, high: usize)
+fn sort_in_place_with_shell<T, F>(arr: &mut [T], low: usize, high: usize, compare: &mut F)
 where
-    T: PartialOrd,
+    F: FnMut(&T, &T) -> Ordering,
 {
     if low >= high {
         return;
     }
 
     let middle = low + (high - low) / 2;
-    sort_in_place_with_shell(arr, low, middle);
-    sort_in_place_with_shell(arr, middle + 1, high);
+    sort_in_place_with_shell(arr, low, middle, compare);
+    sort_in_place_with_shell(arr, middle + 1, high, compare);
 
-    merge_in_place_with_shell(arr, low, high);
+    merge_in_place_with_shell(arr, low, high, compare);
 }
 
 /// 使用希尔排序的方式原地合并 `arr[low..=middle]` 以及 `arr[middle+1..=high]` 两个子数组.
 ///
 /// 时间复杂度 `O(N Log(N))`, 空间复杂度 `O(1)`
-fn merge_in_place_with_shell<T>(arr: &mut [T], low: usize, high: usize)
+fn merge_in_place_with_shell<T, F>(arr: &mut [T], low: usize, high: usize, compare: &mut F)
 where
-    T: PartialOrd,
+    F: FnMut(&T, &T) -> Ordering,
 {
     #[must_use]
     #[inline]
@@ -477,17 +648,135 @@ where
         for i in low..=(high - gap) {
             let j = i + gap;
             // 每次间隔多个元素进行比较和交换.
-            if arr[i] > arr[j] {
-                arr.swap(i, j);
+            if compare(&arr[i], &arr[j]) == Ordering::Greater {
+                swap(arr, i, j);
             }
         }
         gap = next_gap(gap);
     }
 }
 
+/// 归并排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MergeSort;
+
+impl<T: Clone> Sorter<T> for MergeSort {
+    fn name(&self) -> &'static str {
+        "merge-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        merge_sort_by(arr, compare);
+    }
+}
+
+/// 自底向上的归并排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BottomUpMergeSort;
+
+impl<T: Clone> Sorter<T> for BottomUpMergeSort {
+    fn name(&self) -> &'static str {
+        "bottom-up-merge-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        bottom_up_merge_sort_by(arr, compare);
+    }
+}
+
+/// 自顶向下的归并排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TopDownMergeSort;
+
+impl<T: Clone> Sorter<T> for TopDownMergeSort {
+    fn name(&self) -> &'static str {
+        "top-down-merge-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        topdown_merge_sort_by(arr, compare);
+    }
+}
+
+/// 元素较少时使用插入排序的归并排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InsertionMergeSort;
+
+impl<T: Clone> Sorter<T> for InsertionMergeSort {
+    fn name(&self) -> &'static str {
+        "insertion-merge-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        insertion_merge_sort_by(arr, compare);
+    }
+}
+
+/// 元素较少时使用希尔排序的归并排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ShellMergeSort;
+
+impl<T: Clone> Sorter<T> for ShellMergeSort {
+    fn name(&self) -> &'static str {
+        "shell-merge-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        shell_merge_sort_by(arr, compare);
+    }
+}
+
+/// 三路归并排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ThreeWayMergeSort;
+
+impl<T: Clone> Sorter<T> for ThreeWayMergeSort {
+    fn name(&self) -> &'static str {
+        "three-way-merge-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        three_way_merge_sort_by(arr, compare);
+    }
+}
+
+/// 原地归并排序, 不需要额外的内存.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InPlaceMergeSort;
+
+impl<T> Sorter<T> for InPlaceMergeSort {
+    fn name(&self) -> &'static str {
+        "in-place-merge-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        in_place_merge_sort_by(arr, compare);
+    }
+}
+
+/// 参考希尔排序, 通过调整元素间隔减少元素移动的原地归并排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InPlaceShellMergeSort;
+
+impl<T> Sorter<T> for InPlaceShellMergeSort {
+    fn name(&self) -> &'static str {
+        "in-place-shell-merge-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        in_place_shell_merge_sort_by(arr, compare);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{bottom_up_merge_sort, in_place_merge_sort, in_place_shell_merge_sort, insertion_merge_sort, shell_merge_sort, three_way_merge_sort, topdown_merge_sort};
+    use super::{
+        bottom_up_merge_sort, bottom_up_merge_sort_by, bottom_up_merge_sort_by_key,
+        in_place_merge_sort, in_place_merge_sort_by, in_place_merge_sort_by_key,
+        in_place_shell_merge_sort, in_place_shell_merge_sort_by, insertion_merge_sort,
+        insertion_merge_sort_by, merge_sort_by, merge_sort_by_key, shell_merge_sort,
+        shell_merge_sort_by, three_way_merge_sort, three_way_merge_sort_by, topdown_merge_sort,
+    };
 
     fn run_test(sort_func: fn(arr: &mut [i32])) {
         let mut list = [0, 5, 3, 2, 2];
@@ -568,4 +857,39 @@ mod tests {
             ]
         );
     }
+
+    type CompareFn = fn(&i32, &i32) -> std::cmp::Ordering;
+
+    fn run_test_by(sort_func: fn(arr: &mut [i32], compare: CompareFn)) {
+        let mut list = [0, 5, 3, 2, 2];
+        sort_func(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [-2, -5, -45];
+        sort_func(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [-2, -5, -45]);
+    }
+
+    #[test]
+    fn test_merge_sort_by() {
+        run_test_by(merge_sort_by);
+        run_test_by(insertion_merge_sort_by);
+        run_test_by(shell_merge_sort_by);
+        run_test_by(bottom_up_merge_sort_by);
+        run_test_by(three_way_merge_sort_by);
+        run_test_by(in_place_merge_sort_by);
+        run_test_by(in_place_shell_merge_sort_by);
+
+        let mut list = [(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd')];
+        merge_sort_by_key(&mut list, |pair| pair.0);
+        assert_eq!(list, [(1, 'b'), (1, 'd'), (3, 'a'), (3, 'c')]);
+
+        let mut list = [(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd')];
+        bottom_up_merge_sort_by_key(&mut list, |pair| pair.0);
+        assert_eq!(list, [(1, 'b'), (1, 'd'), (3, 'a'), (3, 'c')]);
+
+        let mut list = [(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd')];
+        in_place_merge_sort_by_key(&mut list, |pair| pair.0);
+        assert_eq!(list, [(1, 'b'), (1, 'd'), (3, 'a'), (3, 'c')]);
+    }
 }
@@ -2,15 +2,31 @@
 // Use of this source is governed by General Public License that can be
 // found in the LICENSE file.
 
+use std::cmp::Ordering;
+
+use crate::sorter::{partial_compare, Sorter};
+use crate::stats::swap;
+
 /// Odd even sort is a variant of bubble sort.
 ///
 /// [Odd even sort](https://en.wikipedia.org/wiki/Odd%E2%80%93even_sort)
 pub fn odd_even_sort<T>(arr: &mut [T])
 where
     T: PartialOrd,
+{
+    odd_even_sort_by(arr, partial_compare);
+}
+
+/// Odd even sort with a comparator function.
+pub fn odd_even_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     let mut is_sorted = false;
     let len = arr.len();
+    if len < 2 {
+        return;
+    }
 
     // Keep looping until all indices are traversed.
     while !is_sorted {
@@ -18,25 +34,48 @@ where
 
         // Iterating over all even indices
         for i in (0..(len - 1)).step_by(2) {
-            if arr[i] > arr[i + 1] {
-                arr.swap(i, i + 1);
+            if compare(&arr[i], &arr[i + 1]) == Ordering::Greater {
+                swap(arr, i, i + 1);
                 is_sorted = false;
             }
         }
 
         // Iterating over all odd indices
         for i in (1..(len - 1)).step_by(2) {
-            if arr[i] > arr[i + 1] {
-                arr.swap(i, i + 1);
+            if compare(&arr[i], &arr[i + 1]) == Ordering::Greater {
+                swap(arr, i, i + 1);
                 is_sorted = false;
             }
         }
     }
 }
 
+/// Odd even sort with a key extraction function.
+pub fn odd_even_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    odd_even_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+/// Odd even sort.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct OddEvenSort;
+
+impl<T> Sorter<T> for OddEvenSort {
+    fn name(&self) -> &'static str {
+        "odd-even-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        odd_even_sort_by(arr, compare);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::odd_even_sort;
+    use super::{odd_even_sort, odd_even_sort_by, odd_even_sort_by_key};
 
     #[test]
     fn test_odd_even_sort() {
@@ -68,4 +107,15 @@ mod tests {
             ['A', 'E', 'E', 'I', 'N', 'O', 'Q', 'S', 'S', 'T', 'U', 'Y']
         );
     }
+
+    #[test]
+    fn test_odd_even_sort_by() {
+        let mut list = [0, 5, 3, 2, 2];
+        odd_even_sort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd')];
+        odd_even_sort_by_key(&mut list, |pair| pair.0);
+        assert_eq!(list, [(1, 'b'), (1, 'd'), (3, 'a'), (3, 'c')]);
+    }
 }
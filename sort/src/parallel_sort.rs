@@ -0,0 +1,504 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 多线程排序.
+//!
+//! 这里的排序函数都使用 `std::thread::scope` 创建线程, 参数 `threads` 指定最多使用的线程数,
+//! 为 0 时使用 `std::thread::available_parallelism()` 的值.
+//! 当数组长度低于 `SEQUENTIAL_THRESHOLD` 或者只有一个线程可用时, 改用单线程的排序算法.
+
+use std::cmp::Ordering;
+use std::thread;
+
+use crate::pdqsort::pdqsort_by;
+use crate::sorter::partial_compare;
+use crate::stats::swap;
+use crate::timsort::natural_timsort_by;
+
+/// 数组长度低于这个阈值时, 创建线程的开销超过了并行带来的收益, 直接使用单线程排序.
+pub const SEQUENTIAL_THRESHOLD: usize = 1 << 13;
+
+/// 每个线程对应的采样数, 采样越多, 样本排序划分出的各个桶的大小越均衡.
+const OVERSAMPLING: usize = 32;
+
+fn thread_count(threads: usize) -> usize {
+    if threads == 0 {
+        thread::available_parallelism().map_or(1, usize::from)
+    } else {
+        threads
+    }
+}
+
+/// 并行归并排序.
+///
+/// 把数组分成两半, 分别在两个线程中递归排序, 再并行地合并它们.
+/// 合并时, 在较长片段的中间位置选一个元素, 用二分查找在另一个片段中找到它的位置,
+/// 这样就把一次合并拆成了两个互不相关的合并.
+///
+/// 它是稳定排序.
+pub fn parallel_merge_sort<T>(arr: &mut [T], threads: usize)
+where
+    T: PartialOrd + Clone + Send + Sync,
+{
+    parallel_merge_sort_by(arr, threads, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的并行归并排序.
+pub fn parallel_merge_sort_by<T, F>(arr: &mut [T], threads: usize, compare: F)
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    merge_sort_helper(arr, thread_count(threads), &compare);
+}
+
+/// 按照 `f` 提取出的键值进行并行归并排序.
+pub fn parallel_merge_sort_by_key<T, K, F>(arr: &mut [T], threads: usize, f: F)
+where
+    T: Clone + Send + Sync,
+    K: PartialOrd,
+    F: Fn(&T) -> K + Sync,
+{
+    parallel_merge_sort_by(arr, threads, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+fn merge_sort_helper<T, F>(arr: &mut [T], threads: usize, compare: &F)
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if threads <= 1 || arr.len() < SEQUENTIAL_THRESHOLD {
+        natural_timsort_by(arr, compare);
+        return;
+    }
+
+    // 辅助数组只分配一次, 之后各层递归在它与原数组之间交替合并.
+    let mut aux = arr.to_vec();
+    sort_into(arr, &mut aux, threads, compare);
+}
+
+/// 对 `dst` 排序, `src` 是与它包含相同元素的辅助空间.
+///
+/// 先把 `dst` 的两半当作辅助空间, 在 `src` 中对两半分别排序, 再把它们合并回 `dst`.
+/// 这样每层递归都交换了源和目标的角色, 不需要再复制元素.
+fn sort_into<T, F>(dst: &mut [T], src: &mut [T], threads: usize, compare: &F)
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if threads <= 1 || dst.len() < SEQUENTIAL_THRESHOLD {
+        natural_timsort_by(dst, compare);
+        return;
+    }
+
+    let middle = dst.len() / 2;
+    let left_threads = threads / 2;
+    let (src_left, src_right) = src.split_at_mut(middle);
+    {
+        let (dst_left, dst_right) = dst.split_at_mut(middle);
+        thread::scope(|scope| {
+            scope.spawn(|| sort_into(src_left, dst_left, left_threads, compare));
+            sort_into(src_right, dst_right, threads - left_threads, compare);
+        });
+    }
+    parallel_merge(src_left, src_right, dst, threads, compare);
+}
+
+/// 把两个有序的片段 `left` 和 `right` 合并到 `dst` 中.
+fn parallel_merge<T, F>(left: &[T], right: &[T], dst: &mut [T], threads: usize, compare: &F)
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if threads <= 1 || dst.len() < SEQUENTIAL_THRESHOLD {
+        sequential_merge(left, right, dst, compare);
+        return;
+    }
+
+    // 相等的元素中, 来自左侧片段的总是排在前面, 以保证稳定性.
+    let (left_mid, right_mid) = if left.len() >= right.len() {
+        let left_mid = left.len() / 2;
+        let pivot = &left[left_mid];
+        let right_mid = right.partition_point(|x| compare(x, pivot) == Ordering::Less);
+        (left_mid, right_mid)
+    } else {
+        let right_mid = right.len() / 2;
+        let pivot = &right[right_mid];
+        let left_mid = left.partition_point(|x| compare(x, pivot) != Ordering::Greater);
+        (left_mid, right_mid)
+    };
+
+    let (left1, left2) = left.split_at(left_mid);
+    let (right1, right2) = right.split_at(right_mid);
+    let (dst1, dst2) = dst.split_at_mut(left_mid + right_mid);
+    let first_threads = threads / 2;
+    thread::scope(|scope| {
+        scope.spawn(|| parallel_merge(left1, right1, dst1, first_threads, compare));
+        parallel_merge(left2, right2, dst2, threads - first_threads, compare);
+    });
+}
+
+fn sequential_merge<T, F>(left: &[T], right: &[T], dst: &mut [T], compare: &F)
+where
+    T: Clone,
+    F: Fn(&T, &T) -> Ordering,
+{
+    let mut i = 0;
+    let mut j = 0;
+    for slot in dst.iter_mut() {
+        if j == right.len() || (i < left.len() && compare(&right[j], &left[i]) != Ordering::Less) {
+            slot.clone_from(&left[i]);
+            i += 1;
+        } else {
+            slot.clone_from(&right[j]);
+            j += 1;
+        }
+    }
+}
+
+/// 并行快速排序.
+///
+/// 分区之后, 左右两个子数组分别在两个线程中递归排序.
+/// 线程用完之后, 改用模式消除快速排序 (pdqsort), 所以最坏情况下的时间复杂度也是 `O(n log(n))`.
+///
+/// 它不是稳定排序.
+pub fn parallel_quicksort<T>(arr: &mut [T], threads: usize)
+where
+    T: PartialOrd + Send,
+{
+    parallel_quicksort_by(arr, threads, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的并行快速排序.
+pub fn parallel_quicksort_by<T, F>(arr: &mut [T], threads: usize, compare: F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    quicksort_helper(arr, thread_count(threads), &compare);
+}
+
+/// 按照 `f` 提取出的键值进行并行快速排序.
+pub fn parallel_quicksort_by_key<T, K, F>(arr: &mut [T], threads: usize, f: F)
+where
+    T: Send,
+    K: PartialOrd,
+    F: Fn(&T) -> K + Sync,
+{
+    parallel_quicksort_by(arr, threads, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+fn quicksort_helper<T, F>(arr: &mut [T], threads: usize, compare: &F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if threads <= 1 || arr.len() < SEQUENTIAL_THRESHOLD {
+        pdqsort_by(arr, compare);
+        return;
+    }
+
+    let pivot_index = partition(arr, compare);
+    let (left, right) = arr.split_at_mut(pivot_index);
+    let right = &mut right[1..];
+
+    // 按照子数组的长度分配线程.
+    let left_threads = (threads * left.len() / (left.len() + right.len())).clamp(1, threads - 1);
+    thread::scope(|scope| {
+        scope.spawn(|| quicksort_helper(left, left_threads, compare));
+        quicksort_helper(right, threads - left_threads, compare);
+    });
+}
+
+/// 以首, 中, 尾三个元素的中位数作为基准值, 使用双指针法 (Hoare partition) 进行分区,
+/// 返回基准值所在的位置.
+fn partition<T, F>(arr: &mut [T], compare: &F) -> usize
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    let len = arr.len();
+    let middle = len / 2;
+    let last = len - 1;
+    if compare(&arr[middle], &arr[0]) == Ordering::Less {
+        swap(arr, middle, 0);
+    }
+    if compare(&arr[last], &arr[middle]) == Ordering::Less {
+        swap(arr, last, middle);
+        if compare(&arr[middle], &arr[0]) == Ordering::Less {
+            swap(arr, middle, 0);
+        }
+    }
+    swap(arr, 0, middle);
+
+    let mut left = 1;
+    let mut right = last;
+    loop {
+        while left <= right && compare(&arr[left], &arr[0]) == Ordering::Less {
+            left += 1;
+        }
+        while left <= right && compare(&arr[right], &arr[0]) == Ordering::Greater {
+            right -= 1;
+        }
+        if left >= right {
+            break;
+        }
+        swap(arr, left, right);
+        left += 1;
+        right -= 1;
+    }
+    swap(arr, 0, right);
+    right
+}
+
+/// 并行样本排序 (sample sort).
+///
+/// 1. 用种子为 `seed` 的随机数抽取一批样本, 排序后从中等间隔地选出 `threads - 1` 个分隔值 (splitter);
+/// 2. 把数组分成 `threads` 段, 每个线程用二分查找计算自己那一段的元素各自属于哪个桶;
+/// 3. 按桶原地交换元素, 使每个桶的元素在数组中是连续的;
+/// 4. 每个线程负责一个桶, 对它排序.
+///
+/// 如果数组中有大量重复的元素, 各个桶的大小可能不均衡, 但结果依然是正确的.
+///
+/// 它不是稳定排序.
+pub fn parallel_sample_sort<T>(arr: &mut [T], threads: usize, seed: u64)
+where
+    T: PartialOrd + Send + Sync,
+{
+    parallel_sample_sort_by(arr, threads, seed, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的并行样本排序.
+pub fn parallel_sample_sort_by<T, F>(arr: &mut [T], threads: usize, seed: u64, compare: F)
+where
+    T: Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let threads = thread_count(threads);
+    let len = arr.len();
+    if threads <= 1 || len < SEQUENTIAL_THRESHOLD {
+        pdqsort_by(arr, compare);
+        return;
+    }
+
+    let splitters = choose_splitters(arr, threads, seed, &compare);
+    let num_buckets = splitters.len() + 1;
+
+    // 每个线程计算自己负责的那一段元素所属的桶.
+    let chunk_size = len.div_ceil(threads);
+    let mut bucket_of = vec![0; len];
+    {
+        let items: &[T] = arr;
+        thread::scope(|scope| {
+            for (chunk, indices) in items
+                .chunks(chunk_size)
+                .zip(bucket_of.chunks_mut(chunk_size))
+            {
+                let splitters = &splitters;
+                let compare = &compare;
+                scope.spawn(move || {
+                    for (item, index) in chunk.iter().zip(indices) {
+                        *index = splitters.partition_point(|&splitter| {
+                            compare(&items[splitter], item) != Ordering::Greater
+                        });
+                    }
+                });
+            }
+        });
+    }
+
+    let bounds = distribute(arr, &mut bucket_of, num_buckets);
+
+    // 每个线程负责一个桶, 对它排序.
+    thread::scope(|scope| {
+        let mut rest: &mut [T] = arr;
+        for bucket in bounds.windows(2) {
+            let (dst, tail) = std::mem::take(&mut rest).split_at_mut(bucket[1] - bucket[0]);
+            rest = tail;
+            let compare = &compare;
+            scope.spawn(move || pdqsort_by(dst, compare));
+        }
+    });
+}
+
+/// 按照 `f` 提取出的键值进行并行样本排序.
+pub fn parallel_sample_sort_by_key<T, K, F>(arr: &mut [T], threads: usize, seed: u64, f: F)
+where
+    T: Send + Sync,
+    K: PartialOrd,
+    F: Fn(&T) -> K + Sync,
+{
+    parallel_sample_sort_by(arr, threads, seed, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+/// 从样本中选出有序且互不相同的分隔值, 返回它们在 `arr` 中的下标.
+fn choose_splitters<T, F>(arr: &[T], threads: usize, seed: u64, compare: &F) -> Vec<usize>
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let num_samples = (threads * OVERSAMPLING).min(arr.len());
+    let mut samples: Vec<usize> = (0..num_samples)
+        .map(|_| rng.gen_range(0..arr.len()))
+        .collect();
+    pdqsort_by(&mut samples, |&a, &b| compare(&arr[a], &arr[b]));
+
+    let mut splitters: Vec<usize> = (1..threads)
+        .map(|i| samples[i * num_samples / threads])
+        .collect();
+    splitters.dedup_by(|a, b| compare(&arr[*a], &arr[*b]) == Ordering::Equal);
+    splitters
+}
+
+/// 按照 `bucket_of` 中记录的桶, 原地交换元素, 使同一个桶中的元素连续存放.
+///
+/// 与 American flag sort 的做法一样, 每次把一个元素直接交换到它所属的桶中的下一个空位.
+/// 返回各个桶的边界, 第 `i` 个桶是 `arr[bounds[i]..bounds[i + 1]]`.
+fn distribute<T>(arr: &mut [T], bucket_of: &mut [usize], num_buckets: usize) -> Vec<usize> {
+    let mut bounds = vec![0; num_buckets + 1];
+    for &bucket in bucket_of.iter() {
+        bounds[bucket + 1] += 1;
+    }
+    for i in 1..bounds.len() {
+        bounds[i] += bounds[i - 1];
+    }
+
+    // `next[i]` 是第 `i` 个桶中下一个还没有确定的位置.
+    let mut next = bounds[..num_buckets].to_vec();
+    for bucket in 0..num_buckets {
+        while next[bucket] < bounds[bucket + 1] {
+            let pos = next[bucket];
+            let target = bucket_of[pos];
+            if target != bucket {
+                let dst = next[target];
+                swap(arr, pos, dst);
+                bucket_of.swap(pos, dst);
+            }
+            next[target] += 1;
+        }
+    }
+    bounds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        distribute, parallel_merge_sort, parallel_merge_sort_by_key, parallel_quicksort,
+        parallel_quicksort_by, parallel_sample_sort, parallel_sample_sort_by_key,
+        SEQUENTIAL_THRESHOLD,
+    };
+
+    fn random_list(len: usize) -> Vec<i64> {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+        (0..len)
+            .map(|_| rng.gen_range(-1_000_000..1_000_000))
+            .collect()
+    }
+
+    fn inputs() -> Vec<Vec<i64>> {
+        let len = SEQUENTIAL_THRESHOLD * 8 + 17;
+        let len_i64 = i64::try_from(len).unwrap();
+        vec![
+            vec![],
+            vec![0, 5, 3, 2, 2],
+            random_list(len),
+            (0..len_i64).collect(),
+            (0..len_i64).rev().collect(),
+            vec![42; len],
+            (0..len_i64).map(|i| i % 3).collect(),
+        ]
+    }
+
+    #[test]
+    fn test_parallel_merge_sort() {
+        for threads in [0, 1, 3, 8] {
+            for input in inputs() {
+                let mut expected = input.clone();
+                expected.sort_unstable();
+                let mut list = input;
+                parallel_merge_sort(&mut list, threads);
+                assert_eq!(list, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parallel_merge_sort_stable() {
+        let len = SEQUENTIAL_THRESHOLD * 4;
+        let mut list: Vec<(usize, usize)> = (0..len).map(|i| (i % 10, i)).collect();
+        let mut expected = list.clone();
+        expected.sort_by_key(|pair| pair.0);
+        parallel_merge_sort_by_key(&mut list, 4, |pair| pair.0);
+        assert_eq!(list, expected);
+    }
+
+    #[test]
+    fn test_parallel_quicksort() {
+        for threads in [0, 1, 3, 8] {
+            for input in inputs() {
+                let mut expected = input.clone();
+                expected.sort_unstable();
+                let mut list = input;
+                parallel_quicksort(&mut list, threads);
+                assert_eq!(list, expected);
+            }
+        }
+
+        let mut list = random_list(SEQUENTIAL_THRESHOLD * 4);
+        let mut expected = list.clone();
+        expected.sort_unstable_by(|a, b| b.cmp(a));
+        parallel_quicksort_by(&mut list, 4, |a, b| b.cmp(a));
+        assert_eq!(list, expected);
+    }
+
+    #[test]
+    fn test_parallel_sample_sort() {
+        for threads in [0, 1, 3, 8] {
+            for input in inputs() {
+                let mut expected = input.clone();
+                expected.sort_unstable();
+                let mut list = input;
+                parallel_sample_sort(&mut list, threads, 7);
+                assert_eq!(list, expected);
+            }
+        }
+
+        let mut list: Vec<String> = random_list(SEQUENTIAL_THRESHOLD * 2)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let mut expected = list.clone();
+        expected.sort_by_key(String::len);
+        parallel_sample_sort_by_key(&mut list, 4, 7, String::len);
+        assert!(list
+            .iter()
+            .map(String::len)
+            .eq(expected.iter().map(String::len)));
+    }
+
+    #[test]
+    fn test_distribute() {
+        let mut list = ['c', 'a', 'b', 'a', 'c', 'b'];
+        let mut bucket_of = [2, 0, 1, 0, 2, 1];
+        let bounds = distribute(&mut list, &mut bucket_of, 3);
+        assert_eq!(bounds, [0, 2, 4, 6]);
+        assert_eq!(list, ['a', 'a', 'b', 'b', 'c', 'c']);
+        assert_eq!(bucket_of, [0, 0, 1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn test_parallel_sample_sort_seeds() {
+        let input = random_list(SEQUENTIAL_THRESHOLD * 2);
+        let mut expected = input.clone();
+        expected.sort_unstable();
+        for seed in 0..4 {
+            let mut list = input.clone();
+            parallel_sample_sort(&mut list, 4, seed);
+            assert_eq!(list, expected);
+        }
+    }
+}
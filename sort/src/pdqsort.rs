@@ -0,0 +1,547 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! Pattern-defeating quicksort, 简称 pdqsort.
+//!
+//! 它结合了快速排序的平均性能与堆排序的最坏情况保证, 并针对常见的输入模式做了优化:
+//! - 使用块分区 (block partitioning) 减少分支预测失败
+//! - 检测到分区很不平衡时, 打乱部分元素以破坏输入中的模式, 超过一定次数后改用堆排序
+//! - 检测到数组已基本有序时, 尝试用部分插入排序直接完成排序
+//! - 与前一个基准值相等的元素被集中处理, 所以有大量重复元素时是线性的
+//!
+//! 参考 [pdqsort](https://github.com/orlp/pdqsort)
+
+use std::cmp::Ordering;
+
+use crate::heap_sort::heap_sort_by;
+use crate::insertion_sort::insertion_sort_by;
+use crate::sorter::{partial_compare, Sorter};
+use crate::stats::{swap, SwapCounter};
+
+/// 元素个数不超过这个值时, 使用插入排序.
+const MAX_INSERTION: usize = 20;
+
+/// 块分区中每个块的大小.
+const BLOCK: usize = 64;
+
+/// Pattern-defeating quicksort.
+///
+/// 它不是稳定排序, 最坏情况下的时间复杂度是 `O(n log(n))`.
+pub fn pdqsort<T>(arr: &mut [T])
+where
+    T: PartialOrd,
+{
+    pdqsort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的 pdqsort.
+pub fn pdqsort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+
+    // 允许出现不平衡分区的次数, 超过之后就改用堆排序.
+    let limit = usize::BITS - len.leading_zeros();
+    recurse(arr, &mut compare, None, limit);
+}
+
+/// 按照 `f` 提取出的键值进行 pdqsort.
+pub fn pdqsort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    pdqsort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+/// 排序 `arr`, `pred` 是该子数组左侧紧邻的那个基准值, 它不大于 `arr` 中的任何元素.
+fn recurse<'a, T, F>(mut arr: &'a mut [T], compare: &mut F, mut pred: Option<&'a T>, mut limit: u32)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // 上一次分区是否平衡.
+    let mut was_balanced = true;
+    // 上一次分区时是否没有交换任何元素, 即数组已经分好区了.
+    let mut was_partitioned = true;
+
+    loop {
+        let len = arr.len();
+        if len <= MAX_INSERTION {
+            insertion_sort_by(arr, &mut *compare);
+            return;
+        }
+
+        // 不平衡的分区太多了, 改用堆排序以保证 `O(n log(n))`.
+        if limit == 0 {
+            heap_sort_by(arr, &mut *compare);
+            return;
+        }
+
+        // 上一次分区不平衡, 打乱一些元素, 以破坏输入中的模式.
+        if !was_balanced {
+            break_patterns(arr);
+            limit -= 1;
+        }
+
+        let (pivot, likely_sorted) = choose_pivot(arr, compare);
+
+        // 数组很可能已经是有序的了, 尝试用部分插入排序来完成排序.
+        if was_balanced
+            && was_partitioned
+            && likely_sorted
+            && partial_insertion_sort(arr, compare)
+        {
+            return;
+        }
+
+        // 如果选出的基准值与前一个基准值相等, 那它就是数组中最小的元素,
+        // 把所有与它相等的元素都放到左侧, 它们已经在最终位置了.
+        if let Some(pred) = pred {
+            if compare(pred, &arr[pivot]) != Ordering::Less {
+                let middle = partition_equal(arr, pivot, compare);
+                arr = &mut std::mem::take(&mut arr)[middle..];
+                continue;
+            }
+        }
+
+        let (middle, partitioned) = partition(arr, pivot, compare);
+        was_balanced = middle.min(len - middle) >= len / 8;
+        was_partitioned = partitioned;
+
+        // 先递归处理较短的那一侧, 较长的那一侧在循环中继续处理.
+        let (left, right) = std::mem::take(&mut arr).split_at_mut(middle);
+        let (pivot, right) = right.split_at_mut(1);
+        let pivot = &pivot[0];
+
+        if left.len() < right.len() {
+            recurse(left, compare, pred, limit);
+            arr = right;
+            pred = Some(pivot);
+        } else {
+            recurse(right, compare, Some(pivot), limit);
+            arr = left;
+        }
+    }
+}
+
+/// 以 `arr[pivot]` 为基准值进行分区, 左侧都小于它, 右侧都大于等于它.
+///
+/// 返回基准值的最终位置, 以及分区之前数组是否已经分好区了.
+fn partition<T, F>(arr: &mut [T], pivot: usize, compare: &mut F) -> (usize, bool)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // 先把基准值放到数组头部.
+    swap(arr, 0, pivot);
+    let (middle, was_partitioned) = {
+        let (pivot, rest) = arr.split_at_mut(1);
+        let pivot = &pivot[0];
+
+        // 跳过左侧已经小于基准值的元素, 以及右侧已经大于等于基准值的元素.
+        let len = rest.len();
+        let mut left = 0;
+        let mut right = len;
+        while left < right && compare(&rest[left], pivot) == Ordering::Less {
+            left += 1;
+        }
+        while left < right && compare(&rest[right - 1], pivot) != Ordering::Less {
+            right -= 1;
+        }
+
+        (
+            left + partition_in_blocks(&mut rest[left..right], pivot, compare),
+            left >= right,
+        )
+    };
+
+    // 最后把基准值放到它的最终位置.
+    swap(arr, 0, middle);
+    (middle, was_partitioned)
+}
+
+/// 块分区 (block partitioning), 参考了 `BlockQuicksort`.
+///
+/// 每次从左右两端各取一个块, 先只做比较, 记录下需要交换的元素的偏移量,
+/// 然后再批量交换它们. 这样比较操作中就没有分支了.
+///
+/// 返回小于基准值的元素个数.
+#[allow(clippy::cast_possible_truncation)]
+fn partition_in_blocks<T, F>(arr: &mut [T], pivot: &T, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // 左侧块的起始位置, 以及右侧块的结束位置.
+    let mut left = 0;
+    let mut right = arr.len();
+
+    let mut block_left = BLOCK;
+    let mut offsets_left = [0_u8; BLOCK];
+    let mut start_left = 0;
+    let mut end_left = 0;
+
+    let mut block_right = BLOCK;
+    let mut offsets_right = [0_u8; BLOCK];
+    let mut start_right = 0;
+    let mut end_right = 0;
+
+    loop {
+        // 剩下的元素不多了, 调整最后一轮的块大小, 使得左右两个块刚好覆盖剩下的元素.
+        let is_done = right - left <= 2 * BLOCK;
+        if is_done {
+            let mut remaining = right - left;
+            if start_left < end_left || start_right < end_right {
+                remaining -= BLOCK;
+            }
+            if start_left < end_left {
+                block_right = remaining;
+            } else if start_right < end_right {
+                block_left = remaining;
+            } else {
+                block_left = remaining / 2;
+                block_right = remaining - block_left;
+            }
+        }
+
+        // 扫描左侧块, 记录下大于等于基准值的元素.
+        if start_left == end_left {
+            start_left = 0;
+            end_left = 0;
+            for i in 0..block_left {
+                offsets_left[end_left] = i as u8;
+                end_left += usize::from(compare(&arr[left + i], pivot) != Ordering::Less);
+            }
+        }
+
+        // 扫描右侧块, 记录下小于基准值的元素.
+        if start_right == end_right {
+            start_right = 0;
+            end_right = 0;
+            for i in 0..block_right {
+                offsets_right[end_right] = i as u8;
+                end_right += usize::from(compare(&arr[right - 1 - i], pivot) == Ordering::Less);
+            }
+        }
+
+        // 两两交换左右两侧放错位置的元素.
+        let count = (end_left - start_left).min(end_right - start_right);
+        for k in 0..count {
+            swap(
+                arr,
+                left + offsets_left[start_left + k] as usize,
+                right - 1 - offsets_right[start_right + k] as usize,
+            );
+        }
+        start_left += count;
+        start_right += count;
+
+        if start_left == end_left {
+            left += block_left;
+        }
+        if start_right == end_right {
+            right -= block_right;
+        }
+
+        if is_done {
+            break;
+        }
+    }
+
+    if start_left < end_left {
+        // 左侧块还有放错位置的元素, 把它们依次移到最右侧.
+        while start_left < end_left {
+            end_left -= 1;
+            swap(arr, left + offsets_left[end_left] as usize, right - 1);
+            right -= 1;
+        }
+        right
+    } else if start_right < end_right {
+        // 右侧块还有放错位置的元素, 把它们依次移到最左侧.
+        while start_right < end_right {
+            end_right -= 1;
+            swap(arr, left, right - 1 - offsets_right[end_right] as usize);
+            left += 1;
+        }
+        left
+    } else {
+        left
+    }
+}
+
+/// 把与 `arr[pivot]` 相等的元素放到左侧, 大于它的元素放到右侧.
+///
+/// 调用者保证数组中没有比基准值更小的元素. 返回与基准值相等的元素个数.
+fn partition_equal<T, F>(arr: &mut [T], pivot: usize, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    swap(arr, 0, pivot);
+    let (pivot, rest) = arr.split_at_mut(1);
+    let pivot = &pivot[0];
+
+    let mut left = 0;
+    let mut right = rest.len();
+    loop {
+        while left < right && compare(pivot, &rest[left]) != Ordering::Less {
+            left += 1;
+        }
+        while left < right && compare(pivot, &rest[right - 1]) == Ordering::Less {
+            right -= 1;
+        }
+        if left >= right {
+            break;
+        }
+        right -= 1;
+        swap(rest, left, right);
+        left += 1;
+    }
+
+    // 加上基准值本身.
+    left + 1
+}
+
+/// 选择基准值, 返回它的位置, 以及数组是否很可能已经有序了.
+///
+/// 较短的数组使用三数取中, 较长的数组使用 Tukey's ninther.
+/// 如果在选择的过程中交换次数太多, 说明数组很可能是逆序的, 就把它反转过来.
+fn choose_pivot<T, F>(arr: &mut [T], compare: &mut F) -> (usize, bool)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // 元素个数不少于这个值时, 使用 Tukey's ninther.
+    const SHORTEST_MEDIAN_OF_MEDIANS: usize = 50;
+    // 最多交换的次数.
+    const MAX_SWAPS: usize = 4 * 3;
+
+    let len = arr.len();
+    let mut first = len / 4;
+    let mut second = len / 4 * 2;
+    let mut third = len / 4 * 3;
+    let mut swaps = 0;
+
+    if len >= 8 {
+        if len >= SHORTEST_MEDIAN_OF_MEDIANS {
+            for index in [&mut first, &mut second, &mut third] {
+                let mut prev = *index - 1;
+                let mut next = *index + 1;
+                sort3(arr, compare, &mut prev, index, &mut next, &mut swaps);
+            }
+        }
+        sort3(arr, compare, &mut first, &mut second, &mut third, &mut swaps);
+    }
+
+    if swaps < MAX_SWAPS {
+        (second, swaps == 0)
+    } else {
+        SwapCounter::add(len / 2);
+        arr.reverse();
+        (len - 1 - second, true)
+    }
+}
+
+/// 交换索引值, 使得 `arr[a] <= arr[b]`.
+fn sort2<T, F>(arr: &[T], compare: &mut F, a: &mut usize, b: &mut usize, swaps: &mut usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if compare(&arr[*b], &arr[*a]) == Ordering::Less {
+        std::mem::swap(a, b);
+        *swaps += 1;
+    }
+}
+
+/// 交换索引值, 使得 `arr[a] <= arr[b] <= arr[c]`.
+fn sort3<T, F>(
+    arr: &[T],
+    compare: &mut F,
+    a: &mut usize,
+    b: &mut usize,
+    c: &mut usize,
+    swaps: &mut usize,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort2(arr, compare, a, b, swaps);
+    sort2(arr, compare, b, c, swaps);
+    sort2(arr, compare, a, b, swaps);
+}
+
+/// 对基本有序的数组, 移动少量放错位置的元素来完成排序.
+///
+/// 如果数组最终是有序的, 就返回 `true`.
+fn partial_insertion_sort<T, F>(arr: &mut [T], compare: &mut F) -> bool
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // 最多移动的元素个数.
+    const MAX_STEPS: usize = 5;
+    // 数组较短时, 移动元素的开销不划算.
+    const SHORTEST_SHIFTING: usize = 50;
+
+    let len = arr.len();
+    let mut i = 1;
+
+    for _step in 0..MAX_STEPS {
+        // 找到下一对逆序的相邻元素.
+        while i < len && compare(&arr[i], &arr[i - 1]) != Ordering::Less {
+            i += 1;
+        }
+
+        if i == len {
+            return true;
+        }
+        if len < SHORTEST_SHIFTING {
+            return false;
+        }
+
+        // 交换这一对元素, 然后把它们分别移到合适的位置.
+        swap(arr, i - 1, i);
+        shift_tail(&mut arr[..i], compare);
+        shift_head(&mut arr[i..], compare);
+    }
+
+    false
+}
+
+/// 将最后一个元素向左移动, 直到遇到不大于它的元素.
+fn shift_tail<T, F>(arr: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut i = arr.len();
+    while i >= 2 && compare(&arr[i - 1], &arr[i - 2]) == Ordering::Less {
+        swap(arr, i - 1, i - 2);
+        i -= 1;
+    }
+}
+
+/// 将第一个元素向右移动, 直到遇到不小于它的元素.
+fn shift_head<T, F>(arr: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = arr.len();
+    let mut i = 0;
+    while i + 1 < len && compare(&arr[i + 1], &arr[i]) == Ordering::Less {
+        swap(arr, i, i + 1);
+        i += 1;
+    }
+}
+
+/// 用伪随机数交换数组中间的几个元素, 以破坏可能导致分区不平衡的模式.
+#[allow(clippy::cast_possible_truncation)]
+fn break_patterns<T>(arr: &mut [T]) {
+    let len = arr.len();
+    if len < 8 {
+        return;
+    }
+
+    // xorshift 伪随机数, 以数组长度作为种子, 所以结果是可重现的.
+    let mut random = len as u64;
+    let mut next_random = || {
+        random ^= random << 13;
+        random ^= random >> 7;
+        random ^= random << 17;
+        random as usize
+    };
+
+    let modulus = len.next_power_of_two();
+    let pos = len / 4 * 2;
+    for i in 0..3 {
+        let mut other = next_random() & (modulus - 1);
+        if other >= len {
+            other -= len;
+        }
+        swap(arr, pos - 1 + i, other);
+    }
+}
+
+/// Pattern-defeating quicksort.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PdqSort;
+
+impl<T> Sorter<T> for PdqSort {
+    fn name(&self) -> &'static str {
+        "pdqsort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        pdqsort_by(arr, compare);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pdqsort, pdqsort_by, pdqsort_by_key};
+
+    #[test]
+    fn test_pdqsort() {
+        let mut list = [0, 5, 3, 2, 2];
+        pdqsort(&mut list);
+        assert_eq!(list, [0, 2, 2, 3, 5]);
+
+        let mut list = [-2, -5, -45];
+        pdqsort(&mut list);
+        assert_eq!(list, [-45, -5, -2]);
+
+        let mut list = [
+            -998_166, -996_360, -995_703, -995_238, -995_066, -994_740, -992_987, -983_833,
+            -987_905, -980_069, -977_640,
+        ];
+        pdqsort(&mut list);
+        assert_eq!(
+            list,
+            [
+                -998_166, -996_360, -995_703, -995_238, -995_066, -994_740, -992_987, -987_905,
+                -983_833, -980_069, -977_640,
+            ]
+        );
+
+        let mut list = "EASYQUESTION".chars().collect::<Vec<_>>();
+        pdqsort(&mut list);
+        assert_eq!(
+            list,
+            ['A', 'E', 'E', 'I', 'N', 'O', 'Q', 'S', 'S', 'T', 'U', 'Y']
+        );
+    }
+
+    #[test]
+    fn test_pdqsort_by() {
+        let mut list = [0, 5, 3, 2, 2];
+        pdqsort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = ["apple", "fig", "banana"];
+        pdqsort_by_key(&mut list, |s| s.len());
+        assert_eq!(list, ["fig", "apple", "banana"]);
+    }
+
+    #[test]
+    fn test_pdqsort_patterns() {
+        let len: usize = 10_000;
+        let organ_pipe: Vec<usize> = (0..len / 2).chain((0..len / 2).rev()).collect();
+        let sawtooth: Vec<usize> = (0..len).map(|i| i % 64).collect();
+        let all_equal: Vec<usize> = vec![7; len];
+        let descending: Vec<usize> = (0..len).rev().collect();
+        let random: Vec<usize> = (0..len).map(|i| i.wrapping_mul(2_654_435_761) % 1000).collect();
+
+        for input in [organ_pipe, sawtooth, all_equal, descending, random] {
+            let mut expected = input.clone();
+            expected.sort_unstable();
+
+            // 比较次数应该是 `O(n log(n))` 的, 而不是 `O(n^2)`.
+            let mut comparisons: usize = 0;
+            let mut list = input;
+            pdqsort_by(&mut list, |a, b| {
+                comparisons += 1;
+                a.cmp(b)
+            });
+            assert_eq!(list, expected);
+            assert!(comparisons < 4 * len * len.ilog2() as usize);
+        }
+    }
+}
@@ -4,37 +4,64 @@
 
 #![allow(dead_code)]
 
-use crate::insertion_sort::insertion_sort;
+use std::cmp::Ordering;
+
+use crate::insertion_sort::insertion_sort_by;
+use crate::sorter::{partial_compare, Sorter};
+use crate::stats::swap;
 
 /// 使用最后一个元素作为基准值 pivot
 ///
 /// 如果是已排序好的数组, 这种算法是最差情况
 #[inline]
 pub fn quicksort<T: PartialOrd>(arr: &mut [T]) {
+    quicksort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的快速排序.
+pub fn quicksort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     if arr.len() < 2 {
         return;
     }
-    tail_quicksort_helper(arr, 0, arr.len() - 1);
+    tail_quicksort_helper(arr, 0, arr.len() - 1, &mut compare);
+}
+
+/// 按照 `f` 提取出的键值进行快速排序.
+pub fn quicksort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    quicksort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
 }
 
-fn tail_quicksort_helper<T: PartialOrd>(arr: &mut [T], low: usize, high: usize) {
+fn tail_quicksort_helper<T, F>(arr: &mut [T], low: usize, high: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     if low >= high {
         return;
     }
 
     // 按照基数的位置, 将数组划分成左右两个子数组.
-    let pivot_index = partition_pivot_at_right(arr, low, high);
+    let pivot_index = partition_pivot_at_right(arr, low, high, compare);
     // 对左右两个子数组分别执行快速排序
     if pivot_index > low + 1 {
-        tail_quicksort_helper(arr, low, pivot_index - 1);
+        tail_quicksort_helper(arr, low, pivot_index - 1, compare);
     }
     if pivot_index + 1 < high {
-        tail_quicksort_helper(arr, pivot_index + 1, high);
+        tail_quicksort_helper(arr, pivot_index + 1, high, compare);
     }
 }
 
 // 选择最右侧的元素作为基准值
-fn partition_pivot_at_right<T: PartialOrd>(arr: &mut [T], low: usize, high: usize) -> usize {
+fn partition_pivot_at_right<T, F>(arr: &mut [T], low: usize, high: usize, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     let pivot_index = high;
 
     // 以 pivot 为基准, 把数组划分成三部分: 小于 pivot, pivot, 大于等于 pivot
@@ -42,15 +69,15 @@ fn partition_pivot_at_right<T: PartialOrd>(arr: &mut [T], low: usize, high: usiz
     let mut i = low;
     // j 用于遍历整个数组
     for j in low..high {
-        if arr[j] < arr[pivot_index] {
-            arr.swap(i, j);
+        if compare(&arr[j], &arr[pivot_index]) == Ordering::Less {
+            swap(arr, i, j);
             i += 1;
         }
     }
 
     // 最后把基准值 pivot 移到合适的位置.
     // 此时, 数组中元素的顺序满足以下条件: 小于 pivot, pivot, 大于等于 pivot
-    arr.swap(i, pivot_index);
+    swap(arr, i, pivot_index);
     // 返回的是 pivot 所在的位置
     i
 }
@@ -60,30 +87,53 @@ fn partition_pivot_at_right<T: PartialOrd>(arr: &mut [T], low: usize, high: usiz
 /// 果数组已经是逆序排序的, 这种算法是最差情况, 时间复杂度是 `O(n^2)`
 #[inline]
 pub fn head_quicksort<T: PartialOrd>(arr: &mut [T]) {
+    head_quicksort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare`, 总是选择第一个元素作为基准值.
+pub fn head_quicksort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     if arr.len() < 2 {
         return;
     }
-    head_quicksort_helper(arr, 0, arr.len() - 1);
+    head_quicksort_helper(arr, 0, arr.len() - 1, &mut compare);
+}
+
+/// 按照 `f` 提取出的键值, 总是选择第一个元素作为基准值.
+pub fn head_quicksort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    head_quicksort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
 }
 
-fn head_quicksort_helper<T: PartialOrd>(arr: &mut [T], low: usize, high: usize) {
+fn head_quicksort_helper<T, F>(arr: &mut [T], low: usize, high: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     if low >= high {
         return;
     }
 
     // 按照基数的位置, 将数组划分成左右两个子数组.
-    let pivot_index = partition_pivot_at_left(arr, low, high);
+    let pivot_index = partition_pivot_at_left(arr, low, high, compare);
     // 对左右两个子数组分别执行快速排序
     if pivot_index > low + 1 {
-        head_quicksort_helper(arr, low, pivot_index - 1);
+        head_quicksort_helper(arr, low, pivot_index - 1, compare);
     }
     if pivot_index + 1 < high {
-        head_quicksort_helper(arr, pivot_index + 1, high);
+        head_quicksort_helper(arr, pivot_index + 1, high, compare);
     }
 }
 
 /// 选择最左侧的元素作为基准值
-fn partition_pivot_at_left<T: PartialOrd>(arr: &mut [T], low: usize, high: usize) -> usize {
+fn partition_pivot_at_left<T, F>(arr: &mut [T], low: usize, high: usize, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     let pivot_index = low;
 
     // 以 pivot 为基准, 把数组划分成三部分: 小于等于 pivot, pivot, 大于 pivot
@@ -91,15 +141,15 @@ fn partition_pivot_at_left<T: PartialOrd>(arr: &mut [T], low: usize, high: usize
     let mut i = high;
     // j 用于遍历整个数组
     for j in ((low + 1)..=high).rev() {
-        if arr[j] > arr[pivot_index] {
-            arr.swap(i, j);
+        if compare(&arr[j], &arr[pivot_index]) == Ordering::Greater {
+            swap(arr, i, j);
             i -= 1;
         }
     }
 
     // 最后把基准值 pivot 移到合适的位置.
     // 此时, 数组中元素的顺序满足以下条件: 小于等于 pivot, pivot, 大于 pivot
-    arr.swap(i, pivot_index);
+    swap(arr, i, pivot_index);
     // 返回的是 pivot 所在的位置
     i
 }
@@ -107,30 +157,58 @@ fn partition_pivot_at_left<T: PartialOrd>(arr: &mut [T], low: usize, high: usize
 /// 总是选择第一个元素作为基准值, 并使用双指针法进行数组分区.
 #[inline]
 pub fn two_pointer_quicksort<T: PartialOrd>(arr: &mut [T]) {
+    two_pointer_quicksort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的双指针快速排序.
+pub fn two_pointer_quicksort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     if arr.len() < 2 {
         return;
     }
-    two_pointer_quicksort_helper(arr, 0, arr.len() - 1);
+    two_pointer_quicksort_helper(arr, 0, arr.len() - 1, &mut compare);
+}
+
+/// 按照 `f` 提取出的键值进行双指针快速排序.
+pub fn two_pointer_quicksort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    two_pointer_quicksort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
 }
 
-fn two_pointer_quicksort_helper<T: PartialOrd>(arr: &mut [T], low: usize, high: usize) {
+fn two_pointer_quicksort_helper<T, F>(arr: &mut [T], low: usize, high: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     if low >= high {
         return;
     }
 
     // 按照基数的位置, 将数组划分成左右两个子数组.
-    let pivot_index = partition_with_two_pointers(arr, low, high);
+    let pivot_index = partition_with_two_pointers(arr, low, high, compare);
     // 对左右两个子数组分别执行快速排序
     if pivot_index > low + 1 {
-        two_pointer_quicksort_helper(arr, low, pivot_index - 1);
+        two_pointer_quicksort_helper(arr, low, pivot_index - 1, compare);
     }
     if pivot_index + 1 < high {
-        two_pointer_quicksort_helper(arr, pivot_index + 1, high);
+        two_pointer_quicksort_helper(arr, pivot_index + 1, high, compare);
     }
 }
 
 /// 使用双指针法选择最左侧的元素作为基准值
-fn partition_with_two_pointers<T: PartialOrd>(arr: &mut [T], low: usize, high: usize) -> usize {
+fn partition_with_two_pointers<T, F>(
+    arr: &mut [T],
+    low: usize,
+    high: usize,
+    compare: &mut F,
+) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     let pivot_index = low;
 
     // 使用双指针法遍历数组, 以 pivot 为基准, 把数组划分成三部分:
@@ -139,22 +217,22 @@ fn partition_with_two_pointers<T: PartialOrd>(arr: &mut [T], low: usize, high: u
     let mut right: usize = high;
     while left < right {
         // right 的位置左移, 直到 arr[right] 小于等于 pivot
-        while left < right && arr[right] > arr[pivot_index] {
+        while left < right && compare(&arr[right], &arr[pivot_index]) == Ordering::Greater {
             right -= 1;
         }
 
         // left 的位置右移, 直到 arr[left] 大于 pivot
-        while left < right && arr[left] <= arr[pivot_index] {
+        while left < right && compare(&arr[left], &arr[pivot_index]) != Ordering::Greater {
             left += 1;
         }
 
         // 交换元素
-        arr.swap(left, right);
+        swap(arr, left, right);
     }
 
     // 最后把基准值 pivot 移到合适的位置.
     // 此时, 数组中元素的顺序满足以下条件: 小于等于 pivot, pivot, 大于 pivot
-    arr.swap(left, pivot_index);
+    swap(arr, left, pivot_index);
     // 返回的是 pivot 所在的位置
     left
 }
@@ -162,13 +240,33 @@ fn partition_with_two_pointers<T: PartialOrd>(arr: &mut [T], low: usize, high: u
 /// 如果元素较少, 就使用插入排序
 #[inline]
 pub fn insertion_quicksort<T: PartialOrd>(arr: &mut [T]) {
+    insertion_quicksort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare`, 如果元素较少, 就使用插入排序.
+pub fn insertion_quicksort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     if arr.len() < 2 {
         return;
     }
-    insertion_quicksort_helper(arr, 0, arr.len() - 1);
+    insertion_quicksort_helper(arr, 0, arr.len() - 1, &mut compare);
+}
+
+/// 按照 `f` 提取出的键值, 如果元素较少, 就使用插入排序.
+pub fn insertion_quicksort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    insertion_quicksort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
 }
 
-fn insertion_quicksort_helper<T: PartialOrd>(arr: &mut [T], low: usize, high: usize) {
+fn insertion_quicksort_helper<T, F>(arr: &mut [T], low: usize, high: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     const CUTOFF: usize = 24;
 
     if low >= high {
@@ -177,18 +275,18 @@ fn insertion_quicksort_helper<T: PartialOrd>(arr: &mut [T], low: usize, high: us
 
     // 数组中的元数个数低于一个阈值时, 使用插入排序
     if high - low + 1 < CUTOFF {
-        insertion_sort(&mut arr[low..=high]);
+        insertion_sort_by(&mut arr[low..=high], &mut *compare);
         return;
     }
 
     // 按照基数的位置, 将数组划分成左右两个子数组.
-    let pivot_index = partition_pivot_at_right(arr, low, high);
+    let pivot_index = partition_pivot_at_right(arr, low, high, compare);
     // 对左右两个子数组分别执行快速排序
     if pivot_index > low + 1 {
-        insertion_quicksort_helper(arr, low, pivot_index - 1);
+        insertion_quicksort_helper(arr, low, pivot_index - 1, compare);
     }
     if pivot_index + 1 < high {
-        insertion_quicksort_helper(arr, pivot_index + 1, high);
+        insertion_quicksort_helper(arr, pivot_index + 1, high, compare);
     }
 }
 
@@ -197,13 +295,33 @@ fn insertion_quicksort_helper<T: PartialOrd>(arr: &mut [T], low: usize, high: us
 /// 空间复杂度是 `O(n)`
 #[inline]
 pub fn iterative_quicksort<T: PartialOrd>(arr: &mut [T]) {
+    iterative_quicksort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的迭代形式的快速排序.
+pub fn iterative_quicksort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     if arr.len() < 2 {
         return;
     }
-    iterative_quicksort_helper(arr, 0, arr.len() - 1);
+    iterative_quicksort_helper(arr, 0, arr.len() - 1, &mut compare);
+}
+
+/// 按照 `f` 提取出的键值进行迭代形式的快速排序.
+pub fn iterative_quicksort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    iterative_quicksort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
 }
 
-fn iterative_quicksort_helper<T: PartialOrd>(arr: &mut [T], low: usize, high: usize) {
+fn iterative_quicksort_helper<T, F>(arr: &mut [T], low: usize, high: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     if low >= high {
         return;
     }
@@ -218,7 +336,7 @@ fn iterative_quicksort_helper<T: PartialOrd>(arr: &mut [T], low: usize, high: us
     // 出栈顺序是 (high, low)
     while let (Some(high), Some(low)) = (stack.pop(), stack.pop()) {
         // 按照基数的位置, 将数组划分成左右两个子数组.
-        let pivot_index = partition_pivot_at_right(arr, low, high);
+        let pivot_index = partition_pivot_at_right(arr, low, high, compare);
         // 对左右两个子数组分别执行快速排序
         // 如果左侧子数组还有元素, 就入栈
         if pivot_index > low + 1 {
@@ -233,9 +351,83 @@ fn iterative_quicksort_helper<T: PartialOrd>(arr: &mut [T], low: usize, high: us
     }
 }
 
+/// 快速排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct QuickSort;
+
+impl<T> Sorter<T> for QuickSort {
+    fn name(&self) -> &'static str {
+        "quicksort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        quicksort_by(arr, compare);
+    }
+}
+
+/// 元素较少时使用插入排序的快速排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InsertionQuickSort;
+
+impl<T> Sorter<T> for InsertionQuickSort {
+    fn name(&self) -> &'static str {
+        "insertion-quicksort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        insertion_quicksort_by(arr, compare);
+    }
+}
+
+/// 使用第一个元素作为基准值的快速排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HeadQuickSort;
+
+impl<T> Sorter<T> for HeadQuickSort {
+    fn name(&self) -> &'static str {
+        "head-quicksort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        head_quicksort_by(arr, compare);
+    }
+}
+
+/// 使用双指针法进行数组分区的快速排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TwoPointerQuickSort;
+
+impl<T> Sorter<T> for TwoPointerQuickSort {
+    fn name(&self) -> &'static str {
+        "two-pointer-quicksort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        two_pointer_quicksort_by(arr, compare);
+    }
+}
+
+/// 迭代形式的快速排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IterativeQuickSort;
+
+impl<T> Sorter<T> for IterativeQuickSort {
+    fn name(&self) -> &'static str {
+        "iterative-quicksort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        iterative_quicksort_by(arr, compare);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::quicksort::{head_quicksort, insertion_quicksort, iterative_quicksort, quicksort, two_pointer_quicksort};
+    use crate::quicksort::{
+        head_quicksort, head_quicksort_by, insertion_quicksort, insertion_quicksort_by_key,
+        iterative_quicksort, iterative_quicksort_by, quicksort, quicksort_by, quicksort_by_key,
+        two_pointer_quicksort, two_pointer_quicksort_by,
+    };
 
     fn run_test(sort_func: fn(arr: &mut [i32])) {
         let mut list = [1, 8, 3, 9, 4];
@@ -288,4 +480,36 @@ mod tests {
     fn test_iterative_quicksort() {
         run_test(iterative_quicksort);
     }
+
+    #[test]
+    fn test_quicksort_by() {
+        let descending = |a: &i32, b: &i32| b.cmp(a);
+        let mut list = [0, 5, 3, 2, 2];
+        quicksort_by(&mut list, descending);
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [0, 5, 3, 2, 2];
+        head_quicksort_by(&mut list, descending);
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [0, 5, 3, 2, 2];
+        two_pointer_quicksort_by(&mut list, descending);
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [0, 5, 3, 2, 2];
+        iterative_quicksort_by(&mut list, descending);
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [1.5, -0.5, 3.25, 0.0];
+        quicksort_by(&mut list, f64::total_cmp);
+        assert!(list.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let mut list = ["apple", "fig", "banana"];
+        quicksort_by_key(&mut list, |s| s.len());
+        assert_eq!(list, ["fig", "apple", "banana"]);
+
+        let mut list: Vec<i32> = (0..100).rev().collect();
+        insertion_quicksort_by_key(&mut list, |num| -num);
+        assert_eq!(list, (0..100).rev().collect::<Vec<_>>());
+    }
 }
@@ -2,51 +2,248 @@
 // Use of this source is governed by General Public License that can be found
 // in the LICENSE file.
 
-#[allow(clippy::cast_possible_truncation)]
-pub fn radix_sort(arr: &mut [u32]) {
-    const fn num_digits(mut num: u32) -> usize {
-        let mut count: usize = 0;
-        while num != 0 {
-            count += 1;
-            num /= 10;
-        }
-        count
-    }
+/// 可以用于基数排序的键值.
+///
+/// 键值被看作是 `BYTES` 个字节组成的无符号整数, 按字节比较的顺序与键值本身的顺序一致.
+pub trait RadixKey: Copy {
+    /// 键值包含的字节数, 也就是 LSD 基数排序需要的轮数.
+    const BYTES: usize;
+
+    /// 返回第 `index` 个字节, 0 表示最低位的字节.
+    fn byte(&self, index: usize) -> u8;
+}
+
+macro_rules! impl_radix_key_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl RadixKey for $t {
+                const BYTES: usize = std::mem::size_of::<$t>();
 
-    if arr.is_empty() {
+                #[allow(clippy::cast_possible_truncation)]
+                #[inline]
+                fn byte(&self, index: usize) -> u8 {
+                    (*self >> (8 * index)) as u8
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_radix_key_signed {
+    ($($t:ty => $u:ty),*) => {
+        $(
+            impl RadixKey for $t {
+                const BYTES: usize = std::mem::size_of::<$t>();
+
+                // 翻转符号位, 这样负数就排在了正数的前面, 而且负数之间的相对顺序不变.
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                #[inline]
+                fn byte(&self, index: usize) -> u8 {
+                    const SIGN_BIT: $u = 1 << (<$u>::BITS - 1);
+                    ((*self as $u ^ SIGN_BIT) >> (8 * index)) as u8
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_key_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_radix_key_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+/// LSD (least significant digit) 基数排序.
+///
+/// 从最低位的字节开始, 每一轮都对当前字节做一次计数排序 (counting sort).
+/// 计数排序是稳定的, 所以前面几轮排好的低位顺序会被保留下来.
+/// 如果某一轮所有元素的字节都相同, 就跳过这一轮.
+///
+/// 时间复杂度是 `O(w * (n + 256))`, 其中 `w` 是键值的字节数; 空间复杂度是 `O(n)`.
+///
+/// 它是稳定排序.
+pub fn radix_sort<T>(arr: &mut [T])
+where
+    T: RadixKey,
+{
+    lsd_sort(arr, |item| *item);
+}
+
+/// 按照 `f` 提取出的整数键值进行 LSD 基数排序.
+///
+/// 先对 `(键值, 原始位置)` 组成的数组进行排序, 再依照排好的位置原地交换元素,
+/// 所以元素本身不需要实现 `Clone`, 而 `f` 对每个元素也只调用一次.
+///
+/// 它是稳定排序.
+pub fn radix_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: RadixKey,
+    F: FnMut(&T) -> K,
+{
+    if arr.len() < 2 {
         return;
     }
-    // 获取最大的位数
-    let max_digits: usize = arr
+    let mut keys: Vec<(K, usize)> = arr
         .iter()
-        .map(|num| num_digits(*num))
-        .max()
-        .unwrap_or_default();
-
-    for i in 0..max_digits {
-        // bucket 长度为10, 代表了数字 0~9.
-        let mut buckets = vec![vec![]; 10];
-
-        for num in arr.iter() {
-            // 这个 index 是关键, 它是每个元素在当前位上的数字
-            let index: u32 = *num / 10_u32.pow(i as u32) % 10;
-            buckets[index as usize].push(*num);
+        .enumerate()
+        .map(|(index, item)| (f(item), index))
+        .collect();
+    lsd_sort(&mut keys, |pair| pair.0);
+    let order: Vec<usize> = keys.into_iter().map(|pair| pair.1).collect();
+    apply_permutation(arr, order);
+}
+
+fn lsd_sort<T, K, F>(arr: &mut [T], key: F)
+where
+    T: Copy,
+    K: RadixKey,
+    F: Fn(&T) -> K,
+{
+    if arr.len() < 2 {
+        return;
+    }
+
+    // 两个数组轮流作为输入和输出.
+    let mut buf = arr.to_vec();
+    let mut in_buf = false;
+    for index in 0..K::BYTES {
+        let byte = |item: &T| key(item).byte(index);
+        let moved = if in_buf {
+            counting_pass(&buf, arr, byte)
+        } else {
+            counting_pass(arr, &mut buf, byte)
+        };
+        if moved {
+            in_buf = !in_buf;
         }
+    }
+    if in_buf {
+        arr.copy_from_slice(&buf);
+    }
+}
+
+/// 按照字节 `byte` 把 `src` 中的元素稳定地分配到 `dst` 中.
+///
+/// 如果所有元素的字节都相同, 就什么也不做并返回 `false`.
+fn counting_pass<T, F>(src: &[T], dst: &mut [T], byte: F) -> bool
+where
+    T: Copy,
+    F: Fn(&T) -> u8,
+{
+    let mut counts = [0_usize; 256];
+    for item in src {
+        counts[byte(item) as usize] += 1;
+    }
+    if counts.contains(&src.len()) {
+        return false;
+    }
 
-        let mut index = 0;
-        for bucket in buckets {
-            for num in bucket {
-                // 取出对应的元素, 更新到原始数组中
-                arr[index] = num;
-                index += 1;
+    // 计算每个桶的起始位置.
+    let mut offsets = [0_usize; 256];
+    for i in 1..256 {
+        offsets[i] = offsets[i - 1] + counts[i - 1];
+    }
+
+    for item in src {
+        let bucket = byte(item) as usize;
+        dst[offsets[bucket]] = *item;
+        offsets[bucket] += 1;
+    }
+    true
+}
+
+/// MSD (most significant digit) 基数排序, 用于对字节串进行字典序排序.
+///
+/// 从第一个字节开始, 把元素按照当前字节分到 257 个桶中, 其中第 0 个桶存放已经结束的字节串,
+/// 然后对每个桶递归地处理下一个字节. 较短的子数组改用插入排序.
+///
+/// `String` 和 `&str` 都可以直接排序, UTF-8 编码的字节序与字符的码位顺序是一致的.
+///
+/// 它是稳定排序.
+pub fn msd_radix_sort<T>(arr: &mut [T])
+where
+    T: AsRef<[u8]>,
+{
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+    let mut order: Vec<usize> = (0..len).collect();
+    let mut aux = vec![0; len];
+    msd_helper(arr, &mut order, &mut aux, 0);
+    apply_permutation(arr, order);
+}
+
+fn msd_helper<T>(arr: &[T], order: &mut [usize], aux: &mut [usize], depth: usize)
+where
+    T: AsRef<[u8]>,
+{
+    // 子数组中的元数个数低于一个阈值时, 使用插入排序
+    const CUTOFF: usize = 16;
+
+    // 返回字节串在 `depth` 位置上的桶编号, 字节串已经结束的话返回 0.
+    let bucket_of = |index: usize| -> usize {
+        arr[index]
+            .as_ref()
+            .get(depth)
+            .map_or(0, |&byte| byte as usize + 1)
+    };
+
+    let len = order.len();
+    if len <= CUTOFF {
+        for i in 1..len {
+            let mut j = i;
+            while j > 0 && arr[order[j - 1]].as_ref()[depth..] > arr[order[j]].as_ref()[depth..] {
+                order.swap(j - 1, j);
+                j -= 1;
             }
         }
+        return;
+    }
+
+    let mut counts = [0_usize; 258];
+    for &index in order.iter() {
+        counts[bucket_of(index) + 1] += 1;
+    }
+    for i in 1..counts.len() {
+        counts[i] += counts[i - 1];
+    }
+
+    let aux = &mut aux[..len];
+    for &index in order.iter() {
+        let bucket = bucket_of(index);
+        aux[counts[bucket]] = index;
+        counts[bucket] += 1;
+    }
+    order.copy_from_slice(aux);
+
+    // 第 0 个桶里的字节串都已经结束了, 它们是相等的, 不需要再排序.
+    // 现在 `counts[i]` 是第 `i` 个桶的结束位置.
+    for bucket in 1..257 {
+        let start = counts[bucket - 1];
+        let end = counts[bucket];
+        if end - start > 1 {
+            msd_helper(arr, &mut order[start..end], &mut aux[start..end], depth + 1);
+        }
+    }
+}
+
+/// 原地重排数组, 使得新数组的第 `i` 个元素是原数组的第 `order[i]` 个元素.
+///
+/// 沿着置换中的每个环依次交换元素, 只需要 `O(n)` 次交换.
+fn apply_permutation<T>(arr: &mut [T], mut order: Vec<usize>) {
+    for start in 0..order.len() {
+        let mut current = start;
+        while order[current] != start {
+            let next = order[current];
+            arr.swap(current, next);
+            order[current] = current;
+            current = next;
+        }
+        order[current] = current;
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::radix_sort;
+    use super::{msd_radix_sort, radix_sort, radix_sort_by_key};
 
     #[test]
     fn test_radix_sort() {
@@ -58,4 +255,100 @@ mod tests {
         radix_sort(&mut list);
         assert_eq!(list, [2, 5, 45]);
     }
+
+    #[test]
+    fn test_radix_sort_signed() {
+        let mut list = [-2, -5, -45, 0, i32::MAX, i32::MIN, 7];
+        radix_sort(&mut list);
+        assert_eq!(list, [i32::MIN, -45, -5, -2, 0, 7, i32::MAX]);
+
+        let mut list: [i8; 6] = [127, -128, -1, 0, 1, -127];
+        radix_sort(&mut list);
+        assert_eq!(list, [-128, -127, -1, 0, 1, 127]);
+
+        let mut list: Vec<i64> = (-1000..1000).rev().map(|i| i * 1_000_000_007).collect();
+        let mut expected = list.clone();
+        expected.sort_unstable();
+        radix_sort(&mut list);
+        assert_eq!(list, expected);
+
+        let mut list: [u128; 4] = [u128::MAX, 1 << 100, 0, 1 << 64];
+        radix_sort(&mut list);
+        assert_eq!(list, [0, 1 << 64, 1 << 100, u128::MAX]);
+    }
+
+    #[test]
+    fn test_radix_sort_by_key() {
+        let mut list = [(3, 'a'), (-1, 'b'), (3, 'c'), (-1, 'd'), (0, 'e')];
+        radix_sort_by_key(&mut list, |pair| pair.0);
+        assert_eq!(list, [(-1, 'b'), (-1, 'd'), (0, 'e'), (3, 'a'), (3, 'c')]);
+
+        // 稳定排序可以串联起来使用: 先按次要键排序, 再按主要键排序.
+        let mut list: Vec<(u8, u16, u32)> = (0..300_u16)
+            .map(|i| (u8::try_from(i % 7).unwrap(), i % 5, u32::from(i)))
+            .collect();
+        let mut expected = list.clone();
+        expected.sort_by_key(|item| (item.0, item.1));
+        radix_sort_by_key(&mut list, |item| item.1);
+        radix_sort_by_key(&mut list, |item| item.0);
+        assert_eq!(list, expected);
+    }
+
+    #[test]
+    fn test_msd_radix_sort() {
+        let mut list = [
+            "she",
+            "sells",
+            "seashells",
+            "by",
+            "the",
+            "sea",
+            "shore",
+            "",
+            "s",
+        ];
+        msd_radix_sort(&mut list);
+        assert_eq!(
+            list,
+            [
+                "",
+                "by",
+                "s",
+                "sea",
+                "seashells",
+                "sells",
+                "she",
+                "shore",
+                "the"
+            ]
+        );
+
+        let mut list: Vec<String> = (0..500).map(|i| format!("{}", (i * 7919) % 1000)).collect();
+        let mut expected = list.clone();
+        expected.sort();
+        msd_radix_sort(&mut list);
+        assert_eq!(list, expected);
+
+        let mut list: Vec<Vec<u8>> = vec![vec![1, 2], vec![], vec![0, 255], vec![1], vec![0]];
+        msd_radix_sort(&mut list);
+        assert_eq!(list, [vec![], vec![0], vec![0, 255], vec![1], vec![1, 2]]);
+    }
+
+    #[test]
+    fn test_msd_radix_sort_stable() {
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        struct Record(String, usize);
+
+        impl AsRef<[u8]> for Record {
+            fn as_ref(&self) -> &[u8] {
+                self.0.as_bytes()
+            }
+        }
+
+        let mut list: Vec<Record> = (0..200).map(|i| Record(format!("k{}", i % 3), i)).collect();
+        let mut expected = list.clone();
+        expected.sort_by(|a, b| a.0.cmp(&b.0));
+        msd_radix_sort(&mut list);
+        assert_eq!(list, expected);
+    }
 }
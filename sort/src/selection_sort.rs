@@ -2,9 +2,22 @@
 // Use of this source is governed by General Public License that can be
 // found in the LICENSE file.
 
+use std::cmp::Ordering;
+
+use crate::sorter::{partial_compare, Sorter};
+use crate::stats::swap;
+
 pub fn selection_sort<T>(arr: &mut [T])
 where
     T: PartialOrd,
+{
+    selection_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的选择排序.
+pub fn selection_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     let len = arr.len();
     if arr.len() < 2 {
@@ -15,51 +28,84 @@ where
         // 找到最小元素的索引
         let mut min_index = i;
         for j in (i + 1)..len {
-            if arr[j] < arr[min_index] {
+            if compare(&arr[j], &arr[min_index]) == Ordering::Less {
                 min_index = j;
             }
         }
 
         // 如果最小元素不是 `list[i]`, 就交换两个元素
         if i != min_index {
-            arr.swap(i, min_index);
+            swap(arr, i, min_index);
         }
     }
 }
 
+/// 按照 `f` 提取出的键值进行选择排序.
+pub fn selection_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    selection_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
 /// 递归实现选择排序
 pub fn recursive_selection_sort<T>(arr: &mut [T])
 where
     T: PartialOrd,
 {
-    fn get_min_index<T>(list: &[T], i: usize, len: usize) -> usize
+    recursive_selection_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的递归选择排序.
+pub fn recursive_selection_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    fn get_min_index<T, F>(list: &[T], i: usize, len: usize, compare: &mut F) -> usize
     where
-        T: PartialOrd,
+        F: FnMut(&T, &T) -> Ordering,
     {
         if i == len - 1 {
             return i;
         }
-        let j = get_min_index(list, i + 1, len);
-        if list[i] < list[j] {
+        let j = get_min_index(list, i + 1, len, compare);
+        if compare(&list[i], &list[j]) == Ordering::Less {
             i
         } else {
             j
         }
     }
 
-    let len = arr.len();
-    if arr.len() < 2 {
-        return;
-    }
+    fn sort<T, F>(arr: &mut [T], compare: &mut F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let len = arr.len();
+        if arr.len() < 2 {
+            return;
+        }
+
+        let min_index = get_min_index(arr, 0, len, compare);
+        // 将最小的元素交换到最左侧
+        if min_index != 0 {
+            swap(arr, 0, min_index);
+        }
 
-    let min_index = get_min_index(arr, 0, len);
-    // 将最小的元素交换到最左侧
-    if min_index != 0 {
-        arr.swap(0, min_index);
+        // 递归排序剩下的元素
+        sort(&mut arr[1..], compare);
     }
 
-    // 递归排序剩下的元素
-    recursive_selection_sort(&mut arr[1..]);
+    sort(arr, &mut compare);
+}
+
+/// 按照 `f` 提取出的键值进行递归选择排序.
+pub fn recursive_selection_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    recursive_selection_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
 }
 
 /// 选择排序的一个小优化.
@@ -68,6 +114,14 @@ where
 pub fn two_way_selection_sort<T>(arr: &mut [T])
 where
     T: PartialOrd + std::fmt::Debug,
+{
+    two_way_selection_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的双向选择排序.
+pub fn two_way_selection_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     let len = arr.len();
     if arr.len() < 2 {
@@ -81,33 +135,33 @@ where
         let mut min_index = start;
         let mut max_index = start;
         for i in start..=end {
-            if arr[i] < arr[min_index] {
+            if compare(&arr[i], &arr[min_index]) == Ordering::Less {
                 min_index = i;
             }
-            if arr[i] > arr[max_index] {
+            if compare(&arr[i], &arr[max_index]) == Ordering::Greater {
                 max_index = i;
             }
         }
 
         // 交换最小元素
         if start != min_index {
-            arr.swap(start, min_index);
+            swap(arr, start, min_index);
         }
 
         // 交换最大元素
         if end != max_index {
             if start == min_index {
                 // 如果没有交换最小元素, 说明数组中的元素还没有移动过, 可以直接交换
-                arr.swap(end, max_index);
+                swap(arr, end, max_index);
             } else {
                 // 这时, 最小元素已经移到了最左侧, 我们需要判断这个移位操作给最大值带来的影响.
                 if max_index == start {
                     // 此时, 最大值已经被移到了 `list[min_index]`.
                     if end != min_index {
-                        arr.swap(end, min_index);
+                        swap(arr, end, min_index);
                     }
                 } else {
-                    arr.swap(end, max_index);
+                    swap(arr, end, max_index);
                 }
             }
         }
@@ -119,10 +173,27 @@ where
     }
 }
 
+/// 按照 `f` 提取出的键值进行双向选择排序.
+pub fn two_way_selection_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    two_way_selection_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
 /// 选择排序支持稳定排序
 pub fn stable_selection_sort<T>(arr: &mut [T])
 where
     T: PartialOrd,
+{
+    stable_selection_sort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的稳定选择排序.
+pub fn stable_selection_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     let len = arr.len();
     if arr.len() < 2 {
@@ -133,21 +204,90 @@ where
         // 找到最小元素的索引
         let mut min_index = i;
         for j in (i + 1)..len {
-            if arr[j] < arr[min_index] {
+            if compare(&arr[j], &arr[min_index]) == Ordering::Less {
                 min_index = j;
             }
         }
 
         // 如果最小元素不是 `list[i]`, 就将最小元素插入到这里.
         for j in ((i + 1)..=min_index).rev() {
-            arr.swap(j - 1, j);
+            swap(arr, j - 1, j);
         }
     }
 }
 
+/// 按照 `f` 提取出的键值进行稳定选择排序.
+pub fn stable_selection_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    stable_selection_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+/// 选择排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SelectionSort;
+
+impl<T> Sorter<T> for SelectionSort {
+    fn name(&self) -> &'static str {
+        "selection-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        selection_sort_by(arr, compare);
+    }
+}
+
+/// 递归实现的选择排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RecursiveSelectionSort;
+
+impl<T> Sorter<T> for RecursiveSelectionSort {
+    fn name(&self) -> &'static str {
+        "recursive-selection-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        recursive_selection_sort_by(arr, compare);
+    }
+}
+
+/// 每次同时选出最小值和最大值的选择排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TwoWaySelectionSort;
+
+impl<T> Sorter<T> for TwoWaySelectionSort {
+    fn name(&self) -> &'static str {
+        "two-way-selection-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        two_way_selection_sort_by(arr, compare);
+    }
+}
+
+/// 稳定的选择排序.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StableSelectionSort;
+
+impl<T> Sorter<T> for StableSelectionSort {
+    fn name(&self) -> &'static str {
+        "stable-selection-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        stable_selection_sort_by(arr, compare);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{recursive_selection_sort, selection_sort, stable_selection_sort, two_way_selection_sort};
+    use super::{
+        recursive_selection_sort, recursive_selection_sort_by, selection_sort,
+        selection_sort_by, selection_sort_by_key, stable_selection_sort,
+        stable_selection_sort_by_key, two_way_selection_sort, two_way_selection_sort_by,
+    };
 
     #[test]
     fn test_selection_sort() {
@@ -267,4 +407,27 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_selection_sort_by() {
+        let mut list = [0, 5, 3, 2, 2];
+        selection_sort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [0, 5, 3, 2, 2];
+        recursive_selection_sort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [0, 5, 3, 2, 2];
+        two_way_selection_sort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = ["apple", "fig", "banana"];
+        selection_sort_by_key(&mut list, |s| s.len());
+        assert_eq!(list, ["fig", "apple", "banana"]);
+
+        let mut list = [(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd')];
+        stable_selection_sort_by_key(&mut list, |pair| pair.0);
+        assert_eq!(list, [(1, 'b'), (1, 'd'), (3, 'a'), (3, 'c')]);
+    }
 }
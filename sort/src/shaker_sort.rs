@@ -2,13 +2,29 @@
 // Use of this source is governed by General Public License that can be
 // found in the LICENSE file.
 
+use std::cmp::Ordering;
+
+use crate::sorter::{partial_compare, Sorter};
+use crate::stats::swap;
+
 /// Shaker sort, or Cocktail sort, is an extension to bubble sort, by operating
 /// in two directions.
 pub fn shaker_sort<T>(arr: &mut [T])
 where
     T: PartialOrd,
+{
+    shaker_sort_by(arr, partial_compare);
+}
+
+/// Shaker sort with a comparator function.
+pub fn shaker_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     let len = arr.len();
+    if len < 2 {
+        return;
+    }
     let mut start = 0;
     let mut end = len - 1;
     let mut swapped = true;
@@ -18,8 +34,8 @@ where
         swapped = false;
 
         for i in start..end {
-            if arr[i] > arr[i + 1] {
-                arr.swap(i, i + 1);
+            if compare(&arr[i], &arr[i + 1]) == Ordering::Greater {
+                swap(arr, i, i + 1);
                 swapped = true;
             }
         }
@@ -33,8 +49,8 @@ where
 
         // From right to left, doing the same comparison.
         for i in (start..end).rev() {
-            if arr[i] > arr[i + 1] {
-                arr.swap(i, i + 1);
+            if compare(&arr[i], &arr[i + 1]) == Ordering::Greater {
+                swap(arr, i, i + 1);
                 swapped = true;
             }
         }
@@ -42,9 +58,32 @@ where
     }
 }
 
+/// Shaker sort with a key extraction function.
+pub fn shaker_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    shaker_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+/// Shaker sort.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ShakerSort;
+
+impl<T> Sorter<T> for ShakerSort {
+    fn name(&self) -> &'static str {
+        "shaker-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        shaker_sort_by(arr, compare);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::shaker_sort;
+    use super::{shaker_sort, shaker_sort_by, shaker_sort_by_key};
 
     #[test]
     fn test_shaker_sort() {
@@ -76,4 +115,15 @@ mod tests {
             ['A', 'E', 'E', 'I', 'N', 'O', 'Q', 'S', 'S', 'T', 'U', 'Y']
         );
     }
+
+    #[test]
+    fn test_shaker_sort_by() {
+        let mut list = [0, 5, 3, 2, 2];
+        shaker_sort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd')];
+        shaker_sort_by_key(&mut list, |pair| pair.0);
+        assert_eq!(list, [(1, 'b'), (1, 'd'), (3, 'a'), (3, 'c')]);
+    }
 }
@@ -2,6 +2,11 @@
 // Use of this source is governed by General Public License that can be
 // found in the LICENSE file.
 
+use std::cmp::Ordering;
+
+use crate::sorter::{partial_compare, Sorter};
+use crate::stats::swap;
+
 /// Shell sort is a simple extension to insertion sort that allows exchanging
 /// elements that far apart.
 ///
@@ -9,6 +14,14 @@
 pub fn shell_sort<T>(arr: &mut [T])
 where
     T: PartialOrd,
+{
+    shell_sort_by(arr, partial_compare);
+}
+
+/// Shell sort with a comparator function.
+pub fn shell_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
 {
     const FACTOR: usize = 3;
     let len = arr.len();
@@ -23,8 +36,8 @@ where
         // 使用插入排序, 将 `arr[0..h]` 排序好
         for i in h..len {
             let mut j = i;
-            while j >= h && arr[j - h] > arr[j] {
-                arr.swap(j - h, j);
+            while j >= h && compare(&arr[j - h], &arr[j]) == Ordering::Greater {
+                swap(arr, j - h, j);
                 j -= h;
             }
         }
@@ -33,9 +46,32 @@ where
     }
 }
 
+/// Shell sort with a key extraction function.
+pub fn shell_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    shell_sort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+/// Shell sort.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ShellSort;
+
+impl<T> Sorter<T> for ShellSort {
+    fn name(&self) -> &'static str {
+        "shell-sort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        shell_sort_by(arr, compare);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::shell_sort;
+    use super::{shell_sort, shell_sort_by, shell_sort_by_key};
 
     #[test]
     fn test_shell_sort() {
@@ -67,4 +103,18 @@ mod tests {
             ['A', 'E', 'E', 'I', 'N', 'O', 'Q', 'S', 'S', 'T', 'U', 'Y']
         );
     }
+
+    #[test]
+    fn test_shell_sort_by() {
+        let mut list = [0, 5, 3, 2, 2];
+        shell_sort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, [5, 3, 2, 2, 0]);
+
+        let mut list = [(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd')];
+        shell_sort_by_key(&mut list, |pair| pair.0);
+        assert_eq!(list[0].0, 1);
+        assert_eq!(list[1].0, 1);
+        assert_eq!(list[2].0, 3);
+        assert_eq!(list[3].0, 3);
+    }
 }
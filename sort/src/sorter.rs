@@ -0,0 +1,138 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+use std::cmp::Ordering;
+
+/// 所有排序算法的公共接口.
+///
+/// 它是对象安全的 (object safe), 可以通过 `Box<dyn Sorter<T>>` 在运行时选择排序算法.
+///
+/// 所有基于比较的排序算法都实现了这个 trait, 以下几类除外:
+/// - 桶排序, 计数排序和基数排序: 它们不比较元素, 只支持整数或者由键值提取出的整数,
+///   无法使用任意的比较函数;
+/// - 并行排序: 比较函数要在多个线程中同时调用, 需要 `Fn + Sync`,
+///   而这里的比较函数是 `&mut dyn FnMut`;
+/// - 外部排序: 它处理的是文件中的记录, 而不是内存中的数组.
+pub trait Sorter<T> {
+    /// 排序算法的名称, 与 `total_sort` 中使用的名称一致.
+    fn name(&self) -> &'static str;
+
+    /// 使用比较函数 `compare` 对数组进行排序.
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering);
+
+    /// 按照增序排序.
+    fn sort(&self, arr: &mut [T])
+    where
+        T: PartialOrd,
+    {
+        self.sort_by(arr, &mut partial_compare);
+    }
+
+    /// 按照 `f` 提取出的键值进行增序排序.
+    fn sort_by_key<K, F>(&self, arr: &mut [T], mut f: F)
+    where
+        Self: Sized,
+        K: PartialOrd,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(arr, &mut |a, b| partial_compare(&f(a), &f(b)));
+    }
+}
+
+/// 基于 `PartialOrd` 的比较函数.
+///
+/// 无法比较的两个值 (比如 `NaN`) 被视为相等.
+#[must_use]
+#[inline]
+pub fn partial_compare<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sorter;
+    use crate::bubble_sort::{BubbleSort, RecursiveBubbleSort};
+    use crate::double_sort::DoubleSort;
+    use crate::gnome_sort::GnomeSort;
+    use crate::heap_sort::HeapSort;
+    use crate::insertion_sort::{BinaryInsertionSort, InsertionSort, RecursiveInsertionSort};
+    use crate::introsort::IntroSort;
+    use crate::merge_sort::{
+        BottomUpMergeSort, InPlaceMergeSort, InPlaceShellMergeSort, InsertionMergeSort, MergeSort,
+        ShellMergeSort, ThreeWayMergeSort, TopDownMergeSort,
+    };
+    use crate::odd_even_sort::OddEvenSort;
+    use crate::pdqsort::PdqSort;
+    use crate::quicksort::{
+        HeadQuickSort, InsertionQuickSort, IterativeQuickSort, QuickSort, TwoPointerQuickSort,
+    };
+    use crate::selection_sort::{
+        RecursiveSelectionSort, SelectionSort, StableSelectionSort, TwoWaySelectionSort,
+    };
+    use crate::shaker_sort::ShakerSort;
+    use crate::shell_sort::ShellSort;
+    use crate::timsort::{NaturalTimSort, ShellTimSort, TimSort};
+
+    #[test]
+    fn test_dyn_sorter() {
+        let sorters: Vec<Box<dyn Sorter<i32>>> = vec![
+            Box::new(BinaryInsertionSort),
+            Box::new(BottomUpMergeSort),
+            Box::new(BubbleSort),
+            Box::new(DoubleSort),
+            Box::new(GnomeSort),
+            Box::new(HeadQuickSort),
+            Box::new(HeapSort),
+            Box::new(InPlaceMergeSort),
+            Box::new(InPlaceShellMergeSort),
+            Box::new(InsertionMergeSort),
+            Box::new(InsertionQuickSort),
+            Box::new(InsertionSort),
+            Box::new(IntroSort),
+            Box::new(IterativeQuickSort),
+            Box::new(MergeSort),
+            Box::new(NaturalTimSort),
+            Box::new(OddEvenSort),
+            Box::new(PdqSort),
+            Box::new(QuickSort),
+            Box::new(RecursiveBubbleSort),
+            Box::new(RecursiveInsertionSort),
+            Box::new(RecursiveSelectionSort),
+            Box::new(SelectionSort),
+            Box::new(ShakerSort),
+            Box::new(ShellMergeSort),
+            Box::new(ShellSort),
+            Box::new(ShellTimSort),
+            Box::new(StableSelectionSort),
+            Box::new(ThreeWayMergeSort),
+            Box::new(TimSort),
+            Box::new(TopDownMergeSort),
+            Box::new(TwoPointerQuickSort),
+            Box::new(TwoWaySelectionSort),
+        ];
+        let input: Vec<i32> = (0..100).map(|i| (i * 37) % 41).collect();
+        let mut expected = input.clone();
+        expected.sort_unstable();
+        for sorter in &sorters {
+            let mut list = [0, 5, 3, 2, 2];
+            sorter.sort(&mut list);
+            assert_eq!(list, [0, 2, 2, 3, 5], "{}", sorter.name());
+
+            let mut list = [0, 5, 3, 2, 2];
+            sorter.sort_by(&mut list, &mut |a, b| b.cmp(a));
+            assert_eq!(list, [5, 3, 2, 2, 0], "{}", sorter.name());
+
+            let mut list = input.clone();
+            sorter.sort(&mut list);
+            assert_eq!(list, expected, "{}", sorter.name());
+        }
+    }
+
+    #[test]
+    fn test_sort_by_key() {
+        let mut list = [(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd')];
+        MergeSort.sort_by_key(&mut list, |pair| pair.0);
+        assert_eq!(list, [(1, 'b'), (1, 'd'), (3, 'a'), (3, 'c')]);
+    }
+}
@@ -0,0 +1,334 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 排序算法的统计工具, 用于验证各个算法的复杂度.
+//!
+//! - [`SortStats`] 记录比较次数和元素的复制次数;
+//! - [`Counted`] 包装了数组中的元素, 每次比较或者克隆时都会更新对应的 `SortStats`,
+//!   所以任何只要求 `PartialOrd` (以及 `Clone`) 的排序函数都可以直接使用它;
+//! - [`SwapCounter`] 记录交换元素的次数, 排序算法中的交换都通过 [`swap()`] 完成;
+//! - [`CountingAllocator`] 是一个全局内存分配器, 记录内存分配的次数以及辅助内存的峰值.
+//!
+//! 交换元素只是内存操作, 无法从元素类型上观察到, 所以交换次数使用全局计数器记录.
+//! `reverse()`, `rotate_right()` 这类批量移动元素的操作, 按等价的交换次数计入.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+/// 比较次数和复制次数的计数器.
+///
+/// 计数器使用原子变量, 所以在多线程排序中也可以使用.
+#[derive(Debug, Default)]
+pub struct SortStats {
+    comparisons: AtomicUsize,
+    clones: AtomicUsize,
+}
+
+impl SortStats {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            comparisons: AtomicUsize::new(0),
+            clones: AtomicUsize::new(0),
+        }
+    }
+
+    /// 比较的次数.
+    #[must_use]
+    pub fn comparisons(&self) -> usize {
+        self.comparisons.load(AtomicOrdering::Relaxed)
+    }
+
+    /// 元素被复制的次数, 对于使用辅助数组的算法, 它近似于元素移动的次数.
+    #[must_use]
+    pub fn clones(&self) -> usize {
+        self.clones.load(AtomicOrdering::Relaxed)
+    }
+
+    /// 把所有计数器清零.
+    pub fn reset(&self) {
+        self.comparisons.store(0, AtomicOrdering::Relaxed);
+        self.clones.store(0, AtomicOrdering::Relaxed);
+    }
+
+    /// 用 `Counted` 包装 `values` 中的每个元素.
+    pub fn wrap<T, I>(&self, values: I) -> Vec<Counted<'_, T>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        values
+            .into_iter()
+            .map(|value| Counted { value, stats: self })
+            .collect()
+    }
+
+    /// 返回一个会记录比较次数的比较函数, 用于各个排序算法的 `_by` 版本.
+    pub fn counting<'a, T, F>(&'a self, mut compare: F) -> impl FnMut(&T, &T) -> Ordering + 'a
+    where
+        F: FnMut(&T, &T) -> Ordering + 'a,
+    {
+        move |a, b| {
+            self.comparisons.fetch_add(1, AtomicOrdering::Relaxed);
+            compare(a, b)
+        }
+    }
+}
+
+/// 带计数功能的元素.
+pub struct Counted<'a, T> {
+    value: T,
+    stats: &'a SortStats,
+}
+
+impl<'a, T> Counted<'a, T> {
+    #[must_use]
+    pub const fn new(value: T, stats: &'a SortStats) -> Self {
+        Self { value, stats }
+    }
+
+    #[must_use]
+    pub const fn value(&self) -> &T {
+        &self.value
+    }
+
+    #[must_use]
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Clone> Clone for Counted<'_, T> {
+    fn clone(&self) -> Self {
+        self.stats.clones.fetch_add(1, AtomicOrdering::Relaxed);
+        Self {
+            value: self.value.clone(),
+            stats: self.stats,
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for Counted<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.stats.comparisons.fetch_add(1, AtomicOrdering::Relaxed);
+        self.value == other.value
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for Counted<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.stats.comparisons.fetch_add(1, AtomicOrdering::Relaxed);
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Counted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Counted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+static SWAPS: AtomicUsize = AtomicUsize::new(0);
+
+/// 交换次数的全局计数器.
+///
+/// 所有线程共享同一个计数器, 所以并行排序的交换次数也会被记录.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SwapCounter;
+
+impl SwapCounter {
+    /// 把交换次数清零.
+    pub fn reset() {
+        SWAPS.store(0, AtomicOrdering::Relaxed);
+    }
+
+    /// 从上次调用 `reset()` 到现在的交换次数.
+    #[must_use]
+    pub fn count() -> usize {
+        SWAPS.load(AtomicOrdering::Relaxed)
+    }
+
+    /// 记录 `count` 次交换, 用于批量移动元素的操作.
+    pub fn add(count: usize) {
+        SWAPS.fetch_add(count, AtomicOrdering::Relaxed);
+    }
+}
+
+/// 交换 `arr[a]` 与 `arr[b]`, 并更新交换次数.
+#[inline]
+pub fn swap<T>(arr: &mut [T], a: usize, b: usize) {
+    SWAPS.fetch_add(1, AtomicOrdering::Relaxed);
+    arr.swap(a, b);
+}
+
+/// 交换两个元素的值, 并更新交换次数.
+#[inline]
+pub fn swap_values<T>(a: &mut T, b: &mut T) {
+    SWAPS.fetch_add(1, AtomicOrdering::Relaxed);
+    std::mem::swap(a, b);
+}
+
+/// 交换两个等长切片中的元素, 按元素个数计入交换次数.
+#[inline]
+pub fn swap_slices<T>(a: &mut [T], b: &mut [T]) {
+    SWAPS.fetch_add(a.len(), AtomicOrdering::Relaxed);
+    a.swap_with_slice(b);
+}
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static CURRENT_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// 记录内存分配情况的全局分配器, 实际的分配工作交给 `System` 完成.
+///
+/// 需要在可执行文件中注册它:
+///
+/// ```ignore
+/// #[global_allocator]
+/// static GLOBAL: CountingAllocator = CountingAllocator;
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CountingAllocator;
+
+/// 从上次调用 `CountingAllocator::reset()` 到现在的内存分配情况.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AllocStats {
+    /// 分配 (包括重新分配) 的次数.
+    pub allocations: usize,
+    /// 相对于重置时刻, 额外占用的内存的峰值, 单位是字节.
+    pub peak_bytes: usize,
+}
+
+impl CountingAllocator {
+    /// 清零分配次数, 并把当前占用的内存作为计算峰值的基准.
+    pub fn reset() -> usize {
+        ALLOCATIONS.store(0, AtomicOrdering::Relaxed);
+        let current = CURRENT_BYTES.load(AtomicOrdering::Relaxed);
+        PEAK_BYTES.store(current, AtomicOrdering::Relaxed);
+        current
+    }
+
+    /// 返回从 `reset()` 到现在的统计信息, `baseline` 是 `reset()` 的返回值.
+    #[must_use]
+    pub fn stats(baseline: usize) -> AllocStats {
+        AllocStats {
+            allocations: ALLOCATIONS.load(AtomicOrdering::Relaxed),
+            peak_bytes: PEAK_BYTES
+                .load(AtomicOrdering::Relaxed)
+                .saturating_sub(baseline),
+        }
+    }
+
+    fn record_alloc(size: usize) {
+        ALLOCATIONS.fetch_add(1, AtomicOrdering::Relaxed);
+        let current = CURRENT_BYTES.fetch_add(size, AtomicOrdering::Relaxed) + size;
+        PEAK_BYTES.fetch_max(current, AtomicOrdering::Relaxed);
+    }
+
+    fn record_dealloc(size: usize) {
+        CURRENT_BYTES.fetch_sub(size, AtomicOrdering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::record_dealloc(layout.size());
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Self::record_dealloc(layout.size());
+            Self::record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::alloc::{GlobalAlloc, Layout};
+
+    use super::{Counted, CountingAllocator, SortStats, SwapCounter};
+    use crate::insertion_sort::{insertion_sort, insertion_sort_by};
+    use crate::merge_sort::merge_sort;
+
+    #[test]
+    fn test_counted() {
+        let stats = SortStats::new();
+
+        // 有序数组的插入排序只需要 `n - 1` 次比较.
+        let mut list = stats.wrap(0..100);
+        insertion_sort(&mut list);
+        assert_eq!(stats.comparisons(), 99);
+        assert_eq!(stats.clones(), 0);
+
+        stats.reset();
+        let mut list = stats.wrap([5, 3, 1, 4, 2]);
+        merge_sort(&mut list);
+        assert!(stats.comparisons() > 0);
+        assert!(stats.clones() > 0);
+        let list: Vec<i32> = list.into_iter().map(Counted::into_value).collect();
+        assert_eq!(list, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_counting_compare() {
+        let stats = SortStats::new();
+        let mut list: Vec<i32> = (0..10).rev().collect();
+        insertion_sort_by(&mut list, stats.counting(|a: &i32, b: &i32| a.cmp(b)));
+        assert_eq!(list, (0..10).collect::<Vec<_>>());
+        // 逆序数组的插入排序需要 `n * (n - 1) / 2` 次比较.
+        assert_eq!(stats.comparisons(), 45);
+    }
+
+    #[test]
+    fn test_swap_counter() {
+        // 计数器是全局的, 其它测试用例可能同时在排序, 所以这里只检查下限.
+        SwapCounter::reset();
+        let mut list: Vec<i32> = (0..10).rev().collect();
+        insertion_sort(&mut list);
+        assert_eq!(list, (0..10).collect::<Vec<_>>());
+        // 逆序数组的插入排序需要 `n * (n - 1) / 2` 次交换.
+        assert!(SwapCounter::count() >= 45);
+    }
+
+    #[test]
+    fn test_counting_allocator() {
+        let baseline = CountingAllocator::reset();
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        unsafe {
+            let ptr = CountingAllocator.alloc(layout);
+            assert!(!ptr.is_null());
+            CountingAllocator.dealloc(ptr, layout);
+        }
+        let stats = CountingAllocator::stats(baseline);
+        assert_eq!(stats.allocations, 1);
+        assert_eq!(stats.peak_bytes, 64);
+    }
+}
@@ -2,13 +2,28 @@
 // Use of this source is governed by General Public License that can be found
 // in the LICENSE file.
 
-use crate::insertion_sort::insertion_sort;
-use crate::shell_sort::shell_sort;
+use std::cmp::Ordering;
+
+use crate::insertion_sort::insertion_sort_by;
+use crate::shell_sort::shell_sort_by;
+use crate::sorter::{partial_compare, Sorter};
+use crate::stats::{swap, swap_slices, swap_values, SwapCounter};
 
 /// Timsort 是对归并排序 (merge sort) 的优化.
+///
+/// 这是一个简化的版本, 便于理解, 它把数组分隔成固定大小的子数组. 完整的实现参见 `natural_timsort()`.
 pub fn timsort<T>(arr: &mut [T])
 where
     T: PartialOrd + Clone,
+{
+    timsort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的 Timsort.
+pub fn timsort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     const RUN: usize = 32;
 
@@ -21,7 +36,7 @@ where
     // 插入排序比较善于处理已基本有序的较小的数组.
     for i in (0..len).step_by(RUN) {
         let end = (i + RUN).min(len);
-        insertion_sort(&mut arr[i..end]);
+        insertion_sort_by(&mut arr[i..end], &mut compare);
     }
 
     // 然后将各个子数组合并在一起
@@ -35,7 +50,7 @@ where
             let right = (left + 2 * size - 1).min(len - 1);
 
             if middle < right {
-                merge(arr, left, middle, right);
+                merge(arr, left, middle, right, &mut compare);
             }
         }
 
@@ -43,10 +58,21 @@ where
     }
 }
 
+/// 按照 `f` 提取出的键值进行 Timsort.
+pub fn timsort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    T: Clone,
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    timsort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
 /// 合并子数组 `arr[left..=middle]` 和 `arr[middle+1..=right]`
-fn merge<T>(arr: &mut [T], left: usize, middle: usize, right: usize)
+fn merge<T, F>(arr: &mut [T], left: usize, middle: usize, right: usize, compare: &mut F)
 where
-    T: PartialOrd + Clone,
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     // 先创建辅助数组
     let aux_left = arr[left..=middle].to_vec();
@@ -59,12 +85,13 @@ where
     let mut j = 0;
     let mut k = left;
     while i < left_len && j < right_len {
-        if aux_left[i] < aux_right[j] {
-            arr[k].clone_from(&aux_left[i]);
-            i += 1;
-        } else {
+        // 两个元素相等时, 先取左侧的, 以保证稳定排序.
+        if compare(&aux_left[i], &aux_right[j]) == Ordering::Greater {
             arr[k].clone_from(&aux_right[j]);
             j += 1;
+        } else {
+            arr[k].clone_from(&aux_left[i]);
+            i += 1;
         }
         k += 1;
     }
@@ -89,6 +116,15 @@ where
 pub fn shell_timsort<T>(arr: &mut [T])
 where
     T: PartialOrd + Clone,
+{
+    shell_timsort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare`, 并使用希尔排序代替插入排序.
+pub fn shell_timsort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     const RUN: usize = 64;
 
@@ -101,7 +137,7 @@ where
     // 插入排序比较善于处理已基本有序的较小的数组.
     for i in (0..len).step_by(RUN) {
         let end = (i + RUN).min(len);
-        shell_sort(&mut arr[i..end]);
+        shell_sort_by(&mut arr[i..end], &mut compare);
     }
 
     // 然后将各个子数组合并在一起
@@ -117,7 +153,7 @@ where
             let right = (left + 2 * size - 1).min(len - 1);
 
             if middle < right {
-                merge_with_aux(arr, left, middle, right, &mut aux);
+                merge_with_aux(arr, left, middle, right, &mut aux, &mut compare);
             }
         }
 
@@ -125,10 +161,27 @@ where
     }
 }
 
-/// 合并子数组 `arr[left..=middle]` 和 `arr[middle+1..=right]`
-fn merge_with_aux<T>(arr: &mut [T], left: usize, middle: usize, right: usize, aux: &mut [T])
+/// 按照 `f` 提取出的键值, 并使用希尔排序代替插入排序.
+pub fn shell_timsort_by_key<T, K, F>(arr: &mut [T], mut f: F)
 where
-    T: PartialOrd + Clone,
+    T: Clone,
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    shell_timsort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+/// 合并子数组 `arr[left..=middle]` 和 `arr[middle+1..=right]`
+fn merge_with_aux<T, F>(
+    arr: &mut [T],
+    left: usize,
+    middle: usize,
+    right: usize,
+    aux: &mut [T],
+    compare: &mut F,
+) where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
 {
     // 先初始化辅助数组
     for i in left..=right {
@@ -140,12 +193,12 @@ where
     let mut j = middle + 1;
     let mut k = left;
     while i <= middle && j <= right {
-        if aux[i] < aux[j] {
-            arr[k].clone_from(&aux[i]);
-            i += 1;
-        } else {
+        if compare(&aux[i], &aux[j]) == Ordering::Greater {
             arr[k].clone_from(&aux[j]);
             j += 1;
+        } else {
+            arr[k].clone_from(&aux[i]);
+            i += 1;
         }
         k += 1;
     }
@@ -162,9 +215,619 @@ where
     }
 }
 
+/// 真正的 Timsort, 与 Python 以及 Java 中的实现一致.
+///
+/// 与上面的 `timsort()` 不同, 它会:
+/// - 检测数组中已有的升序或者严格降序的片段 (natural run), 降序的片段会被反转
+/// - 根据数组长度计算 `min_run`, 较短的片段使用二分插入排序扩充到 `min_run`
+/// - 使用一个栈记录各个片段, 并保持栈中片段长度的约束条件, 以平衡合并操作
+/// - 合并时如果一侧连续胜出多次, 就进入飞奔模式 (galloping mode)
+///
+/// 对于基本有序的数组, 它的时间复杂度接近 `O(n)`. 它是稳定排序.
+pub fn natural_timsort<T>(arr: &mut [T])
+where
+    T: PartialOrd + Clone,
+{
+    natural_timsort_by(arr, partial_compare);
+}
+
+/// 使用比较函数 `compare` 的 natural timsort.
+pub fn natural_timsort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+
+    // 数组较短时, 不需要合并, 直接使用二分插入排序.
+    if len < MIN_MERGE {
+        let run_len = count_run_and_make_ascending(arr, &mut compare);
+        binary_insertion_sort(arr, run_len, &mut compare);
+        return;
+    }
+
+    let min_run = min_run_length(len);
+    let mut state = MergeState {
+        compare: &mut compare,
+        min_gallop: MIN_GALLOP,
+        runs: Vec::new(),
+    };
+
+    let mut low = 0;
+    while low < len {
+        // 找到下一个片段, 如果它太短, 就扩充到 `min_run`.
+        let mut run_len = count_run_and_make_ascending(&mut arr[low..], state.compare);
+        if run_len < min_run {
+            let force = min_run.min(len - low);
+            binary_insertion_sort(&mut arr[low..low + force], run_len, state.compare);
+            run_len = force;
+        }
+
+        // 片段入栈, 并在需要时合并.
+        state.runs.push(Run {
+            start: low,
+            len: run_len,
+        });
+        state.merge_collapse(arr);
+
+        low += run_len;
+    }
+
+    // 最后合并栈中剩下的所有片段.
+    state.merge_force_collapse(arr);
+}
+
+/// 按照 `f` 提取出的键值进行 natural timsort.
+pub fn natural_timsort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    T: Clone,
+    K: PartialOrd,
+    F: FnMut(&T) -> K,
+{
+    natural_timsort_by(arr, |a, b| partial_compare(&f(a), &f(b)));
+}
+
+/// 数组长度小于这个值时, 不再合并.
+const MIN_MERGE: usize = 32;
+
+/// 进入飞奔模式的初始阈值.
+const MIN_GALLOP: usize = 7;
+
+/// 计算片段的最小长度.
+///
+/// 如果 `n` 是 2 的幂, 就返回 `MIN_MERGE / 2`, 否则返回 `[MIN_MERGE / 2, MIN_MERGE]`
+/// 之间的一个数 `k`, 使得 `n / k` 接近但略小于 2 的幂, 这样合并时是平衡的.
+const fn min_run_length(mut n: usize) -> usize {
+    let mut r = 0;
+    while n >= MIN_MERGE {
+        r |= n & 1;
+        n >>= 1;
+    }
+    n + r
+}
+
+/// 找到从数组头部开始的片段, 并返回它的长度.
+///
+/// 片段要么是升序的 `a[0] <= a[1] <= a[2] <= ...`, 要么是严格降序的 `a[0] > a[1] > a[2] > ...`.
+/// 降序的片段会被反转. 这里要求严格降序, 是为了保证稳定排序.
+fn count_run_and_make_ascending<T, F>(arr: &mut [T], compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = arr.len();
+    if len < 2 {
+        return len;
+    }
+
+    let mut run_end = 2;
+    if compare(&arr[1], &arr[0]) == Ordering::Less {
+        while run_end < len && compare(&arr[run_end], &arr[run_end - 1]) == Ordering::Less {
+            run_end += 1;
+        }
+        SwapCounter::add(run_end / 2);
+        arr[..run_end].reverse();
+    } else {
+        while run_end < len && compare(&arr[run_end], &arr[run_end - 1]) != Ordering::Less {
+            run_end += 1;
+        }
+    }
+
+    run_end
+}
+
+/// 二分插入排序, `arr[..start]` 已经是有序的了.
+///
+/// 新元素插入到与它相等的元素之后, 以保证稳定排序.
+fn binary_insertion_sort<T, F>(arr: &mut [T], start: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in start.max(1)..arr.len() {
+        let mut left = 0;
+        let mut right = i;
+        while left < right {
+            let middle = left + (right - left) / 2;
+            if compare(&arr[i], &arr[middle]) == Ordering::Less {
+                right = middle;
+            } else {
+                left = middle + 1;
+            }
+        }
+        SwapCounter::add(i - left);
+        arr[left..=i].rotate_right(1);
+    }
+}
+
+/// 找到 `key` 在有序数组 `arr` 中最左侧的插入位置 `k`, 满足 `arr[k - 1] < key <= arr[k]`.
+///
+/// 从 `hint` 开始, 以 1, 3, 7, 15, ... 的间隔向左或者向右飞奔, 找到大致范围后再二分查找.
+fn gallop_left<T, F>(key: &T, arr: &[T], hint: usize, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = arr.len();
+    let mut last_offset = 0;
+    let mut offset = 1;
+
+    // 最终要在 `[low, high)` 之间二分查找.
+    let (mut low, mut high) = if compare(key, &arr[hint]) == Ordering::Greater {
+        // 向右飞奔, 直到 `arr[hint + last_offset] < key <= arr[hint + offset]`
+        let max_offset = len - hint;
+        while offset < max_offset && compare(key, &arr[hint + offset]) == Ordering::Greater {
+            last_offset = offset;
+            offset = (offset << 1) + 1;
+        }
+        (hint + last_offset + 1, hint + offset.min(max_offset))
+    } else {
+        // 向左飞奔, 直到 `arr[hint - offset] < key <= arr[hint - last_offset]`
+        let max_offset = hint + 1;
+        while offset < max_offset && compare(key, &arr[hint - offset]) != Ordering::Greater {
+            last_offset = offset;
+            offset = (offset << 1) + 1;
+        }
+        (hint + 1 - offset.min(max_offset), hint - last_offset)
+    };
+
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if compare(key, &arr[middle]) == Ordering::Greater {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    high
+}
+
+/// 找到 `key` 在有序数组 `arr` 中最右侧的插入位置 `k`, 满足 `arr[k - 1] <= key < arr[k]`.
+fn gallop_right<T, F>(key: &T, arr: &[T], hint: usize, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = arr.len();
+    let mut last_offset = 0;
+    let mut offset = 1;
+
+    let (mut low, mut high) = if compare(key, &arr[hint]) == Ordering::Less {
+        // 向左飞奔, 直到 `arr[hint - offset] <= key < arr[hint - last_offset]`
+        let max_offset = hint + 1;
+        while offset < max_offset && compare(key, &arr[hint - offset]) == Ordering::Less {
+            last_offset = offset;
+            offset = (offset << 1) + 1;
+        }
+        (hint + 1 - offset.min(max_offset), hint - last_offset)
+    } else {
+        // 向右飞奔, 直到 `arr[hint + last_offset] <= key < arr[hint + offset]`
+        let max_offset = len - hint;
+        while offset < max_offset && compare(key, &arr[hint + offset]) != Ordering::Less {
+            last_offset = offset;
+            offset = (offset << 1) + 1;
+        }
+        (hint + last_offset + 1, hint + offset.min(max_offset))
+    };
+
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if compare(key, &arr[middle]) == Ordering::Less {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    high
+}
+
+/// 栈中的一个片段, 即 `arr[start..start + len]`.
+#[derive(Debug, Clone, Copy)]
+struct Run {
+    start: usize,
+    len: usize,
+}
+
+/// 合并过程中的状态.
+struct MergeState<'a, F> {
+    compare: &'a mut F,
+    /// 进入飞奔模式的阈值, 它会根据数据的特点动态调整.
+    min_gallop: usize,
+    /// 待合并的片段.
+    runs: Vec<Run>,
+}
+
+impl<F> MergeState<'_, F> {
+    /// 检查栈顶的片段, 合并它们直到满足以下约束条件:
+    /// 1. `runs[i - 2].len > runs[i - 1].len + runs[i].len`
+    /// 2. `runs[i - 1].len > runs[i].len`
+    ///
+    /// 这里同时检查了栈顶的四个片段, 修复了早期 Java 以及 Python 实现中的问题.
+    fn merge_collapse<T>(&mut self, arr: &mut [T])
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        while self.runs.len() > 1 {
+            let mut n = self.runs.len() - 2;
+            let runs = &self.runs;
+            if (n > 0 && runs[n - 1].len <= runs[n].len + runs[n + 1].len)
+                || (n > 1 && runs[n - 2].len <= runs[n - 1].len + runs[n].len)
+            {
+                if runs[n - 1].len < runs[n + 1].len {
+                    n -= 1;
+                }
+            } else if runs[n].len > runs[n + 1].len {
+                break;
+            }
+            self.merge_at(arr, n);
+        }
+    }
+
+    /// 合并栈中剩下的所有片段.
+    fn merge_force_collapse<T>(&mut self, arr: &mut [T])
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        while self.runs.len() > 1 {
+            let mut n = self.runs.len() - 2;
+            if n > 0 && self.runs[n - 1].len < self.runs[n + 1].len {
+                n -= 1;
+            }
+            self.merge_at(arr, n);
+        }
+    }
+
+    /// 合并栈中的第 `i` 和 `i + 1` 个片段.
+    fn merge_at<T>(&mut self, arr: &mut [T], i: usize)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let run_a = self.runs[i];
+        let run_b = self.runs[i + 1];
+        self.runs[i].len = run_a.len + run_b.len;
+        self.runs.remove(i + 1);
+
+        let arr = &mut arr[run_a.start..run_b.start + run_b.len];
+
+        // 片段 A 中比 B 的第一个元素还小的那部分元素已经在最终位置了, 跳过它们.
+        let skip = gallop_right(&arr[run_a.len], &arr[..run_a.len], 0, self.compare);
+        let arr = &mut arr[skip..];
+        let len1 = run_a.len - skip;
+        if len1 == 0 {
+            return;
+        }
+
+        // 片段 B 中比 A 的最后一个元素还大的那部分元素也已经在最终位置了.
+        let len2 = gallop_left(&arr[len1 - 1], &arr[len1..], run_b.len - 1, self.compare);
+        if len2 == 0 {
+            return;
+        }
+
+        // 将较短的那个片段复制到辅助数组中.
+        let arr = &mut arr[..len1 + len2];
+        if len1 <= len2 {
+            self.merge_low(arr, len1);
+        } else {
+            self.merge_high(arr, len1);
+        }
+    }
+
+    /// 从左向右合并 `arr[..len1]` 和 `arr[len1..]`, 左侧片段较短.
+    ///
+    /// 调用者保证 `arr[len1]` 小于左侧片段的所有元素, 而左侧片段的最后一个元素大于右侧片段的所有元素.
+    ///
+    /// 辅助数组中的元素被放回原数组时使用的是交换操作, 所以只需要在开始时复制一次.
+    #[allow(clippy::too_many_lines)]
+    fn merge_low<T>(&mut self, arr: &mut [T], len1: usize)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let total = arr.len();
+        let mut tmp = arr[..len1].to_vec();
+        let tmp_len = len1;
+        let mut len1 = len1;
+        let mut len2 = total - len1;
+
+        // 下一个要合并的元素分别是 `tmp[tmp_len - len1]` 和 `arr[total - len2]`,
+        // 要写入的位置是 `arr[total - len1 - len2]`.
+        swap(arr, 0, total - len2);
+        len2 -= 1;
+        if len2 == 0 {
+            swap_slices(&mut arr[total - len1..], &mut tmp[tmp_len - len1..]);
+            return;
+        }
+        if len1 == 1 {
+            for i in 0..len2 {
+                swap(arr, total - len1 - len2 + i, total - len2 + i);
+            }
+            swap_values(&mut arr[total - 1], &mut tmp[tmp_len - 1]);
+            return;
+        }
+
+        let mut min_gallop = self.min_gallop;
+        'outer: loop {
+            // 两侧连续胜出的次数.
+            let mut count1: usize = 0;
+            let mut count2: usize = 0;
+
+            // 逐个比较元素, 直到某一侧连续胜出 `min_gallop` 次.
+            loop {
+                let dest = total - len1 - len2;
+                if (self.compare)(&arr[total - len2], &tmp[tmp_len - len1]) == Ordering::Less {
+                    swap(arr, dest, total - len2);
+                    len2 -= 1;
+                    count2 += 1;
+                    count1 = 0;
+                    if len2 == 0 {
+                        break 'outer;
+                    }
+                } else {
+                    swap_values(&mut arr[dest], &mut tmp[tmp_len - len1]);
+                    len1 -= 1;
+                    count1 += 1;
+                    count2 = 0;
+                    if len1 == 1 {
+                        break 'outer;
+                    }
+                }
+                if (count1 | count2) >= min_gallop {
+                    break;
+                }
+            }
+
+            // 飞奔模式, 一次移动一批元素, 直到效果不明显.
+            loop {
+                count1 = gallop_right(
+                    &arr[total - len2],
+                    &tmp[tmp_len - len1..],
+                    0,
+                    self.compare,
+                );
+                if count1 != 0 {
+                    let dest = total - len1 - len2;
+                    let pos1 = tmp_len - len1;
+                    swap_slices(&mut arr[dest..dest + count1], &mut tmp[pos1..pos1 + count1]);
+                    len1 -= count1;
+                    if len1 <= 1 {
+                        break 'outer;
+                    }
+                }
+                swap(arr, total - len1 - len2, total - len2);
+                len2 -= 1;
+                if len2 == 0 {
+                    break 'outer;
+                }
+
+                count2 = gallop_left(
+                    &tmp[tmp_len - len1],
+                    &arr[total - len2..],
+                    0,
+                    self.compare,
+                );
+                if count2 != 0 {
+                    let dest = total - len1 - len2;
+                    let pos2 = total - len2;
+                    for i in 0..count2 {
+                        swap(arr, dest + i, pos2 + i);
+                    }
+                    len2 -= count2;
+                    if len2 == 0 {
+                        break 'outer;
+                    }
+                }
+                swap_values(&mut arr[total - len1 - len2], &mut tmp[tmp_len - len1]);
+                len1 -= 1;
+                if len1 == 1 {
+                    break 'outer;
+                }
+
+                min_gallop = min_gallop.saturating_sub(1);
+                if count1 < MIN_GALLOP && count2 < MIN_GALLOP {
+                    break;
+                }
+            }
+
+            // 离开飞奔模式, 提高下次进入的门槛.
+            min_gallop += 2;
+        }
+        self.min_gallop = min_gallop.max(1);
+
+        if len1 == 1 {
+            // 左侧片段只剩下最后一个元素, 它比右侧剩下的元素都大.
+            for i in 0..len2 {
+                swap(arr, total - len1 - len2 + i, total - len2 + i);
+            }
+            swap_values(&mut arr[total - 1], &mut tmp[tmp_len - 1]);
+        } else if len1 > 0 {
+            // 右侧片段已经合并完了.
+            swap_slices(&mut arr[total - len1..], &mut tmp[tmp_len - len1..]);
+        }
+    }
+
+    /// 从右向左合并 `arr[..len1]` 和 `arr[len1..]`, 右侧片段较短.
+    ///
+    /// 调用者保证的条件与 `merge_low()` 相同.
+    fn merge_high<T>(&mut self, arr: &mut [T], len1: usize)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut tmp = arr[len1..].to_vec();
+        let mut len1 = len1;
+        let mut len2 = tmp.len();
+
+        // 下一个要合并的元素分别是 `arr[len1 - 1]` 和 `tmp[len2 - 1]`,
+        // 要写入的位置是 `arr[len1 + len2 - 1]`.
+        swap(arr, len1 + len2 - 1, len1 - 1);
+        len1 -= 1;
+        if len1 == 0 {
+            swap_slices(&mut arr[..len2], &mut tmp[..len2]);
+            return;
+        }
+        if len2 == 1 {
+            for i in (0..len1).rev() {
+                swap(arr, i + 1, i);
+            }
+            swap_values(&mut arr[0], &mut tmp[0]);
+            return;
+        }
+
+        let mut min_gallop = self.min_gallop;
+        'outer: loop {
+            let mut count1: usize = 0;
+            let mut count2: usize = 0;
+
+            loop {
+                let dest = len1 + len2 - 1;
+                if (self.compare)(&tmp[len2 - 1], &arr[len1 - 1]) == Ordering::Less {
+                    swap(arr, dest, len1 - 1);
+                    len1 -= 1;
+                    count1 += 1;
+                    count2 = 0;
+                    if len1 == 0 {
+                        break 'outer;
+                    }
+                } else {
+                    swap_values(&mut arr[dest], &mut tmp[len2 - 1]);
+                    len2 -= 1;
+                    count2 += 1;
+                    count1 = 0;
+                    if len2 == 1 {
+                        break 'outer;
+                    }
+                }
+                if (count1 | count2) >= min_gallop {
+                    break;
+                }
+            }
+
+            loop {
+                count1 = len1 - gallop_right(&tmp[len2 - 1], &arr[..len1], len1 - 1, self.compare);
+                if count1 != 0 {
+                    let src = len1 - count1;
+                    for i in (0..count1).rev() {
+                        swap(arr, src + len2 + i, src + i);
+                    }
+                    len1 -= count1;
+                    if len1 == 0 {
+                        break 'outer;
+                    }
+                }
+                swap_values(&mut arr[len1 + len2 - 1], &mut tmp[len2 - 1]);
+                len2 -= 1;
+                if len2 == 1 {
+                    break 'outer;
+                }
+
+                count2 = len2 - gallop_left(&arr[len1 - 1], &tmp[..len2], len2 - 1, self.compare);
+                if count2 != 0 {
+                    let dest = len1 + len2 - count2;
+                    swap_slices(&mut arr[dest..len1 + len2], &mut tmp[len2 - count2..len2]);
+                    len2 -= count2;
+                    if len2 <= 1 {
+                        break 'outer;
+                    }
+                }
+                swap(arr, len1 + len2 - 1, len1 - 1);
+                len1 -= 1;
+                if len1 == 0 {
+                    break 'outer;
+                }
+
+                min_gallop = min_gallop.saturating_sub(1);
+                if count1 < MIN_GALLOP && count2 < MIN_GALLOP {
+                    break;
+                }
+            }
+
+            min_gallop += 2;
+        }
+        self.min_gallop = min_gallop.max(1);
+
+        if len2 == 1 {
+            // 右侧片段只剩下第一个元素, 它比左侧剩下的元素都小.
+            for i in (0..len1).rev() {
+                swap(arr, i + 1, i);
+            }
+            swap_values(&mut arr[0], &mut tmp[0]);
+        } else if len2 > 0 {
+            // 左侧片段已经合并完了.
+            swap_slices(&mut arr[..len2], &mut tmp[..len2]);
+        }
+    }
+}
+
+/// Timsort.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TimSort;
+
+impl<T: Clone> Sorter<T> for TimSort {
+    fn name(&self) -> &'static str {
+        "timsort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        timsort_by(arr, compare);
+    }
+}
+
+/// 真正的 Timsort.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NaturalTimSort;
+
+impl<T: Clone> Sorter<T> for NaturalTimSort {
+    fn name(&self) -> &'static str {
+        "natural-timsort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        natural_timsort_by(arr, compare);
+    }
+}
+
+/// 使用希尔排序代替插入排序的 timsort.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ShellTimSort;
+
+impl<T: Clone> Sorter<T> for ShellTimSort {
+    fn name(&self) -> &'static str {
+        "shell-timsort"
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        shell_timsort_by(arr, compare);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::timsort::{shell_timsort, timsort};
+    use crate::timsort::{
+        natural_timsort, natural_timsort_by, natural_timsort_by_key, shell_timsort,
+        shell_timsort_by, shell_timsort_by_key, timsort, timsort_by, timsort_by_key,
+    };
 
     #[test]
     fn test_timsort() {
@@ -227,4 +890,93 @@ mod tests {
             ['A', 'E', 'E', 'I', 'N', 'O', 'Q', 'S', 'S', 'T', 'U', 'Y']
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_timsort_by() {
+        let mut list: Vec<i32> = (0..100).collect();
+        timsort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, (0..100).rev().collect::<Vec<_>>());
+
+        let mut list: Vec<i32> = (0..200).collect();
+        shell_timsort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, (0..200).rev().collect::<Vec<_>>());
+
+        let mut list: Vec<(usize, usize)> = (0..100).map(|i| (i % 3, i)).collect();
+        timsort_by_key(&mut list, |pair| pair.0);
+        for pair in list.windows(2) {
+            assert!(pair[0].0 < pair[1].0 || (pair[0].0 == pair[1].0 && pair[0].1 < pair[1].1));
+        }
+
+        let mut list: Vec<(usize, usize)> = (0..200).map(|i| (i % 3, i)).collect();
+        shell_timsort_by_key(&mut list, |pair| pair.0);
+        assert!(list.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    }
+
+    #[test]
+    fn test_natural_timsort() {
+        let mut list = [0, 5, 3, 2, 2];
+        natural_timsort(&mut list);
+        assert_eq!(list, [0, 2, 2, 3, 5]);
+
+        let mut list = [-2, -5, -45];
+        natural_timsort(&mut list);
+        assert_eq!(list, [-45, -5, -2]);
+
+        let mut list = "EASYQUESTION".chars().collect::<Vec<_>>();
+        natural_timsort(&mut list);
+        assert_eq!(
+            list,
+            ['A', 'E', 'E', 'I', 'N', 'O', 'Q', 'S', 'S', 'T', 'U', 'Y']
+        );
+
+        // 多个升序以及降序的片段.
+        let mut list: Vec<i32> = (0..500)
+            .chain((200..700).rev())
+            .chain(100..300)
+            .chain((0..1000).map(|i| (i * 7919) % 1013))
+            .collect();
+        let mut expected = list.clone();
+        expected.sort_unstable();
+        natural_timsort(&mut list);
+        assert_eq!(list, expected);
+    }
+
+    #[test]
+    fn test_natural_timsort_by() {
+        let mut list: Vec<i32> = (0..1000).collect();
+        natural_timsort_by(&mut list, |a, b| b.cmp(a));
+        assert_eq!(list, (0..1000).rev().collect::<Vec<_>>());
+
+        // 稳定排序
+        let mut list: Vec<(usize, usize)> = (0..2000).map(|i| ((i * 7919) % 17, i)).collect();
+        natural_timsort_by_key(&mut list, |pair| pair.0);
+        for pair in list.windows(2) {
+            assert!(pair[0].0 < pair[1].0 || (pair[0].0 == pair[1].0 && pair[0].1 < pair[1].1));
+        }
+    }
+
+    #[test]
+    fn test_natural_timsort_nearly_sorted() {
+        // 对于已经有序的数组, 只需要 `n - 1` 次比较.
+        let len: usize = 10_000;
+        let mut list: Vec<usize> = (0..len).collect();
+        let mut comparisons: usize = 0;
+        natural_timsort_by(&mut list, |a, b| {
+            comparisons += 1;
+            a.cmp(b)
+        });
+        assert_eq!(comparisons, len - 1);
+
+        // 由几个有序片段拼接起来的数组, 比较次数接近线性.
+        let mut list: Vec<usize> = (0..4)
+            .flat_map(|i| (i..len / 4 * 4).step_by(4))
+            .collect();
+        let mut comparisons: usize = 0;
+        natural_timsort_by(&mut list, |a, b| {
+            comparisons += 1;
+            a.cmp(b)
+        });
+        assert_eq!(list, (0..len).collect::<Vec<_>>());
+        assert!(comparisons < 3 * len);
+    }
+}
@@ -3,19 +3,25 @@
 // in the LICENSE file.
 
 use std::fmt;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader, Read};
+use std::io::{self, BufRead, BufReader};
 
+use crate::dataset::{generate, Distribution, DEFAULT_SEED};
+
+/// Check whether `list` is sorted in ascending order, and print the first
+/// pair in wrong order.
+///
+/// Use `verify::check_sorted()` to get the error instead.
 pub fn is_sorted<T>(list: &[T]) -> bool
 where
     T: PartialOrd + fmt::Debug,
 {
-    for i in 0..(list.len() - 1) {
-        if list[i] > list[i + 1] {
+    for i in 1..list.len() {
+        if list[i - 1] > list[i] {
             println!(
-                "Order error at: {i}, values: ({:?}, {:?})",
-                list[i],
-                list[i + 1]
+                "Order error at: {}, values: ({:?}, {:?})",
+                i - 1,
+                list[i - 1],
+                list[i]
             );
             return false;
         }
@@ -45,7 +51,7 @@ where
     }
 }
 
-/// Read integers from stdin.
+/// Read integers from stdin, lines starting with `#` are skipped.
 ///
 /// # Panics
 /// Raise panic if invalid integer found.
@@ -55,6 +61,9 @@ pub fn read_ints() -> Vec<i32> {
     let buffer = BufReader::new(io::stdin());
     let mut input_iter = buffer.lines();
     while let Some(Ok(line)) = input_iter.next() {
+        if line.starts_with('#') {
+            continue;
+        }
         for word in line.split_whitespace() {
             let value = word.parse::<i32>().expect("Invalid integer");
             v.push(value);
@@ -76,45 +85,62 @@ pub fn read_strings() -> Vec<String> {
     v
 }
 
-/// Generate random integers.
+/// Generate random integers, uniformly distributed in the whole `i32` range.
+///
+/// The same seed `dataset::DEFAULT_SEED` is used every time, so that results
+/// of benchmarks are reproducible.
 ///
 /// # Errors
-/// Returns error if failed to rand system random file.
+/// Never returns error, the `Result` is kept for compatibility.
 pub fn random_ints(len: usize) -> Result<Vec<i32>, io::Error> {
-    let mut file = File::open("/dev/urandom")?;
-    let mut buf = vec![0; len * 4];
-    file.read_exact(&mut buf)?;
-
-    let mut nums = vec![0; len];
-    for i in 0..len {
-        let array: [u8; 4] = [buf[4 * i], buf[4 * i + 1], buf[4 * i + 2], buf[4 * i + 3]];
-        nums[i] = i32::from_le_bytes(array);
-    }
-    Ok(nums)
+    random_ints_in_range(len, i32::MIN, i32::MAX, DEFAULT_SEED)
 }
 
-
-/// Generate random integers in specific range.
+/// Generate random integers uniformly distributed in `[min, max]`, using `seed`.
+///
+/// Same seed always generates the same integers.
 ///
 /// # Errors
-/// Returns error if failed to rand system random file.
-pub fn random_ints_in_range(len: usize, min: i32, max: i32) -> Result<Vec<i32>, io::Error> {
-    let mut arr = random_ints(len)?;
-    for num in &mut arr {
-        *num = (*num).max(min).min(max);
-    }
-
-    Ok(arr)
+/// Never returns error, the `Result` is kept for compatibility.
+///
+/// # Panics
+/// Raise panic if `min > max`.
+pub fn random_ints_in_range(
+    len: usize,
+    min: i32,
+    max: i32,
+    seed: u64,
+) -> Result<Vec<i32>, io::Error> {
+    Ok(generate(&Distribution::Uniform { min, max }, len, seed))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::util::random_ints;
+    use crate::util::{is_sorted, random_ints, random_ints_in_range};
+
+    #[test]
+    fn test_is_sorted() {
+        assert!(is_sorted::<i32>(&[]));
+        assert!(is_sorted(&[1]));
+        assert!(is_sorted(&[1, 2, 2, 3]));
+        assert!(!is_sorted(&[1, 3, 2]));
+    }
 
     #[test]
     fn test_random_ints() {
         let len = 100;
         let nums: Vec<i32> = random_ints(len).unwrap();
         assert_eq!(nums.len(), len);
+        assert_eq!(nums, random_ints(len).unwrap());
+    }
+
+    #[test]
+    fn test_random_ints_in_range() {
+        let nums: Vec<i32> = random_ints_in_range(1000, -5, 5, 1).unwrap();
+        assert!(nums.iter().all(|num| (-5..=5).contains(num)));
+        let at_bounds = nums.iter().filter(|&&num| num == -5 || num == 5).count();
+        assert!(at_bounds < 500);
+        assert_eq!(nums, random_ints_in_range(1000, -5, 5, 1).unwrap());
+        assert_ne!(nums, random_ints_in_range(1000, -5, 5, 2).unwrap());
     }
 }
\ No newline at end of file
@@ -0,0 +1,601 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 排序算法的正确性检查.
+//!
+//! - [`check_sorted_by()`] 检查结果是否有序;
+//! - [`check_permutation()`] 检查结果是否是输入的一个排列, 也就是没有丢失或者复制元素;
+//! - [`check_stable()`] 使用带标签的元素 [`Tagged`] 检查排序是否稳定;
+//! - [`Pattern`] 生成各种对排序算法不友好的输入, [`verify_sort()`] 用它们逐一检查排序函数.
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::sorter::partial_compare;
+
+/// 检查失败的原因.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    /// `output[index]` 大于 `output[index + 1]`.
+    NotSorted { index: usize },
+    /// 输出与输入的长度不同.
+    LengthMismatch { input: usize, output: usize },
+    /// 输出不是输入的一个排列, `index` 是两者排序后第一个不同的位置.
+    NotPermutation { index: usize },
+    /// `output[index]` 与 `output[index + 1]` 的键值相等, 但是它们的相对顺序被改变了.
+    NotStable { index: usize },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotSorted { index } => write!(f, "Order error at: {index}"),
+            Self::LengthMismatch { input, output } => {
+                write!(f, "Length changed from {input} to {output}")
+            }
+            Self::NotPermutation { index } => {
+                write!(
+                    f,
+                    "Output is not a permutation of input, differs at: {index}"
+                )
+            }
+            Self::NotStable { index } => write!(f, "Stability error at: {index}"),
+        }
+    }
+}
+
+impl Error for VerifyError {}
+
+/// 检查失败时的上下文, 包括输入的模式和长度.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyFailure {
+    pub pattern: Pattern,
+    pub len: usize,
+    pub error: VerifyError,
+}
+
+impl fmt::Display for VerifyFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} input of length {}: {}",
+            self.pattern, self.len, self.error
+        )
+    }
+}
+
+impl Error for VerifyFailure {}
+
+/// 检查 `output` 是否是增序的.
+///
+/// # Errors
+/// 返回第一个逆序的位置.
+pub fn check_sorted<T: PartialOrd>(output: &[T]) -> Result<(), VerifyError> {
+    check_sorted_by(output, partial_compare)
+}
+
+/// 检查 `output` 是否按照比较函数 `compare` 排好序.
+///
+/// # Errors
+/// 返回第一个逆序的位置.
+pub fn check_sorted_by<T, F>(output: &[T], mut compare: F) -> Result<(), VerifyError>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    output
+        .windows(2)
+        .position(|pair| compare(&pair[0], &pair[1]) == Ordering::Greater)
+        .map_or(Ok(()), |index| Err(VerifyError::NotSorted { index }))
+}
+
+/// 检查 `output` 是否是 `input` 的一个排列.
+///
+/// 把两者的副本排序后逐个比较, 所以要求 `T` 的 `PartialOrd` 与 `PartialEq` 是一致的.
+///
+/// # Errors
+/// 长度不同, 或者存在不同的元素时返回错误.
+pub fn check_permutation<T>(input: &[T], output: &[T]) -> Result<(), VerifyError>
+where
+    T: PartialOrd + Clone,
+{
+    if input.len() != output.len() {
+        return Err(VerifyError::LengthMismatch {
+            input: input.len(),
+            output: output.len(),
+        });
+    }
+    let mut expected = input.to_vec();
+    expected.sort_by(partial_compare);
+    let mut actual = output.to_vec();
+    actual.sort_by(partial_compare);
+    expected
+        .iter()
+        .zip(&actual)
+        .position(|(a, b)| a != b)
+        .map_or(Ok(()), |index| Err(VerifyError::NotPermutation { index }))
+}
+
+/// 带标签的元素, 只按照键值 `key` 进行比较, 标签 `tag` 记录了它在输入中的位置.
+///
+/// 稳定排序之后, 键值相等的元素的标签应该是递增的.
+#[derive(Debug, Clone, Copy)]
+pub struct Tagged {
+    pub key: i32,
+    pub tag: usize,
+}
+
+impl Tagged {
+    /// 给 `keys` 中的每个元素加上它的位置作为标签.
+    #[must_use]
+    pub fn tag_all(keys: &[i32]) -> Vec<Self> {
+        keys.iter()
+            .enumerate()
+            .map(|(tag, &key)| Self { key, tag })
+            .collect()
+    }
+}
+
+impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl PartialOrd for Tagged {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.key.partial_cmp(&other.key)
+    }
+}
+
+/// 检查 `output` 是否是 `input` 的一个排列, 并且是稳定排序的结果.
+///
+/// 与 `check_permutation()` 不同, 这里会同时比较键值和标签.
+///
+/// # Errors
+/// 返回第一个不满足条件的位置.
+pub fn check_stable(input: &[Tagged], output: &[Tagged]) -> Result<(), VerifyError> {
+    check_tagged_permutation(input, output)?;
+    check_sorted(output)?;
+    output
+        .windows(2)
+        .position(|pair| pair[0].key == pair[1].key && pair[0].tag > pair[1].tag)
+        .map_or(Ok(()), |index| Err(VerifyError::NotStable { index }))
+}
+
+fn check_tagged_permutation(input: &[Tagged], output: &[Tagged]) -> Result<(), VerifyError> {
+    let pairs = |list: &[Tagged]| -> Vec<(i32, usize)> {
+        let mut pairs: Vec<(i32, usize)> = list.iter().map(|item| (item.key, item.tag)).collect();
+        pairs.sort_unstable();
+        pairs
+    };
+    if input.len() != output.len() {
+        return Err(VerifyError::LengthMismatch {
+            input: input.len(),
+            output: output.len(),
+        });
+    }
+    pairs(input)
+        .iter()
+        .zip(&pairs(output))
+        .position(|(a, b)| a != b)
+        .map_or(Ok(()), |index| Err(VerifyError::NotPermutation { index }))
+}
+
+/// 输入数据的模式.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pattern {
+    /// 在 `[0, len)` 范围内均匀分布的随机数, 包含一些重复的值.
+    Random,
+    /// 已经排好序的.
+    Sorted,
+    /// 逆序的.
+    Reverse,
+    /// 所有元素都相等.
+    AllEqual,
+    /// 先升序再降序, 形如管风琴.
+    OrganPipe,
+    /// 只有很少几个不同的值.
+    FewUnique,
+    /// 由多个升序片段组成, 形如锯齿.
+    Sawtooth,
+    /// 针对 "三数取中" 快速排序的最坏输入 (median-of-3 killer).
+    QuicksortKiller,
+}
+
+impl Pattern {
+    pub const ALL: [Self; 8] = [
+        Self::Random,
+        Self::Sorted,
+        Self::Reverse,
+        Self::AllEqual,
+        Self::OrganPipe,
+        Self::FewUnique,
+        Self::Sawtooth,
+        Self::QuicksortKiller,
+    ];
+
+    /// 生成长度为 `len` 的输入, 相同的 `seed` 总是生成相同的随机数据.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn generate(self, len: usize, seed: u64) -> Vec<i32> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let n = len as i32;
+        match self {
+            Self::Random => (0..len).map(|_| rng.gen_range(0..n.max(1))).collect(),
+            Self::Sorted => (0..n).collect(),
+            Self::Reverse => (0..n).rev().collect(),
+            Self::AllEqual => vec![42; len],
+            Self::OrganPipe => (0..n / 2).chain((0..n - n / 2).rev()).collect(),
+            Self::FewUnique => (0..len).map(|_| rng.gen_range(0..4)).collect(),
+            Self::Sawtooth => (0..n).map(|i| i % 16).collect(),
+            Self::QuicksortKiller => median_of_three_killer(len),
+        }
+    }
+}
+
+/// 生成 Musser 在 "Introspective Sorting and Selection Algorithms" 中给出的输入,
+/// 使用首, 中, 尾三数取中的快速排序在它上面的时间复杂度退化为 `O(n^2)`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+fn median_of_three_killer(len: usize) -> Vec<i32> {
+    let k = len / 2;
+    let mut arr: Vec<i32> = (0..len as i32).collect();
+    for i in 1..=k {
+        if i % 2 == 1 {
+            arr[i - 1] = i as i32;
+            arr[i] = (k + i) as i32;
+        }
+        arr[k + i - 1] = 2 * i as i32;
+    }
+    arr
+}
+
+/// `McIlroy` 的 "A Killer Adversary for Quicksort" 中的对手算法 (antiqsort).
+///
+/// 开始时所有元素都是未确定的 "气体" (gas), 排序算法比较两个气体元素时, 把其中一个冻结为
+/// 当前最小的固体 (solid) 值. 这样构造出的输入会让确定性的快速排序尽可能多地进行比较.
+///
+/// `sort` 需要使用给定的比较函数对数组排序, 返回构造出的输入.
+pub fn antiqsort<S>(len: usize, sort: S) -> Vec<i32>
+where
+    S: FnOnce(&mut [usize], &mut dyn FnMut(&usize, &usize) -> Ordering),
+{
+    let gas = len;
+    let mut values = vec![gas; len];
+    let mut solid: usize = 0;
+    let mut candidate: usize = 0;
+    let mut indices: Vec<usize> = (0..len).collect();
+
+    sort(&mut indices, &mut |&x, &y| {
+        if values[x] == gas && values[y] == gas {
+            let frozen = if x == candidate { x } else { y };
+            values[frozen] = solid;
+            solid += 1;
+        }
+        if values[x] == gas {
+            candidate = x;
+        } else if values[y] == gas {
+            candidate = y;
+        }
+        values[x].cmp(&values[y])
+    });
+
+    values
+        .into_iter()
+        .map(|value| i32::try_from(value).unwrap_or(i32::MAX))
+        .collect()
+}
+
+/// 测试用的长度, 包含了各种边界情况.
+pub const LENGTHS: [usize; 12] = [0, 1, 2, 3, 4, 7, 16, 17, 33, 64, 100, 257];
+
+/// 用 `Pattern::ALL` 中的各种输入检查排序函数 `sort`.
+///
+/// 每种模式都使用 `LENGTHS` 中的长度, 检查结果是否有序, 是否是输入的一个排列;
+/// 如果 `stable` 为 `true`, 还会检查排序是否稳定.
+///
+/// # Errors
+/// 返回第一个检查失败的输入.
+pub fn verify_sort<F>(sort: F, stable: bool) -> Result<(), VerifyFailure>
+where
+    F: FnMut(&mut [Tagged]),
+{
+    verify_sort_with_lengths(sort, stable, &LENGTHS)
+}
+
+/// 与 `verify_sort()` 相同, 但是使用 `lengths` 中的长度.
+///
+/// 并行排序只有在输入足够长时才会使用多个线程, 需要用它检查更长的输入.
+///
+/// # Errors
+/// 返回第一个检查失败的输入.
+pub fn verify_sort_with_lengths<F>(
+    mut sort: F,
+    stable: bool,
+    lengths: &[usize],
+) -> Result<(), VerifyFailure>
+where
+    F: FnMut(&mut [Tagged]),
+{
+    for pattern in Pattern::ALL {
+        for &len in lengths {
+            let input = Tagged::tag_all(&pattern.generate(len, len as u64));
+            let mut output = input.clone();
+            sort(&mut output);
+            let result = if stable {
+                check_stable(&input, &output)
+            } else {
+                check_tagged_permutation(&input, &output).and_then(|()| check_sorted(&output))
+            };
+            result.map_err(|error| VerifyFailure {
+                pattern,
+                len,
+                error,
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// 用 `Pattern::ALL` 中的各种输入检查只适用于整数的排序函数 `sort`.
+///
+/// # Errors
+/// 返回第一个检查失败的输入.
+pub fn verify_int_sort<F>(mut sort: F) -> Result<(), VerifyFailure>
+where
+    F: FnMut(&mut [i32]),
+{
+    for pattern in Pattern::ALL {
+        for len in LENGTHS {
+            let input = pattern.generate(len, len as u64);
+            let mut output = input.clone();
+            sort(&mut output);
+            check_permutation(&input, &output)
+                .and_then(|()| check_sorted(&output))
+                .map_err(|error| VerifyFailure {
+                    pattern,
+                    len,
+                    error,
+                })?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{
+        antiqsort, check_permutation, check_sorted, check_stable, verify_int_sort, verify_sort,
+        verify_sort_with_lengths, Pattern, Tagged, VerifyError,
+    };
+    use crate::bubble_sort::{bubble_sort, recursive_bubble_sort};
+    use crate::bucket_sort::{bucket_sort, generic_bucket_sort, shell_bucket_sort};
+    use crate::counting_sort::{counting_sort, counting_sort_generic, counting_sort_with_map};
+    use crate::double_sort::double_sort;
+    use crate::gnome_sort::gnome_sort;
+    use crate::heap_sort::heap_sort;
+    use crate::insertion_sort::{binary_insertion_sort, insertion_sort, recursive_insertion_sort};
+    use crate::introsort::{introsort, introsort_by};
+    use crate::merge_sort::{
+        bottom_up_merge_sort, in_place_merge_sort, in_place_shell_merge_sort, insertion_merge_sort,
+        merge_sort, shell_merge_sort, three_way_merge_sort, topdown_merge_sort,
+    };
+    use crate::odd_even_sort::odd_even_sort;
+    use crate::parallel_sort::{
+        parallel_merge_sort, parallel_quicksort, parallel_sample_sort, SEQUENTIAL_THRESHOLD,
+    };
+    use crate::pdqsort::{pdqsort, pdqsort_by};
+    use crate::quicksort::{
+        head_quicksort, insertion_quicksort, iterative_quicksort, quicksort, quicksort_by,
+        two_pointer_quicksort,
+    };
+    use crate::radix_sort::{msd_radix_sort, radix_sort, radix_sort_by_key};
+    use crate::selection_sort::{
+        recursive_selection_sort, selection_sort, stable_selection_sort, two_way_selection_sort,
+    };
+    use crate::shaker_sort::shaker_sort;
+    use crate::shell_sort::shell_sort;
+    use crate::timsort::{natural_timsort, shell_timsort, timsort};
+
+    type SortFn = fn(&mut [Tagged]);
+    type IntSortFn = fn(&mut [i32]);
+    type SortByFn = fn(&mut [i32], &mut dyn FnMut(&i32, &i32) -> Ordering);
+
+    /// 把键值编码为大端字节序, 并翻转符号位, 这样字节的字典序与整数的大小顺序一致.
+    struct KeyBytes {
+        bytes: [u8; 4],
+        item: Tagged,
+    }
+
+    impl AsRef<[u8]> for KeyBytes {
+        fn as_ref(&self) -> &[u8] {
+            &self.bytes
+        }
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn tagged_msd_radix_sort(arr: &mut [Tagged]) {
+        let mut keys: Vec<KeyBytes> = arr
+            .iter()
+            .map(|&item| KeyBytes {
+                bytes: ((item.key as u32) ^ 0x8000_0000).to_be_bytes(),
+                item,
+            })
+            .collect();
+        msd_radix_sort(&mut keys);
+        for (dst, key) in arr.iter_mut().zip(keys) {
+            *dst = key.item;
+        }
+    }
+
+    const STABLE_SORTS: &[(&str, SortFn)] = &[
+        ("binary_insertion_sort", binary_insertion_sort),
+        ("bottom_up_merge_sort", bottom_up_merge_sort),
+        ("bubble_sort", bubble_sort),
+        ("gnome_sort", gnome_sort),
+        ("insertion_merge_sort", insertion_merge_sort),
+        ("insertion_sort", insertion_sort),
+        ("merge_sort", merge_sort),
+        ("msd_radix_sort", tagged_msd_radix_sort),
+        ("natural_timsort", natural_timsort),
+        ("odd_even_sort", odd_even_sort),
+        ("parallel_merge_sort", |arr| parallel_merge_sort(arr, 4)),
+        ("radix_sort_by_key", |arr| {
+            radix_sort_by_key(arr, |item| item.key);
+        }),
+        ("recursive_bubble_sort", recursive_bubble_sort),
+        ("recursive_insertion_sort", recursive_insertion_sort),
+        ("shaker_sort", shaker_sort),
+        ("stable_selection_sort", stable_selection_sort),
+        ("timsort", timsort),
+        ("topdown_merge_sort", topdown_merge_sort),
+    ];
+
+    const UNSTABLE_SORTS: &[(&str, SortFn)] = &[
+        ("double_sort", double_sort),
+        ("head_quicksort", head_quicksort),
+        ("heap_sort", heap_sort),
+        ("in_place_merge_sort", in_place_merge_sort),
+        ("in_place_shell_merge_sort", in_place_shell_merge_sort),
+        ("insertion_quicksort", insertion_quicksort),
+        ("introsort", introsort),
+        ("iterative_quicksort", iterative_quicksort),
+        ("parallel_quicksort", |arr| parallel_quicksort(arr, 4)),
+        ("parallel_sample_sort", |arr| parallel_sample_sort(arr, 4, 7)),
+        ("pdqsort", pdqsort),
+        ("quicksort", quicksort),
+        ("recursive_selection_sort", recursive_selection_sort),
+        ("selection_sort", selection_sort),
+        ("shell_merge_sort", shell_merge_sort),
+        ("shell_sort", shell_sort),
+        ("shell_timsort", shell_timsort),
+        ("three_way_merge_sort", three_way_merge_sort),
+        ("two_pointer_quicksort", two_pointer_quicksort),
+        ("two_way_selection_sort", two_way_selection_sort),
+    ];
+
+    const INT_SORTS: &[(&str, IntSortFn)] = &[
+        ("bucket_sort", bucket_sort),
+        ("counting_sort", counting_sort),
+        ("counting_sort_generic", counting_sort_generic),
+        ("counting_sort_with_map", counting_sort_with_map),
+        ("generic_bucket_sort", generic_bucket_sort),
+        ("radix_sort", radix_sort),
+        ("shell_bucket_sort", shell_bucket_sort),
+    ];
+
+    #[test]
+    fn test_stable_sorts() {
+        for (name, sort) in STABLE_SORTS {
+            if let Err(failure) = verify_sort(sort, true) {
+                panic!("{name}: {failure}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_unstable_sorts() {
+        for (name, sort) in UNSTABLE_SORTS {
+            if let Err(failure) = verify_sort(sort, false) {
+                panic!("{name}: {failure}");
+            }
+        }
+    }
+
+    /// 超过 `SEQUENTIAL_THRESHOLD` 的长度, 以检查并行的合并以及分桶过程.
+    const PARALLEL_LENGTHS: [usize; 2] = [SEQUENTIAL_THRESHOLD + 1, 3 * SEQUENTIAL_THRESHOLD + 17];
+
+    #[test]
+    fn test_parallel_sorts() {
+        let stable: &[(&str, SortFn)] =
+            &[("parallel_merge_sort", |arr| parallel_merge_sort(arr, 4))];
+        let unstable: &[(&str, SortFn)] = &[
+            ("parallel_quicksort", |arr| parallel_quicksort(arr, 4)),
+            ("parallel_sample_sort", |arr| parallel_sample_sort(arr, 4, 7)),
+        ];
+        for (sorts, is_stable) in [(stable, true), (unstable, false)] {
+            for (name, sort) in sorts {
+                if let Err(failure) = verify_sort_with_lengths(sort, is_stable, &PARALLEL_LENGTHS) {
+                    panic!("{name}: {failure}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_int_sorts() {
+        for (name, sort) in INT_SORTS {
+            if let Err(failure) = verify_int_sort(sort) {
+                panic!("{name}: {failure}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_checks() {
+        assert_eq!(check_sorted::<i32>(&[]), Ok(()));
+        assert_eq!(
+            check_sorted(&[1, 3, 2]),
+            Err(VerifyError::NotSorted { index: 1 })
+        );
+        assert_eq!(
+            check_permutation(&[1, 2, 2], &[1, 1, 2]),
+            Err(VerifyError::NotPermutation { index: 1 })
+        );
+        assert_eq!(
+            check_permutation(&[1, 2], &[1]),
+            Err(VerifyError::LengthMismatch {
+                input: 2,
+                output: 1
+            })
+        );
+
+        let input = Tagged::tag_all(&[1, 0, 1]);
+        let swapped = [input[1], input[2], input[0]];
+        assert_eq!(
+            check_stable(&input, &swapped),
+            Err(VerifyError::NotStable { index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_patterns_are_reproducible() {
+        for pattern in Pattern::ALL {
+            assert_eq!(pattern.generate(100, 7), pattern.generate(100, 7));
+            assert_eq!(pattern.generate(100, 7).len(), 100);
+        }
+    }
+
+    #[test]
+    fn test_antiqsort() {
+        let len: usize = 2000;
+        let count_comparisons = |input: &[i32], sort: SortByFn| {
+            let mut list = input.to_vec();
+            let mut comparisons: usize = 0;
+            sort(&mut list, &mut |a, b| {
+                comparisons += 1;
+                a.cmp(b)
+            });
+            assert_eq!(check_sorted(&list), Ok(()));
+            comparisons
+        };
+
+        // 针对 quicksort 构造的输入会让它退化为 `O(n^2)`.
+        let killer = antiqsort(len, |arr, compare| quicksort_by(arr, compare));
+        let comparisons = count_comparisons(&killer, |arr, compare| quicksort_by(arr, compare));
+        assert!(comparisons > len * len / 8);
+
+        // 内省排序和 pdqsort 在针对它们自己构造的输入上依然是 `O(n log(n))` 的.
+        let limit = 4 * len * len.ilog2() as usize;
+        let killer = antiqsort(len, |arr, compare| introsort_by(arr, compare));
+        assert!(count_comparisons(&killer, |arr, compare| introsort_by(arr, compare)) < limit);
+        let killer = antiqsort(len, |arr, compare| pdqsort_by(arr, compare));
+        assert!(count_comparisons(&killer, |arr, compare| pdqsort_by(arr, compare)) < limit);
+    }
+}
@@ -0,0 +1 @@
+../../../deque/src/array_deque.rs
\ No newline at end of file
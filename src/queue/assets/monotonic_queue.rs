@@ -0,0 +1 @@
+../../../queue/src/monotonic_queue.rs
\ No newline at end of file
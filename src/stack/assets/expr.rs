@@ -0,0 +1 @@
+../../../stack/src/expr.rs
\ No newline at end of file
@@ -0,0 +1 @@
+../../../stack/src/monotonic_stack.rs
\ No newline at end of file
@@ -7,6 +7,8 @@ use std::fmt;
 use std::fmt::Formatter;
 use std::hash::{Hash, Hasher};
 
+use crate::traits::{BoundedStack, Stack};
+
 /// 使用数组实现静态栈结构
 pub struct ArrayStack<T> {
     top: usize,
@@ -82,6 +84,35 @@ impl<T> ArrayStack<T> {
     }
 }
 
+impl<T> Stack<T> for ArrayStack<T> {
+    #[inline]
+    fn push(&mut self, value: T) -> Result<(), T> {
+        Self::push(self, value)
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<T> {
+        Self::pop(self)
+    }
+
+    #[inline]
+    fn top(&self) -> Option<&T> {
+        Self::top(self)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        Self::len(self)
+    }
+}
+
+impl<T> BoundedStack<T> for ArrayStack<T> {
+    #[inline]
+    fn capacity(&self) -> usize {
+        Self::capacity(self)
+    }
+}
+
 impl<T: PartialEq> PartialEq for ArrayStack<T> {
     fn eq(&self, other: &Self) -> bool {
         self.top == other.top && PartialEq::eq(&self.buf, &other.buf)
@@ -10,6 +10,8 @@ use std::hash::{Hash, Hasher};
 use std::mem::ManuallyDrop;
 use std::ptr::NonNull;
 
+use crate::traits::{BoundedStack, Stack};
+
 /// 使用数组实现静态栈结构
 pub struct ArrayStack2<T> {
     top: usize,
@@ -92,6 +94,35 @@ impl<T> ArrayStack2<T> {
     }
 }
 
+impl<T> Stack<T> for ArrayStack2<T> {
+    #[inline]
+    fn push(&mut self, value: T) -> Result<(), T> {
+        Self::push(self, value)
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<T> {
+        Self::pop(self)
+    }
+
+    #[inline]
+    fn top(&self) -> Option<&T> {
+        Self::top(self)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        Self::len(self)
+    }
+}
+
+impl<T> BoundedStack<T> for ArrayStack2<T> {
+    #[inline]
+    fn capacity(&self) -> usize {
+        Self::capacity(self)
+    }
+}
+
 impl<T: PartialEq> PartialEq for ArrayStack2<T> {
     fn eq(&self, other: &Self) -> bool {
         self.top == other.top && PartialEq::eq(&self.buf, &other.buf)
@@ -0,0 +1,756 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 算术表达式的解析与求值.
+//!
+//! 分三个步骤完成:
+//! - 词法分析, 把输入的字符串切分成数字, 标识符, 运算符和括号等记号
+//! - 使用调度场算法 (shunting-yard), 把中缀表达式转换成后缀表达式 (逆波兰表达式)
+//! - 使用一个操作数栈, 对后缀表达式求值
+//!
+//! 支持四则运算, 取余 `%`, 乘方 `^`, 一元负号, 括号, 变量以及函数调用.
+//! 所有的错误都会记录它在输入字符串中的字节偏移量.
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::Peekable;
+use std::str::FromStr;
+
+use crate::vec_stack::VecStack;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// 无法识别的字符.
+    UnexpectedChar(char),
+    /// 数字的格式不正确, 比如 `1.2.3`.
+    InvalidNumber,
+    /// 记号出现在了不该出现的位置, 比如 `1 + * 2`.
+    UnexpectedToken,
+    /// 表达式不完整, 比如 `1 +`.
+    UnexpectedEnd,
+    /// 括号不匹配.
+    UnmatchedParen,
+    /// 逗号出现在函数调用之外.
+    MisplacedComma,
+    /// 变量未定义.
+    UnknownVariable,
+    /// 函数未定义.
+    UnknownFunction,
+    /// 函数的参数个数不正确.
+    ArgumentCount,
+    /// 除数或者取余运算的右操作数为 0.
+    DivisionByZero,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedChar(ch) => write!(f, "unexpected character {ch:?}"),
+            Self::InvalidNumber => f.write_str("invalid number"),
+            Self::UnexpectedToken => f.write_str("unexpected token"),
+            Self::UnexpectedEnd => f.write_str("unexpected end of expression"),
+            Self::UnmatchedParen => f.write_str("unmatched parenthesis"),
+            Self::MisplacedComma => f.write_str("comma outside of function call"),
+            Self::UnknownVariable => f.write_str("unknown variable"),
+            Self::UnknownFunction => f.write_str("unknown function"),
+            Self::ArgumentCount => f.write_str("wrong number of arguments"),
+            Self::DivisionByZero => f.write_str("division by zero"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExprError {
+    kind: ErrorKind,
+    offset: usize,
+}
+
+impl ExprError {
+    #[must_use]
+    #[inline]
+    pub const fn new(kind: ErrorKind, offset: usize) -> Self {
+        Self { kind, offset }
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// 出错的位置, 即输入字符串中的字节偏移量.
+    #[must_use]
+    #[inline]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.kind, self.offset)
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    /// 一元负号.
+    Neg,
+}
+
+impl Operator {
+    /// 优先级, 数值越大越先计算.
+    ///
+    /// 负号的优先级低于乘方, 所以 `-2^2` 等于 `-4`.
+    #[must_use]
+    pub const fn precedence(self) -> u8 {
+        match self {
+            Self::Add | Self::Sub => 1,
+            Self::Mul | Self::Div | Self::Rem => 2,
+            Self::Neg => 3,
+            Self::Pow => 4,
+        }
+    }
+
+    #[must_use]
+    pub const fn associativity(self) -> Associativity {
+        match self {
+            Self::Pow | Self::Neg => Associativity::Right,
+            _ => Associativity::Left,
+        }
+    }
+
+    #[must_use]
+    pub const fn is_unary(self) -> bool {
+        matches!(self, Self::Neg)
+    }
+
+    #[must_use]
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Rem => "%",
+            Self::Pow => "^",
+            Self::Neg => "neg",
+        }
+    }
+
+    /// 栈顶的运算符 `self` 是否要在 `incoming` 入栈之前先输出.
+    const fn goes_before(self, incoming: Self) -> bool {
+        let (top, new) = (self.precedence(), incoming.precedence());
+        top > new || (top == new && matches!(incoming.associativity(), Associativity::Left))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Number(f64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Caret,
+    LeftParen,
+    RightParen,
+    Comma,
+}
+
+/// 词法分析得到的记号, 以及它在输入字符串中的字节偏移量.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub offset: usize,
+}
+
+const fn punctuation(byte: u8) -> Option<TokenKind> {
+    let kind = match byte {
+        b'+' => TokenKind::Plus,
+        b'-' => TokenKind::Minus,
+        b'*' => TokenKind::Star,
+        b'/' => TokenKind::Slash,
+        b'%' => TokenKind::Percent,
+        b'^' => TokenKind::Caret,
+        b'(' => TokenKind::LeftParen,
+        b')' => TokenKind::RightParen,
+        b',' => TokenKind::Comma,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// 返回从 `pos` 开始的数字的结束位置, 包括小数部分和指数部分.
+fn scan_number(bytes: &[u8], mut pos: usize) -> usize {
+    while pos < bytes.len() && (bytes[pos].is_ascii_digit() || bytes[pos] == b'.') {
+        pos += 1;
+    }
+    // 只有 `e` 后面跟着数字时, 才把它当作指数部分.
+    if pos < bytes.len() && (bytes[pos] == b'e' || bytes[pos] == b'E') {
+        let mut exp = pos + 1;
+        if exp < bytes.len() && (bytes[exp] == b'+' || bytes[exp] == b'-') {
+            exp += 1;
+        }
+        if exp < bytes.len() && bytes[exp].is_ascii_digit() {
+            pos = exp;
+            while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+                pos += 1;
+            }
+        }
+    }
+    pos
+}
+
+/// 词法分析, 把表达式切分成记号.
+///
+/// # Errors
+///
+/// 遇到无法识别的字符, 或者数字格式不正确时, 返回错误.
+pub fn tokenize(input: &str) -> Result<Vec<Token>, ExprError> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        let offset = pos;
+        let byte = bytes[pos];
+        let kind = if byte.is_ascii_whitespace() {
+            pos += 1;
+            continue;
+        } else if let Some(kind) = punctuation(byte) {
+            pos += 1;
+            kind
+        } else if byte.is_ascii_digit() || byte == b'.' {
+            pos = scan_number(bytes, pos);
+            let value = input[offset..pos]
+                .parse()
+                .map_err(|_| ExprError::new(ErrorKind::InvalidNumber, offset))?;
+            TokenKind::Number(value)
+        } else if byte.is_ascii_alphabetic() || byte == b'_' {
+            while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_') {
+                pos += 1;
+            }
+            TokenKind::Ident(input[offset..pos].to_owned())
+        } else {
+            // 前面只跳过了 ASCII 字符, 所以 `offset` 总是位于字符边界上.
+            let ch = input[offset..].chars().next().unwrap_or_default();
+            return Err(ExprError::new(ErrorKind::UnexpectedChar(ch), offset));
+        };
+        tokens.push(Token { kind, offset });
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PostfixKind {
+    Number(f64),
+    Variable(String),
+    Operator(Operator),
+    /// 函数调用, 以及参数个数.
+    Call {
+        name: String,
+        argc: usize,
+    },
+}
+
+/// 后缀表达式中的一项, 以及它在输入字符串中的字节偏移量.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostfixItem {
+    pub kind: PostfixKind,
+    pub offset: usize,
+}
+
+impl fmt::Display for PostfixItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            PostfixKind::Number(value) => write!(f, "{value}"),
+            PostfixKind::Variable(name) => f.write_str(name),
+            PostfixKind::Operator(op) => f.write_str(op.symbol()),
+            PostfixKind::Call { name, argc } => write!(f, "{name}/{argc}"),
+        }
+    }
+}
+
+/// 调度场算法中, 运算符栈里的元素.
+enum Pending {
+    Operator(Operator, usize),
+    /// 左括号, 如果是函数调用的括号, 还要记录函数名及其偏移量.
+    Paren {
+        offset: usize,
+        function: Option<(String, usize)>,
+    },
+}
+
+/// 把中缀表达式转换成后缀表达式.
+struct Converter<I: Iterator<Item = Token>> {
+    tokens: Peekable<I>,
+    output: Vec<PostfixItem>,
+    pending: VecStack<Pending>,
+    /// 每个尚未结束的函数调用中, 已经遇到的逗号个数.
+    commas: VecStack<usize>,
+    /// 下一个记号是否应该是操作数.
+    expect_operand: bool,
+}
+
+impl<I: Iterator<Item = Token>> Converter<I> {
+    fn new(tokens: I) -> Self {
+        Self {
+            tokens: tokens.peekable(),
+            output: Vec::new(),
+            pending: VecStack::new(),
+            commas: VecStack::new(),
+            expect_operand: true,
+        }
+    }
+
+    fn emit(&mut self, kind: PostfixKind, offset: usize) {
+        self.output.push(PostfixItem { kind, offset });
+    }
+
+    /// 把栈顶连续的运算符输出, 直到遇到左括号或者 `stop` 返回 `true`.
+    fn flush_operators<F: Fn(Operator) -> bool>(&mut self, stop: F) {
+        while let Some(&Pending::Operator(op, offset)) = self.pending.top() {
+            if stop(op) {
+                break;
+            }
+            self.pending.pop();
+            self.emit(PostfixKind::Operator(op), offset);
+        }
+    }
+
+    fn operand(&mut self, kind: PostfixKind, offset: usize) -> Result<(), ExprError> {
+        if !self.expect_operand {
+            return Err(ExprError::new(ErrorKind::UnexpectedToken, offset));
+        }
+        self.emit(kind, offset);
+        self.expect_operand = false;
+        Ok(())
+    }
+
+    fn ident(&mut self, name: String, offset: usize) -> Result<(), ExprError> {
+        let Some(paren) = self
+            .tokens
+            .next_if(|token| token.kind == TokenKind::LeftParen)
+        else {
+            return self.operand(PostfixKind::Variable(name), offset);
+        };
+        if !self.expect_operand {
+            return Err(ExprError::new(ErrorKind::UnexpectedToken, offset));
+        }
+        // 没有参数的函数调用.
+        if self
+            .tokens
+            .next_if(|token| token.kind == TokenKind::RightParen)
+            .is_some()
+        {
+            self.emit(PostfixKind::Call { name, argc: 0 }, offset);
+            self.expect_operand = false;
+            return Ok(());
+        }
+        self.pending.push(Pending::Paren {
+            offset: paren.offset,
+            function: Some((name, offset)),
+        });
+        self.commas.push(0);
+        Ok(())
+    }
+
+    fn operator(&mut self, op: Operator, offset: usize) -> Result<(), ExprError> {
+        if op.is_unary() {
+            // 前缀运算符的左侧没有操作数, 不需要弹出任何运算符.
+            self.pending.push(Pending::Operator(op, offset));
+            return Ok(());
+        }
+        if self.expect_operand {
+            return Err(ExprError::new(ErrorKind::UnexpectedToken, offset));
+        }
+        self.flush_operators(|top| !top.goes_before(op));
+        self.pending.push(Pending::Operator(op, offset));
+        self.expect_operand = true;
+        Ok(())
+    }
+
+    fn left_paren(&mut self, offset: usize) -> Result<(), ExprError> {
+        if !self.expect_operand {
+            return Err(ExprError::new(ErrorKind::UnexpectedToken, offset));
+        }
+        self.pending.push(Pending::Paren {
+            offset,
+            function: None,
+        });
+        Ok(())
+    }
+
+    fn right_paren(&mut self, offset: usize) -> Result<(), ExprError> {
+        if self.expect_operand {
+            return Err(ExprError::new(ErrorKind::UnexpectedToken, offset));
+        }
+        self.flush_operators(|_| false);
+        match self.pending.pop() {
+            Some(Pending::Paren {
+                function: Some((name, name_offset)),
+                ..
+            }) => {
+                let commas = self.commas.pop().unwrap_or_default();
+                self.emit(
+                    PostfixKind::Call {
+                        name,
+                        argc: commas + 1,
+                    },
+                    name_offset,
+                );
+            }
+            Some(Pending::Paren { function: None, .. }) => (),
+            _ => return Err(ExprError::new(ErrorKind::UnmatchedParen, offset)),
+        }
+        Ok(())
+    }
+
+    fn comma(&mut self, offset: usize) -> Result<(), ExprError> {
+        if self.expect_operand {
+            return Err(ExprError::new(ErrorKind::UnexpectedToken, offset));
+        }
+        self.flush_operators(|_| false);
+        if !matches!(
+            self.pending.top(),
+            Some(Pending::Paren {
+                function: Some(_),
+                ..
+            })
+        ) {
+            return Err(ExprError::new(ErrorKind::MisplacedComma, offset));
+        }
+        let commas = self.commas.pop().unwrap_or_default();
+        self.commas.push(commas + 1);
+        self.expect_operand = true;
+        Ok(())
+    }
+
+    fn convert(mut self, end: usize) -> Result<Vec<PostfixItem>, ExprError> {
+        while let Some(Token { kind, offset }) = self.tokens.next() {
+            match kind {
+                TokenKind::Number(value) => self.operand(PostfixKind::Number(value), offset)?,
+                TokenKind::Ident(name) => self.ident(name, offset)?,
+                // 一元正号不影响结果, 直接忽略.
+                TokenKind::Plus if self.expect_operand => (),
+                TokenKind::Plus => self.operator(Operator::Add, offset)?,
+                TokenKind::Minus if self.expect_operand => self.operator(Operator::Neg, offset)?,
+                TokenKind::Minus => self.operator(Operator::Sub, offset)?,
+                TokenKind::Star => self.operator(Operator::Mul, offset)?,
+                TokenKind::Slash => self.operator(Operator::Div, offset)?,
+                TokenKind::Percent => self.operator(Operator::Rem, offset)?,
+                TokenKind::Caret => self.operator(Operator::Pow, offset)?,
+                TokenKind::LeftParen => self.left_paren(offset)?,
+                TokenKind::RightParen => self.right_paren(offset)?,
+                TokenKind::Comma => self.comma(offset)?,
+            }
+        }
+
+        if self.expect_operand {
+            return Err(ExprError::new(ErrorKind::UnexpectedEnd, end));
+        }
+        while let Some(pending) = self.pending.pop() {
+            match pending {
+                Pending::Operator(op, offset) => self.emit(PostfixKind::Operator(op), offset),
+                Pending::Paren { offset, .. } => {
+                    return Err(ExprError::new(ErrorKind::UnmatchedParen, offset));
+                }
+            }
+        }
+        Ok(self.output)
+    }
+}
+
+/// 使用调度场算法, 把中缀表达式转换成后缀表达式.
+///
+/// # Errors
+///
+/// 表达式的词法或者语法不正确时, 返回错误.
+pub fn to_postfix(input: &str) -> Result<Vec<PostfixItem>, ExprError> {
+    let tokens = tokenize(input)?;
+    Converter::new(tokens.into_iter()).convert(input.len())
+}
+
+type Builtin = fn(&[f64]) -> f64;
+
+#[derive(Debug, Clone, Copy)]
+enum Arity {
+    Exact(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    const fn accepts(self, argc: usize) -> bool {
+        match self {
+            Self::Exact(n) => argc == n,
+            Self::AtLeast(n) => argc >= n,
+        }
+    }
+}
+
+/// 内置函数, 调用前已经检查过参数个数.
+fn builtin(name: &str) -> Option<(Arity, Builtin)> {
+    let function: (Arity, Builtin) = match name {
+        "abs" => (Arity::Exact(1), |args| args[0].abs()),
+        "sqrt" => (Arity::Exact(1), |args| args[0].sqrt()),
+        "exp" => (Arity::Exact(1), |args| args[0].exp()),
+        "ln" => (Arity::Exact(1), |args| args[0].ln()),
+        "log" => (Arity::Exact(1), |args| args[0].log10()),
+        "sin" => (Arity::Exact(1), |args| args[0].sin()),
+        "cos" => (Arity::Exact(1), |args| args[0].cos()),
+        "tan" => (Arity::Exact(1), |args| args[0].tan()),
+        "floor" => (Arity::Exact(1), |args| args[0].floor()),
+        "ceil" => (Arity::Exact(1), |args| args[0].ceil()),
+        "round" => (Arity::Exact(1), |args| args[0].round()),
+        "pow" => (Arity::Exact(2), |args| args[0].powf(args[1])),
+        "min" => (Arity::AtLeast(1), |args| {
+            args.iter().copied().fold(f64::INFINITY, f64::min)
+        }),
+        "max" => (Arity::AtLeast(1), |args| {
+            args.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+        }),
+        _ => return None,
+    };
+    Some(function)
+}
+
+/// 内置常量, 用户定义的同名变量优先.
+fn constant(name: &str) -> Option<f64> {
+    match name {
+        "pi" => Some(std::f64::consts::PI),
+        "e" => Some(std::f64::consts::E),
+        _ => None,
+    }
+}
+
+/// 解析后的表达式, 以后缀形式保存, 可以多次求值.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    postfix: Vec<PostfixItem>,
+}
+
+impl Expr {
+    /// # Errors
+    ///
+    /// 表达式的词法或者语法不正确时, 返回错误.
+    pub fn parse(input: &str) -> Result<Self, ExprError> {
+        let postfix = to_postfix(input)?;
+        Ok(Self { postfix })
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn postfix(&self) -> &[PostfixItem] {
+        &self.postfix
+    }
+
+    /// 使用给定的变量对表达式求值.
+    ///
+    /// # Errors
+    ///
+    /// 变量或者函数未定义, 函数参数个数不正确, 或者除数为 0 时, 返回错误.
+    pub fn eval<K, S>(&self, vars: &HashMap<K, f64, S>) -> Result<f64, ExprError>
+    where
+        K: Borrow<str> + Hash + Eq,
+        S: BuildHasher,
+    {
+        let mut stack: VecStack<f64> = VecStack::with_capacity(self.postfix.len());
+        // 后缀表达式由 `to_postfix()` 生成, 操作数总是足够的.
+        let pop = |stack: &mut VecStack<f64>| stack.pop().unwrap_or_default();
+
+        for item in &self.postfix {
+            let offset = item.offset;
+            let value = match &item.kind {
+                PostfixKind::Number(value) => *value,
+                PostfixKind::Variable(name) => vars
+                    .get(name.as_str())
+                    .copied()
+                    .or_else(|| constant(name))
+                    .ok_or_else(|| ExprError::new(ErrorKind::UnknownVariable, offset))?,
+                PostfixKind::Operator(Operator::Neg) => -pop(&mut stack),
+                PostfixKind::Operator(op) => {
+                    let rhs = pop(&mut stack);
+                    let lhs = pop(&mut stack);
+                    apply(*op, lhs, rhs, offset)?
+                }
+                PostfixKind::Call { name, argc } => {
+                    let (arity, function) = builtin(name)
+                        .ok_or_else(|| ExprError::new(ErrorKind::UnknownFunction, offset))?;
+                    if !arity.accepts(*argc) {
+                        return Err(ExprError::new(ErrorKind::ArgumentCount, offset));
+                    }
+                    let mut args = vec![0.0; *argc];
+                    for arg in args.iter_mut().rev() {
+                        *arg = pop(&mut stack);
+                    }
+                    function(&args)
+                }
+            };
+            stack.push(value);
+        }
+
+        debug_assert_eq!(stack.len(), 1);
+        Ok(pop(&mut stack))
+    }
+}
+
+fn apply(op: Operator, lhs: f64, rhs: f64, offset: usize) -> Result<f64, ExprError> {
+    let value = match op {
+        Operator::Add => lhs + rhs,
+        Operator::Sub => lhs - rhs,
+        Operator::Mul => lhs * rhs,
+        Operator::Div | Operator::Rem if rhs == 0.0 => {
+            return Err(ExprError::new(ErrorKind::DivisionByZero, offset));
+        }
+        Operator::Div => lhs / rhs,
+        Operator::Rem => lhs % rhs,
+        Operator::Pow => lhs.powf(rhs),
+        Operator::Neg => -rhs,
+    };
+    Ok(value)
+}
+
+impl FromStr for Expr {
+    type Err = ExprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// 以逆波兰表达式的形式输出, 各项之间以空格分隔.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, item) in self.postfix.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{item}")?;
+        }
+        Ok(())
+    }
+}
+
+/// 解析表达式并立即求值.
+///
+/// # Errors
+///
+/// 解析或者求值失败时, 返回错误.
+pub fn eval<K, S>(input: &str, vars: &HashMap<K, f64, S>) -> Result<f64, ExprError>
+where
+    K: Borrow<str> + Hash + Eq,
+    S: BuildHasher,
+{
+    Expr::parse(input)?.eval(vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{eval, tokenize, ErrorKind, Expr, ExprError, TokenKind};
+
+    fn calc(input: &str) -> Result<f64, ExprError> {
+        eval(input, &HashMap::<&str, f64>::new())
+    }
+
+    fn error(input: &str) -> (ErrorKind, usize) {
+        let err = calc(input).unwrap_err();
+        (err.kind(), err.offset())
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize("3.5e2 * (x_1 - .5)").unwrap();
+        let kinds: Vec<TokenKind> = tokens.iter().map(|token| token.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::Number(350.0),
+                TokenKind::Star,
+                TokenKind::LeftParen,
+                TokenKind::Ident("x_1".to_owned()),
+                TokenKind::Minus,
+                TokenKind::Number(0.5),
+                TokenKind::RightParen,
+            ]
+        );
+        let offsets: Vec<usize> = tokens.iter().map(|token| token.offset).collect();
+        assert_eq!(offsets, [0, 6, 8, 9, 13, 15, 17]);
+    }
+
+    #[test]
+    fn test_to_postfix() {
+        let expr = Expr::parse("3 + 4 * 2 / (1 - 5) ^ 2 ^ 3").unwrap();
+        assert_eq!(expr.to_string(), "3 4 2 * 1 5 - 2 3 ^ ^ / +");
+
+        let expr: Expr = "-2 ^ 2 + max(a, -b, 1) * sin(pi)".parse().unwrap();
+        assert_eq!(expr.to_string(), "2 2 ^ neg a b neg 1 max/3 pi sin/1 * +");
+    }
+
+    #[test]
+    fn test_eval() {
+        assert_eq!(calc("1 + 2 * 3"), Ok(7.0));
+        assert_eq!(calc("(1 + 2) * 3"), Ok(9.0));
+        assert_eq!(calc("10 - 4 - 3"), Ok(3.0));
+        assert_eq!(calc("2 ^ 3 ^ 2"), Ok(512.0));
+        assert_eq!(calc("-2 ^ 2"), Ok(-4.0));
+        assert_eq!(calc("2 * -3"), Ok(-6.0));
+        assert_eq!(calc("--3 + +1"), Ok(4.0));
+        assert_eq!(calc("7 % 4"), Ok(3.0));
+        assert_eq!(calc("pow(2, 10) - max(1, 5, 3) + abs(-1)"), Ok(1020.0));
+        assert_eq!(calc("min(4, sqrt(9))"), Ok(3.0));
+
+        let mut vars = HashMap::new();
+        vars.insert("x".to_owned(), 3.0);
+        vars.insert("rate".to_owned(), 0.5);
+        let expr = Expr::parse("x * x - rate * 4").unwrap();
+        assert_eq!(expr.eval(&vars), Ok(7.0));
+        vars.insert("x".to_owned(), 4.0);
+        assert_eq!(expr.eval(&vars), Ok(14.0));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(error("1 + $"), (ErrorKind::UnexpectedChar('$'), 4));
+        assert_eq!(error("1.2.3"), (ErrorKind::InvalidNumber, 0));
+        assert_eq!(error("1 + * 2"), (ErrorKind::UnexpectedToken, 4));
+        assert_eq!(error("2 (3)"), (ErrorKind::UnexpectedToken, 2));
+        assert_eq!(error("1 +"), (ErrorKind::UnexpectedEnd, 3));
+        assert_eq!(error(""), (ErrorKind::UnexpectedEnd, 0));
+        assert_eq!(error("()"), (ErrorKind::UnexpectedToken, 1));
+        assert_eq!(error("(1 + 2"), (ErrorKind::UnmatchedParen, 0));
+        assert_eq!(error("1 + 2)"), (ErrorKind::UnmatchedParen, 5));
+        assert_eq!(error("max(1, )"), (ErrorKind::UnexpectedToken, 7));
+        assert_eq!(error("(1, 2)"), (ErrorKind::MisplacedComma, 2));
+        assert_eq!(error("1 + y"), (ErrorKind::UnknownVariable, 4));
+        assert_eq!(error("2 * foo(1)"), (ErrorKind::UnknownFunction, 4));
+        assert_eq!(error("sqrt(1, 2)"), (ErrorKind::ArgumentCount, 0));
+        assert_eq!(error("max()"), (ErrorKind::ArgumentCount, 0));
+        assert_eq!(error("1 / (2 - 2)"), (ErrorKind::DivisionByZero, 2));
+
+        let err = calc("1 +").unwrap_err();
+        assert_eq!(err.to_string(), "unexpected end of expression at offset 3");
+    }
+}
@@ -14,3 +14,10 @@ pub mod array_stack;
 pub mod array_stack2;
 pub mod vec_stack;
 pub mod list_stack;
+pub mod expr;
+pub mod monotonic_stack;
+pub mod traits;
+
+pub use expr::{Expr, ExprError};
+pub use monotonic_stack::MonotonicStack;
+pub use traits::{BoundedStack, Stack};
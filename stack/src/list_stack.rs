@@ -8,6 +8,8 @@ use std::fmt;
 use std::fmt::Formatter;
 use std::hash::{Hash, Hasher};
 
+use crate::traits::Stack;
+
 #[allow(clippy::linkedlist)]
 pub struct ListStack<T> (LinkedList<T>);
 
@@ -54,6 +56,29 @@ impl<T> ListStack<T> {
     }
 }
 
+impl<T> Stack<T> for ListStack<T> {
+    #[inline]
+    fn push(&mut self, value: T) -> Result<(), T> {
+        Self::push(self, value);
+        Ok(())
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<T> {
+        Self::pop(self)
+    }
+
+    #[inline]
+    fn top(&self) -> Option<&T> {
+        Self::top(self)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        Self::len(self)
+    }
+}
+
 impl<T> Default for ListStack<T> {
     #[inline]
     fn default() -> Self {
@@ -0,0 +1,375 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 单调栈, 从栈底到栈顶的元素保持单调.
+//!
+//! 新元素入栈之前, 先把破坏单调性的栈顶元素依次出栈. 每个元素最多入栈和出栈各一次,
+//! 所以处理 n 个元素的总时间复杂度是 O(n).
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::traits::Stack;
+
+/// 单调栈中元素的排列顺序.
+pub trait Monotonicity {
+    /// 在栈中相邻的两个元素, `below` 位于 `above` 下面时是否满足单调性.
+    fn holds<T: Ord>(below: &T, above: &T) -> bool;
+}
+
+/// 从栈底到栈顶严格递增.
+#[derive(Debug, Clone, Copy)]
+pub struct Increasing;
+
+/// 从栈底到栈顶单调不减, 允许相等的元素.
+#[derive(Debug, Clone, Copy)]
+pub struct NonDecreasing;
+
+/// 从栈底到栈顶严格递减.
+#[derive(Debug, Clone, Copy)]
+pub struct Decreasing;
+
+/// 从栈底到栈顶单调不增, 允许相等的元素.
+#[derive(Debug, Clone, Copy)]
+pub struct NonIncreasing;
+
+impl Monotonicity for Increasing {
+    fn holds<T: Ord>(below: &T, above: &T) -> bool {
+        below < above
+    }
+}
+
+impl Monotonicity for NonDecreasing {
+    fn holds<T: Ord>(below: &T, above: &T) -> bool {
+        below <= above
+    }
+}
+
+impl Monotonicity for Decreasing {
+    fn holds<T: Ord>(below: &T, above: &T) -> bool {
+        below > above
+    }
+}
+
+impl Monotonicity for NonIncreasing {
+    fn holds<T: Ord>(below: &T, above: &T) -> bool {
+        below >= above
+    }
+}
+
+pub struct MonotonicStack<T, O = Increasing> {
+    buf: Vec<T>,
+    _order: PhantomData<O>,
+}
+
+impl<T: Ord, O: Monotonicity> Default for MonotonicStack<T, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord, O: Monotonicity> MonotonicStack<T, O> {
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            _order: PhantomData,
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buf: Vec::with_capacity(capacity),
+            _order: PhantomData,
+        }
+    }
+
+    /// 将元素入栈, 破坏单调性的栈顶元素会被出栈并释放.
+    #[inline]
+    pub fn push(&mut self, value: T) {
+        self.push_with(value, drop);
+    }
+
+    /// 将元素入栈, 破坏单调性的栈顶元素按出栈顺序传给 `on_pop`.
+    pub fn push_with<F: FnMut(T)>(&mut self, value: T, mut on_pop: F) {
+        while let Some(top) = self.buf.last() {
+            if O::holds(top, &value) {
+                break;
+            }
+            if let Some(top) = self.buf.pop() {
+                on_pop(top);
+            }
+        }
+        self.buf.push(value);
+    }
+
+    /// 将栈顶元素出栈
+    ///
+    /// 当栈已经空时, 返回 `None`
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.buf.pop()
+    }
+
+    /// 返回栈顶元素
+    #[must_use]
+    #[inline]
+    pub fn top(&self) -> Option<&T> {
+        self.buf.last()
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.buf.clear();
+    }
+
+    /// 返回栈中的元素, 从栈底到栈顶排列.
+    #[must_use]
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        &self.buf
+    }
+}
+
+impl<T: Ord, O: Monotonicity> Stack<T> for MonotonicStack<T, O> {
+    /// 将元素入栈, 总是返回 `Ok(())`.
+    #[inline]
+    fn push(&mut self, value: T) -> Result<(), T> {
+        Self::push(self, value);
+        Ok(())
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<T> {
+        Self::pop(self)
+    }
+
+    #[inline]
+    fn top(&self) -> Option<&T> {
+        Self::top(self)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        Self::len(self)
+    }
+}
+
+impl<T: fmt::Debug, O> fmt::Debug for MonotonicStack<T, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.buf, f)
+    }
+}
+
+/// 带下标的元素, 只比较元素的值.
+struct Indexed<T> {
+    index: usize,
+    value: T,
+}
+
+impl<T: PartialEq> PartialEq for Indexed<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for Indexed<T> {}
+
+impl<T: Ord> PartialOrd for Indexed<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for Indexed<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+/// 对每个元素, 查找它之后第一个满足条件的元素的下标; 入栈时被弹出的元素, 其答案就是新元素.
+fn next_by<T, I, O>(values: I) -> Vec<Option<usize>>
+where
+    T: Ord,
+    I: IntoIterator<Item = T>,
+    O: Monotonicity,
+{
+    let mut result = Vec::new();
+    let mut stack = MonotonicStack::<Indexed<T>, O>::new();
+    for (index, value) in values.into_iter().enumerate() {
+        result.push(None);
+        stack.push_with(Indexed { index, value }, |popped| {
+            result[popped.index] = Some(index);
+        });
+    }
+    result
+}
+
+/// 对每个元素, 查找它之前最近的满足条件的元素的下标; 入栈后, 新元素下面的就是答案.
+fn previous_by<T, I, O>(values: I) -> Vec<Option<usize>>
+where
+    T: Ord,
+    I: IntoIterator<Item = T>,
+    O: Monotonicity,
+{
+    let mut stack = MonotonicStack::<Indexed<T>, O>::new();
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| {
+            stack.push(Indexed { index, value });
+            let slice = stack.as_slice();
+            slice.len().checked_sub(2).map(|below| slice[below].index)
+        })
+        .collect()
+}
+
+/// 对每个元素, 返回它之后第一个严格大于它的元素的下标, 如果不存在, 就是 `None`.
+///
+/// 比如 "每日温度" 问题中, 等待的天数就是 `next_greater()` 的结果减去当前下标.
+pub fn next_greater<T, I>(values: I) -> Vec<Option<usize>>
+where
+    T: Ord,
+    I: IntoIterator<Item = T>,
+{
+    // 栈顶元素小于新元素时出栈, 所以栈中的元素单调不增.
+    next_by::<T, I, NonIncreasing>(values)
+}
+
+/// 对每个元素, 返回它之后第一个严格小于它的元素的下标, 如果不存在, 就是 `None`.
+pub fn next_smaller<T, I>(values: I) -> Vec<Option<usize>>
+where
+    T: Ord,
+    I: IntoIterator<Item = T>,
+{
+    next_by::<T, I, NonDecreasing>(values)
+}
+
+/// 对每个元素, 返回它之前最近的严格大于它的元素的下标, 如果不存在, 就是 `None`.
+pub fn previous_greater<T, I>(values: I) -> Vec<Option<usize>>
+where
+    T: Ord,
+    I: IntoIterator<Item = T>,
+{
+    // 栈顶元素小于等于新元素时出栈, 所以栈中的元素严格递减.
+    previous_by::<T, I, Decreasing>(values)
+}
+
+/// 对每个元素, 返回它之前最近的严格小于它的元素的下标, 如果不存在, 就是 `None`.
+pub fn previous_smaller<T, I>(values: I) -> Vec<Option<usize>>
+where
+    T: Ord,
+    I: IntoIterator<Item = T>,
+{
+    previous_by::<T, I, Increasing>(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        next_greater, next_smaller, previous_greater, previous_smaller, Decreasing, Increasing,
+        MonotonicStack, NonDecreasing,
+    };
+    use crate::traits::Stack;
+
+    /// 暴力求解, 用于验证结果.
+    fn brute_force<F>(values: &[i32], forward: bool, pred: F) -> Vec<Option<usize>>
+    where
+        F: Fn(i32, i32) -> bool,
+    {
+        (0..values.len())
+            .map(|i| {
+                if forward {
+                    (i + 1..values.len()).find(|&j| pred(values[j], values[i]))
+                } else {
+                    (0..i).rev().find(|&j| pred(values[j], values[i]))
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_push() {
+        let mut stack = MonotonicStack::<i32, Increasing>::new();
+        for value in [3, 1, 4, 1, 5, 9, 2, 6] {
+            stack.push(value);
+        }
+        assert_eq!(stack.as_slice(), &[1, 2, 6]);
+
+        let mut stack = MonotonicStack::<i32, NonDecreasing>::new();
+        for value in [3, 1, 4, 1, 5, 9, 2, 6] {
+            stack.push(value);
+        }
+        assert_eq!(stack.as_slice(), &[1, 1, 2, 6]);
+
+        let mut stack = MonotonicStack::<i32, Decreasing>::new();
+        let mut popped = Vec::new();
+        for value in [5, 3, 4, 4, 1] {
+            stack.push_with(value, |top| popped.push(top));
+        }
+        assert_eq!(stack.as_slice(), &[5, 4, 1]);
+        assert_eq!(popped, [3, 4]);
+        assert_eq!(stack.top(), Some(&1));
+        assert_eq!(stack.pop(), Some(1));
+        assert_eq!(stack.len(), 2);
+    }
+
+    #[test]
+    fn test_stack_trait() {
+        let mut stack = MonotonicStack::<i32>::new();
+        assert_eq!(Stack::push(&mut stack, 2), Ok(()));
+        assert_eq!(Stack::push(&mut stack, 1), Ok(()));
+        assert_eq!(Stack::top(&stack), Some(&1));
+        assert_eq!(Stack::len(&stack), 1);
+    }
+
+    #[test]
+    fn test_daily_temperatures() {
+        let temperatures = [73, 74, 75, 71, 69, 72, 76, 73];
+        let days: Vec<usize> = next_greater(temperatures)
+            .into_iter()
+            .enumerate()
+            .map(|(i, next)| next.map_or(0, |j| j - i))
+            .collect();
+        assert_eq!(days, [1, 1, 4, 2, 1, 1, 0, 0]);
+    }
+
+    #[test]
+    fn test_helpers() {
+        let values = [2, 7, 7, 1, 8, 2, 8, 1, 8, 2, 8, 4, 5, 9, 0, 4, 5];
+        assert_eq!(
+            next_greater(values),
+            brute_force(&values, true, |other, x| other > x)
+        );
+        assert_eq!(
+            next_smaller(values),
+            brute_force(&values, true, |other, x| other < x)
+        );
+        assert_eq!(
+            previous_greater(values),
+            brute_force(&values, false, |other, x| other > x)
+        );
+        assert_eq!(
+            previous_smaller(values.iter()),
+            brute_force(&values, false, |other, x| other < x)
+        );
+        assert!(next_greater(Vec::<i32>::new()).is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by General Public License that can be found
+// in the LICENSE file.
+
+//! 各种栈的公共接口.
+//!
+//! 基于这些 trait, 括号匹配这类算法只需要实现一次, 就可以用于所有的栈.
+
+/// 后进先出 (LIFO) 的栈.
+pub trait Stack<T> {
+    /// 将元素入栈
+    ///
+    /// # Errors
+    ///
+    /// 当栈已满时返回 `Err(value)`, 容量不受限制的栈总是返回 `Ok(())`.
+    fn push(&mut self, value: T) -> Result<(), T>;
+
+    /// 将栈顶元素出栈
+    ///
+    /// 当栈已经空时, 返回 `None`
+    fn pop(&mut self) -> Option<T>;
+
+    /// 返回栈顶元素
+    fn top(&self) -> Option<&T>;
+
+    /// 返回当前栈中的元素个数
+    fn len(&self) -> usize;
+
+    /// 检查栈是否空
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 容量固定的栈.
+pub trait BoundedStack<T>: Stack<T> {
+    /// 返回栈的容量
+    fn capacity(&self) -> usize;
+
+    /// 检查栈是否已满
+    fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundedStack, Stack};
+    use crate::array_stack::ArrayStack;
+    use crate::array_stack2::ArrayStack2;
+    use crate::list_stack::ListStack;
+    use crate::vec_stack::VecStack;
+
+    /// 检查括号是否匹配, 适用于任意的栈.
+    fn is_balanced<S: Stack<char>>(mut stack: S, s: &str) -> bool {
+        for c in s.chars() {
+            let matched = match c {
+                '(' | '[' | '{' => stack.push(c).is_ok(),
+                ')' => stack.pop() == Some('('),
+                ']' => stack.pop() == Some('['),
+                '}' => stack.pop() == Some('{'),
+                _ => true,
+            };
+            if !matched {
+                return false;
+            }
+        }
+        stack.is_empty()
+    }
+
+    /// 交替入栈和出栈, 每次弹出的都是最后压入的元素.
+    fn check_lifo<S: Stack<i32>>(mut stack: S) {
+        assert!(stack.is_empty());
+        assert_eq!(stack.pop(), None);
+        assert_eq!(stack.push(1), Ok(()));
+        assert_eq!(stack.push(2), Ok(()));
+        assert_eq!(stack.pop(), Some(2));
+        assert_eq!(stack.push(3), Ok(()));
+        assert_eq!(stack.push(4), Ok(()));
+        assert_eq!(stack.len(), 3);
+        assert_eq!(stack.pop(), Some(4));
+        assert_eq!(stack.pop(), Some(3));
+        assert_eq!(stack.pop(), Some(1));
+        assert!(stack.is_empty());
+        assert_eq!(stack.pop(), None);
+    }
+
+    /// `top()` 只查看栈顶元素, 不会将它出栈.
+    fn check_top<S: Stack<i32>>(mut stack: S) {
+        assert_eq!(stack.top(), None);
+        for i in 0..4 {
+            assert_eq!(stack.push(i), Ok(()));
+            assert_eq!(stack.top(), Some(&i));
+            assert_eq!(stack.top(), Some(&i));
+            assert_eq!(stack.len(), usize::try_from(i).unwrap() + 1);
+        }
+        // 出栈之后, 下面的元素成为新的栈顶.
+        assert_eq!(stack.pop(), Some(3));
+        assert_eq!(stack.top(), Some(&2));
+        assert_eq!(stack.len(), 3);
+    }
+
+    /// 栈满时入栈失败, 栈顶保持不变.
+    fn check_bounded_stack<S: BoundedStack<i32>>(mut stack: S) {
+        let capacity = stack.capacity();
+        for i in 0..capacity {
+            assert!(!stack.is_full());
+            assert_eq!(stack.push(i32::try_from(i).unwrap()), Ok(()));
+        }
+        let last = i32::try_from(capacity).unwrap() - 1;
+        assert!(stack.is_full());
+        assert_eq!(stack.push(-1), Err(-1));
+        assert_eq!(stack.top(), Some(&last));
+        assert_eq!(stack.len(), capacity);
+
+        assert_eq!(stack.pop(), Some(last));
+        assert!(!stack.is_full());
+        assert_eq!(stack.push(-1), Ok(()));
+        assert_eq!(stack.top(), Some(&-1));
+    }
+
+    #[test]
+    fn test_lifo_order() {
+        check_lifo(ArrayStack::new(4));
+        check_lifo(ArrayStack2::new(4));
+        check_lifo(VecStack::new());
+        check_lifo(ListStack::new());
+    }
+
+    #[test]
+    fn test_top() {
+        check_top(ArrayStack::new(4));
+        check_top(ArrayStack2::new(4));
+        check_top(VecStack::new());
+        check_top(ListStack::new());
+    }
+
+    #[test]
+    fn test_bounded_stack() {
+        check_bounded_stack(ArrayStack::new(5));
+        check_bounded_stack(ArrayStack2::new(5));
+    }
+
+    #[test]
+    fn test_generic_algorithm() {
+        for (s, expected) in [
+            ("([]{()})", true),
+            ("([)]", false),
+            ("((", false),
+            ("", true),
+        ] {
+            assert_eq!(is_balanced(ArrayStack::new(8), s), expected);
+            assert_eq!(is_balanced(ArrayStack2::new(8), s), expected);
+            assert_eq!(is_balanced(VecStack::new(), s), expected);
+            assert_eq!(is_balanced(ListStack::new(), s), expected);
+        }
+        // 超出容量时也视为不匹配.
+        assert!(!is_balanced(ArrayStack::new(2), "((()))"));
+    }
+}
@@ -7,6 +7,8 @@ use std::fmt;
 use std::fmt::Formatter;
 use std::hash::{Hash, Hasher};
 
+use crate::traits::Stack;
+
 pub struct VecStack<T: Sized>(Vec<T>);
 
 impl<T> Default for VecStack<T> {
@@ -55,26 +57,49 @@ impl<T> VecStack<T> {
     /// 检查栈是否空
     #[must_use]
     #[inline]
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
 
     /// 返回当前栈中的元素个数
     #[must_use]
     #[inline]
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         self.0.len()
     }
 
     /// 返回栈的容量
     #[must_use]
     #[inline]
-    pub fn capacity(&self) -> usize {
+    pub const fn capacity(&self) -> usize {
         self.0.capacity()
     }
 }
 
 
+impl<T> Stack<T> for VecStack<T> {
+    #[inline]
+    fn push(&mut self, value: T) -> Result<(), T> {
+        Self::push(self, value);
+        Ok(())
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<T> {
+        Self::pop(self)
+    }
+
+    #[inline]
+    fn top(&self) -> Option<&T> {
+        Self::top(self)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        Self::len(self)
+    }
+}
+
 impl<T: PartialEq> PartialEq for VecStack<T> {
     fn eq(&self, other: &Self) -> bool {
         PartialEq::eq(&self.0, &other.0)